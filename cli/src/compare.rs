@@ -11,6 +11,160 @@ use tract_core::internal::*;
 use crate::display_params::DisplayParams;
 use crate::*;
 use crate::tensor::RunParams;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Debug)]
+pub struct CompareParams {
+    pub cumulative: bool,
+    pub tolerance: Approximation,
+    pub report: Option<PathBuf>,
+}
+
+/// Numerical difference between the obtained and reference values of an outlet.
+#[derive(Clone, Debug, Serialize)]
+pub struct OutletDiff {
+    pub turn: usize,
+    pub node_id: usize,
+    pub node_name: String,
+    pub output: usize,
+    pub ok: bool,
+    pub max_abs_error: f64,
+    pub mean_abs_error: f64,
+    pub relative_error: f64,
+    pub cosine_similarity: f64,
+    pub worst_index: Vec<usize>,
+    pub error: Option<String>,
+}
+
+impl OutletDiff {
+    fn new(
+        turn: usize,
+        node: (usize, &str),
+        output: usize,
+        ok: bool,
+        obtained: &Tensor,
+        reference: &Tensor,
+    ) -> OutletDiff {
+        let mut diff = OutletDiff {
+            turn,
+            node_id: node.0,
+            node_name: node.1.to_string(),
+            output,
+            ok,
+            max_abs_error: 0.0,
+            mean_abs_error: 0.0,
+            relative_error: 0.0,
+            cosine_similarity: 1.0,
+            worst_index: vec![],
+            error: None,
+        };
+        if let Err(e) = diff.measure(obtained, reference) {
+            diff.ok = false;
+            diff.max_abs_error = f64::NAN;
+            diff.mean_abs_error = f64::NAN;
+            diff.relative_error = f64::NAN;
+            diff.cosine_similarity = f64::NAN;
+            diff.worst_index = vec![];
+            diff.error = Some(format!("{:#}", e));
+        }
+        diff
+    }
+
+    fn measure(&mut self, obtained: &Tensor, reference: &Tensor) -> TractResult<()> {
+        use tract_core::ndarray::Dimension;
+        ensure!(
+            obtained.shape() == reference.shape(),
+            "Shape mismatch {:?} != {:?}",
+            obtained.shape(),
+            reference.shape()
+        );
+        let obtained = obtained.cast_to::<f32>()?;
+        let reference = reference.cast_to::<f32>()?;
+        let (mut sum_abs, mut dot, mut norm_a, mut norm_b, mut norm_diff) = (0., 0., 0., 0., 0.);
+        for ((ix, &a), &b) in obtained
+            .to_array_view::<f32>()?
+            .indexed_iter()
+            .zip(reference.to_array_view::<f32>()?.iter())
+        {
+            let (a, b) = (a as f64, b as f64);
+            let err = if a == b || (a.is_nan() && b.is_nan()) { 0.0 } else { (a - b).abs() };
+            let err = if err.is_nan() { f64::INFINITY } else { err };
+            if self.worst_index.is_empty() || err > self.max_abs_error {
+                self.max_abs_error = err;
+                self.worst_index = ix.slice().to_vec();
+            }
+            sum_abs += err;
+            if a.is_finite() && b.is_finite() {
+                dot += a * b;
+                norm_a += a * a;
+                norm_b += b * b;
+                norm_diff += (a - b) * (a - b);
+            }
+        }
+        if obtained.len() > 0 {
+            self.mean_abs_error = sum_abs / obtained.len() as f64;
+        }
+        self.relative_error = if norm_b > 0. {
+            (norm_diff / norm_b).sqrt()
+        } else if norm_diff == 0. {
+            0.
+        } else {
+            f64::INFINITY
+        };
+        self.cosine_similarity = if norm_a > 0. && norm_b > 0. {
+            dot / (norm_a.sqrt() * norm_b.sqrt())
+        } else if norm_a == norm_b {
+            1.
+        } else {
+            0.
+        };
+        Ok(())
+    }
+
+    fn summary(&self) -> String {
+        if let Some(e) = &self.error {
+            return format!("no numerical diff: {}", e);
+        }
+        format!(
+            "max abs err: {:.3e} at {:?}, mean abs err: {:.3e}, rel err: {:.3e}, cosine: {:.6}",
+            self.max_abs_error,
+            self.worst_index,
+            self.mean_abs_error,
+            self.relative_error,
+            self.cosine_similarity
+        )
+    }
+}
+
+fn write_report(path: &Path, diffs: &[OutletDiff]) -> CliResult<()> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => serde_json::to_writer_pretty(fs::File::create(path)?, diffs)?,
+        Some("csv") => {
+            let mut file = fs::File::create(path)?;
+            writeln!(file, "turn,node_id,node_name,output,ok,max_abs_error,mean_abs_error,relative_error,cosine_similarity,worst_index,error")?;
+            for d in diffs {
+                writeln!(
+                    file,
+                    "{},{},\"{}\",{},{},{},{},{},{},{},\"{}\"",
+                    d.turn,
+                    d.node_id,
+                    d.node_name.replace('"', "\"\""),
+                    d.output,
+                    d.ok,
+                    d.max_abs_error,
+                    d.mean_abs_error,
+                    d.relative_error,
+                    d.cosine_similarity,
+                    d.worst_index.iter().join("x"),
+                    d.error.as_deref().unwrap_or("").replace('"', "\"\"")
+                )?;
+            }
+        }
+        _ => bail!("Report file name must end with .json or .csv, got {:?}", path),
+    }
+    Ok(())
+}
 
 pub fn handle(
     params: &mut Parameters,
@@ -20,28 +174,37 @@ pub fn handle(
 ) -> CliResult<()> {
     let run_params = RunParams::from_subcommand(params, sub_matches)?;
 
-    let cumulative = sub_matches.is_present("cumulative");
+    let comparison = CompareParams {
+        cumulative: sub_matches.is_present("cumulative"),
+        tolerance: sub_matches
+            .value_of("tolerance")
+            .map(|s| s.parse())
+            .transpose()?
+            .unwrap_or(Approximation::Approximate),
+        report: sub_matches.value_of("report").map(PathBuf::from),
+    };
+    let comparison = &comparison;
     let resilent = sub_matches.is_present("resilient");
     if sub_matches.value_of("stage").is_some() {
         // --with is by pipeline and put in params
-        return handle_reference_stage(cumulative, params, &output_params, &run_params);
+        return handle_reference_stage(comparison, params, &output_params, &run_params);
     } else if let Some(npz) = sub_matches.value_of("npz") {
-        return handle_npz(cumulative, npz, params, &output_params, &run_params);
+        return handle_npz(comparison, npz, params, &output_params, &run_params);
     } else if sub_matches.is_present("twice") {
-        return handle_twice(cumulative, params, &output_params, &run_params);
+        return handle_twice(comparison, params, &output_params, &run_params);
     }
     if let Some(pbdir) = sub_matches.value_of("pbdir") {
-        return handle_pbdir(cumulative, pbdir, params, &output_params, &run_params);
+        return handle_pbdir(comparison, pbdir, params, &output_params, &run_params);
     }
     if sub_matches.is_present("tf") {
-        return handle_tensorflow(cumulative, resilent, params, &output_params, &run_params);
+        return handle_tensorflow(comparison, resilent, params, &output_params, &run_params);
     }
     bail!("No comparison target found")
 }
 
 #[cfg(not(feature = "conform"))]
 pub fn handle_tensorflow(
-    _cumulative: bool,
+    _comparison: &CompareParams,
     _resilient: bool,
     _params: &mut Parameters,
     _output_params: &DisplayParams,
@@ -52,7 +215,7 @@ pub fn handle_tensorflow(
 
 #[cfg(feature = "conform")]
 pub fn handle_tensorflow(
-    cumulative: bool,
+    comparison: &CompareParams,
     resilient: bool,
     params: &mut Parameters,
     output_params: &DisplayParams,
@@ -116,7 +279,7 @@ pub fn handle_tensorflow(
         all_values.insert(name.to_string(), vec![Ok(generated[ix].clone().into_arc_tensor())]);
     }
     dispatch_model_no_pulse!(params.tract_model, |m| compare(
-        cumulative,
+        comparison,
         m,
         &all_values,
         &params,
@@ -126,7 +289,7 @@ pub fn handle_tensorflow(
 }
 
 pub fn handle_npz(
-    cumulative: bool,
+    comparison: &CompareParams,
     npz: &str,
     params: &Parameters,
     output_params: &DisplayParams,
@@ -157,7 +320,7 @@ pub fn handle_npz(
         }
     }
     dispatch_model_no_pulse!(params.tract_model, |m| compare(
-        cumulative,
+        comparison,
         m,
         &values,
         params,
@@ -168,7 +331,7 @@ pub fn handle_npz(
 
 #[cfg(not(feature = "onnx"))]
 pub fn handle_pbdir(
-    _cumulative: bool,
+    _comparison: &CompareParams,
     _pbdir: &str,
     _params: &Parameters,
     _output_params: &DisplayParams,
@@ -179,7 +342,7 @@ pub fn handle_pbdir(
 
 #[cfg(feature = "onnx")]
 pub fn handle_pbdir(
-    cumulative: bool,
+    comparison: &CompareParams,
     pbdir: &str,
     params: &Parameters,
     output_params: &DisplayParams,
@@ -194,7 +357,7 @@ pub fn handle_pbdir(
         values.insert(tensor.name.to_string(), vec![Ok(Arc::new(tensor.try_into()?))]);
     }
     dispatch_model_no_pulse!(params.tract_model, |m| compare(
-        cumulative,
+        comparison,
         m,
         &values,
         params,
@@ -204,18 +367,18 @@ pub fn handle_pbdir(
 }
 
 pub fn handle_twice(
-    cumulative: bool,
+    comparison: &CompareParams,
     params: &Parameters,
     output_params: &DisplayParams,
     run_params: &RunParams,
 ) -> CliResult<()> {
     let reference_model =
         params.tract_model.downcast_ref::<TypedModel>().context("Only work with a typed model")?;
-    handle_with_model(cumulative, params, output_params, reference_model, run_params)
+    handle_with_model(comparison, params, output_params, reference_model, run_params)
 }
 
 pub fn handle_reference_stage(
-    cumulative: bool,
+    comparison: &CompareParams,
     params: &Parameters,
     output_params: &DisplayParams,
     run_params: &RunParams,
@@ -226,11 +389,11 @@ pub fn handle_reference_stage(
     let reference_model = reference_model
         .downcast_ref::<TypedModel>()
         .context("Only work with a typed reference model")?;
-    handle_with_model(cumulative, params, output_params, reference_model, run_params)
+    handle_with_model(comparison, params, output_params, reference_model, run_params)
 }
 
 pub fn handle_with_model(
-    cumulative: bool,
+    comparison: &CompareParams,
     params: &Parameters,
     output_params: &DisplayParams,
     reference_model: &TypedModel,
@@ -257,7 +420,7 @@ pub fn handle_with_model(
         })?;
     }
     dispatch_model_no_pulse!(params.tract_model, |m| compare(
-        cumulative,
+        comparison,
         m,
         &values,
         params,
@@ -267,7 +430,7 @@ pub fn handle_with_model(
 }
 
 pub fn compare<F, O>(
-    cumulative: bool,
+    comparison: &CompareParams,
     tract: &Graph<F, O>,
     all_values: &HashMap<String, Vec<CliResult<Arc<Tensor>>>>,
     params: &Parameters,
@@ -290,6 +453,7 @@ where
     let mut failing = std::collections::HashSet::new();
     let mut unchecked = std::collections::HashSet::new();
     let mut ok = 0;
+    let mut diffs = vec![];
     fn canonic(s: &str) -> String {
         s.replace('.', "_").replace('-', "_")
     }
@@ -359,9 +523,17 @@ where
                                     error = Some("Output number mismatch".to_string());
                                 } else {
                                     for ix in 0..node.outputs.len() {
-                                        if let Err(e) =
-                                            obtained[ix].close_enough(&reference[ix], true)
-                                        {
+                                        let check = obtained[ix]
+                                            .close_enough(&reference[ix], comparison.tolerance);
+                                        let diff = OutletDiff::new(
+                                            turn,
+                                            (node.id, &node.name),
+                                            ix,
+                                            check.is_ok(),
+                                            &obtained[ix],
+                                            &reference[ix],
+                                        );
+                                        if let Err(e) = check {
                                             error = Some("Mismatch value".to_string());
                                             let mut msg = vec![Red
                                                 .bold()
@@ -370,6 +542,7 @@ where
                                                     turn, ix, e
                                                 ))
                                                 .to_string()];
+                                            msg.push(diff.summary());
                                             msg.push(format!("got     : {:?}", obtained[ix]));
                                             msg.push(format!("ref     : {:?}", reference[ix]));
                                             tags.sections.push(msg);
@@ -380,6 +553,7 @@ where
                                                 OutletId::new(node.id, ix)
                                             )
                                         }
+                                        diffs.push(diff);
                                     }
                                 }
                             } else {
@@ -395,7 +569,8 @@ where
                 } else {
                     ok += 1;
                 }
-                let result = if cumulative { tested.or(reference) } else { reference.or(tested) };
+                let result =
+                    if comparison.cumulative { tested.or(reference) } else { reference.or(tested) };
                 result.with_context(|| {
                     format!("Failure to compute and no reference value for {}", node)
                 })
@@ -414,6 +589,10 @@ where
         annotations.node_mut(node.id.into()).style = Some(color);
     }
 
+    if let Some(report) = &comparison.report {
+        write_report(report, &diffs)?;
+    }
+
     if log_enabled!(Info) {
         terminal::render(tract, &annotations, output_params)?;
     } else {
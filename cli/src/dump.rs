@@ -104,6 +104,17 @@ pub fn handle(
         }
     }
 
+    #[cfg(feature = "onnx")]
+    if let Some(path) = sub_matches.value_of("onnx") {
+        if let Some(mut typed) = model.downcast_ref::<TypedModel>().cloned() {
+            rename_outputs(&mut typed, sub_matches)?;
+            let file = std::fs::File::create(path)?;
+            tract_onnx::onnx().write(&typed, file).context("Writing model to ONNX")?;
+        } else {
+            bail!("Only typed model can be dumped")
+        }
+    }
+
     if options.cost {
         let total = annotations.tags.values().sum::<NodeTags>();
         let assert =
@@ -122,6 +122,7 @@ fn main() -> tract_core::anyhow::Result<()> {
         .arg(arg!(--"extract-decluttered-sub" [SUB] "Zoom on a subgraph after decluttering by parent node name"))
 
         .arg(arg!(--"half-floats" "Convert the decluttered network from f32 to f16"))
+        .arg(arg!(--"weight-quant" [FORMAT] "Store matmul and convolution weights as i8 or i4 with per-group scales (i8, i4, i4/g64...)"))
         .arg(arg!(--set [set] ... "Set a symbol to a concrete value after decluttering"))
 
         // deprecated
@@ -134,6 +135,8 @@ fn main() -> tract_core::anyhow::Result<()> {
         .arg(arg!(--"nnef-tract-pulse" "Allow usage of tract-pulse extension in NNEF dump and load"))
 
         .arg(arg!(-O --optimize "Optimize before running"))
+        .arg(arg!(--"mmm-autotune" [MODE] "Pick f32 matmul kernels from a benchmark profile (off, cached, tune)"))
+        .arg(arg!(--"mmm-profile" [PATH] "Location of the matmul kernel profile"))
         .arg(arg!(--pulse [PULSE] "Translate to pulse network"))
 
         .arg(arg!(--"machine-friendly" "Machine friendly output"))
@@ -174,6 +177,19 @@ fn main() -> tract_core::anyhow::Result<()> {
                 .long("resilient")
                 .takes_value(false)
                 .help("Try nodes one per one to mitigate crashes"),
+        )
+        .arg(
+            Arg::new("tolerance")
+                .long("tolerance")
+                .takes_value(true)
+                .possible_values(["exact", "close", "approximate"])
+                .help("Tolerance used to accept a value (default: approximate)"),
+        )
+        .arg(
+            Arg::new("report")
+                .long("report")
+                .takes_value(true)
+                .help("Write per-outlet numerical differences to a .json or .csv file"),
         );
     let compare = run_options(compare);
     app = app.subcommand(output_options(compare));
@@ -311,6 +327,12 @@ fn dump_subcommand<'a>() -> clap::Command<'a> {
             .long("nnef-graph")
             .help("Dump the network definition (without the weights) as a graph.nnef-like file"),
             )
+        .arg(
+            Arg::new("onnx")
+            .takes_value(true)
+            .long("onnx")
+            .help("Dump the network in ONNX format (decluttered typed models only)"),
+            )
         .arg(
             Arg::new("inner")
             .takes_value(true)
@@ -455,6 +477,13 @@ fn handle(matches: clap::ArgMatches, probe: Option<&Probe>) -> CliResult<()> {
         return Ok(());
     }
 
+    if let Some(path) = matches.value_of("mmm-profile") {
+        tract_core::tract_linalg::mmm::autotune::set_profile_path(Some(path.into()));
+    }
+    if let Some(mode) = matches.value_of("mmm-autotune") {
+        tract_core::tract_linalg::mmm::autotune::set_mode(mode.parse()?);
+    }
+
     let builder_result = Parameters::from_clap(&matches, probe);
     #[allow(unused_mut)]
     let mut params = match builder_result {
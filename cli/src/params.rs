@@ -698,6 +698,13 @@ impl Parameters {
                 tract_core::half::HalfTranslator.translate_model(&m)
             });
         }
+        if let Some(quant) = matches.value_of("weight-quant") {
+            let quant: tract_core::weight_quant::WeightQuant = quant.parse()?;
+            stage!("weight-quant", typed_model -> typed_model, |m:TypedModel| {
+                use tract_core::model::translator::Translate;
+                tract_core::weight_quant::WeightQuantTranslator(quant).translate_model(&m)
+            });
+        }
         if let Some(set) = matches.values_of("set") {
             let mut values = SymbolValues::default();
             for set in set {
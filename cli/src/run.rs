@@ -212,7 +212,7 @@ fn run_pulse_t(model: &PulsedModel, params: &Parameters) -> CliResult<TVec<Arc<T
     let input_fact = model.input_fact(0)?;
     let output_fact = model.output_fact(0)?;
 
-    let output_pulse = output_fact.pulse();
+    let output_pulse = output_fact.fixed_pulse()?;
     //    println!("output_fact: {:?}", output_fact);
     let axis = input_fact.axis;
     let name = model.node_name(model.input_outlets()?[0].node);
@@ -226,12 +226,12 @@ fn run_pulse_t(model: &PulsedModel, params: &Parameters) -> CliResult<TVec<Arc<T
         .to_usize()?;
     let mut output_shape = output_fact.shape.to_vec();
     output_shape[output_fact.axis] =
-        (output_dim as usize + output_fact.delay + 4 * output_fact.pulse()).to_dim();
+        (output_dim as usize + output_fact.delay + 4 * output_pulse).to_dim();
     let output_shape: TVec<usize> = output_shape.iter().map(|d| d.to_usize().unwrap()).collect();
     let plan = SimplePlan::new(model)?;
     let mut state = ::tract_core::plan::SimpleState::new(&plan)?;
     //    println!("output_shape: {:?}", output_shape);
-    let pulse = input_fact.pulse();
+    let pulse = input_fact.fixed_pulse()?;
     let mut result = tract_ndarray::ArrayD::<f32>::default(&*output_shape);
     let input = input.to_array_view::<f32>()?;
     for ix in 0..input_dim.divceil(pulse) {
@@ -26,7 +26,7 @@ pub fn handle(params: &Parameters, options: &DisplayParams) -> CliResult<()> {
 
     let decl_input_fact = decl.input_fact(0)?;
     let pulsed_input_fact = pulsed.input_fact(0)?;
-    let input_pulse = pulsed_input_fact.pulse();
+    let input_pulse = pulsed_input_fact.fixed_pulse()?;
 
     let annotations = crate::annotations::Annotations::from_model(&*params.tract_model)?
         .with_graph_def(&*params.tract_model, &params.graph)?;
@@ -49,7 +49,7 @@ pub fn handle(params: &Parameters, options: &DisplayParams) -> CliResult<()> {
             let outlet = OutletId::new(node, output_slot);
 
             let pulsed_output_fact = pulsed.outlet_fact(pulsed_outlet)?;
-            let output_pulse = pulsed_output_fact.pulse();
+            let output_pulse = pulsed_output_fact.fixed_pulse()?;
             let output_axis = pulsed_output_fact.axis;
             let delay = pulsed_output_fact.delay;
 
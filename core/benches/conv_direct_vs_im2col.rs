@@ -53,6 +53,7 @@ impl Problem {
             group: 1,
             bias: None,
             q_params: None,
+            weight_quant: None,
         };
 
         let mut model = TypedModel::default();
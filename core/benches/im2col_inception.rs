@@ -40,6 +40,7 @@ fn b(
         group: 1,
         bias: None,
         q_params: None,
+        weight_quant: None,
     };
 
     let mut m = TypedModel::default();
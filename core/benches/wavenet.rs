@@ -73,7 +73,7 @@ fn mmm(c: &mut Criterion) {
                 let op = LirMatMulUnary {
                     c_fact: f32::fact([8, 64]),
                     micro_ops: tract_ndarray::arr0((
-                        packed_a.into_arc_tensor(),
+                        PackedA::Packed(packed_a.into_arc_tensor()),
                         vec![ProtoFusedSpec::Store],
                     ))
                     .into_dyn(),
@@ -80,6 +80,7 @@ mod late_bind;
 pub mod model;
 pub mod optim;
 pub mod plan;
+pub mod weight_quant;
 
 pub use dyn_clone;
 
@@ -167,6 +167,59 @@ impl AsRef<[TDim]> for ShapeFact {
     }
 }
 
+/// Facts about the tensors held by a `Sequence` or `Optional` value.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[allow(clippy::large_enum_variant)]
+pub enum SequenceFact {
+    /// The number of elements is known, and each of them has its own fact.
+    Known(Vec<TypedFact>),
+    /// Unknown number of elements (at most one for an optional), all matching the fact.
+    Dynamic(TypedFact),
+}
+
+impl SequenceFact {
+    pub fn known_len(&self) -> Option<usize> {
+        match self {
+            SequenceFact::Known(facts) => Some(facts.len()),
+            SequenceFact::Dynamic(_) => None,
+        }
+    }
+
+    /// Fact matching every element, if there is one.
+    pub fn common(&self) -> Option<TypedFact> {
+        match self {
+            SequenceFact::Known(facts) => {
+                let first = facts.first()?.without_value();
+                if facts.iter().all(|f| f.without_value() == first) {
+                    Some(first)
+                } else {
+                    None
+                }
+            }
+            SequenceFact::Dynamic(fact) => Some(fact.clone()),
+        }
+    }
+
+    /// Fact of the element at `index`, or of any element if the index is unknown.
+    pub fn element(&self, index: Option<i64>) -> TractResult<TypedFact> {
+        match (self, index) {
+            (SequenceFact::Known(facts), Some(index)) => {
+                let len = facts.len() as i64;
+                ensure!(
+                    -len <= index && index < len,
+                    "Index {} out of bounds for a sequence of {} elements",
+                    index,
+                    len
+                );
+                Ok(facts[if index < 0 { index + len } else { index } as usize].clone())
+            }
+            _ => self
+                .common()
+                .with_context(|| format!("Can not determine which of {:?} is accessed", self)),
+        }
+    }
+}
+
 /// Fully determined tensor information for TypedModel.
 #[derive(Clone, PartialEq, Eq, Hash)]
 pub struct TypedFact {
@@ -178,6 +231,8 @@ pub struct TypedFact {
     pub konst: Option<Arc<Tensor>>,
     /// optional uniform value
     pub uniform: Option<Arc<Tensor>>,
+    /// facts about the elements of sequence and optional values
+    pub sequence: Option<Arc<SequenceFact>>,
 }
 
 impl_dyn_hash!(TypedFact);
@@ -201,14 +256,34 @@ impl TypedFact {
 
     pub fn dt_scalar(datum_type: DatumType) -> TypedFact {
         let void: &[usize] = &[];
-        TypedFact { datum_type, shape: ShapeFact::from(void), konst: None, uniform: None }
+        TypedFact {
+            datum_type,
+            shape: ShapeFact::from(void),
+            konst: None,
+            uniform: None,
+            sequence: None,
+        }
     }
 
     pub fn dt_shape<S>(datum_type: DatumType, shape: S) -> TypedFact
     where
         S: Into<ShapeFact>,
     {
-        TypedFact { datum_type, shape: shape.into(), konst: None, uniform: None }
+        TypedFact { datum_type, shape: shape.into(), konst: None, uniform: None, sequence: None }
+    }
+
+    /// A scalar `Sequence` value holding the described tensors.
+    pub fn sequence_of(elements: SequenceFact) -> TypedFact {
+        let mut fact = Self::dt_scalar(DatumType::Sequence);
+        fact.sequence = Some(Arc::new(elements));
+        fact
+    }
+
+    /// A scalar `Optional` value holding at most one of the described tensors.
+    pub fn optional_of(elements: SequenceFact) -> TypedFact {
+        let mut fact = Self::dt_scalar(DatumType::Optional);
+        fact.sequence = Some(Arc::new(elements));
+        fact
     }
 
     pub fn rank(&self) -> usize {
@@ -257,7 +332,9 @@ impl TypedFact {
     }
 
     pub fn without_value(&self) -> Self {
-        Self::dt_shape(self.datum_type, self.shape.clone())
+        let mut fact = Self::dt_shape(self.datum_type, self.shape.clone());
+        fact.sequence = self.sequence.clone();
+        fact
     }
 }
 
@@ -332,11 +409,24 @@ impl<'t> From<&'t Tensor> for TypedFact {
 
 impl From<Arc<Tensor>> for TypedFact {
     fn from(t: Arc<Tensor>) -> TypedFact {
+        let elements: Option<Vec<Arc<Tensor>>> = match t.datum_type() {
+            DatumType::Sequence if t.rank() == 0 => {
+                t.to_scalar::<TensorSequence>().ok().map(|s| s.0.clone())
+            }
+            DatumType::Optional if t.rank() == 0 => {
+                t.to_scalar::<TensorOption>().ok().map(|o| o.0.iter().cloned().collect())
+            }
+            _ => None,
+        };
+        let sequence = elements.map(|elements| {
+            Arc::new(SequenceFact::Known(elements.into_iter().map(TypedFact::from).collect()))
+        });
         TypedFact {
             datum_type: t.datum_type(),
             shape: ShapeFact::from_dims(t.shape().iter().map(TDim::from)),
             uniform: t.as_uniform().map(Arc::new),
             konst: Some(t),
+            sequence,
         }
     }
 }
@@ -349,10 +439,17 @@ impl<'a> From<&'a TypedFact> for TypedFact {
 
 impl fmt::Debug for TypedFact {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        match self.konst {
-            Some(ref k) => write!(fmt, "{:?}", k),
-            None if self.rank() > 0 => write!(fmt, "{:?},{:?}", self.shape, self.datum_type),
-            None => write!(fmt, "{:?}", self.datum_type),
+        use tract_itertools::Itertools;
+        match (&self.konst, self.sequence.as_deref()) {
+            (Some(ref k), _) => write!(fmt, "{:?}", k),
+            (None, Some(SequenceFact::Known(facts))) if self.rank() == 0 => {
+                write!(fmt, "{:?}[{:?}]", self.datum_type, facts.iter().format(", "))
+            }
+            (None, Some(SequenceFact::Dynamic(fact))) if self.rank() == 0 => {
+                write!(fmt, "{:?}[{:?}...]", self.datum_type, fact)
+            }
+            (None, _) if self.rank() > 0 => write!(fmt, "{:?},{:?}", self.shape, self.datum_type),
+            (None, _) => write!(fmt, "{:?}", self.datum_type),
         }
     }
 }
@@ -41,6 +41,7 @@ mod graph;
 mod node;
 pub mod order;
 mod patch;
+mod surgery;
 pub mod translator;
 pub mod typed;
 
@@ -0,0 +1,288 @@
+//! High level model editing: subgraph extraction, grafting and node removal.
+//!
+//! Operations removing nodes compact the model, so node ids are not stable
+//! across them. Names and outlet labels are.
+use crate::internal::*;
+use crate::ops::source::TypedSource;
+
+impl TypedModel {
+    /// Extract the part of the model computing `outputs` from `inputs`.
+    ///
+    /// Each input outlet becomes a source of the new model, named after the node it cuts
+    /// (or "name.slot" for a secondary output). The subgraph must not depend on model inputs
+    /// that are not in `inputs`.
+    pub fn extract_subgraph(
+        &self,
+        inputs: &[OutletId],
+        outputs: &[OutletId],
+    ) -> TractResult<TypedModel> {
+        let mut model = self.clone();
+        let mut sources = tvec!();
+        let mut patch = TypedModelPatch::new("extract subgraph");
+        for &input in inputs {
+            let fact = model.outlet_fact(input)?.without_value();
+            let name = cut_name(&model, input);
+            let source = model.add_source(format!("{}.input", name), fact)?;
+            let tap = patch.tap_model(&model, source)?;
+            patch.shunt_outside(&model, input, tap)?;
+            sources.push((source, name));
+        }
+        let outputs = outputs
+            .iter()
+            .map(|o| inputs.iter().position(|i| i == o).map(|ix| sources[ix].0).unwrap_or(*o))
+            .collect::<TVec<_>>();
+        patch.apply(&mut model)?;
+        model.set_input_outlets(&sources.iter().map(|s| s.0).collect::<TVec<_>>())?;
+        model.set_output_outlets(&outputs)?;
+        model.compact()?;
+        for node in model.nodes() {
+            if node.op_is::<TypedSource>() && !model.inputs.contains(&OutletId::new(node.id, 0)) {
+                bail!("Subgraph depends on {}, which is not one of the cut inputs", node);
+            }
+        }
+        for (ix, (_, name)) in sources.iter().enumerate() {
+            if model.node_by_name(name).is_err() {
+                let id = model.inputs[ix].node;
+                model.rename_node(id, name)?;
+            }
+        }
+        model.check_consistency()?;
+        Ok(model)
+    }
+
+    /// Copy all nodes of `other` in this model, feeding its inputs from `inputs`.
+    ///
+    /// Node names and outlet labels from `other` are prefixed by `prefix`. Returns the outlets
+    /// matching the outputs of `other`.
+    pub fn graft(
+        &mut self,
+        other: &TypedModel,
+        prefix: &str,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        ensure!(
+            other.inputs.len() == inputs.len(),
+            "Grafted model has {} inputs, got {} outlets",
+            other.inputs.len(),
+            inputs.len()
+        );
+        let mut mapping = HashMap::<OutletId, OutletId>::new();
+        for (theirs, ours) in other.inputs.iter().zip(inputs.iter()) {
+            let (expected, got) = (other.outlet_fact(*theirs)?, self.outlet_fact(*ours)?);
+            ensure!(
+                expected.datum_type == got.datum_type && expected.shape == got.shape,
+                "Grafted model input {} expects {:?}, got {:?}",
+                other.node(theirs.node).name,
+                expected,
+                got
+            );
+            mapping.insert(*theirs, *ours);
+        }
+        for id in other.eval_order()? {
+            let node = other.node(id);
+            if (0..node.outputs.len()).all(|slot| mapping.contains_key(&OutletId::new(id, slot))) {
+                continue;
+            }
+            if node.op_is::<TypedSource>() {
+                bail!("Grafted model source {} is not one of its inputs", node);
+            }
+            let name = format!("{}.{}", prefix, node.name);
+            ensure!(self.node_by_name(&name).is_err(), "Model already has a node named {}", name);
+            let inputs = node.inputs.iter().map(|i| mapping[i]).collect::<TVec<_>>();
+            let wires = self.wire_node(name, node.op.clone(), &inputs)?;
+            for (slot, wire) in wires.into_iter().enumerate() {
+                if let Some(label) = other.outlet_label(OutletId::new(id, slot)) {
+                    self.set_outlet_label(wire, format!("{}.{}", prefix, label))?;
+                }
+                mapping.insert(OutletId::new(id, slot), wire);
+            }
+        }
+        let outputs = other.outputs.iter().map(|o| mapping[o]).collect();
+        self.check_consistency()?;
+        Ok(outputs)
+    }
+
+    /// Insert `other`, a model with one input and one output, on the wire from `outlet`.
+    ///
+    /// All consumers of `outlet` (including model outputs) are rewired to the output of
+    /// `other`, which also inherits the outlet label. Returns this new outlet.
+    pub fn splice(
+        &mut self,
+        outlet: OutletId,
+        other: &TypedModel,
+        prefix: &str,
+    ) -> TractResult<OutletId> {
+        ensure!(
+            other.inputs.len() == 1 && other.outputs.len() == 1,
+            "Spliced model must have exactly one input and one output"
+        );
+        let (before, after) = (self.outlet_fact(outlet)?, other.output_fact(0)?);
+        ensure!(
+            before.datum_type == after.datum_type && before.shape == after.shape,
+            "Spliced model output {:?} does not match {:?}",
+            after,
+            before
+        );
+        let successors = self.outlet_successors(outlet).to_vec();
+        let spliced = self.graft(other, prefix, &[outlet])?[0];
+        for succ in successors {
+            self.add_edge(spliced, succ)?;
+        }
+        for o in self.outputs.iter_mut() {
+            if *o == outlet {
+                *o = spliced;
+            }
+        }
+        if let Some(label) = self.outlet_labels.remove(&outlet) {
+            self.set_outlet_label(spliced, label)?;
+        }
+        self.check_consistency()?;
+        Ok(spliced)
+    }
+
+    /// Replace the operator of a node, keeping its inputs and name.
+    pub fn replace_node_op(
+        &mut self,
+        node: usize,
+        op: impl Into<Box<dyn TypedOp>>,
+    ) -> TractResult<()> {
+        let node = self.node(node);
+        let patch = TypedModelPatch::replace_single_op(self, node, &node.inputs, op)?
+            .with_context(format!("replacing op of {}", node));
+        patch.apply(self)?;
+        self.compact()?;
+        self.check_consistency()
+    }
+
+    /// Remove a node, connecting the consumers of its single output to its first input.
+    pub fn bypass_node(&mut self, node: usize) -> TractResult<()> {
+        let node = self.node(node);
+        ensure!(
+            !node.inputs.is_empty() && node.outputs.len() == 1,
+            "Can only bypass a node with inputs and a single output, {} has {} and {}",
+            node,
+            node.inputs.len(),
+            node.outputs.len()
+        );
+        let (input, output) = (self.outlet_fact(node.inputs[0])?, &node.outputs[0].fact);
+        ensure!(
+            input.datum_type == output.datum_type && input.shape == output.shape,
+            "Can not bypass {}: it turns {:?} into {:?}",
+            node,
+            input,
+            output
+        );
+        let patch = TypedModelPatch::shunt_one_op(self, node)?;
+        patch.apply(self)?;
+        self.compact()?;
+        self.check_consistency()
+    }
+
+    /// Remove a node whose outputs are not consumed by other nodes.
+    ///
+    /// Its outputs are dropped from the model outputs (and it is dropped from the inputs if it
+    /// is a source). Nodes that become useless are removed too.
+    pub fn remove_node(&mut self, node: usize) -> TractResult<()> {
+        let node = self.node(node);
+        if let Some(succ) = node.outputs.iter().flat_map(|o| o.successors.iter()).next() {
+            bail!("Can not remove {}, it is used by {}", node, self.node(succ.node));
+        }
+        let id = node.id;
+        self.outputs.retain(|o| o.node != id);
+        self.inputs.retain(|i| i.node != id);
+        self.compact()?;
+        self.check_consistency()
+    }
+}
+
+fn cut_name(model: &TypedModel, outlet: OutletId) -> String {
+    let name = &model.node(outlet.node).name;
+    if outlet.slot == 0 {
+        name.to_string()
+    } else {
+        format!("{}.{}", name, outlet.slot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::{math, nn};
+
+    // x -> a (+1) -> b (*2) -> c (sigmoid)
+    fn chain() -> TractResult<TypedModel> {
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", f32::fact([3]))?;
+        let a = model.wire_node("a", math::add::unary(rctensor1(&[1f32])), &[x])?;
+        let b = model.wire_node("b", math::mul::unary(rctensor1(&[2f32])), &a)?;
+        let c = model.wire_node("c", nn::sigmoid(), &b)?;
+        model.set_outlet_label(b[0], "b_label".to_string())?;
+        model.set_output_outlets(&c)?;
+        Ok(model)
+    }
+
+    fn run(model: &TypedModel, input: Tensor) -> TractResult<Arc<Tensor>> {
+        Ok(model.clone().into_runnable()?.run(tvec!(input))?.remove(0))
+    }
+
+    #[test]
+    fn extract_middle() -> TractResult<()> {
+        let model = chain()?;
+        let a = OutletId::new(model.node_id_by_name("a")?, 0);
+        let b = OutletId::new(model.node_id_by_name("b")?, 0);
+        let sub = model.extract_subgraph(&[a], &[b])?;
+        assert_eq!(sub.nodes().len(), 2);
+        assert_eq!(sub.node(sub.inputs[0].node).name, "a");
+        assert_eq!(sub.outlet_label(sub.outputs[0]), Some("b_label"));
+        assert_eq!(*run(&sub, tensor1(&[1f32, 2., 3.]))?, tensor1(&[2f32, 4., 6.]));
+        Ok(())
+    }
+
+    #[test]
+    fn extract_needs_all_inputs() -> TractResult<()> {
+        let model = chain()?;
+        let b = OutletId::new(model.node_id_by_name("b")?, 0);
+        assert!(model.extract_subgraph(&[], &[b]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn splice_and_graft() -> TractResult<()> {
+        let mut model = chain()?;
+        let mut neg = TypedModel::default();
+        let s = neg.add_source("s", f32::fact([3]))?;
+        let n = neg.wire_node("neg", math::neg(), &[s])?;
+        neg.set_output_outlets(&n)?;
+
+        let b = OutletId::new(model.node_id_by_name("b")?, 0);
+        let spliced = model.splice(b, &neg, "inserted")?;
+        assert_eq!(model.node(spliced.node).name, "inserted.neg");
+        assert_eq!(model.outlet_label(spliced), Some("b_label"));
+        let found = run(&model, tensor1(&[0f32, 0., 0.]))?;
+        found.close_enough(&tensor1(&[0.11920292f32; 3]), Approximation::Close)?;
+
+        let x = model.inputs[0];
+        let grafted = model.graft(&neg, "side", &[x])?;
+        model.outputs.push(grafted[0]);
+        let outputs = model.into_runnable()?.run(tvec!(tensor1(&[1f32, 2., 3.])))?;
+        assert_eq!(*outputs[1], tensor1(&[-1f32, -2., -3.]));
+        Ok(())
+    }
+
+    #[test]
+    fn bypass_replace_remove() -> TractResult<()> {
+        let mut model = chain()?;
+        model.bypass_node(model.node_id_by_name("b")?)?;
+        assert!(model.node_by_name("b").is_err());
+        assert_eq!(*run(&model, tensor1(&[-1f32, -1., -1.]))?, tensor1(&[0.5f32; 3]));
+
+        model.replace_node_op(model.node_id_by_name("c")?, math::neg())?;
+        assert_eq!(*run(&model, tensor1(&[1f32, 2., 3.]))?, tensor1(&[-2f32, -3., -4.]));
+
+        assert!(model.remove_node(model.node_id_by_name("a")?).is_err());
+        model.remove_node(model.node_id_by_name("c")?)?;
+        assert_eq!(model.outputs.len(), 0);
+        assert_eq!(model.nodes().len(), 1);
+        Ok(())
+    }
+}
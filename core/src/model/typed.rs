@@ -173,6 +173,15 @@ impl TypedModel {
         values.translate_model(self)
     }
 
+    /// Value of a small integer or TDim outlet, evaluated symbolically through shape
+    /// computations (Cast, Gather, Concat, Slice, AxisOp, arithmetic) from constants.
+    ///
+    /// The result is a TDim tensor, where symbols may appear. None if the outlet is not a
+    /// shape-like computation.
+    pub fn symbolic_value(&self, outlet: OutletId) -> TractResult<Option<Arc<Tensor>>> {
+        crate::optim::symbolic_value(self, outlet, &mut HashMap::default())
+    }
+
     /// Translate the graph to locally optimized operators (LIR or MIR ops).
     pub fn optimize(&mut self) -> TractResult<()> {
         crate::optim::Optimizer::codegen().optimize(self)
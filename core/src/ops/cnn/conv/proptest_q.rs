@@ -2,6 +2,7 @@ use super::proptest::*;
 use crate::ops::cnn::KernelFormat::*;
 use crate::ops::cnn::*;
 use crate::ops::math::round_ties_to_even;
+use crate::ops::matmul::mir_quant::QParamKind;
 use crate::ops::matmul::*;
 use crate::ops::nn::DataFormat::*;
 use crate::ops::nn::*;
@@ -57,12 +58,22 @@ impl QConvProblem {
         let n = *self.shape_in.n().unwrap_or(&1);
         let ci_per_g = self.shape_in.c() / self.group;
         let co_per_g = self.co / self.group;
-        let a0 = self.qp.a0.as_static().unwrap().cast_to_scalar::<i32>().unwrap();
+        let per_channel = |qp: &QParamKind| -> Vec<f32> {
+            let t = qp.as_static().unwrap().cast_to::<f32>().unwrap().into_owned();
+            let t = t.into_array::<f32>().unwrap();
+            t.broadcast(self.co).unwrap().to_vec()
+        };
+        let a0 = per_channel(&self.qp.a0);
         let b0 = self.qp.b0.as_static().unwrap().cast_to_scalar::<i32>().unwrap();
         let c0 = self.qp.c0.as_static().unwrap().cast_to_scalar::<i32>().unwrap();
-        let scale = self.qp.c_scale.as_static().unwrap().cast_to_scalar::<f32>().unwrap()
-            / self.qp.a_scale.as_static().unwrap().cast_to_scalar::<f32>().unwrap()
-            / self.qp.b_scale.as_static().unwrap().cast_to_scalar::<f32>().unwrap();
+        let scale: Vec<f32> = per_channel(&self.qp.a_scale)
+            .iter()
+            .map(|a_scale| {
+                self.qp.c_scale.as_static().unwrap().cast_to_scalar::<f32>().unwrap()
+                    / a_scale
+                    / self.qp.b_scale.as_static().unwrap().cast_to_scalar::<f32>().unwrap()
+            })
+            .collect();
         let shape_out: TVec<usize> = izip!(self.shape_in.hw_dims(), self.geo_ker())
             .map(|(i, k)| (*i + 1).saturating_sub(*k))
             .collect();
@@ -104,7 +115,8 @@ impl QConvProblem {
                                     }
                                 }
                                 let k = self.kernel[&*kernel_coords] as i32;
-                                temp[&*output_coords] += (k - a0) * (i - b0);
+                                temp[&*output_coords] +=
+                                    (k - a0[co + g * co_per_g] as i32) * (i - b0);
                             }
                         }
                     }
@@ -116,11 +128,16 @@ impl QConvProblem {
             shape[shape_out.c_axis()] = bias.len();
             temp += &bias.clone().into_shape(shape).unwrap();
         }
-        temp.mapv(|i| {
-            (round_ties_to_even(i as f32 / scale as f32) as i32 + c0)
-                .max(std::i8::MIN as i32)
-                .min(std::i8::MAX as i32) as i8
-        })
+        let c_axis = shape_out.c_axis();
+        temp.indexed_iter()
+            .map(|(coords, &i)| {
+                (round_ties_to_even(i as f32 / scale[coords[c_axis]]) as i32 + c0)
+                    .max(std::i8::MIN as i32)
+                    .min(std::i8::MAX as i32) as i8
+            })
+            .collect::<Array1<i8>>()
+            .into_shape(temp.shape())
+            .unwrap()
     }
 
     fn tract(&self) -> anyhow::Result<ArrayD<i8>> {
@@ -529,3 +546,53 @@ fn bias_3() {
     .check()
     .unwrap();
 }
+
+#[test]
+fn per_channel_0() {
+    let mut qp = MatMulQParams::noop_static(i8::datum_type());
+    qp.a0 = tensor1(&[0i8, 3]).into();
+    qp.a_scale = tensor1(&[1f32, 0.5]).into();
+    for optim in [false, true] {
+        QConvProblem {
+            shape_in: CHW.from_n_c_hw(1, 2, &[3]).unwrap(),
+            co: 2,
+            kernel_format: OIHW,
+            group: 1,
+            data: arr2(&[[1, -2, 3], [4, 5, -6]]).into_dyn(),
+            kernel: arr3(&[[[1, 2], [3, 4]], [[5, -6], [7, 8]]]).into_dyn(),
+            bias: Some(arr1(&[1, -1]).into_dyn()),
+            qp: qp.clone(),
+            optim,
+        }
+        .check()
+        .unwrap();
+    }
+}
+
+#[test]
+fn per_channel_group() {
+    let mut qp = MatMulQParams::noop_static(i8::datum_type());
+    qp.a0 = tensor1(&[0i8, 1, -2, 3]).into();
+    qp.a_scale = tensor1(&[1f32, 0.5, 2., 0.25]).into();
+    qp.c_scale = tensor0(2f32).into();
+    for (fmt, data) in [
+        (HWC, arr2(&[[1, -2], [3, 4], [5, -6]]).into_dyn()),
+        (CHW, arr2(&[[1, 3, 5], [-2, 4, -6]]).into_dyn()),
+    ] {
+        for optim in [false, true] {
+            QConvProblem {
+                shape_in: fmt.from_n_c_hw(1, 2, &[3]).unwrap(),
+                co: 4,
+                kernel_format: OIHW,
+                group: 2,
+                data: data.clone(),
+                kernel: arr3(&[[[1, 2]], [[3, 4]], [[5, -6]], [[7, 8]]]).into_dyn(),
+                bias: None,
+                qp: qp.clone(),
+                optim,
+            }
+            .check()
+            .unwrap();
+        }
+    }
+}
@@ -11,11 +11,13 @@ use super::im2col::Im2Col;
 use crate::ops::cnn::conv::KernelFormat;
 use crate::ops::cnn::pools::{ConcretePoolGeometry, PoolGeometry, PoolSpec};
 use crate::ops::matmul::lir_unary::{
-    ConcreteMatMulGeometry, LirMatMulUnary, MatMulGeometry, ProtoFusedSpec, SymbolicMatMulGeometry,
+    ConcreteMatMulGeometry, LirMatMulUnary, MatMulGeometry, PackedA, ProtoFusedSpec,
+    SymbolicMatMulGeometry,
 };
 use crate::ops::matmul::MatMulQParams;
 use crate::ops::nn::{BaseDataShape, DataFormat, DataShape};
 
+use tract_linalg::frame::weight_quant::{QuantizedWeights, WeightQuant};
 use tract_linalg::frame::Packer;
 use tract_linalg::mmm::MatMatMul;
 
@@ -32,6 +34,9 @@ pub struct ConvUnary {
     pub bias: Option<Arc<Tensor>>,
 
     pub q_params: Option<(DatumType, MatMulQParams)>,
+
+    #[new(default)]
+    pub weight_quant: Option<WeightQuant>,
 }
 
 impl_dyn_hash!(ConvUnary);
@@ -66,12 +71,22 @@ impl ConvUnary {
         packer: &Packer,
         k: usize,
         m: usize,
-    ) -> TractResult<ArrayD<Arc<Tensor>>> {
+    ) -> TractResult<ArrayD<PackedA>> {
         let kernel = self.kernel_as_group_o_ihw()?;
         unsafe {
             let mut packed_as = Array1::from(
                 (0..self.group)
                     .map(|g| {
+                        if let Some(quant) = self.weight_quant {
+                            let weights = kernel.view_at_prefix(&[g])?;
+                            let weights = QuantizedWeights::quantize(
+                                quant,
+                                m,
+                                k,
+                                weights.as_slice::<f32>()?,
+                            )?;
+                            return Ok(PackedA::QuantizedWeights(Arc::new(weights)));
+                        }
                         let packed = Tensor::uninitialized_aligned_dt(
                             kernel.datum_type(),
                             &[packer.len(k, m)],
@@ -83,7 +98,7 @@ impl ConvUnary {
                             1,
                             0,
                         );
-                        Ok(packed.into_arc_tensor())
+                        Ok(PackedA::Packed(packed.into_arc_tensor()))
                     })
                     .collect::<TractResult<Vec<_>>>()?,
             )
@@ -203,8 +218,8 @@ impl ConvUnary {
             c_dt,
         )?;
 
-        let a0 = params[0];
-        let a_scale = params[1];
+        let mut a0 = params[0];
+        let mut a_scale = params[1];
         let mut b0 = params[2];
         let b_scale = params[3];
         let c0 = params[4];
@@ -215,8 +230,6 @@ impl ConvUnary {
         let (_, m, k, n, mmm) = self.compute_geo(&b_fact)?;
         let output_shape = self.pool_spec.output_shape(&b_fact.shape)?;
 
-        let abc_scale = qmm::combine_scales(model, name, a_scale, b_scale, c_scale)?;
-
         let im2col = model.wire_node(
             format!("{}.im2col", name),
             Im2Col::new(self.pool_spec.clone(), self.group, k, &b_fact.shape, mmm.clone())?,
@@ -283,6 +296,40 @@ impl ConvUnary {
         } else {
             (has_group + has_n, 1 + has_n + has_group)
         };
+        // per-channel kernel parameters are split by group, and laid along m
+        for (param, param_name) in [(&mut a0, "a0"), (&mut a_scale, "a_scale")] {
+            let len = model.outlet_fact(*param)?.shape.volume();
+            if model.outlet_fact(*param)?.rank() == 0 {
+                continue;
+            }
+            ensure!(
+                len == self.output_channels().to_dim(),
+                "Expected a per-tensor or per-output-channel {}, got {:?}",
+                param_name,
+                model.outlet_fact(*param)?
+            );
+            if self.group > 1 {
+                *param = model.wire_node(
+                    format!("{}.{}_split_group", name, param_name),
+                    AxisOp::Reshape(0, tvec!(len), tvec!(self.group.to_dim(), m.to_dim())),
+                    &[*param],
+                )?[0];
+            }
+            *param = qmm::wire_per_axis_qparam(
+                model,
+                &format!("{}.{}", name, param_name),
+                *param,
+                mmm_output_shape.len(),
+                m_axis,
+            )?;
+        }
+        for param in [b0, b_scale, c0, c_scale] {
+            ensure!(
+                model.outlet_fact(param)?.shape.volume().is_one(),
+                "Convolution only supports per-channel quantization parameters for the kernel"
+            );
+        }
+        let abc_scale = qmm::combine_scales(model, name, a_scale, b_scale, c_scale)?;
         let wire = qmm::compensate_zero_points(
             model,
             name,
@@ -429,7 +476,7 @@ impl ConvUnary {
         let input_shape = b_fact.shape.as_concrete().unwrap().to_vec();
         let mut geo = geo.to_concrete(&input_shape)?.into_owned();
         let mut input_shape: DataShape = self.pool_spec.data_format.shape(input_shape.into())?;
-        let padding = self.pool_spec.computed_padding(input_shape.hw_dims());
+        let padding = self.pool_spec.computed_padding(input_shape.hw_dims())?;
         if padding.iter().any(|axis| axis.pad_before != 0 || axis.pad_after != 0) {
             let mut pads = vec![(0, 0); b_fact.rank()];
             for (ix, ax) in padding.iter().enumerate() {
@@ -710,6 +757,9 @@ impl Op for ConvUnary {
         if let Some(b) = &self.bias {
             info.push(format!("Bias: {:?}", b))
         }
+        if let Some(quant) = &self.weight_quant {
+            info.push(format!("Weight quantization: {}", quant))
+        }
         Ok(info)
     }
 
@@ -865,7 +915,7 @@ impl TypedOp for ConvUnary {
                 .strides
                 .clone()
                 .unwrap_or_else(|| tvec!(1; kernel_spatial_shape.len())),
-        );
+        )?;
         let n_output_points: TDim =
             output_dims.iter().map(|d| d.convoluted.clone()).product::<TDim>();
         let n_output_channels = self.output_channels().to_dim();
@@ -977,6 +1027,7 @@ impl TypedOp for ConvUnary {
             group: self.group,
             bias: self.bias.clone(),
             q_params: self.q_params.clone(),
+            weight_quant: self.weight_quant,
         };
         Ok(Some(AxisChangeConsequence {
             substitute_op: Some(Box::new(new_op)),
@@ -1031,7 +1082,7 @@ impl TypedOp for ConvUnary {
                     .all(|i| self.pool_spec.stride(i) == 1 && self.pool_spec.dilation(i) == 1)
                 && self.group == 1
             {
-                use crate::ops::matmul::MatMulUnary;
+                use crate::ops::matmul::{MatMulUnary, WeightQuantMatMulUnary};
                 let mut patch = TypedModelPatch::default();
                 let mut wire = patch.tap_model(model, node.inputs[0])?;
                 let input_c_is_last = input_shape.c_axis() == input_shape.rank() - 1;
@@ -1056,16 +1107,19 @@ impl TypedOp for ConvUnary {
                     .clone()
                     .into_shape(kernel_shape)?
                     .broadcast_into_rank(operating_rank)?;
-                wire = patch.wire_node(
-                    &format!("{}.matmul", &node.name),
-                    MatMulUnary::new(
-                        kernel.into_arc_tensor(),
-                        self.kernel_fmt == KernelFormat::HWIO,
-                        input_c_is_last,
-                        input_c_is_last,
-                    ),
-                    &[wire],
-                )?[0];
+                let matmul = MatMulUnary::new(
+                    kernel.into_arc_tensor(),
+                    self.kernel_fmt == KernelFormat::HWIO,
+                    input_c_is_last,
+                    input_c_is_last,
+                );
+                let matmul: Box<dyn TypedOp> = match self.weight_quant {
+                    Some(quant) => WeightQuantMatMulUnary::from_mat_mul_unary(&matmul, quant)?
+                        .map(|op| Box::new(op) as Box<dyn TypedOp>)
+                        .unwrap_or_else(|| Box::new(matmul)),
+                    None => Box::new(matmul),
+                };
+                wire = patch.wire_node(&format!("{}.matmul", &node.name), matmul, &[wire])?[0];
                 if let Some(ref bias) = self.bias {
                     let bias_shape =
                         if input_c_is_last { [1, bias.len()] } else { [bias.len(), 1] };
@@ -1162,6 +1216,7 @@ mod test {
             group: 1,
             bias: None,
             q_params: Some((i32::datum_type(), MatMulQParams::all_dynamic(1))),
+            weight_quant: None,
         };
         let input = tvec!(
             rctensor4(&[[[[1u8, 2, 3], [4, 5, 6], [7, 8, 9]]]]),
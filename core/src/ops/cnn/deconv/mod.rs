@@ -33,6 +33,10 @@ pub fn output_shape<D: DimLike>(
     Ok(output_shape.shape)
 }
 
+/// Adjustments making a deconvolution with explicit or valid padding produce `output_geo`.
+///
+/// With SAME padding the output geometry is input * stride: use `padding_and_adjustments` to
+/// reach other output geometries.
 pub fn adjustments(
     pool_spec: &PoolSpec,
     input_geo: &[usize],
@@ -47,7 +51,19 @@ pub fn adjustments(
     let pad: TVec<usize> = match &pool_spec.padding {
         PaddingSpec::Explicit(beg, end, _) => (0..rank).map(|r| beg[r] + end[r]).collect(),
         PaddingSpec::Valid => tvec!(0; rank),
-        _ => todo!("Unsupported combination of deconvolution arguments"),
+        PaddingSpec::SameUpper | PaddingSpec::SameLower => {
+            let same_geo = tract_itertools::izip!(input_geo, pool_spec.strides().as_ref())
+                .map(|(x, s)| x * s)
+                .collect::<TVec<usize>>();
+            ensure!(
+                &*same_geo == output_geo,
+                "SAME padding deconvolution from {:?} produces {:?}, not {:?}",
+                input_geo,
+                same_geo,
+                output_geo
+            );
+            return Ok(tvec!(0; rank));
+        }
     };
     tract_itertools::izip!(
         input_geo,
@@ -58,8 +74,104 @@ pub fn adjustments(
         pad,
     )
     .map(|(x, k, y, s, d, p)| {
-        let adj = y.to_usize()? + p - s * (x.to_usize()? - 1) - (k.to_usize()? - 1) * d - 1;
-        Ok(adj)
+        let full = s * x.saturating_sub(1) + (k - 1) * d + 1;
+        (y + p)
+            .checked_sub(full)
+            .with_context(|| format!("Deconvolution from {} can not produce {}", x, y))
     })
     .collect::<TractResult<TVec<usize>>>()
 }
+
+/// Padding and adjustments making a deconvolution produce `output_geo`.
+///
+/// SAME_UPPER and SAME_LOWER are translated to explicit padding: the total padding is the
+/// excess of the full deconvolution (including `output_padding`) over the requested output,
+/// split before and after according to the SAME variant, like ONNX ConvTranspose does when
+/// `output_shape` is given. When the full deconvolution is shorter than the requested output,
+/// the difference becomes an adjustment. Valid and explicit padding are kept, and
+/// `output_padding` is ignored as the adjustments are derived from `output_geo`.
+pub fn padding_and_adjustments(
+    pool_spec: &PoolSpec,
+    input_geo: &[usize],
+    output_geo: &[usize],
+    output_padding: &[usize],
+) -> TractResult<(PaddingSpec, TVec<usize>)> {
+    let upper = match pool_spec.padding {
+        PaddingSpec::SameUpper => true,
+        PaddingSpec::SameLower => false,
+        _ => {
+            return Ok((pool_spec.padding.clone(), adjustments(pool_spec, input_geo, output_geo)?))
+        }
+    };
+    let mut before = tvec!();
+    let mut after = tvec!();
+    let mut adjustments = tvec!();
+    for (x, k, y, s, d, op) in tract_itertools::izip!(
+        input_geo,
+        &pool_spec.kernel_shape,
+        output_geo,
+        pool_spec.strides().as_ref(),
+        pool_spec.dilations().as_ref(),
+        output_padding,
+    ) {
+        let full = s * x.saturating_sub(1) + (k - 1) * d + 1 + op;
+        if let Some(total) = full.checked_sub(*y) {
+            let (b, a) =
+                if upper { (total / 2, total - total / 2) } else { (total - total / 2, total / 2) };
+            before.push(b);
+            after.push(a);
+            adjustments.push(*op);
+        } else {
+            before.push(0);
+            after.push(0);
+            adjustments.push(op + y - full);
+        }
+    }
+    Ok((PaddingSpec::Explicit(before, after, false), adjustments))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::nn::DataFormat;
+
+    fn pool_spec(padding: PaddingSpec, kernel: usize, stride: usize) -> PoolSpec {
+        PoolSpec::new(DataFormat::NCHW, tvec!(kernel), padding, None, Some(tvec!(stride)), Some(1))
+    }
+
+    #[test]
+    fn same_upper_for_output_shape() {
+        let ps = pool_spec(PaddingSpec::SameUpper, 3, 2);
+        let (padding, adj) = padding_and_adjustments(&ps, &[4], &[8], &[0]).unwrap();
+        assert_eq!(padding, PaddingSpec::Explicit(tvec!(0), tvec!(1), false));
+        assert_eq!(adj, tvec!(0));
+        let (padding, adj) = padding_and_adjustments(&ps, &[4], &[7], &[0]).unwrap();
+        assert_eq!(padding, PaddingSpec::Explicit(tvec!(1), tvec!(1), false));
+        assert_eq!(adj, tvec!(0));
+    }
+
+    #[test]
+    fn same_lower_for_output_shape_with_output_padding() {
+        let ps = pool_spec(PaddingSpec::SameLower, 3, 2);
+        let (padding, adj) = padding_and_adjustments(&ps, &[4], &[8], &[1]).unwrap();
+        assert_eq!(padding, PaddingSpec::Explicit(tvec!(1), tvec!(1), false));
+        assert_eq!(adj, tvec!(1));
+    }
+
+    #[test]
+    fn same_for_output_shape_longer_than_full() {
+        let ps = pool_spec(PaddingSpec::SameUpper, 1, 3);
+        let (padding, adj) = padding_and_adjustments(&ps, &[4], &[12], &[0]).unwrap();
+        assert_eq!(padding, PaddingSpec::Explicit(tvec!(0), tvec!(0), false));
+        assert_eq!(adj, tvec!(2));
+        let shape = output_shape(&PoolSpec { padding, ..ps }, &[1usize, 1, 4], &adj).unwrap();
+        assert_eq!(&*shape, &[1, 1, 12]);
+    }
+
+    #[test]
+    fn same_adjustments() {
+        let ps = pool_spec(PaddingSpec::SameUpper, 3, 2);
+        assert_eq!(adjustments(&ps, &[4], &[8]).unwrap(), tvec!(0));
+        assert!(adjustments(&ps, &[4], &[7]).is_err());
+    }
+}
@@ -40,7 +40,11 @@ impl Arbitrary for DeconvProblem {
                     Just(opt),
                     any::<DataFormat>(),
                     any::<KernelFormat>(),
-                    prop_oneof![Just(PaddingSpec::Valid), Just(PaddingSpec::SameUpper)],
+                    prop_oneof![
+                        Just(PaddingSpec::Valid),
+                        Just(PaddingSpec::SameUpper),
+                        Just(PaddingSpec::SameLower)
+                    ],
                     1usize..3,                         // n
                     1usize..4,                         // ci / group
                     1usize..4,                         // co / group
@@ -51,14 +55,6 @@ impl Arbitrary for DeconvProblem {
                     1usize..4,                         // group
                 )
             })
-            .prop_filter(
-                "dilation, strides and shapes in SAME",
-                |(_, _, _, pad, _, _, _, hwk, _, strides, dilations, _)| {
-                    pad == &PaddingSpec::Valid
-                        || tract_itertools::izip!(hwk, dilations, strides)
-                            .all(|(k, d, s)| (k - 1) * d > s - 1)
-                },
-            )
             .prop_flat_map(
                 |(
                     opt,
@@ -86,6 +82,7 @@ impl Arbitrary for DeconvProblem {
                         }
                     };
                     let data_shape = df.from_n_c_hw(n, ci_over_group * group, &hwi).unwrap();
+                    let adjustments: Vec<_> = strides.iter().map(|s| 0..*s).collect();
                     (
                         Just(opt),
                         Just(df),
@@ -96,6 +93,7 @@ impl Arbitrary for DeconvProblem {
                         proptest::option::of(tensor(&[co_over_group * group])),
                         Just(strides),
                         Just(dilations),
+                        adjustments,
                         Just(group),
                     )
                 },
@@ -111,9 +109,9 @@ impl Arbitrary for DeconvProblem {
                     bias,
                     strides,
                     dilations,
+                    adjustments,
                     group,
                 )| {
-                    let adjustments = tvec!(0; kernel.ndim() - 2); // FIXME maybe
                     DeconvProblem {
                         optimized,
                         data_format,
@@ -124,7 +122,7 @@ impl Arbitrary for DeconvProblem {
                         bias,
                         strides: strides.into(),
                         dilations: dilations.into(),
-                        adjustments,
+                        adjustments: adjustments.into(),
                         group,
                     }
                 },
@@ -185,28 +183,38 @@ impl DeconvProblem {
         let input_shape = self.data_format.shape(self.input.shape()).unwrap();
         let n = if self.data_format.has_n() { self.input.shape()[0] } else { 1 };
         let kernel_hwdims = self.kernel_format.spatial_shape(self.kernel.shape());
-        let valid_output_shape_geo: TVec<usize> = tract_itertools::izip!(
+        let full_output_shape_geo: TVec<usize> = tract_itertools::izip!(
             input_shape.hw_dims(),
             kernel_hwdims,
             self.strides.iter(),
-            self.dilations.iter()
+            self.dilations.iter(),
+            self.adjustments.iter(),
         )
-        .map(|(i, k, s, d)| (i - 1) * s + (k - 1) * d + 1)
+        .map(|(i, k, s, d, a)| (i - 1) * s + (k - 1) * d + 1 + a)
         .collect();
-        let paddings: TVec<(usize, usize)> = if self.padding == PaddingSpec::Valid {
-            tvec![(0, 0); valid_output_shape_geo.len()]
-        } else {
-            tract_itertools::izip!(input_shape.hw_dims(), &valid_output_shape_geo, &self.strides)
-                .map(|(i, o, s)| o - i * s)
-                .map(|total| (total / 2, total - total / 2))
-                .collect()
-        };
-        let output_shape_geo = if self.padding == PaddingSpec::Valid {
-            valid_output_shape_geo
-        } else {
-            tract_itertools::izip!(input_shape.hw_dims(), &self.strides)
-                .map(|(i, s)| i * s)
-                .collect()
+        // SAME: output is input * stride, full deconvolution is cropped by the excess
+        let (output_shape_geo, paddings): (TVec<usize>, TVec<(usize, usize)>) = match self.padding {
+            PaddingSpec::Valid => {
+                (full_output_shape_geo.clone(), tvec![(0, 0); full_output_shape_geo.len()])
+            }
+            PaddingSpec::SameUpper | PaddingSpec::SameLower => {
+                tract_itertools::izip!(input_shape.hw_dims(), &full_output_shape_geo, &self.strides)
+                    .map(|(i, o, s)| {
+                        let total = o.saturating_sub(i * s);
+                        let pads = if self.padding == PaddingSpec::SameUpper {
+                            (total / 2, total - total / 2)
+                        } else {
+                            (total - total / 2, total / 2)
+                        };
+                        (i * s, pads)
+                    })
+                    .unzip()
+            }
+            PaddingSpec::Explicit(ref before, ref after, _) => {
+                tract_itertools::izip!(&full_output_shape_geo, before, after)
+                    .map(|(o, b, a)| (o - b - a, (*b, *a)))
+                    .unzip()
+            }
         };
         let output_shape = self.data_format.from_n_c_hw(n, co, output_shape_geo).unwrap();
         let mut output = ArrayD::zeros(&*output_shape.shape);
@@ -377,7 +385,7 @@ fn test_strides_1() {
         bias: None,
         strides: tvec!(2),
         dilations: tvec!(1),
-        adjustments: tvec!(0, 0),
+        adjustments: tvec!(0),
         group: 1,
     };
     pb.check();
@@ -395,7 +403,7 @@ fn test_same_upper_1() {
         bias: None,
         strides: tvec!(1),
         dilations: tvec!(1),
-        adjustments: tvec!(0, 0),
+        adjustments: tvec!(0),
         group: 1,
     };
     pb.check();
@@ -413,7 +421,7 @@ fn test_same_upper_dil() {
         bias: None,
         strides: tvec!(1),
         dilations: tvec!(2),
-        adjustments: tvec!(0, 0),
+        adjustments: tvec!(0),
         group: 1,
     };
     pb.check();
@@ -431,7 +439,7 @@ fn test_same_upper_strides() {
         bias: None,
         strides: tvec!(2),
         dilations: tvec!(1),
-        adjustments: tvec!(0, 0),
+        adjustments: tvec!(0),
         group: 1,
     };
     pb.check();
@@ -601,3 +609,62 @@ fn test_issue_optim_2d() {
     };
     pb.check();
 }
+
+#[test]
+fn test_same_lower_adjustments() {
+    let pb = DeconvProblem {
+        optimized: false,
+        data_format: HWC,
+        kernel_format: OIHW,
+        padding: PaddingSpec::SameLower,
+        input: arr2(&[[1.0], [2.0]]).into_dyn(),
+        kernel: arr3(&[[[1.0, 2.0, 3.0]]]).into_dyn(),
+        bias: None,
+        strides: tvec!(2),
+        dilations: tvec!(1),
+        adjustments: tvec!(1),
+        group: 1,
+    };
+    pb.check();
+}
+
+#[test]
+fn test_same_upper_kernel_shorter_than_stride() {
+    let pb = DeconvProblem {
+        optimized: true,
+        data_format: HWC,
+        kernel_format: OIHW,
+        padding: PaddingSpec::SameUpper,
+        input: arr2(&[[1.0], [2.0]]).into_dyn(),
+        kernel: arr3(&[[[1.0]]]).into_dyn(),
+        bias: Some(arr1(&[1.0f32]).into_dyn()),
+        strides: tvec!(3),
+        dilations: tvec!(1),
+        adjustments: tvec!(0),
+        group: 1,
+    };
+    pb.check();
+}
+
+#[test]
+fn test_explicit_from_same_output_shape() {
+    let pool_spec =
+        PoolSpec::new(NCHW, tvec!(3), PaddingSpec::SameUpper, None, Some(tvec!(2)), Some(1));
+    let (padding, adjustments) =
+        super::padding_and_adjustments(&pool_spec, &[3], &[5], &[0]).unwrap();
+    let pb = DeconvProblem {
+        optimized: false,
+        data_format: NCHW,
+        kernel_format: OIHW,
+        padding,
+        input: arr3(&[[[1.0, 2.0, 3.0]]]).into_dyn(),
+        kernel: arr3(&[[[1.0, 2.0, 3.0]]]).into_dyn(),
+        bias: None,
+        strides: tvec!(2),
+        dilations: tvec!(1),
+        adjustments,
+        group: 1,
+    };
+    assert_eq!(pb.op_eval().shape(), &[1, 1, 5]);
+    pb.check();
+}
@@ -1,5 +1,6 @@
 use crate::internal::*;
 use crate::ops::cnn::KernelFormat;
+use crate::ops::cnn::PaddingSpec;
 use crate::ops::cnn::PoolSpec;
 
 // no-bias, no-group, f32
@@ -197,7 +198,15 @@ impl TypedOp for DeconvUnary {
         }
         for geo_axis in 0..self.pool_spec.kernel_shape.len() {
             let kernel_len = self.pool_spec.kernel_shape[geo_axis];
-            if kernel_len == 1 {
+            if kernel_len == 1
+                && self.pool_spec.stride(geo_axis) == 1
+                && self.adjustments[geo_axis] == 0
+                && (self.pool_spec.padding.valid_dim(geo_axis, true)
+                    || matches!(
+                        self.pool_spec.padding,
+                        PaddingSpec::SameUpper | PaddingSpec::SameLower
+                    ))
+            {
                 invariants
                     .axes
                     .push(AxisInfo::simple(geo_axis + self.pool_spec.data_format.h_axis()))
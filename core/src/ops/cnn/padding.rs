@@ -46,14 +46,49 @@ impl PaddingSpec {
         }
     }
 
+    fn check_geometry(
+        &self,
+        rank: usize,
+        kernel_spatial_shape: &[usize],
+        dilations: &[usize],
+        strides: &[usize],
+    ) -> TractResult<()> {
+        ensure!(
+            kernel_spatial_shape.len() == rank && dilations.len() == rank && strides.len() == rank,
+            "Inconsistent geometry for {} spatial axes: kernel {:?}, dilations {:?}, strides {:?}",
+            rank,
+            kernel_spatial_shape,
+            dilations,
+            strides
+        );
+        ensure!(
+            kernel_spatial_shape.iter().chain(dilations).chain(strides).all(|x| *x > 0),
+            "Kernel dims, dilations and strides must be positive, got {:?}, {:?} and {:?}",
+            kernel_spatial_shape,
+            dilations,
+            strides
+        );
+        if let PaddingSpec::Explicit(bef, aft, _) = self {
+            ensure!(
+                bef.len() == rank && aft.len() == rank,
+                "Expected paddings for {} spatial axes, got {:?} and {:?}",
+                rank,
+                bef,
+                aft
+            );
+        }
+        Ok(())
+    }
+
     pub fn compute<D: DimLike>(
         &self,
         input_spatial_shape: &[D],
         kernel_spatial_shape: &[usize],
         dilations: &[usize],
         strides: &[usize],
-    ) -> TVec<ComputedPaddedDim<D>> {
-        (0..input_spatial_shape.len())
+    ) -> TractResult<TVec<ComputedPaddedDim<D>>> {
+        self.check_geometry(input_spatial_shape.len(), kernel_spatial_shape, dilations, strides)?;
+        Ok((0..input_spatial_shape.len())
             .map(|d| {
                 self.compute_one(
                     d,
@@ -63,7 +98,7 @@ impl PaddingSpec {
                     strides[d],
                 )
             })
-            .collect()
+            .collect())
     }
 
     pub fn compute_for_deconv<D: DimLike>(
@@ -74,6 +109,13 @@ impl PaddingSpec {
         strides: &[usize],
         adjustments: &[usize],
     ) -> TractResult<TVec<ComputedPaddedDim<D>>> {
+        self.check_geometry(conv_spatial_shape.len(), kernel_spatial_shape, dilations, strides)?;
+        ensure!(
+            adjustments.len() == conv_spatial_shape.len(),
+            "Expected adjustments for {} spatial axes, got {:?}",
+            conv_spatial_shape.len(),
+            adjustments
+        );
         (0..conv_spatial_shape.len())
             .map(|d| {
                 self.compute_one_for_deconv(
@@ -223,22 +265,16 @@ impl PaddingSpec {
         adjustment: usize,
         upper: bool,
     ) -> TractResult<ComputedPaddedDim<D>> {
-        if (kernel - 1) * dilation < stride {
-            bail!("Invalid axis geometry for SAME padding: expect (kernel_len - 1) * dilation > stride - 1");
-        }
+        // output is always input * stride. the full deconvolution is cropped when it is
+        // bigger, the output extends past its end otherwise (kernel shorter than stride)
         let kernel_field = (kernel - 1) * dilation + 1;
-        let crop = kernel_field + adjustment - stride;
+        let crop = (kernel_field + adjustment).saturating_sub(stride);
         let lower_crop = crop / 2;
         let higher_crop = crop - lower_crop;
         let (before, after) =
             if upper { (lower_crop, higher_crop) } else { (higher_crop, lower_crop) };
-        let deconvoluted = (convoluted.clone() - 1) * stride + kernel_field - before - after;
-        Ok(ComputedPaddedDim::new(
-            deconvoluted,
-            convoluted.clone(),
-            before.into(),
-            after.into(),
-        ))
+        let deconvoluted = convoluted.clone() * stride;
+        Ok(ComputedPaddedDim::new(deconvoluted, convoluted.clone(), before.into(), after.into()))
     }
 }
 
@@ -311,4 +347,42 @@ mod tests {
     fn same_upper() {
         assert_eq!(PS::same(&7usize, 1usize, 1, 2, true), ComputedPaddedDim::new(7, 4, 0, 0));
     }
+
+    #[test]
+    fn same_for_deconv() {
+        assert_eq!(
+            PS::same_for_deconv(&3usize, 3, 1, 2, 0, true).unwrap(),
+            ComputedPaddedDim::new(6, 3, 0, 1)
+        );
+        assert_eq!(
+            PS::same_for_deconv(&3usize, 3, 1, 2, 0, false).unwrap(),
+            ComputedPaddedDim::new(6, 3, 1, 0)
+        );
+    }
+
+    #[test]
+    fn same_for_deconv_adjustment() {
+        assert_eq!(
+            PS::same_for_deconv(&3usize, 3, 1, 2, 1, true).unwrap(),
+            ComputedPaddedDim::new(6, 3, 1, 1)
+        );
+    }
+
+    #[test]
+    fn same_for_deconv_short_kernel() {
+        assert_eq!(
+            PS::same_for_deconv(&3usize, 1, 1, 2, 0, true).unwrap(),
+            ComputedPaddedDim::new(6, 3, 0, 0)
+        );
+    }
+
+    #[test]
+    fn bad_geometry_is_an_error() {
+        assert!(PS::Valid.compute(&[4usize, 4], &[2, 2], &[1, 1], &[1, 0]).is_err());
+        assert!(PS::Valid.compute(&[4usize, 4], &[2, 2], &[1, 1], &[1]).is_err());
+        assert!(PS::Valid.compute(&[4usize, 4], &[2], &[1, 1], &[1, 1]).is_err());
+        assert!(PS::Valid.compute(&[4usize, 4], &[2, 0], &[1, 1], &[1, 1]).is_err());
+        let pads = PS::Explicit(tvec!(1), tvec!(1), false);
+        assert!(pads.compute(&[4usize, 4], &[2, 2], &[1, 1], &[1, 1]).is_err());
+    }
 }
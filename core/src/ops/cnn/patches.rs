@@ -77,13 +77,13 @@ impl PatchSpec {
         PatchSpec { output_inner_stride, ..self }
     }
 
-    pub fn into_patch(self) -> Patch {
+    pub fn into_patch(self) -> TractResult<Patch> {
         let dims = self.padding.compute(
             &*self.input_shape,
             &*self.kernel_shape,
             &*self.dilations,
             &*self.strides,
-        );
+        )?;
         let output: TVec<usize> = dims.iter().map(|d| d.convoluted).collect();
         let pad_before: TVec<usize> = dims.iter().map(|d| d.pad_before).collect();
         let pad_after: TVec<usize> = dims.iter().map(|d| d.pad_after).collect();
@@ -205,7 +205,7 @@ impl PatchSpec {
         let op_strides_times_input_storage_strides =
             zip(&self.strides, &input_storage_strides).map(|(a, b)| (*a as isize * b)).collect();
 
-        Patch {
+        Ok(Patch {
             spec: self,
             padded: pad_before.iter().any(|&p| p != 0) || pad_after.iter().any(|&p| p != 0),
             pad_before,
@@ -222,7 +222,7 @@ impl PatchSpec {
             zones,
             valid_zone_id: valid_zone,
             zone_strides,
-        }
+        })
     }
 }
 
@@ -687,7 +687,8 @@ pub mod test {
             .with_kernel_shape(tvec!(kdim))
             .with_padding(PaddingSpec::Explicit(tvec![pad_before], tvec![bad_after], true))
             .with_strides(tvec![stride])
-            .into_patch();
+            .into_patch()
+            .unwrap();
         patch.output_shape[0]
     }
 
@@ -717,7 +718,8 @@ pub mod test {
                 .with_dilations(dilations.into())
                 .with_kernel_shape(kdim.into())
                 .with_strides(tvec![1; kdim.len()])
-                .into_patch();
+                .into_patch()
+                .unwrap();
         patch.data_field
     }
 
@@ -786,7 +788,8 @@ pub mod test {
                             .with_padding(pad)
                             .with_strides(tvec![strides.0, strides.1])
                             .with_output_inner_stride(output_inner_stride)
-                            .into_patch(),
+                            .into_patch()
+                            .unwrap(),
                         input,
                         data_format: fmt,
                     }
@@ -950,7 +953,8 @@ pub mod test {
             .with_kernel_shape(tvec![2, 1])
             .with_padding(PaddingSpec::SameLower)
             .with_strides(tvec![1, 2])
-            .into_patch();
+            .into_patch()
+            .unwrap();
         Problem { patch, input, data_format: input_shape.fmt }.check_visitor();
     }
 
@@ -959,8 +963,10 @@ pub mod test {
         let input_shape = NCHW.from_n_c_hw(1, 2, &[1, 1]).unwrap();
         let input = tensor4(&[[[[0.]], [[1f32]]]]);
         assert_eq!(&*input.shape(), &*input_shape.shape);
-        let patch =
-            PatchSpec::for_data_shape(input_shape.clone()).with_output_inner_stride(2).into_patch();
+        let patch = PatchSpec::for_data_shape(input_shape.clone())
+            .with_output_inner_stride(2)
+            .into_patch()
+            .unwrap();
         Problem { patch, input, data_format: input_shape.fmt }.check_visitor();
     }
 
@@ -969,8 +975,10 @@ pub mod test {
         let input_shape = NHWC.from_n_c_hw(1, 2, &[2, 1]).unwrap();
         let input = tensor4(&[[[[0., 0.]], [[1., 0f32]]]]);
         assert_eq!(&*input.shape(), &*input_shape.shape);
-        let patch =
-            PatchSpec::for_data_shape(input_shape.clone()).with_output_inner_stride(2).into_patch();
+        let patch = PatchSpec::for_data_shape(input_shape.clone())
+            .with_output_inner_stride(2)
+            .into_patch()
+            .unwrap();
         Problem { patch, input, data_format: input_shape.fmt }.check_visitor();
     }
 
@@ -983,7 +991,8 @@ pub mod test {
             .with_kernel_shape(tvec!(1, 2))
             .with_output_inner_stride(1)
             .with_padding(PaddingSpec::SameLower)
-            .into_patch();
+            .into_patch()
+            .unwrap();
         Problem { patch, input, data_format: input_shape.fmt }.check_visitor();
     }
 
@@ -992,7 +1001,7 @@ pub mod test {
         let input_shape = NCHW.from_n_c_hw(1, 1, &[2, 1]).unwrap();
         let input = tensor4(&[[[[0.], [1f32]]]]);
         assert_eq!(&*input.shape(), &*input_shape.shape);
-        let patch = PatchSpec::for_data_shape(input_shape.clone()).into_patch();
+        let patch = PatchSpec::for_data_shape(input_shape.clone()).into_patch().unwrap();
         Problem { patch, input, data_format: input_shape.fmt }.check_zone_visitor();
     }
 
@@ -1001,7 +1010,7 @@ pub mod test {
         let input_shape = NCHW.from_n_c_hw(1, 1, &[1, 2]).unwrap();
         let input = tensor4(&[[[[0., 1f32]]]]);
         assert_eq!(&*input.shape(), &*input_shape.shape);
-        let patch = PatchSpec::for_data_shape(input_shape.clone()).into_patch();
+        let patch = PatchSpec::for_data_shape(input_shape.clone()).into_patch().unwrap();
         Problem { patch, input, data_format: input_shape.fmt }.check_zone_visitor();
     }
 }
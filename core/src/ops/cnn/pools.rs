@@ -50,13 +50,16 @@ impl PoolSpec {
             .map_or_else(|| vec![1; self.kernel_shape.len()].into(), |d| d.into())
     }
 
-    pub fn computed_padding<D: DimLike>(&self, input_hw: &[D]) -> TVec<ComputedPaddedDim<D>> {
+    pub fn computed_padding<D: DimLike>(
+        &self,
+        input_hw: &[D],
+    ) -> TractResult<TVec<ComputedPaddedDim<D>>> {
         self.padding.compute(input_hw, &*self.kernel_shape, &self.dilations(), &self.strides())
     }
 
     pub fn output_shape<D: DimLike>(&self, input: &[D]) -> TractResult<BaseDataShape<D, TVec<D>>> {
         let ishape: BaseDataShape<D, TVec<D>> = self.data_format.shape(input.into())?;
-        let computed = self.computed_padding(ishape.hw_dims());
+        let computed = self.computed_padding(ishape.hw_dims())?;
         let spatial_dims = computed.into_iter().map(|d| d.convoluted).collect::<TVec<D>>();
         let oshape = self.data_format.from_n_c_hw(
             ishape.n().cloned().unwrap_or_else(|| 1.into()),
@@ -122,7 +125,7 @@ impl super::ResolveTo<ConcretePoolGeometry> for SymbolicPoolGeometry {
         if let Some(dilations) = self.pool_spec.dilations.clone() {
             spec = spec.with_dilations(dilations);
         }
-        let patch = spec.into_patch();
+        let patch = spec.into_patch()?;
         let output_shape = input_shape.fmt.from_n_c_hw(
             *input_shape.n().unwrap_or(&1),
             self.pool_spec.output_channel_override.unwrap_or(*input_shape.c()),
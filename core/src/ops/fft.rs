@@ -0,0 +1,314 @@
+use crate::internal::*;
+use num_traits::{Float, FromPrimitive};
+use rustfft::{FftDirection, FftNum, FftPlanner};
+use tract_ndarray::Axis;
+
+/// Discrete Fourier transform of a complex tensor along one axis.
+///
+/// The inverse transform is normalized (scaled by 1/n), so that applying both
+/// directions is the identity.
+#[derive(Clone, Debug, Hash)]
+pub struct Fft {
+    pub axis: usize,
+    pub inverse: bool,
+}
+
+impl_dyn_hash!(Fft);
+
+impl Fft {
+    fn eval_t<T: FftNum + Float + FromPrimitive>(&self, tensor: &mut Tensor) -> TractResult<()>
+    where
+        Complex<T>: Datum,
+    {
+        let len = tensor.shape()[self.axis];
+        if len == 0 {
+            return Ok(());
+        }
+        let direction = if self.inverse { FftDirection::Inverse } else { FftDirection::Forward };
+        let fft = FftPlanner::<T>::new().plan_fft(len, direction);
+        let scale = if self.inverse {
+            T::from_usize(len).context("Invalid fft length")?.recip()
+        } else {
+            T::one()
+        };
+        let mut array = tensor.to_array_view_mut::<Complex<T>>()?;
+        let mut buffer = Vec::with_capacity(len);
+        for mut lane in array.lanes_mut(Axis(self.axis)) {
+            buffer.clear();
+            buffer.extend(lane.iter().copied());
+            fft.process(&mut buffer);
+            lane.iter_mut().zip(buffer.iter()).for_each(|(x, y)| *x = *y * scale);
+        }
+        Ok(())
+    }
+}
+
+impl Op for Fft {
+    fn name(&self) -> Cow<str> {
+        "Fft".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("axis:{} inverse:{}", self.axis, self.inverse)])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Fft {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let mut tensor = args_1!(inputs).into_tensor();
+        match tensor.datum_type() {
+            DatumType::ComplexF32 => self.eval_t::<f32>(&mut tensor)?,
+            DatumType::ComplexF64 => self.eval_t::<f64>(&mut tensor)?,
+            dt => bail!("{} does not support {:?}", self.name(), dt),
+        }
+        Ok(tvec!(tensor.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for Fft {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        ensure!(
+            inputs[0].datum_type == DatumType::ComplexF32
+                || inputs[0].datum_type == DatumType::ComplexF64,
+            "{} expects a complex input, got {:?}",
+            self.name(),
+            inputs[0].datum_type
+        );
+        ensure!(self.axis < inputs[0].rank(), "Invalid axis {} for {:?}", self.axis, inputs[0]);
+        Ok(tvec!(inputs[0].datum_type.fact(inputs[0].shape.clone())))
+    }
+
+    fn invariants(
+        &self,
+        inputs: &[&TypedFact],
+        _outputs: &[&TypedFact],
+    ) -> TractResult<Invariants> {
+        Ok((0..inputs[0].rank()).filter(|ax| *ax != self.axis).map(AxisInfo::simple).collect())
+    }
+
+    as_op!();
+}
+
+/// Split a complex signal along `axis` into overlapping frames of `frame` samples,
+/// `stride` samples apart, optionally multiplied by a real `window`.
+///
+/// The frame axis is inserted right after `axis`, which now counts the frames.
+/// Follow with a `Fft` on `axis + 1` to get a short-time Fourier transform.
+#[derive(Clone, Debug, Hash)]
+pub struct Stft {
+    pub axis: usize,
+    pub frame: usize,
+    pub stride: usize,
+    pub window: Option<Arc<Tensor>>,
+}
+
+impl_dyn_hash!(Stft);
+
+impl Stft {
+    pub fn frames(&self, input_len: &TDim) -> TDim {
+        (input_len.clone() - self.frame) / self.stride + 1
+    }
+
+    fn eval_t<T: Datum + Float>(&self, input: &Tensor) -> TractResult<Tensor>
+    where
+        Complex<T>: Datum,
+    {
+        let input_len = input.shape()[self.axis];
+        ensure!(
+            input_len >= self.frame,
+            "{} needs at least {} samples, got {}",
+            self.name(),
+            self.frame,
+            input_len
+        );
+        let frames = (input_len - self.frame) / self.stride + 1;
+        let mut shape: TVec<usize> = input.shape().into();
+        shape[self.axis] = frames;
+        shape.insert(self.axis + 1, self.frame);
+        let window = self.window.as_ref().map(|w| w.as_slice::<T>()).transpose()?;
+        let input = input.to_array_view::<Complex<T>>()?;
+        let mut output = Tensor::zero::<Complex<T>>(&shape)?;
+        let mut view = output.to_array_view_mut::<Complex<T>>()?;
+        for f in 0..frames {
+            let mut frame = view.index_axis_mut(Axis(self.axis), f);
+            for i in 0..self.frame {
+                let w = window.map(|w| w[i]).unwrap_or_else(T::one);
+                frame.index_axis_mut(Axis(self.axis), i).zip_mut_with(
+                    &input.index_axis(Axis(self.axis), f * self.stride + i),
+                    |o, i| *o = *i * w,
+                );
+            }
+        }
+        Ok(output)
+    }
+}
+
+impl Op for Stft {
+    fn name(&self) -> Cow<str> {
+        "Stft".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "axis:{} frame:{} stride:{} window:{}",
+            self.axis,
+            self.frame,
+            self.stride,
+            self.window.is_some()
+        )])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Stft {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let output = match input.datum_type() {
+            DatumType::ComplexF32 => self.eval_t::<f32>(&input)?,
+            DatumType::ComplexF64 => self.eval_t::<f64>(&input)?,
+            dt => bail!("{} does not support {:?}", self.name(), dt),
+        };
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for Stft {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let fact = inputs[0];
+        let real = match fact.datum_type {
+            DatumType::ComplexF32 => DatumType::F32,
+            DatumType::ComplexF64 => DatumType::F64,
+            dt => bail!("{} does not support {:?}", self.name(), dt),
+        };
+        ensure!(self.axis < fact.rank(), "Invalid axis {} for {:?}", self.axis, fact);
+        ensure!(self.frame > 0 && self.stride > 0, "Frame and stride must be positive");
+        if let Some(window) = &self.window {
+            ensure!(
+                window.datum_type() == real && window.shape() == [self.frame],
+                "Expected a {:?} window of {} samples, got {:?}",
+                real,
+                self.frame,
+                window
+            );
+        }
+        let mut shape = fact.shape.to_tvec();
+        shape[self.axis] = self.frames(&fact.shape[self.axis]);
+        shape.insert(self.axis + 1, self.frame.to_dim());
+        Ok(tvec!(fact.datum_type.fact(shape)))
+    }
+
+    fn invariants(
+        &self,
+        inputs: &[&TypedFact],
+        _outputs: &[&TypedFact],
+    ) -> TractResult<Invariants> {
+        Ok((0..inputs[0].rank())
+            .filter(|ax| *ax != self.axis)
+            .map(|ax| AxisInfo {
+                inputs: tvec!(Some(ax)),
+                outputs: tvec!(Some(ax + (ax > self.axis) as usize)),
+                period: 1,
+                disposable: true,
+            })
+            .collect())
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::math::{ComplexToInnerDim, InnerDimToComplex};
+
+    fn complex(re: &[f32]) -> Tensor {
+        let data: Vec<Complex<f32>> = re.iter().map(|x| Complex::new(*x, 0.)).collect();
+        Tensor::from_shape(&[data.len()], &data).unwrap()
+    }
+
+    fn naive_dft(signal: &[Complex<f32>]) -> Vec<Complex<f32>> {
+        let n = signal.len();
+        (0..n)
+            .map(|k| {
+                signal
+                    .iter()
+                    .enumerate()
+                    .map(|(t, x)| {
+                        let angle = -2. * std::f32::consts::PI * (k * t) as f32 / n as f32;
+                        x * Complex::new(angle.cos(), angle.sin())
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+
+    fn close(a: &[Complex<f32>], b: &[Complex<f32>]) -> bool {
+        a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| (a - b).norm() < 1e-4)
+    }
+
+    #[test]
+    fn fft_matches_naive_dft() -> TractResult<()> {
+        let input = complex(&[1., 2., 0., -1., 3.]);
+        let output =
+            Fft { axis: 0, inverse: false }.eval(tvec!(input.clone().into_arc_tensor()))?;
+        let expected = naive_dft(input.as_slice::<Complex<f32>>()?);
+        assert!(close(output[0].as_slice::<Complex<f32>>()?, &expected));
+        Ok(())
+    }
+
+    #[test]
+    fn inverse_is_normalized() -> TractResult<()> {
+        let input = complex(&[1., 2., 0., -1., 3., 4.]);
+        let freq = Fft { axis: 0, inverse: false }.eval(tvec!(input.clone().into_arc_tensor()))?;
+        let back = Fft { axis: 0, inverse: true }.eval(freq)?;
+        assert!(close(back[0].as_slice::<Complex<f32>>()?, input.as_slice::<Complex<f32>>()?));
+        Ok(())
+    }
+
+    #[test]
+    fn fft_on_inner_axis() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let source = model.add_source("input", f32::fact([2, 4, 2]))?;
+        let wire = model.wire_node("complex", InnerDimToComplex, &[source])?[0];
+        let wire = model.wire_node("fft", Fft { axis: 1, inverse: false }, &[wire])?[0];
+        let wire = model.wire_node("real", ComplexToInnerDim, &[wire])?[0];
+        model.set_output_outlets(&[wire])?;
+        let input = tensor3(&[
+            [[1f32, 0.], [1., 0.], [1., 0.], [1., 0.]],
+            [[0f32, 0.], [1., 0.], [0., 0.], [0., 0.]],
+        ]);
+        let output = model.into_runnable()?.run(tvec!(input))?;
+        output[0].close_enough(
+            &tensor3(&[
+                [[4f32, 0.], [0., 0.], [0., 0.], [0., 0.]],
+                [[1f32, 0.], [0., -1.], [-1., 0.], [0., 1.]],
+            ]),
+            true,
+        )
+    }
+
+    #[test]
+    fn stft_frames() -> TractResult<()> {
+        let op = Stft { axis: 0, frame: 3, stride: 2, window: Some(rctensor1(&[0f32, 1., 2.])) };
+        let input = complex(&[1., 2., 3., 4., 5., 6.]);
+        let output = op.eval(tvec!(input.into_arc_tensor()))?;
+        assert_eq!(output[0].shape(), &[2, 3]);
+        let re: Vec<f32> = output[0].as_slice::<Complex<f32>>()?.iter().map(|c| c.re).collect();
+        assert_eq!(re, &[0., 2., 6., 0., 4., 10.]);
+        assert_eq!(op.frames(&6.to_dim()), 2.to_dim());
+        Ok(())
+    }
+}
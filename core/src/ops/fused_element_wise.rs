@@ -0,0 +1,191 @@
+use crate::internal::*;
+use crate::ops::binary::BinMiniOp;
+use crate::ops::element_wise::ElementWiseMiniOp;
+
+/// Size in bytes of the blocks a FusedElementWise runs its program on. Small
+/// enough for the block to stay in L1 while the whole program runs over it.
+const BLOCK_BYTES: usize = 32 * 1024;
+
+/// One step of a FusedElementWise program, working on an accumulator.
+#[derive(Debug, Clone, Hash)]
+pub enum FusedStep {
+    /// acc <- f(acc)
+    Map(Box<dyn ElementWiseMiniOp>),
+    /// acc <- a op acc, with a scalar constant a
+    Uniform(Box<dyn BinMiniOp>, Arc<Tensor>),
+    /// acc <- acc op input[ix] if acc_is_left, input[ix] op acc otherwise
+    WithInput(Box<dyn BinMiniOp>, usize, bool),
+}
+
+impl FusedStep {
+    fn validation(&self) -> Validation {
+        match self {
+            FusedStep::Map(op) => op.validation(),
+            FusedStep::Uniform(op, _) | FusedStep::WithInput(op, _, _) => op.validation(),
+        }
+    }
+
+    fn cost_per_element(&self, dt: DatumType) -> TVec<(Cost, usize)> {
+        match self {
+            FusedStep::Map(op) => op.cost_per_element(dt),
+            FusedStep::Uniform(op, _) | FusedStep::WithInput(op, _, _) => op.cost_per_element(dt),
+        }
+    }
+}
+
+/// A chain of same-shape element-wise operations, evaluated block by block.
+///
+/// Input 0 feeds the accumulator, the other inputs are the right-hand sides of
+/// the `WithInput` steps and have the same shape as input 0.
+#[derive(Debug, Clone, Hash, new)]
+pub struct FusedElementWise {
+    pub steps: Vec<FusedStep>,
+}
+
+impl_dyn_hash!(FusedElementWise);
+
+impl Op for FusedElementWise {
+    fn name(&self) -> Cow<str> {
+        "FusedElementWise".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(self
+            .steps
+            .iter()
+            .map(|step| match step {
+                FusedStep::Map(op) => op.name(),
+                FusedStep::Uniform(op, a) => format!("{} {:?}", op.name(), a),
+                FusedStep::WithInput(op, ix, true) => format!("{} input #{}", op.name(), ix),
+                FusedStep::WithInput(op, ix, false) => format!("input #{} {}", ix, op.name()),
+            })
+            .collect())
+    }
+
+    fn validation(&self) -> Validation {
+        if self.steps.iter().any(|s| s.validation() == Validation::Rounding) {
+            Validation::Rounding
+        } else {
+            Validation::Accurate
+        }
+    }
+
+    op_core_lir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for FusedElementWise {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let mut output = inputs.remove(0).into_tensor();
+        let dt = output.datum_type();
+        let item_size = dt.size_of();
+        let len = output.len();
+        let block = (BLOCK_BYTES / item_size).min(len);
+        let mut acc = unsafe { Tensor::uninitialized_dt(dt, &[block])? };
+        let mut other = unsafe { Tensor::uninitialized_dt(dt, &[block])? };
+        for start in (0..len).step_by(block.max(1)) {
+            let end = (start + block).min(len);
+            if end - start != acc.len() {
+                acc = unsafe { Tensor::uninitialized_dt(dt, &[end - start])? };
+                other = unsafe { Tensor::uninitialized_dt(dt, &[end - start])? };
+            }
+            let range = start * item_size..end * item_size;
+            unsafe {
+                acc.as_bytes_mut().copy_from_slice(&output.as_bytes()[range.clone()]);
+            }
+            for step in &self.steps {
+                match step {
+                    FusedStep::Map(op) => op.eval_in_place(&mut acc)?,
+                    FusedStep::Uniform(op, a) => op.eval_uniform_in_place(a, &mut acc)?,
+                    FusedStep::WithInput(op, ix, acc_is_left) => {
+                        // input 0 is read back from output, its current block is not
+                        // written yet
+                        let source = if *ix == 0 { &output } else { &*inputs[ix - 1] };
+                        unsafe {
+                            other.as_bytes_mut().copy_from_slice(&source.as_bytes()[range.clone()]);
+                        }
+                        if *acc_is_left {
+                            op.eval_unicast_in_place(&acc, &mut other)?;
+                            std::mem::swap(&mut acc, &mut other);
+                        } else {
+                            op.eval_unicast_in_place(&other, &mut acc)?;
+                        }
+                    }
+                }
+            }
+            unsafe {
+                output.as_bytes_mut()[range].copy_from_slice(acc.as_bytes());
+            }
+        }
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for FusedElementWise {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(inputs[0].without_value()))
+    }
+
+    fn cost(&self, inputs: &[&TypedFact]) -> TractResult<TVec<(Cost, TDim)>> {
+        let count: TDim = inputs[0].shape.iter().product();
+        Ok(self
+            .steps
+            .iter()
+            .flat_map(|step| step.cost_per_element(inputs[0].datum_type))
+            .map(|(c, n)| (c, count.clone() * n))
+            .collect())
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::element_wise::ElementWiseOp;
+    use crate::ops::{math, nn};
+
+    fn swish_like(shape: &[usize]) -> TractResult<TypedModel> {
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", f32::fact(shape))?;
+        let scale = model.add_const("scale", tensor2(&[[0.5f32]]))?;
+        let bias = model.add_const("bias", tensor2(&[[1f32]]))?;
+        let w = model.wire_node("mul", math::mul::bin_typed(), &[x, scale])?;
+        let w = model.wire_node("add", math::add::bin_typed(), &[w[0], bias])?;
+        let w = model.wire_node("sigmoid", nn::sigmoid(), &w)?;
+        let w = model.wire_node("swish", math::mul::bin_typed(), &[w[0], x])?;
+        model.set_output_outlets(&w)?;
+        Ok(model)
+    }
+
+    #[test]
+    fn fuse_swish_chain() -> TractResult<()> {
+        let shape = [3, 5000];
+        let model = swish_like(&shape)?;
+        let input = Tensor::from_shape(
+            &shape,
+            &(0..15000).map(|i| i as f32 / 1000.0 - 7.0).collect::<Vec<_>>(),
+        )?;
+        let expected = model.clone().into_runnable()?.run(tvec!(input.clone()))?;
+        let optimized = model.into_optimized()?;
+        assert_eq!(optimized.nodes().iter().filter(|n| n.op_is::<FusedElementWise>()).count(), 1);
+        assert!(!optimized.nodes().iter().any(|n| n.op_is::<ElementWiseOp>()));
+        let found = optimized.into_runnable()?.run(tvec!(input))?;
+        found[0].close_enough(&expected[0], Approximation::Close)
+    }
+
+    #[test]
+    fn do_not_fuse_through_shared_intermediate() -> TractResult<()> {
+        let mut model = swish_like(&[2, 3])?;
+        let sigmoid = model.node_by_name("sigmoid")?.id;
+        model.set_output_outlets(&[model.output_outlets()?[0], OutletId::new(sigmoid, 0)])?;
+        let optimized = model.into_optimized()?;
+        let fused = optimized.nodes().iter().find(|n| n.op_is::<FusedElementWise>()).unwrap();
+        assert_eq!(fused.op_as::<FusedElementWise>().unwrap().steps.len(), 3);
+        Ok(())
+    }
+}
@@ -0,0 +1,182 @@
+use crate::internal::*;
+
+pub(super) type ComplexF32 = Complex<f32>;
+pub(super) type ComplexF64 = Complex<f64>;
+
+/// Reinterpret a float tensor with a trailing axis of dimension 2 as a complex tensor.
+///
+/// The trailing axis holds the (real, imaginary) pairs and is removed.
+#[derive(Clone, Debug, Default, Hash)]
+pub struct InnerDimToComplex;
+
+impl_dyn_hash!(InnerDimToComplex);
+
+impl Op for InnerDimToComplex {
+    fn name(&self) -> Cow<str> {
+        "InnerDimToComplex".into()
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for InnerDimToComplex {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let output = match input.datum_type() {
+            DatumType::F16 => inner_dim_to_complex::<f16>(&input)?,
+            DatumType::F32 => inner_dim_to_complex::<f32>(&input)?,
+            DatumType::F64 => inner_dim_to_complex::<f64>(&input)?,
+            dt => bail!("{} does not support {:?}", self.name(), dt),
+        };
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for InnerDimToComplex {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let fact = inputs[0];
+        let dt = match fact.datum_type {
+            DatumType::F16 => DatumType::ComplexF16,
+            DatumType::F32 => DatumType::ComplexF32,
+            DatumType::F64 => DatumType::ComplexF64,
+            dt => bail!("{} does not support {:?}", self.name(), dt),
+        };
+        ensure!(
+            fact.rank() > 0 && fact.shape[fact.rank() - 1] == 2.to_dim(),
+            "{} expects a trailing axis of dimension 2, got {:?}",
+            self.name(),
+            fact
+        );
+        Ok(tvec!(dt.fact(&fact.shape[0..fact.rank() - 1])))
+    }
+
+    fn invariants(
+        &self,
+        inputs: &[&TypedFact],
+        _outputs: &[&TypedFact],
+    ) -> TractResult<Invariants> {
+        Ok((0..inputs[0].rank() - 1).map(AxisInfo::simple).collect())
+    }
+
+    as_op!();
+}
+
+/// Split a complex tensor in (real, imaginary) pairs along a new trailing axis of dimension 2.
+#[derive(Clone, Debug, Default, Hash)]
+pub struct ComplexToInnerDim;
+
+impl_dyn_hash!(ComplexToInnerDim);
+
+impl Op for ComplexToInnerDim {
+    fn name(&self) -> Cow<str> {
+        "ComplexToInnerDim".into()
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for ComplexToInnerDim {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let output = match input.datum_type() {
+            DatumType::ComplexF16 => complex_to_inner_dim::<f16>(&input)?,
+            DatumType::ComplexF32 => complex_to_inner_dim::<f32>(&input)?,
+            DatumType::ComplexF64 => complex_to_inner_dim::<f64>(&input)?,
+            dt => bail!("{} does not support {:?}", self.name(), dt),
+        };
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for ComplexToInnerDim {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let fact = inputs[0];
+        let dt = match fact.datum_type {
+            DatumType::ComplexF16 => DatumType::F16,
+            DatumType::ComplexF32 => DatumType::F32,
+            DatumType::ComplexF64 => DatumType::F64,
+            dt => bail!("{} does not support {:?}", self.name(), dt),
+        };
+        let mut shape = fact.shape.to_tvec();
+        shape.push(2.to_dim());
+        Ok(tvec!(dt.fact(shape)))
+    }
+
+    fn invariants(
+        &self,
+        inputs: &[&TypedFact],
+        _outputs: &[&TypedFact],
+    ) -> TractResult<Invariants> {
+        Ok((0..inputs[0].rank()).map(AxisInfo::simple).collect())
+    }
+
+    as_op!();
+}
+
+fn inner_dim_to_complex<T: Datum + Copy>(input: &Tensor) -> TractResult<Tensor>
+where
+    Complex<T>: Datum,
+{
+    let shape = input.shape();
+    ensure!(shape.last() == Some(&2), "Expected a trailing axis of dimension 2, got {:?}", shape);
+    let data: Vec<Complex<T>> =
+        input.as_slice::<T>()?.chunks(2).map(|pair| Complex::new(pair[0], pair[1])).collect();
+    Tensor::from_shape(&shape[..shape.len() - 1], &data)
+}
+
+fn complex_to_inner_dim<T: Datum + Copy>(input: &Tensor) -> TractResult<Tensor>
+where
+    Complex<T>: Datum,
+{
+    let mut shape: TVec<usize> = input.shape().into();
+    shape.push(2);
+    let data: Vec<T> = input.as_slice::<Complex<T>>()?.iter().flat_map(|c| [c.re, c.im]).collect();
+    Tensor::from_shape(&shape, &data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::math::{mul, sub};
+
+    #[test]
+    fn round_trip() -> TractResult<()> {
+        let input = tensor2(&[[1f32, 2.], [3., 4.], [5., 6.]]);
+        let complex = InnerDimToComplex.eval(tvec!(input.clone().into_arc_tensor()))?;
+        assert_eq!(complex[0].datum_type(), DatumType::ComplexF32);
+        assert_eq!(complex[0].shape(), &[3]);
+        assert_eq!(complex[0].as_slice::<Complex<f32>>()?[1], Complex::new(3., 4.));
+        let back = ComplexToInnerDim.eval(complex)?;
+        assert_eq!(*back[0], input);
+        Ok(())
+    }
+
+    #[test]
+    fn complex_arithmetic() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let a = model.add_source("a", f32::fact([2, 2]))?;
+        let b = model.add_source("b", f32::fact([2, 2]))?;
+        let a = model.wire_node("a_complex", InnerDimToComplex, &[a])?[0];
+        let b = model.wire_node("b_complex", InnerDimToComplex, &[b])?[0];
+        let product = model.wire_node("mul", mul::bin_typed(), &[a, b])?[0];
+        let diff = model.wire_node("sub", sub::bin_typed(), &[product, a])?[0];
+        let output = model.wire_node("output", ComplexToInnerDim, &[diff])?[0];
+        model.set_output_outlets(&[output])?;
+        let a = tensor2(&[[1f32, 2.], [0., 1.]]);
+        let b = tensor2(&[[3f32, -1.], [0., 1.]]);
+        let result = model.into_runnable()?.run(tvec!(a, b))?;
+        // (1+2i)(3-i) - (1+2i) = 4+3i, i.i - i = -1-i
+        assert_eq!(*result[0], tensor2(&[[4f32, 3.], [-1., -1.]]));
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 #![allow(clippy::clone_on_copy)]
 use super::binary::*;
 use crate::internal::*;
-use crate::ops::quant::scale_by;
+use crate::ops::quant::{declutter_quant_bin, scale_by};
 use num_traits::bounds::Bounded;
 use num_traits::int::PrimInt;
 use num_traits::{Float, Zero};
@@ -10,13 +10,18 @@ pub use tract_data::prelude::round_ties_to_even;
 use tract_linalg::{ScaleShiftAndRound, Scaler};
 use tract_num_traits::AsPrimitive;
 
+mod complex;
+use complex::{ComplexF32, ComplexF64};
+pub use complex::{ComplexToInnerDim, InnerDimToComplex};
+
 bin_to_super_type!(add, Add,
+                   declutter_bin: declutter_quant_bin,
                    declutter_unary: declutter_unary_add,
                    flip:commute,
                    linalg: Add,
                    validation: Validation::Rounding,
                    q: [i8, u8, i32, i32] => add_quant;
-                   [f32, i8, i16, i32, i64, u8, u16, u32, u64, f16, f64, TDim] => |c, a, b| *c = a.clone() + b);
+                   [f32, i8, i16, i32, i64, u8, u16, u32, u64, f16, f64, TDim, ComplexF32, ComplexF64] => |c, a, b| *c = a.clone() + b);
 
 fn add_quant<T>(c: &mut T, a: &T, b: &T, zp: i32, _: f32)
 where
@@ -41,9 +46,10 @@ fn declutter_unary_add(
 }
 
 bin_to_super_type!(sub, Sub,
+    declutter_bin: declutter_quant_bin,
     declutter_unary: declutter_unary_sub, flip:flip_sub, linalg:Sub,
     q: [i8, u8, i32, i32] => sub_quant;
-    [f32, i8, i16, i32, i64, u8, u16, u32, u64, f16, f64, TDim] => |c, a, b| *c = a.clone() - b);
+    [f32, i8, i16, i32, i64, u8, u16, u32, u64, f16, f64, TDim, ComplexF32, ComplexF64] => |c, a, b| *c = a.clone() - b);
 
 fn sub_quant<T>(c: &mut T, a: &T, b: &T, zp: i32, _: f32)
 where
@@ -73,6 +79,7 @@ fn declutter_unary_sub(
 
 bin_to_super_type!(mul, Mul,
 cost: |dt| tvec!((Cost::FMA(dt), 1)),
+declutter_bin: declutter_quant_bin,
 declutter_unary: declutter_unary_mul,
 flip: commute,
 linalg: Mul,
@@ -115,7 +122,7 @@ out_of_place: |c:&mut Tensor, a:&Tensor, b: &Tensor| -> TractResult<bool> {
     }
 },
 q: [i8, u8, i32] => |c, a, b, _, _| *c = a.clone() * b;
-[f32, i8, i16, i32, i64, u8, u16, u32, u64, f16, f64, TDim] => |c, a, b| *c = a.clone() * b
+[f32, i8, i16, i32, i64, u8, u16, u32, u64, f16, f64, TDim, ComplexF32, ComplexF64] => |c, a, b| *c = a.clone() * b
 );
 
 bin_to_super_type!(div, Div,
@@ -160,7 +167,7 @@ out_of_place: |c:&mut Tensor, a:&Tensor, b: &Tensor| -> TractResult<bool> {
             Ok(false)
         }
 },
-[f32, i8, i16, i32, i64, u8, u16, u32, u64, f16, f64] => |c, a, b| *c = a.clone() / b
+[f32, i8, i16, i32, i64, u8, u16, u32, u64, f16, f64, ComplexF32, ComplexF64] => |c, a, b| *c = a.clone() / b
 );
 
 bin_to_super_type!(rem, Rem,
@@ -224,6 +231,9 @@ bin_to_super_type!(flipped_shift_right, FlippedShiftRight,
                    [i8, i16, i32, i64, u8, u16, u32, u64] => |c, a, b| *c = *b >> *a);
 
 fn flip_sub(_op: &dyn BinMiniOp, t: &Arc<Tensor>) -> Option<UnaryOp> {
+    if t.datum_type().is_complex() {
+        return None;
+    }
     let mut t = t.clone().into_tensor();
     fn negate<T: Datum + std::ops::Neg<Output = T>>(t: &mut Tensor) {
         t.as_slice_mut::<T>().unwrap().iter_mut().for_each(|p| *p = -p.clone());
@@ -237,6 +247,9 @@ fn flip_sub(_op: &dyn BinMiniOp, t: &Arc<Tensor>) -> Option<UnaryOp> {
 }
 
 fn flip_div(_op: &dyn BinMiniOp, t: &Arc<Tensor>) -> Option<UnaryOp> {
+    if t.datum_type().is_complex() {
+        return None;
+    }
     let mut t = t.clone().into_tensor();
     fn inverse<T: Datum + num_traits::Float>(t: &mut Tensor) {
         t.as_slice_mut::<T>().unwrap().iter_mut().for_each(|p| *p = p.recip());
@@ -259,6 +272,9 @@ fn declutter_unary_mul(
     node: &TypedNode,
     a: &Arc<Tensor>,
 ) -> TractResult<Option<TypedModelPatch>> {
+    if a.datum_type().is_complex() {
+        return Ok(None);
+    }
     if let Some(patch) =
         declutter_as_shift(model, node, a, Box::new(FlippedShiftLeft)).context("declutte_as_shift")?
     {
@@ -413,19 +429,19 @@ element_wise!(abs, Abs, [i8, i16, i32, i64, f16, f32, i32] => |_, xs| {
 };
 q: [i8, u8, i32, i32] => f32::abs);
 
-element_wise!(exp, Exp, [f16, f32, f64] => |_, xs| {
-    xs.iter_mut().for_each(|x| *x = x.exp());
-    Ok(())
-};
-q: [i8, u8, i32, i32] => f32::exp;
+element_wise!(exp, Exp,
+ [f16] => |_, xs| { (tract_linalg::ops().exp_f16)().run(xs) },
+ [f32] => |_, xs| { (tract_linalg::ops().exp_f32)().run(xs) },
+ [f64] => |_, xs| { xs.iter_mut().for_each(|x| *x = x.exp()); Ok(()) };
+ q: [i8, u8, i32, i32] => f32::exp;
 validation: Validation::Rounding
 );
 
-element_wise!(ln, Ln, [f16, f32, f64] => |_, xs| {
-    xs.iter_mut().for_each(|x| *x = x.ln());
-    Ok(())
-};
-q: [i8, u8, i32, i32] => f32::ln;
+element_wise!(ln, Ln,
+ [f16] => |_, xs| { (tract_linalg::ops().ln_f16)().run(xs) },
+ [f32] => |_, xs| { (tract_linalg::ops().ln_f32)().run(xs) },
+ [f64] => |_, xs| { xs.iter_mut().for_each(|x| *x = x.ln()); Ok(()) };
+ q: [i8, u8, i32, i32] => f32::ln;
 validation: Validation::Rounding
 );
 
@@ -576,7 +592,8 @@ element_wise!(tanh, Tanh,
  [f32] => |_, xs| { (tract_linalg::ops().tanh_f32)().run(xs) },
  [f64] => |_, xs| { xs.iter_mut().for_each(|x| *x = x.tanh()); Ok(()) };
  q: [i8, u8, i32] => f32::tanh;
- cost: |dt| {tvec!((Cost::FMA(dt), 11), (Cost::Div(dt), 1))}
+ cost: |dt| {tvec!((Cost::FMA(dt), 11), (Cost::Div(dt), 1))};
+ declutter: crate::ops::nn::declutter_gelu_tanh
 );
 
 element_wise!(acosh, Acosh, [f16, f32, f64] => |_, xs| {
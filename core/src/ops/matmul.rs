@@ -3,6 +3,7 @@ pub mod mir;
 pub mod mir_quant;
 pub mod mir_quant_unary;
 pub mod mir_unary;
+pub mod mir_weight_quant_unary;
 pub mod pack;
 
 use crate::internal::*;
@@ -12,7 +13,8 @@ use tract_ndarray::prelude::*;
 
 pub use self::mir::MatMul;
 pub use self::mir_quant::{MatMulQParams, QMatMul};
-pub use self::mir_unary::MatMulUnary;
+pub use self::mir_unary::{MatMulUnary, PackedACache};
+pub use self::mir_weight_quant_unary::WeightQuantMatMulUnary;
 use self::pack::MatMatMulPack;
 
 pub fn compute_shape<D: DimLike>(
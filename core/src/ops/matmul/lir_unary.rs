@@ -1,11 +1,59 @@
 use crate::internal::*;
 use ndarray::*;
 
+use tract_linalg::frame::sparse::BlockSparseWeights;
+use tract_linalg::frame::weight_quant::QuantizedWeights;
 use tract_linalg::mmm::{
-    BinOp, FusedSpec, InputStoreSpec, MatMatMul, OutputStore, OutputStoreSpec, ScratchSpace,
+    BinOp, FusedSpec, InputStore, InputStoreSpec, MatMatMul, OutputStore, OutputStoreSpec,
+    ScratchSpace,
 };
 use tract_linalg::Scaler;
 
+/// Constant A operand, either packed ahead of time, packed on the fly from
+/// weight-only quantized storage, or multiplied from block-sparse storage.
+#[derive(Clone, Debug, Hash)]
+pub enum PackedA {
+    Packed(Arc<Tensor>),
+    QuantizedWeights(Arc<QuantizedWeights>),
+    Sparse(Arc<BlockSparseWeights>),
+}
+
+impl PackedA {
+    pub fn datum_type(&self) -> DatumType {
+        match self {
+            PackedA::Packed(t) => t.datum_type(),
+            PackedA::QuantizedWeights(_) | PackedA::Sparse(_) => f32::datum_type(),
+        }
+    }
+
+    fn params(&self) -> (DatumType, usize) {
+        match self {
+            PackedA::Packed(t) => (t.datum_type().unquantized(), t.len()),
+            PackedA::QuantizedWeights(w) => (u8::datum_type(), w.data.len() + 4 * w.scales.len()),
+            PackedA::Sparse(w) => (f32::datum_type(), w.values.len() + w.cols.len()),
+        }
+    }
+
+    unsafe fn add_mat_mul<'t>(
+        &self,
+        mmm: &dyn MatMatMul,
+        k: usize,
+        b: InputStore,
+    ) -> FusedSpec<'t> {
+        match self {
+            PackedA::Packed(t) => FusedSpec::AddMatMul {
+                k,
+                a: mmm.a_packed(t.datum_type().size_of(), k).wrap(&t.view()),
+                b,
+            },
+            PackedA::QuantizedWeights(w) => {
+                FusedSpec::AddMatMul { k, a: mmm.a_quantized_weights(w.clone()), b }
+            }
+            PackedA::Sparse(w) => FusedSpec::AddSparseMatMul { a: w.clone(), b },
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Clone, Hash, Debug)]
 pub enum ProtoFusedSpec {
     BinScalar(AttrOrInput, BinOp),
@@ -89,7 +137,7 @@ pub struct LirMatMulUnary {
     pub c_fact: TypedFact,
     pub c_m_axis: usize,
     pub c_n_axis: usize,
-    pub micro_ops: ArrayD<(Arc<Tensor>, Vec<ProtoFusedSpec>)>,
+    pub micro_ops: ArrayD<(PackedA, Vec<ProtoFusedSpec>)>,
     pub c_final_shape: ShapeFact,
     pub geometry: MatMulGeometry,
     pub mmm: Box<dyn MatMatMul>,
@@ -206,7 +254,6 @@ fn eval(
 ) -> TractResult<TVec<Arc<Tensor>>> {
     unsafe {
         debug_assert!(op.micro_ops.len() > 0);
-        let size_of_a = (*op.micro_ops.as_ptr()).0.datum_type().size_of();
         let mut c = Tensor::uninitialized_dt(op.c_fact.datum_type, c_shape)?;
         let c_storage = op.mmm.c_view(c_m_axis, c_n_axis);
         if op
@@ -232,13 +279,13 @@ fn eval(
                 }
                 let (pa, fused) = ops.iter().next().unwrap();
                 let c_store = c_storage.wrap(&c_view);
-                let mut f = tvec!(FusedSpec::AddMatMul {
-                    k: geometry.k,
-                    a: op.mmm.a_packed(size_of_a, geometry.k).wrap(&pa.view()),
-                    b: geometry
+                let mut f = tvec!(pa.add_mat_mul(
+                    &*op.mmm,
+                    geometry.k,
+                    geometry
                         .b_storage
                         .wrap(&TensorView::at_prefix_unchecked(&inputs[0], &*b_prefix))?,
-                });
+                ));
                 f.extend(
                     fused.iter().map(|f| f.resolve(inputs, prefix.slice(), &c_storage, c_store)),
                 );
@@ -248,11 +295,11 @@ fn eval(
             let (pa, fused) = &*op.micro_ops.as_ptr();
             let c_store = c_storage.wrap(&c.view_mut());
             let mut f = Vec::with_capacity(fused.len() + 1);
-            f.push(FusedSpec::AddMatMul {
-                k: geometry.k,
-                a: op.mmm.a_packed(size_of_a, geometry.k).wrap(&pa.view()),
-                b: geometry.b_storage.wrap(&inputs[0].view())?,
-            });
+            f.push(pa.add_mat_mul(
+                &*op.mmm,
+                geometry.k,
+                geometry.b_storage.wrap(&inputs[0].view())?,
+            ));
             for ix in 0..fused.len() {
                 f.push(fused.get_unchecked(ix).resolve(inputs, &[], &c_storage, c_store));
             }
@@ -283,8 +330,8 @@ impl TypedOp for LirMatMulUnary {
         Ok(tvec!(
             (Cost::FMA(self.mmm.internal_type()), sums * self.geometry.k().as_ref()),
             (
-                Cost::Params(self.micro_ops.as_slice().unwrap()[0].0.datum_type().unquantized()),
-                self.micro_ops.iter().fold(0.to_dim(), |sum, a| sum + a.0.len())
+                Cost::Params(self.micro_ops.as_slice().unwrap()[0].0.params().0),
+                self.micro_ops.iter().fold(0.to_dim(), |sum, a| sum + a.0.params().1)
             )
         ))
     }
@@ -559,6 +559,8 @@ pub(crate) fn wire_matmul_quant(
     let rank = a_fact.rank();
     let m_axis = rank - 2 + c_trans as usize;
     let n_axis = rank - 1 - c_trans as usize;
+    let mut params: TVec<OutletId> = params.into();
+    wire_per_axis_qparams(model, name, &mut params, rank, m_axis, n_axis)?;
 
     if let Some(bias) = bias {
         result = wire_with_rank_broadcast(
@@ -599,6 +601,65 @@ pub(crate) fn wire_matmul_quant(
     requant(model, name, result, output_type, abc_scale, params[4])
 }
 
+/// Reshapes per-axis quantization parameters so they broadcast over the
+/// result of the product.
+///
+/// Rank-1 zero points and scales for a are per-row, and are laid along
+/// `m_axis`. The ones for b are per-column, and are laid along `n_axis`.
+/// Scalar parameters are left untouched, and c parameters must be scalars.
+pub(crate) fn wire_per_axis_qparams(
+    model: &mut TypedModel,
+    name: &str,
+    params: &mut [OutletId],
+    rank: usize,
+    m_axis: usize,
+    n_axis: usize,
+) -> TractResult<()> {
+    let names = ["a0", "a_scale", "b0", "b_scale", "c0", "c_scale"];
+    for (ix, axis) in [(0, m_axis), (1, m_axis), (2, n_axis), (3, n_axis)] {
+        params[ix] = wire_per_axis_qparam(
+            model,
+            &format!("{}.{}", name, names[ix]),
+            params[ix],
+            rank,
+            axis,
+        )?;
+    }
+    for ix in 4..6 {
+        ensure!(
+            model.outlet_fact(params[ix])?.shape.volume().is_one(),
+            "{} must be a per-tensor quantization parameter",
+            names[ix]
+        );
+    }
+    Ok(())
+}
+
+/// Reshapes a per-axis quantization parameter so that its last axis is laid
+/// on `axis` of a tensor of rank `rank`. Scalars are left untouched.
+pub(crate) fn wire_per_axis_qparam(
+    model: &mut TypedModel,
+    name: &str,
+    param: OutletId,
+    rank: usize,
+    axis: usize,
+) -> TractResult<OutletId> {
+    let param_rank = model.outlet_fact(param)?.rank();
+    if param_rank == 0 {
+        return Ok(param);
+    }
+    ensure!(param_rank <= axis + 1, "Can not lay {} of rank {} on axis {}", name, param_rank, axis);
+    let mut wire = param;
+    for ix in axis + 1..rank {
+        wire = model.wire_node(
+            format!("{}.per_axis_{}", name, ix),
+            AxisOp::Add(param_rank + ix - axis - 1),
+            &[wire],
+        )?[0];
+    }
+    Ok(wire)
+}
+
 pub(crate) fn combine_scales(
     model: &mut TypedModel,
     name: &str,
@@ -1376,4 +1437,71 @@ mod test {
             },
         ));
     }
+
+    fn per_axis_check(const_a: bool, const_b: bool, c_trans: bool) {
+        let a = arr2(&[[1i8, -2, 3], [4, 5, -6]]);
+        let b = arr2(&[[7i8, 8], [-9, 10], [11, -12]]);
+        let a0 = [1i8, -2];
+        let a_scale = [0.5f32, 1.0];
+        let b0 = [0i8, 3];
+        let b_scale = [1.0f32, 0.25];
+        let c_scale = 2.0f32;
+        let reference = Array2::from_shape_fn((2, 2), |(m, n)| {
+            let acc: f32 = (0..3)
+                .map(|k| {
+                    (a[(m, k)] as f32 - a0[m] as f32)
+                        * a_scale[m]
+                        * (b[(k, n)] as f32 - b0[n] as f32)
+                        * b_scale[n]
+                })
+                .sum();
+            round_ties_to_right(acc / c_scale).max(-128).min(127) as i8
+        });
+        let reference = if c_trans { reference.reversed_axes() } else { reference };
+
+        let mut model = TypedModel::default();
+        let mut inputs = vec![];
+        let a_wire = if const_a {
+            model.add_const("a", a.clone().into_tensor()).unwrap()
+        } else {
+            inputs.push(a.clone().into_tensor());
+            model.add_source("a", i8::fact(&[2, 3])).unwrap()
+        };
+        let b_wire = if const_b {
+            model.add_const("b", b.clone().into_tensor()).unwrap()
+        } else {
+            inputs.push(b.clone().into_tensor());
+            model.add_source("b", i8::fact(&[3, 2])).unwrap()
+        };
+        let bias = model.add_const("bias", tensor0(0i32)).unwrap();
+        let qparams = MatMulQParams {
+            a0: tensor1(&a0).into(),
+            a_scale: tensor1(&a_scale).into(),
+            b0: tensor1(&b0).into(),
+            b_scale: tensor1(&b_scale).into(),
+            c0: tensor0(0i8).into(),
+            c_scale: tensor0(c_scale).into(),
+        };
+        let op = QMatMul::new(false, false, c_trans, i8::datum_type(), qparams);
+        let output = model.wire_node("qmm", op, &[a_wire, b_wire, bias]).unwrap();
+        model.set_output_outlets(&output).unwrap();
+        for model in [model.clone(), model.into_optimized().unwrap()] {
+            let output = model.into_runnable().unwrap().run(inputs.iter().cloned().collect());
+            let output = output.unwrap().remove(0).into_tensor().into_array::<i8>().unwrap();
+            assert_eq!(output, reference.clone().into_dyn());
+        }
+    }
+
+    #[test]
+    fn per_axis() {
+        for (const_a, const_b, c_trans) in [
+            (false, false, false),
+            (true, false, false),
+            (false, true, false),
+            (true, false, true),
+            (false, true, true),
+        ] {
+            per_axis_check(const_a, const_b, c_trans);
+        }
+    }
 }
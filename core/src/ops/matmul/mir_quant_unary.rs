@@ -2,7 +2,9 @@ use ops::matmul::mir_quant::wire_matmul_quant;
 
 use crate::internal::*;
 use crate::ops;
-use crate::ops::matmul::mir_quant::{combine_scales, requant, wire_offset_u8_as_i8};
+use crate::ops::matmul::mir_quant::{
+    combine_scales, requant, wire_offset_u8_as_i8, wire_per_axis_qparams,
+};
 use crate::ops::matmul::*;
 use mir_quant::MatMulQParams;
 use mir_quant::QParamKind;
@@ -149,7 +151,7 @@ impl TypedOp for QMatMulUnary {
             let mut invs = super::mir_unary::mir_unary_invariants(
                 inputs[0],
                 outputs[0],
-                &self.a,
+                self.a.shape(),
                 self.b_trans,
                 self.c_trans,
             )?;
@@ -241,11 +243,25 @@ impl TypedOp for QMatMulUnary {
             } else {
                 self.bias.clone()
             };
+            let mut params = self.params.clone();
+            for qp in [&mut params.a0, &mut params.a_scale] {
+                match qp {
+                    QParamKind::Attr(t) if t.len() > 1 => {
+                        *qp = QParamKind::Attr(t.slice(0, start, end)?.into_arc_tensor())
+                    }
+                    QParamKind::FromInput(ix)
+                        if model.outlet_fact(node.inputs[*ix])?.rank() > 0 =>
+                    {
+                        return Ok(None)
+                    }
+                    _ => (),
+                }
+            }
             let wire = patch.tap_model(model, node.inputs[0])?;
             return Ok(Some((
                 patch.wire_node(
                     format!("{}.{}", node.name, suffix),
-                    Self { a, bias, ..self.clone() },
+                    Self { a, bias, params, ..self.clone() },
                     &[wire],
                 )?[0],
                 true,
@@ -286,7 +302,7 @@ impl TypedOp for QMatMulUnary {
                     .skip(1)
                     .map(|o| patch.tap_model(model, *o))
                     .collect::<TractResult<TVec<_>>>()?;
-                let params_outlets = self.params.as_outlet_ids(
+                let mut params_outlets = self.params.as_outlet_ids(
                     &mut patch,
                     &*node.name,
                     &input_outlets,
@@ -294,6 +310,15 @@ impl TypedOp for QMatMulUnary {
                     model.node_input_facts(node.id)?[0].datum_type,
                     self.output_type,
                 )?;
+                let rank = self.a.rank();
+                wire_per_axis_qparams(
+                    &mut patch,
+                    &node.name,
+                    &mut params_outlets,
+                    rank,
+                    rank - 2 + self.c_trans as usize,
+                    rank - 1 - self.c_trans as usize,
+                )?;
 
                 let scale = combine_scales(
                     &mut patch,
@@ -1,6 +1,9 @@
-use super::lir_unary::{ConcreteMatMulGeometry, LirMatMulUnary, MatMulGeometry, ProtoFusedSpec};
+use super::lir_unary::{
+    ConcreteMatMulGeometry, LirMatMulUnary, MatMulGeometry, PackedA, ProtoFusedSpec,
+};
 use super::*;
 use crate::internal::*;
+use tract_linalg::frame::sparse::{sparsity_threshold, BlockSparseWeights};
 use tract_ndarray::prelude::*;
 
 /// The pseudo Unary matrix multiplier. A is constant, B is the input
@@ -64,7 +67,7 @@ impl TypedOp for MatMulUnary {
     }
 
     fn invariants(&self, inputs: &[&TypedFact], outputs: &[&TypedFact]) -> TractResult<Invariants> {
-        mir_unary_invariants(inputs[0], outputs[0], &self.a, self.b_trans, self.c_trans)
+        mir_unary_invariants(inputs[0], outputs[0], self.a.shape(), self.b_trans, self.c_trans)
     }
 
     fn change_axes(
@@ -179,26 +182,35 @@ impl MatMulUnary {
         b_shape: &[usize],
         b_dt: DatumType,
     ) -> TractResult<TypedModelPatch> {
-        let mut patch = TypedModelPatch::default();
-        let mut wire = patch.tap_model(model, node.inputs[0])?;
-
         let c_dt = output_type(self.a.datum_type());
         let (m, k, n, c_shape) =
             compute_shape(self.a.shape(), b_shape, self.a_trans, self.b_trans, self.c_trans)?;
+        let mmm = mmm_for_unary(self.a.datum_type(), b_dt, c_dt, m, k, n)?;
+        let sparse =
+            self.a.datum_type() == f32::datum_type() && mmm.internal_type() == f32::datum_type();
+        let threshold = sparsity_threshold();
 
-        let mmm = tract_linalg::ops()
-            .mmm(self.a.datum_type(), b_dt, c_dt, Some(m), Some(k), Some(n))
-            .with_context(|| {
-                format!(
-                    "No matrix multiplier for {:?}x{:?} to {:?}",
-                    self.a.datum_type(),
-                    b_dt,
-                    c_dt
-                )
-            })?;
-
-        let packed_as =
+        let key = PackingKey {
+            a: Arc::as_ptr(&self.a) as usize,
+            a_trans: self.a_trans,
+            packer: mmm.a_pack(),
+            mr: mmm.mr(),
+            k,
+            m,
+            sparsity_threshold: if sparse { threshold.to_bits() } else { 0 },
+        };
+        let packed_as = packed_a_cache(&self.a, key, || {
             Array::from_shape_fn(&self.a.shape()[0..self.a.rank() - 2], |a_prefix| unsafe {
+                if sparse {
+                    let mut a = self.a.to_array_view_unchecked::<f32>();
+                    a_prefix.slice().iter().for_each(|d| a.index_axis_inplace(Axis(0), *d));
+                    let a = a.into_dimensionality::<Ix2>().unwrap();
+                    let a = if self.a_trans { a.reversed_axes() } else { a };
+                    if BlockSparseWeights::block_sparsity(mmm.mr(), a.view()) >= threshold {
+                        let a = BlockSparseWeights::from_dense(mmm.mr(), a);
+                        return (PackedA::Sparse(Arc::new(a)), vec![ProtoFusedSpec::Store]);
+                    }
+                }
                 let mut pa = Tensor::uninitialized_aligned_dt(
                     self.a.datum_type(),
                     &[mmm.a_pack().len(k, m)],
@@ -211,47 +223,22 @@ impl MatMulUnary {
                     !self.a_trans as usize,
                     self.a_trans as usize,
                 );
-                (pa.into_arc_tensor(), vec![ProtoFusedSpec::Store])
-            });
-        unsafe {
-            let mut packed_b_shape: TVec<usize> = b_shape[..b_shape.len() - 2].into();
-            packed_b_shape.push(mmm.b_pack().len(k, n));
-            wire = patch.wire_node(
-                format!("{}.pack", &*node.name),
-                super::MatMatMulPack {
-                    packer: mmm.b_pack(),
-                    trans: self.b_trans,
-                    output_shape: packed_b_shape,
-                },
-                &[wire],
-            )?[0];
-            let b_storage = mmm.b_packed(b_dt.size_of(), k);
-            let rank = c_shape.len();
-            let mut strides = natural_strides(&c_shape);
-            let mut overrided_shape = c_shape.clone();
-            if self.c_trans {
-                overrided_shape.swap(rank - 2, rank - 1);
-                strides.swap(rank - 2, rank - 1);
-            }
-            let geometry = ConcreteMatMulGeometry { m, k, n, b_storage };
-            wire = patch.wire_node(
-                format!("{}.matmatmul", &*node.name),
-                LirMatMulUnary {
-                    c_fact: c_dt.fact(&c_shape),
-                    geometry: MatMulGeometry::Concrete(geometry),
-                    micro_ops: packed_as,
-                    c_m_axis: rank - 2 + self.c_trans as usize,
-                    c_n_axis: rank - 2 + !self.c_trans as usize,
-                    c_final_shape: c_shape.into(),
-                    reshape_post: vec![],
-                    mmm,
-                },
-                &[wire],
-            )?[0];
-            patch.shunt_outside(model, OutletId::new(node.id, 0), wire)?;
-            patch.obliterate(node.id)?;
-        }
-        Ok(patch)
+                (PackedA::Packed(pa.into_arc_tensor()), vec![ProtoFusedSpec::Store])
+            })
+        });
+        lir_mat_mul_unary_patch(
+            model,
+            node,
+            mmm,
+            (m, k, n),
+            c_dt,
+            c_shape,
+            b_shape,
+            b_dt,
+            self.b_trans,
+            self.c_trans,
+            packed_as,
+        )
     }
 
     fn declutter_precusor_is_concat(
@@ -414,17 +401,177 @@ impl MatMulUnary {
     }
 }
 
+type PackedAs = ArrayD<(PackedA, Vec<ProtoFusedSpec>)>;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct PackingKey {
+    a: usize,
+    a_trans: bool,
+    packer: tract_linalg::frame::Packer,
+    mr: usize,
+    k: usize,
+    m: usize,
+    sparsity_threshold: u32,
+}
+
+/// Packings of constant A operands, shared by the models optimized in its scope.
+///
+/// Plans optimized from one decluttered model (the shape-specialized variants of a polymorphic
+/// plan, typically) share the constants, so they pack them only once. Entries are dropped when
+/// their source tensor is. Outside of a scope, A is packed for each optimized model.
+#[derive(Default)]
+pub struct PackedACache(std::sync::Mutex<HashMap<PackingKey, (std::sync::Weak<Tensor>, PackedAs)>>);
+
+impl std::fmt::Debug for PackedACache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PackedACache")
+    }
+}
+
+thread_local! {
+    static PACKED_A_CACHE: std::cell::RefCell<Option<Arc<PackedACache>>> = Default::default();
+}
+
+impl PackedACache {
+    /// Runs `f` with this cache in use for the codegen of the current thread.
+    pub fn scope<R>(self: &Arc<Self>, f: impl FnOnce() -> R) -> R {
+        struct Restore(Option<Arc<PackedACache>>);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                let previous = self.0.take();
+                PACKED_A_CACHE.with(|c| *c.borrow_mut() = previous);
+            }
+        }
+        let _restore = Restore(PACKED_A_CACHE.with(|c| c.borrow_mut().replace(self.clone())));
+        f()
+    }
+
+    fn get_or_pack(
+        &self,
+        a: &Arc<Tensor>,
+        key: PackingKey,
+        pack: impl FnOnce() -> PackedAs,
+    ) -> PackedAs {
+        {
+            let mut cache = self.0.lock().unwrap_or_else(|e| e.into_inner());
+            cache.retain(|_, (source, _)| source.strong_count() > 0);
+            if let Some((_, packed)) = cache.get(&key) {
+                return packed.clone();
+            }
+        }
+        // pack without holding the lock, if another thread packed concurrently, keep its result
+        let packed = pack();
+        let mut cache = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        match cache.get(&key) {
+            Some((source, packed)) if source.strong_count() > 0 => packed.clone(),
+            _ => {
+                cache.insert(key, (Arc::downgrade(a), packed.clone()));
+                packed
+            }
+        }
+    }
+}
+
+/// Packs A, through the packed A cache in scope if any.
+fn packed_a_cache(a: &Arc<Tensor>, key: PackingKey, pack: impl FnOnce() -> PackedAs) -> PackedAs {
+    match PACKED_A_CACHE.with(|c| c.borrow().clone()) {
+        Some(cache) => cache.get_or_pack(a, key, pack),
+        None => pack(),
+    }
+}
+
+pub(super) fn mmm_for_unary(
+    a_dt: DatumType,
+    b_dt: DatumType,
+    c_dt: DatumType,
+    m: usize,
+    k: usize,
+    n: usize,
+) -> TractResult<Box<dyn tract_linalg::mmm::MatMatMul>> {
+    let tuned = if n > 1
+        && a_dt == f32::datum_type()
+        && b_dt == f32::datum_type()
+        && c_dt == f32::datum_type()
+    {
+        tract_linalg::mmm::autotune::mmm_f32(m, k, n)
+    } else {
+        None
+    };
+    tuned
+        .or_else(|| tract_linalg::ops().mmm(a_dt, b_dt, c_dt, Some(m), Some(k), Some(n)))
+        .with_context(|| format!("No matrix multiplier for {:?}x{:?} to {:?}", a_dt, b_dt, c_dt))
+}
+
+/// Replace a unary matmul node by B packing and a LirMatMulUnary running on the
+/// given A micro ops.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn lir_mat_mul_unary_patch(
+    model: &TypedModel,
+    node: &TypedNode,
+    mmm: Box<dyn tract_linalg::mmm::MatMatMul>,
+    (m, k, n): (usize, usize, usize),
+    c_dt: DatumType,
+    c_shape: TVec<usize>,
+    b_shape: &[usize],
+    b_dt: DatumType,
+    b_trans: bool,
+    c_trans: bool,
+    micro_ops: ArrayD<(PackedA, Vec<ProtoFusedSpec>)>,
+) -> TractResult<TypedModelPatch> {
+    let mut patch = TypedModelPatch::default();
+    let mut wire = patch.tap_model(model, node.inputs[0])?;
+    unsafe {
+        let mut packed_b_shape: TVec<usize> = b_shape[..b_shape.len() - 2].into();
+        packed_b_shape.push(mmm.b_pack().len(k, n));
+        wire = patch.wire_node(
+            format!("{}.pack", &*node.name),
+            super::MatMatMulPack {
+                packer: mmm.b_pack(),
+                trans: b_trans,
+                output_shape: packed_b_shape,
+            },
+            &[wire],
+        )?[0];
+        let b_storage = mmm.b_packed(b_dt.size_of(), k);
+        let rank = c_shape.len();
+        let mut strides = natural_strides(&c_shape);
+        let mut overrided_shape = c_shape.clone();
+        if c_trans {
+            overrided_shape.swap(rank - 2, rank - 1);
+            strides.swap(rank - 2, rank - 1);
+        }
+        let geometry = ConcreteMatMulGeometry { m, k, n, b_storage };
+        wire = patch.wire_node(
+            format!("{}.matmatmul", &*node.name),
+            LirMatMulUnary {
+                c_fact: c_dt.fact(&c_shape),
+                geometry: MatMulGeometry::Concrete(geometry),
+                micro_ops,
+                c_m_axis: rank - 2 + c_trans as usize,
+                c_n_axis: rank - 2 + !c_trans as usize,
+                c_final_shape: c_shape.into(),
+                reshape_post: vec![],
+                mmm,
+            },
+            &[wire],
+        )?[0];
+        patch.shunt_outside(model, OutletId::new(node.id, 0), wire)?;
+        patch.obliterate(node.id)?;
+    }
+    Ok(patch)
+}
+
 pub(super) fn mir_unary_invariants(
     input_fact: &TypedFact,
     output_fact: &TypedFact,
-    a: &Tensor,
+    a_shape: &[usize],
     b_trans: bool,
     c_trans: bool,
 ) -> TractResult<Invariants> {
     if input_fact.shape.rank() != output_fact.shape.rank() {
         return Ok(Invariants::none());
     }
-    let mut broadcasted_a_shape: TVec<_> = a.shape().into();
+    let mut broadcasted_a_shape: TVec<_> = a_shape.into();
     while broadcasted_a_shape.len() < input_fact.shape.rank() {
         broadcasted_a_shape.insert(0, 1);
     }
@@ -441,3 +588,63 @@ pub(super) fn mir_unary_invariants(
     };
     Ok(invars.into_iter().collect())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops::binary::UnaryOp;
+    use crate::ops::math;
+
+    fn pruned(a_trans: bool) -> TractResult<()> {
+        // three columns out of four are zero, so 75% of the blocks are empty
+        // whatever the kernel mr
+        let (m, k, n) = (37, 24, 7);
+        let a = Array3::from_shape_fn((1, m, k), |(_, m, k)| {
+            if k % 4 == 0 {
+                (m + k) as f32 / 8. - 2.
+            } else {
+                0.
+            }
+        })
+        .into_tensor();
+        let a = if a_trans { a.permute_axes(&[0, 2, 1])? } else { a };
+        let bias =
+            Array3::from_shape_fn((1, m, 1), |(_, m, _)| m as f32 / 4. - 3.).into_arc_tensor();
+        let mut model = TypedModel::default();
+        let source = model.add_source("b", f32::fact([1, k, n]))?;
+        let wire = model.wire_node(
+            "mm",
+            MatMulUnary::new(a.into_arc_tensor(), a_trans, false, false),
+            &[source],
+        )?;
+        let wire = model.wire_node("bias", math::add::unary(bias), &wire)?;
+        let wire = model.wire_node("relu", math::max::unary(rctensor3(&[[[0f32]]])), &wire)?;
+        model.set_output_outlets(&wire)?;
+
+        let b = Tensor::from_shape(
+            &[1, k, n],
+            &(0..k * n).map(|i| (i % 11) as f32 / 5. - 1.).collect::<Vec<_>>(),
+        )?;
+        let expected = model.clone().into_runnable()?.run(tvec!(b.clone()))?.remove(0);
+        let optimized = model.into_optimized()?;
+        let lir = optimized
+            .nodes()
+            .iter()
+            .find_map(|n| n.op_as::<LirMatMulUnary>())
+            .context("no LirMatMulUnary")?;
+        assert!(lir.micro_ops.iter().all(|(a, _)| matches!(a, PackedA::Sparse(_))));
+        assert!(optimized.nodes().iter().all(|n| !n.op_is::<UnaryOp>()));
+        let found = optimized.into_runnable()?.run(tvec!(b))?.remove(0);
+        found.close_enough(&expected, true)
+    }
+
+    #[test]
+    fn pruned_weights_are_sparse() -> TractResult<()> {
+        pruned(false)
+    }
+
+    #[test]
+    fn pruned_weights_are_sparse_trans() -> TractResult<()> {
+        pruned(true)
+    }
+}
@@ -0,0 +1,230 @@
+use super::lir_unary::{PackedA, ProtoFusedSpec};
+use super::mir_unary::{lir_mat_mul_unary_patch, mir_unary_invariants, mmm_for_unary};
+use super::*;
+use crate::internal::*;
+use tract_linalg::frame::weight_quant::{QuantizedWeights, WeightQuant};
+use tract_ndarray::prelude::*;
+
+/// Unary matrix multiplier with a constant A stored as int8 or int4 weights and
+/// per-group scales. B and C are f32. A is m×k, broadcast on a prefix of ones.
+#[derive(Debug, Clone, new, Hash)]
+pub struct WeightQuantMatMulUnary {
+    pub a: Arc<QuantizedWeights>,
+    pub a_rank: usize,
+    pub b_trans: bool,
+    pub c_trans: bool,
+}
+
+impl_dyn_hash!(WeightQuantMatMulUnary);
+
+impl WeightQuantMatMulUnary {
+    /// Quantize the weights of a f32 MatMulUnary, if its A is a single matrix.
+    pub fn from_mat_mul_unary(op: &MatMulUnary, quant: WeightQuant) -> TractResult<Option<Self>> {
+        let rank = op.a.rank();
+        if op.a.datum_type() != f32::datum_type()
+            || rank < 2
+            || op.a.shape()[..rank - 2].iter().any(|d| *d != 1)
+        {
+            return Ok(None);
+        }
+        let mut a =
+            op.a.to_array_view::<f32>()?
+                .into_shape((op.a.shape()[rank - 2], op.a.shape()[rank - 1]))?;
+        if op.a_trans {
+            a = a.reversed_axes();
+        }
+        let (m, k) = a.dim();
+        let weights: Vec<f32> = a.iter().cloned().collect();
+        let a = QuantizedWeights::quantize(quant, m, k, &weights)?;
+        Ok(Some(WeightQuantMatMulUnary::new(Arc::new(a), rank, op.b_trans, op.c_trans)))
+    }
+
+    pub fn a_shape(&self) -> TVec<usize> {
+        let mut shape: TVec<usize> = tvec!(1; self.a_rank - 2);
+        shape.push(self.a.m);
+        shape.push(self.a.k);
+        shape
+    }
+}
+
+impl Op for WeightQuantMatMulUnary {
+    fn name(&self) -> Cow<str> {
+        "WeightQuantMatMulUnary".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![
+            format!("b_trans:{:?} c_trans:{:?}", self.b_trans, self.c_trans),
+            format!("A: {:?} {}", self.a_shape(), self.a.quant),
+        ])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for WeightQuantMatMulUnary {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let a = self.a.dequantize().into_shape(&self.a_shape())?;
+        let t = eval(&a, &inputs[0], false, self.b_trans, self.c_trans)?;
+        Ok(tvec!(t.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for WeightQuantMatMulUnary {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        ensure!(
+            inputs[0].rank() == self.a_rank,
+            "Inconsistent matmul between input {:?} and weights {:?} (rank mismatch)",
+            inputs[0],
+            self.a_shape()
+        );
+        ensure!(
+            inputs[0].datum_type == f32::datum_type(),
+            "Expected f32 input, got {:?}",
+            inputs[0]
+        );
+        let (_m, _k, _n, c_shape) = compute_shape(
+            &self.a_shape().iter().map(|d| d.to_dim()).collect::<TVec<_>>(),
+            &inputs[0].shape,
+            false,
+            self.b_trans,
+            self.c_trans,
+        )?;
+        Ok(tvec!(f32::fact(c_shape)))
+    }
+
+    fn invariants(&self, inputs: &[&TypedFact], outputs: &[&TypedFact]) -> TractResult<Invariants> {
+        mir_unary_invariants(inputs[0], outputs[0], &self.a_shape(), self.b_trans, self.c_trans)
+    }
+
+    fn change_axes(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+        _io: InOut,
+        change: &AxisOp,
+    ) -> TractResult<Option<AxisChangeConsequence>> {
+        let b = &model.outlet_fact(node.inputs[0])?;
+        let op = match change {
+            AxisOp::Move(from, to) if *from == b.rank() - 2 && *to == b.rank() - 1 => {
+                Self { b_trans: !self.b_trans, c_trans: !self.c_trans, ..self.clone() }
+            }
+            AxisOp::Add(axis) if *axis < b.rank() - 1 => {
+                Self { a_rank: self.a_rank + 1, ..self.clone() }
+            }
+            AxisOp::Rm(axis) if b.rank() - axis > 2 => {
+                Self { a_rank: self.a_rank - 1, ..self.clone() }
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(AxisChangeConsequence::new(model, node, Some(Box::new(op)), change)))
+    }
+
+    fn cost(&self, inputs: &[&TypedFact]) -> TractResult<TVec<(Cost, TDim)>> {
+        let mut cost = super::mir::cost(
+            &self.a_shape(),
+            &inputs[0].shape.to_tvec(),
+            f32::datum_type(),
+            false,
+            self.b_trans,
+        )?;
+        cost.push((Cost::Params(u8::datum_type()), self.a.data.len().to_dim()));
+        cost.push((Cost::Params(f32::datum_type()), self.a.scales.len().to_dim()));
+        Ok(cost)
+    }
+
+    fn codegen(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        let b = args_1!(model.node_input_facts(node.id)?);
+        let b_shape = if let Some(b_shape) = b.shape.as_concrete() {
+            b_shape
+        } else {
+            return Ok(None);
+        };
+        let (m, k, n, c_shape) =
+            compute_shape(&self.a_shape(), b_shape, false, self.b_trans, self.c_trans)?;
+        let f32 = f32::datum_type();
+        let mmm = mmm_for_unary(f32, f32, f32, m, k, n)?;
+        if mmm.internal_type() != f32 {
+            return Ok(None);
+        }
+        let micro_ops = Array::from_elem(
+            &*tvec!(1; self.a_rank - 2),
+            (PackedA::QuantizedWeights(self.a.clone()), vec![ProtoFusedSpec::Store]),
+        );
+        Ok(Some(lir_mat_mul_unary_patch(
+            model,
+            node,
+            mmm,
+            (m, k, n),
+            f32,
+            c_shape,
+            b_shape,
+            f32,
+            self.b_trans,
+            self.c_trans,
+            micro_ops,
+        )?))
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tract_linalg::frame::weight_quant::WeightFormat;
+
+    fn check(format: WeightFormat, a_trans: bool, b_trans: bool, c_trans: bool) -> TractResult<()> {
+        // one extremal value per group of 4 along k makes every scale 0.5, so
+        // quantization is lossless and results can be compared closely
+        let max = if format == WeightFormat::Int8 { 127 } else { 7 };
+        let a = tract_ndarray::Array3::from_shape_fn((1, 6, 10), |(_, m, k)| {
+            let q = if k % 4 == 0 { max } else { (m * 10 + k) as i32 % (2 * max + 1) - max };
+            q as f32 * 0.5
+        })
+        .into_tensor();
+        let a = if a_trans { a.permute_axes(&[0, 2, 1])? } else { a };
+        let op = MatMulUnary::new(a.into_arc_tensor(), a_trans, b_trans, c_trans);
+        let quant = WeightQuant { format, group_size: 4 };
+        let wq = WeightQuantMatMulUnary::from_mat_mul_unary(&op, quant)?.unwrap();
+        let b_shape: &[usize] = if b_trans { &[1, 5, 10] } else { &[1, 10, 5] };
+        let b = Tensor::from_shape(b_shape, &(0..50).map(|i| i as f32 / 10.).collect::<Vec<_>>())?;
+
+        let expected = op.eval(tvec!(b.clone().into_arc_tensor()))?.remove(0);
+        let found = wq.eval(tvec!(b.clone().into_arc_tensor()))?.remove(0);
+        found.close_enough(&expected, true)?;
+
+        let mut model = TypedModel::default();
+        let source = model.add_source("b", f32::fact(b_shape))?;
+        let output = model.wire_node("mm", wq, &[source])?;
+        model.set_output_outlets(&output)?;
+        let model = model.into_optimized()?;
+        assert!(model.nodes().iter().all(|n| !n.op_is::<WeightQuantMatMulUnary>()));
+        let found = model.into_runnable()?.run(tvec!(b))?.remove(0);
+        found.close_enough(&expected, true)
+    }
+
+    #[test]
+    fn i8() -> TractResult<()> {
+        check(WeightFormat::Int8, false, false, false)
+    }
+
+    #[test]
+    fn i4() -> TractResult<()> {
+        check(WeightFormat::Int4, false, false, false)
+    }
+
+    #[test]
+    fn i4_trans() -> TractResult<()> {
+        check(WeightFormat::Int4, true, true, true)
+    }
+}
@@ -20,6 +20,8 @@ pub mod change_axes;
 pub mod cnn;
 pub mod downsample;
 pub mod dummy;
+pub mod fft;
+pub mod fused_element_wise;
 pub mod identity;
 pub mod konst;
 pub mod logic;
@@ -28,7 +30,9 @@ pub mod matmul;
 pub mod nn;
 pub mod quant;
 pub mod scan;
+pub mod sequence;
 pub mod source;
+pub mod string;
 pub mod unimpl;
 
 pub use downsample::Downsample;
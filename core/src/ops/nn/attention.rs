@@ -0,0 +1,397 @@
+use crate::internal::*;
+use crate::ops::binary::{BinMiniOp, TypedBinOp, UnaryOp};
+use crate::ops::math::{Add, Mul};
+use crate::ops::matmul::MatMul;
+use crate::ops::nn::Softmax;
+use ndarray::prelude::*;
+
+/// Mask values at or below this threshold are considered masked out.
+const MASKED: f32 = -1e4;
+
+/// Scaled dot-product self-attention restricted to a band around the query.
+///
+/// Queries, keys and values are laid out as `[..., T, D]`. Query `t` attends
+/// to the keys `t - left_context ..= t + right_context` (clamped to the
+/// sequence), which is the form of attention a chunked streaming encoder can
+/// run with a bounded key/value cache.
+#[derive(Clone, Debug, new, Educe)]
+#[educe(Hash)]
+pub struct BandedAttention {
+    pub left_context: usize,
+    pub right_context: usize,
+    #[educe(Hash(method = "hash_f32"))]
+    pub scale: f32,
+}
+
+impl_dyn_hash!(BandedAttention);
+
+impl BandedAttention {
+    /// Attend a single query to a window of keys and values, writing the
+    /// weighted sum of values to `output`.
+    pub fn attend(
+        &self,
+        query: ArrayView1<f32>,
+        keys: ArrayView2<f32>,
+        values: ArrayView2<f32>,
+        mut output: ArrayViewMut1<f32>,
+    ) {
+        let scores = keys.dot(&query) * self.scale;
+        let max = scores.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let weights = scores.mapv(|s| (s - max).exp());
+        let sum = weights.sum();
+        output.assign(&(weights.dot(&values) / sum));
+    }
+
+    fn eval_t(&self, q: &Tensor, k: &Tensor, v: &Tensor) -> TractResult<Tensor> {
+        let rank = q.rank();
+        let t = q.shape()[rank - 2];
+        let n = q.shape()[..rank - 2].iter().product::<usize>();
+        let q = q.to_array_view::<f32>()?.into_shape((n, t, q.shape()[rank - 1]))?;
+        let k = k.to_array_view::<f32>()?.into_shape((n, t, k.shape()[rank - 1]))?;
+        let v = v.to_array_view::<f32>()?.into_shape((n, t, v.shape()[rank - 1]))?;
+        let mut output = Array3::<f32>::zeros((n, t, v.shape()[2]));
+        for b in 0..n {
+            for query in 0..t {
+                let lo = query.saturating_sub(self.left_context);
+                let hi = (query + self.right_context + 1).min(t);
+                self.attend(
+                    q.slice(s![b, query, ..]),
+                    k.slice(s![b, lo..hi, ..]),
+                    v.slice(s![b, lo..hi, ..]),
+                    output.slice_mut(s![b, query, ..]),
+                );
+            }
+        }
+        Ok(output.into_tensor())
+    }
+}
+
+// the variable input and the constant operand of a binary op with a constant
+fn const_operand<M: BinMiniOp>(
+    model: &TypedModel,
+    node: &TypedNode,
+) -> TractResult<Option<(OutletId, Arc<Tensor>)>> {
+    if let Some(op) = node.op_as::<UnaryOp>() {
+        if op.mini_op.is::<M>() {
+            return Ok(Some((node.inputs[0], op.a.clone())));
+        }
+    } else if let Some(op) = node.op_as::<TypedBinOp>() {
+        if op.0.is::<M>() {
+            for ix in 0..2 {
+                if let Some(konst) = &model.outlet_fact(node.inputs[ix])?.konst {
+                    return Ok(Some((node.inputs[1 - ix], konst.clone())));
+                }
+            }
+        }
+    }
+    Ok(None)
+}
+
+// context of an additive mask made of a band of zeroes around the diagonal,
+// surrounded by masked values
+fn band_context(mask: &Tensor, t: usize) -> TractResult<Option<(usize, usize)>> {
+    if mask.len() != t * t || mask.shape().iter().rev().skip(2).any(|d| *d != 1) {
+        return Ok(None);
+    }
+    let mask = mask.cast_to::<f32>()?;
+    let mask = mask.to_array_view::<f32>()?.into_shape((t, t))?;
+    let mut left = 0;
+    let mut right = 0;
+    for ((query, key), v) in mask.indexed_iter() {
+        if *v == 0.0 {
+            left = left.max(query.saturating_sub(key));
+            right = right.max(key.saturating_sub(query));
+        }
+    }
+    let is_band = mask.indexed_iter().all(|((query, key), v)| {
+        if key + left >= query && key <= query + right {
+            *v == 0.0
+        } else {
+            *v <= MASKED
+        }
+    });
+    Ok(Some((left, right)).filter(|_| is_band))
+}
+
+// context of a mask that depends on no model input: read from the mask if it is a
+// constant, otherwise from the mask subgraph evaluated at two values of the symbolic
+// length `t`, which must agree
+fn mask_band(model: &TypedModel, mask: OutletId, t: &TDim) -> TractResult<Option<(usize, usize)>> {
+    if let Some(konst) = &model.outlet_fact(mask)?.konst {
+        return if let Ok(t) = t.to_usize() { band_context(konst, t) } else { Ok(None) };
+    }
+    let symbols = t.symbols();
+    if symbols.len() != 1 {
+        return Ok(None);
+    }
+    let symbol = symbols.into_iter().next().unwrap();
+    let subgraph = if let Ok(subgraph) = model.extract_subgraph(&[], &[mask]) {
+        subgraph
+    } else {
+        return Ok(None);
+    };
+    let plan = SimplePlan::new(subgraph)?;
+    let mut bands = tvec!();
+    for probe in [32, 64] {
+        let values = SymbolValues::default().with(symbol, probe);
+        let t = t.eval(&values).to_usize()?;
+        let mut state = SimpleState::new(&plan)?;
+        state.session_state.resolved_symbols = values;
+        match state.run(tvec!()) {
+            Ok(mask) => bands.push(band_context(&mask[0], t)?),
+            Err(_) => return Ok(None),
+        }
+    }
+    Ok(bands[0].filter(|_| bands[0] == bands[1]))
+}
+
+// the scores and the mask operands of the masking Add
+fn mask_operands(
+    model: &TypedModel,
+    node: &TypedNode,
+) -> TractResult<Option<(OutletId, OutletId)>> {
+    if let Some(op) = node.op_as::<TypedBinOp>() {
+        if op.0.is::<Add>() {
+            let depends_on_inputs = |outlet: OutletId| -> TractResult<bool> {
+                let order = crate::model::order::eval_order_for_nodes(
+                    model.nodes(),
+                    &[],
+                    &[outlet.node],
+                    &[],
+                )?;
+                Ok(order.iter().any(|n| model.node(*n).op_is::<crate::ops::source::TypedSource>()))
+            };
+            for ix in 0..2 {
+                if !depends_on_inputs(node.inputs[ix])? {
+                    return Ok(Some((node.inputs[1 - ix], node.inputs[ix])));
+                }
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Recognize `softmax(q·kᵀ × scale + mask)·v` over the last axis, where `mask` is a
+/// band computed without model inputs (a constant, or a subgraph of the symbolic
+/// sequence length), and replace it by a BandedAttention. `softmax` is the Softmax node.
+pub(crate) fn declutter_banded_attention(
+    model: &TypedModel,
+    softmax: &TypedNode,
+) -> TractResult<Option<TypedModelPatch>> {
+    let single_succ = |node: &TypedNode| node.outputs[0].successors.len() == 1;
+    let is_matmul = |node: &TypedNode, b_trans: bool| matches!(node.op_as::<MatMul>(), Some(mm) if !mm.a_trans && mm.b_trans == b_trans && !mm.c_trans);
+    let scores_fact = model.outlet_fact(softmax.inputs[0])?;
+    let rank = scores_fact.rank();
+    if rank < 2 || scores_fact.datum_type != f32::datum_type() || !single_succ(softmax) {
+        return Ok(None);
+    }
+    if !matches!(softmax.op_as::<Softmax>(), Some(op) if *op.axes == [rank - 1]) {
+        return Ok(None);
+    }
+    let t = &scores_fact.shape[rank - 1];
+    let attend = model.node(softmax.outputs[0].successors[0].node);
+    if !is_matmul(attend, false) || attend.inputs[0] != softmax.id.into() {
+        return Ok(None);
+    }
+    let masked = model.node(softmax.inputs[0].node);
+    let (scaled, band) = if let Some((scaled, mask)) = mask_operands(model, masked)? {
+        (scaled, mask_band(model, mask, t)?)
+    } else if let Some((scaled, mask)) = const_operand::<Add>(model, masked)? {
+        (scaled, if let Ok(t) = t.to_usize() { band_context(&mask, t)? } else { None })
+    } else {
+        return Ok(None);
+    };
+    let (left_context, right_context) = if let Some(band) = band { band } else { return Ok(None) };
+    let mut scores = model.node(scaled.node);
+    let mut scale = 1.0;
+    if let Some((unscaled, factor)) = const_operand::<Mul>(model, scores)? {
+        if factor.len() != 1 || !single_succ(scores) {
+            return Ok(None);
+        }
+        scale = factor.cast_to_scalar::<f32>()?;
+        scores = model.node(unscaled.node);
+    }
+    if !is_matmul(scores, true) || !single_succ(scores) || !single_succ(masked) {
+        return Ok(None);
+    }
+    let (q, k, v) = (scores.inputs[0], scores.inputs[1], attend.inputs[1]);
+    let op = BandedAttention::new(left_context, right_context, scale);
+    let facts =
+        [q, k, v].iter().map(|o| model.outlet_fact(*o)).collect::<TractResult<TVec<_>>>()?;
+    match op.output_facts(&facts) {
+        Ok(output) if output[0] == *model.outlet_fact(attend.id.into())? => (),
+        _ => return Ok(None),
+    }
+    let mut patch = TypedModelPatch::default();
+    let taps =
+        [q, k, v].iter().map(|o| patch.tap_model(model, *o)).collect::<TractResult<TVec<_>>>()?;
+    let wire = patch.wire_node(&*attend.name, op, &taps)?;
+    patch.shunt_outside(model, attend.id.into(), wire[0])?;
+    Ok(Some(patch))
+}
+
+impl Op for BandedAttention {
+    fn name(&self) -> Cow<str> {
+        "BandedAttention".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "context: -{}..+{} scale: {}",
+            self.left_context, self.right_context, self.scale
+        )])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for BandedAttention {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (q, k, v) = args_3!(inputs);
+        let mut output_shape: TVec<usize> = q.shape().into();
+        output_shape[q.rank() - 1] = v.shape()[v.rank() - 1];
+        let output = self.eval_t(&q, &k, &v)?.into_shape(&output_shape)?;
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for BandedAttention {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let (q, k, v) = (inputs[0], inputs[1], inputs[2]);
+        let rank = q.rank();
+        ensure!(rank >= 2, "BandedAttention expects inputs of rank 2 or more, got {:?}", q);
+        ensure!(
+            q.datum_type == f32::datum_type()
+                && k.datum_type == f32::datum_type()
+                && v.datum_type == f32::datum_type(),
+            "BandedAttention only supports f32"
+        );
+        ensure!(
+            k.rank() == rank && v.rank() == rank,
+            "BandedAttention inputs must have the same rank"
+        );
+        ensure!(
+            q.shape[..rank - 1] == k.shape[..rank - 1]
+                && q.shape[..rank - 1] == v.shape[..rank - 1],
+            "BandedAttention inputs must agree on all axes but the last one, got {:?} {:?} {:?}",
+            q.shape,
+            k.shape,
+            v.shape
+        );
+        ensure!(
+            q.shape[rank - 1] == k.shape[rank - 1],
+            "BandedAttention queries and keys must have the same depth, got {:?} and {:?}",
+            q.shape,
+            k.shape
+        );
+        let mut shape = q.shape.to_tvec();
+        shape[rank - 1] = v.shape[rank - 1].clone();
+        Ok(tvec!(f32::fact(shape)))
+    }
+
+    fn invariants(&self, inputs: &[&TypedFact], outputs: &[&TypedFact]) -> TractResult<Invariants> {
+        (0..inputs[0].rank() - 2)
+            .map(|axis| AxisInfo::for_facts(inputs, outputs, axis))
+            .collect::<TractResult<TVec<_>>>()
+            .map(Invariants::from)
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn band(left: usize, right: usize) -> impl Fn(usize, usize) -> f32 {
+        move |q, k| if k + left >= q && k <= q + right { 0.0 } else { -1e9 }
+    }
+
+    // softmax(q·kᵀ × scale + mask)·v over [1, t, d] inputs
+    fn decomposed(t: usize, mask: impl Fn(usize, usize) -> f32) -> TypedModel {
+        let mut model = TypedModel::default();
+        let q = model.add_source("q", f32::fact([1, t, 3])).unwrap();
+        let k = model.add_source("k", f32::fact([1, t, 3])).unwrap();
+        let v = model.add_source("v", f32::fact([1, t, 2])).unwrap();
+        let qk = MatMul { a_trans: false, b_trans: true, c_trans: false };
+        let scores = model.wire_node("scores", qk, &[q, k]).unwrap();
+        let scale = model.add_const("scale", rctensor3(&[[[0.5f32]]])).unwrap();
+        let scaled =
+            model.wire_node("scaled", TypedBinOp(Box::new(Mul)), &[scores[0], scale]).unwrap();
+        let mask = Array3::from_shape_fn((1, t, t), |(_, q, k)| mask(q, k));
+        let mask = model.add_const("mask", mask.into_arc_tensor()).unwrap();
+        let masked =
+            model.wire_node("masked", TypedBinOp(Box::new(Add)), &[scaled[0], mask]).unwrap();
+        let softmax = crate::ops::nn::Softmax::new(tvec!(2), f32::datum_type());
+        let weights = model.wire_node("weights", softmax, &masked).unwrap();
+        let wv = MatMul { a_trans: false, b_trans: false, c_trans: false };
+        let output = model.wire_node("output", wv, &[weights[0], v]).unwrap();
+        model.set_output_outlets(&output).unwrap();
+        model
+    }
+
+    #[test]
+    fn decomposed_band_is_replaced() {
+        let model = decomposed(5, band(2, 1));
+        let decluttered = model.clone().into_decluttered().unwrap();
+        assert_eq!(decluttered.nodes().len(), 4);
+        let node = decluttered.node(decluttered.output_outlets().unwrap()[0].node);
+        let op = node.op_as::<BandedAttention>().unwrap();
+        assert_eq!((op.left_context, op.right_context, op.scale), (2, 1, 0.5));
+
+        let input = |shape: &[usize], seed: usize| {
+            let len = shape.iter().product::<usize>();
+            tensor1(&(0..len).map(|i| ((i * 7 + seed) % 11) as f32 / 5. - 1.).collect::<Vec<_>>())
+                .into_shape(shape)
+                .unwrap()
+        };
+        let inputs = tvec!(input(&[1, 5, 3], 0), input(&[1, 5, 3], 3), input(&[1, 5, 2], 5));
+        let expected = model.into_runnable().unwrap().run(inputs.clone()).unwrap();
+        let found = decluttered.into_runnable().unwrap().run(inputs).unwrap();
+        found[0].close_enough(&expected[0], true).unwrap();
+    }
+
+    #[test]
+    fn decomposed_without_band_is_kept() {
+        let not_a_band = |q, k| if q == 2 && k == 0 { -1.0 } else { band(1, 0)(q, k) };
+        let diagonal_masked = |q, k| if q == k { -1e9 } else { 0.0 };
+        for model in [decomposed(4, not_a_band), decomposed(4, diagonal_masked)] {
+            let model = model.into_decluttered().unwrap();
+            assert!(model.nodes().iter().all(|n| !n.op_is::<BandedAttention>()));
+        }
+    }
+
+    #[test]
+    fn softmax_over_queries_is_kept() {
+        let mut model = decomposed(4, band(1, 0));
+        let softmax = model.node_by_name("weights").unwrap().id;
+        model.node_mut(softmax).op = Box::new(Softmax::new(tvec!(1), f32::datum_type()));
+        let model = model.into_decluttered().unwrap();
+        assert!(model.nodes().iter().all(|n| !n.op_is::<BandedAttention>()));
+    }
+
+    #[test]
+    fn band_limits_context() {
+        let op = BandedAttention::new(0, 0, 1.0);
+        let q = tensor2(&[[1f32], [1.], [1.]]);
+        let k = tensor2(&[[1f32], [2.], [3.]]);
+        let v = tensor2(&[[10f32], [20.], [30.]]);
+        let output = op.eval(tvec!(q.into(), k.into(), v.into())).unwrap();
+        assert_eq!(*output[0], tensor2(&[[10f32], [20.], [30.]]));
+    }
+
+    #[test]
+    fn uniform_scores_average_values() {
+        let op = BandedAttention::new(1, 1, 0.5);
+        let q = Tensor::zero::<f32>(&[1, 3, 2]).unwrap();
+        let k = Tensor::zero::<f32>(&[1, 3, 2]).unwrap();
+        let v = tensor3(&[[[0f32], [3.], [6.]]]);
+        let output = op.eval(tvec!(q.into(), k.into(), v.into())).unwrap();
+        assert_eq!(*output[0], tensor3(&[[[1.5f32], [3.], [4.5]]]));
+    }
+}
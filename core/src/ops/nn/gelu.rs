@@ -0,0 +1,181 @@
+use crate::internal::*;
+use crate::ops::binary::{BinMiniOp, TypedBinOp, UnaryOp};
+use crate::ops::element_wise::ElementWiseOp;
+use crate::ops::math::{Add, Cube, Mul};
+
+/// Replaces the erf based GELU subgraph, 0.5 * x * (1 + erf(x / sqrt(2))), by a Gelu op.
+///
+/// The rule is anchored on the Erf node, which lives outside of core (in onnx-opl).
+pub fn declutter_gelu_erf(
+    model: &TypedModel,
+    erf: &TypedNode,
+) -> TractResult<Option<TypedModelPatch>> {
+    let scale = model.node(erf.inputs[0].node);
+    if !unary_is::<Mul>(scale, std::f32::consts::FRAC_1_SQRT_2) {
+        return Ok(None);
+    }
+    declutter_gelu(model, erf, scale.inputs[0], super::gelu())
+}
+
+/// Replaces the tanh approximated GELU subgraph,
+/// 0.5 * x * (1 + tanh(sqrt(2 / pi) * (x + 0.044715 * x^3))), by a GeluApproximate op.
+pub(crate) fn declutter_gelu_tanh(
+    model: &TypedModel,
+    tanh: &TypedNode,
+) -> TractResult<Option<TypedModelPatch>> {
+    let scale = model.node(tanh.inputs[0].node);
+    if !unary_is::<Mul>(scale, (2.0 / std::f32::consts::PI).sqrt()) {
+        return Ok(None);
+    }
+    let sum = model.node(scale.inputs[0].node);
+    if !binary_is::<Add>(sum) {
+        return Ok(None);
+    }
+    for (x, cubic) in [(sum.inputs[0], sum.inputs[1]), (sum.inputs[1], sum.inputs[0])] {
+        let cubic = model.node(cubic.node);
+        if !unary_is::<Mul>(cubic, 0.044715) {
+            continue;
+        }
+        let cube = model.node(cubic.inputs[0].node);
+        if cube.inputs[..] == [x]
+            && cube.op_as::<ElementWiseOp>().map(|ew| ew.0.is::<Cube>()).unwrap_or(false)
+        {
+            return declutter_gelu(model, tanh, x, super::gelu_approximate());
+        }
+    }
+    Ok(None)
+}
+
+/// Matches the common tail, 0.5 * x * (1 + nonlinearity), in any of the association orders
+/// frontends produce, and wires `gelu` on x in place of it.
+fn declutter_gelu(
+    model: &TypedModel,
+    nonlinearity: &TypedNode,
+    x: OutletId,
+    gelu: ElementWiseOp,
+) -> TractResult<Option<TypedModelPatch>> {
+    let dt = model.outlet_fact(x)?.datum_type;
+    if dt != f32::datum_type() && dt != f16::datum_type() {
+        return Ok(None);
+    }
+    let one_plus = if let Some(succ) = only_succ(model, nonlinearity) {
+        succ
+    } else {
+        return Ok(None);
+    };
+    if !unary_is::<Add>(one_plus, 1.0) {
+        return Ok(None);
+    }
+    let next = if let Some(succ) = only_succ(model, one_plus) {
+        succ
+    } else {
+        return Ok(None);
+    };
+    let output = if unary_is::<Mul>(next, 0.5) {
+        // (0.5 * (1 + f)) * x
+        only_succ(model, next).filter(|n| binary_is::<Mul>(n) && other_input(n, next) == Some(x))
+    } else if binary_is::<Mul>(next) {
+        match other_input(next, one_plus) {
+            // (x * (1 + f)) * 0.5
+            Some(other) if other == x => only_succ(model, next).filter(|n| unary_is::<Mul>(n, 0.5)),
+            // (0.5 * x) * (1 + f)
+            Some(other) => {
+                let half = model.node(other.node);
+                Some(next).filter(|_| unary_is::<Mul>(half, 0.5) && half.inputs[..] == [x])
+            }
+            None => None,
+        }
+    } else {
+        None
+    };
+    if let Some(output) = output {
+        let mut patch = TypedModelPatch::default();
+        let wire = patch.tap_model(model, x)?;
+        let wire = patch.wire_node(&output.name, gelu, &[wire])?[0];
+        patch.shunt_outside(model, output.id.into(), wire)?;
+        Ok(Some(patch))
+    } else {
+        Ok(None)
+    }
+}
+
+fn only_succ<'m>(model: &'m TypedModel, node: &TypedNode) -> Option<&'m TypedNode> {
+    if node.outputs.len() != 1 || node.outputs[0].successors.len() != 1 {
+        return None;
+    }
+    Some(model.node(node.outputs[0].successors[0].node))
+}
+
+fn other_input(node: &TypedNode, prec: &TypedNode) -> Option<OutletId> {
+    let prec = OutletId::new(prec.id, 0);
+    if node.inputs[0] == prec {
+        Some(node.inputs[1])
+    } else if node.inputs[1] == prec {
+        Some(node.inputs[0])
+    } else {
+        None
+    }
+}
+
+fn binary_is<Mini: BinMiniOp>(node: &TypedNode) -> bool {
+    node.op_as::<TypedBinOp>().map(|op| op.0.is::<Mini>()).unwrap_or(false)
+}
+
+fn unary_is<Mini: BinMiniOp>(node: &TypedNode, value: f32) -> bool {
+    node.op_as::<UnaryOp>()
+        .filter(|op| op.mini_op.is::<Mini>())
+        .and_then(|op| op.a.as_uniform())
+        .and_then(|a| a.cast_to_scalar::<f32>().ok())
+        .map(|a| (a - value).abs() <= 1e-5 * value.abs())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::math;
+
+    fn half_x_times(model: &mut TypedModel, x: OutletId, f: OutletId) -> TractResult<OutletId> {
+        let one_plus = model.wire_node("one_plus", math::add::unary(rctensor2(&[[1f32]])), &[f])?;
+        let times_x = model.wire_node("times_x", math::mul::bin_typed(), &[x, one_plus[0]])?;
+        Ok(model.wire_node("half", math::mul::unary(rctensor2(&[[0.5f32]])), &times_x)?[0])
+    }
+
+    fn tanh_gelu(cubic: f32) -> TractResult<TypedModel> {
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", f32::fact([2, 3]))?;
+        let cube = model.wire_node("cube", math::cube(), &[x])?;
+        let cubic = model.wire_node("cubic", math::mul::unary(rctensor2(&[[cubic]])), &cube)?;
+        let sum = model.wire_node("sum", math::add::bin_typed(), &[x, cubic[0]])?;
+        let scale = (2.0 / std::f32::consts::PI).sqrt();
+        let scaled = model.wire_node("scaled", math::mul::unary(rctensor2(&[[scale]])), &sum)?;
+        let tanh = model.wire_node("tanh", math::tanh(), &scaled)?;
+        let y = half_x_times(&mut model, x, tanh[0])?;
+        model.set_output_outlets(&[y])?;
+        Ok(model)
+    }
+
+    fn run(model: TypedModel) -> TractResult<Arc<Tensor>> {
+        let input = tensor2(&[[-3f32, -1., -0.1], [0., 0.5, 2.]]);
+        Ok(model.into_runnable()?.run(tvec!(input))?.remove(0))
+    }
+
+    #[test]
+    fn decomposed_tanh_gelu_is_fused() -> TractResult<()> {
+        let model = tanh_gelu(0.044715)?;
+        let decluttered = model.clone().into_decluttered()?;
+        assert_eq!(decluttered.nodes().len(), 2);
+        assert!(decluttered.nodes()[1]
+            .op_as::<ElementWiseOp>()
+            .map(|ew| ew.0.is::<super::super::GeluApproximate>())
+            .unwrap_or(false));
+        run(decluttered)?.close_enough(&*run(model)?, Approximation::Close)
+    }
+
+    #[test]
+    fn other_cubic_coefficient_is_kept() -> TractResult<()> {
+        let decluttered = tanh_gelu(0.5)?.into_decluttered()?;
+        assert!(decluttered.nodes().len() > 2);
+        Ok(())
+    }
+}
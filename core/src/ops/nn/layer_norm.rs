@@ -0,0 +1,338 @@
+use crate::internal::*;
+
+/// Layer normalization over the trailing axes (from `axis` to the last one),
+/// followed by an affine transform. Inputs are the data, the scale and the
+/// bias, the two latter being broadcast to the normalized axes.
+///
+/// Float inputs are normalized in f32. QU8 and QI8 inputs are normalized
+/// with integer arithmetic only: mean and variance are computed on the raw
+/// integers (where the input scale and zero point cancel out), the
+/// normalized values are kept with 16 fractional bits, and the result is
+/// requantized to `output_dt`.
+#[derive(Clone, Debug, new, Educe)]
+#[educe(Hash)]
+pub struct LayerNorm {
+    pub axis: usize,
+    #[educe(Hash(method = "hash_f32"))]
+    pub epsilon: f32,
+    pub output_dt: DatumType,
+}
+
+impl_dyn_hash!(LayerNorm);
+
+impl LayerNorm {
+    /// Broadcast a scale or bias input to the normalized axes of `shape`.
+    fn param(&self, param: &Tensor, shape: &[usize]) -> TractResult<Vec<f32>> {
+        let param = param.cast_to::<f32>()?;
+        let view = param.to_array_view::<f32>()?;
+        let view = view.broadcast(&shape[self.axis..]).with_context(|| {
+            format!("Broadcasting {:?} to normalized shape {:?}", param, &shape[self.axis..])
+        })?;
+        Ok(view.iter().copied().collect())
+    }
+
+    fn eval_float(&self, input: &Tensor, scale: &[f32], bias: &[f32]) -> TractResult<Tensor> {
+        let mut output = input.cast_to::<f32>()?.into_owned();
+        let len = scale.len();
+        for row in output.as_slice_mut::<f32>()?.chunks_mut(len) {
+            let mean = row.iter().sum::<f32>() / len as f32;
+            let var = row.iter().map(|x| (x - mean).powi(2)).sum::<f32>() / len as f32;
+            let inv_std = (var + self.epsilon).sqrt().recip();
+            for ((x, s), b) in row.iter_mut().zip(scale).zip(bias) {
+                *x = (*x - mean) * inv_std * s + b;
+            }
+        }
+        Ok(output.cast_to_dt(self.output_dt)?.into_owned())
+    }
+
+    fn eval_quant(&self, input: &Tensor, scale: &[f32], bias: &[f32]) -> TractResult<Tensor> {
+        let (_, in_scale) = input.datum_type().zp_scale();
+        let (out_zero_point, out_scale) = self.output_dt.zp_scale();
+        let len = scale.len() as i64;
+        // The per-element factors are folded with the output scale once, so
+        // the loop below only deals with integers.
+        let scale: Vec<i128> = scale
+            .iter()
+            .map(|s| (*s as f64 / out_scale as f64 * 65536.0).round() as i128)
+            .collect();
+        let bias: Vec<i64> = bias
+            .iter()
+            .map(|b| (*b as f64 / out_scale as f64).round() as i64 + out_zero_point as i64)
+            .collect();
+        // epsilon in the unit of len² times the variance of the raw integers, with 32
+        // fractional bits so it does not vanish when the input scale is coarse
+        let epsilon =
+            (self.epsilon as f64 / (in_scale as f64).powi(2) * (len * len) as f64 * 4294967296.0)
+                .round() as i128;
+        let min = self.output_dt.unquantized().min_value().cast_to_scalar::<i64>()?;
+        let max = self.output_dt.unquantized().max_value().cast_to_scalar::<i64>()?;
+        let ints = input.cast_to::<i32>()?;
+        let mut output = Tensor::zero::<i32>(input.shape())?;
+        for (row, out) in ints
+            .as_slice::<i32>()?
+            .chunks(scale.len())
+            .zip(output.as_slice_mut::<i32>()?.chunks_mut(scale.len()))
+        {
+            let sum: i64 = row.iter().map(|&x| x as i64).sum();
+            let sum_sq: i64 = row.iter().map(|&x| (x as i64).pow(2)).sum();
+            let var = (((len * sum_sq - sum * sum) as i128) << 32) + epsilon;
+            // len * std, with 16 fractional bits
+            let std = (isqrt(var as u128) as i128).max(1);
+            for (((x, y), s), b) in row.iter().zip(out.iter_mut()).zip(&scale).zip(&bias) {
+                let diff = (len * *x as i64 - sum) as i128;
+                let normalized = rounding_div(diff << 32, std);
+                let scaled = (normalized * s + (1 << 31)) >> 32;
+                *y = (scaled as i64 + b).max(min).min(max) as i32;
+            }
+        }
+        let mut output = output.cast_to_dt(self.output_dt.unquantized())?.into_owned();
+        unsafe { output.set_datum_type(self.output_dt) };
+        Ok(output)
+    }
+}
+
+fn rounding_div(a: i128, b: i128) -> i128 {
+    if a >= 0 {
+        (a + b / 2) / b
+    } else {
+        (a - b / 2) / b
+    }
+}
+
+fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    let bits = 128 - n.leading_zeros();
+    let mut x = 1u128 << (bits / 2 + 1);
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+impl Op for LayerNorm {
+    fn name(&self) -> Cow<str> {
+        "LayerNorm".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("axis: {} epsilon: {}", self.axis, self.epsilon)])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for LayerNorm {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (input, scale, bias) = args_3!(inputs);
+        if input.len() == 0 {
+            return Ok(tvec!(Tensor::zero_dt(self.output_dt, input.shape())?.into_arc_tensor()));
+        }
+        let scale = self.param(&scale, input.shape())?;
+        let bias = self.param(&bias, input.shape())?;
+        let output = if input.datum_type().is_quantized() {
+            self.eval_quant(&input, &scale, &bias)?
+        } else {
+            self.eval_float(&input, &scale, &bias)?
+        };
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for LayerNorm {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        ensure!(inputs.len() == 3, "LayerNorm expects input, scale and bias");
+        let dt = inputs[0].datum_type;
+        if dt.is_float() {
+            ensure!(
+                dt == self.output_dt,
+                "LayerNorm input {:?} and output {:?} types in float case should be equal",
+                dt,
+                self.output_dt
+            );
+        } else if dt.is_quantized() {
+            ensure!(
+                self.output_dt.is_quantized(),
+                "Quantized LayerNorm must have input {:?} and output {:?} quantized",
+                dt,
+                self.output_dt
+            );
+        } else {
+            bail!("Unsupported datum type in LayerNorm: {:?}", dt);
+        }
+        ensure!(self.axis < inputs[0].rank(), "LayerNorm axis {} out of range", self.axis);
+        for param in &inputs[1..] {
+            ensure!(
+                param.rank() <= inputs[0].rank() - self.axis,
+                "LayerNorm scale and bias must be broadcastable to the normalized axes, got {:?}",
+                param
+            );
+        }
+        Ok(tvec!(self.output_dt.fact(inputs[0].shape.clone())))
+    }
+
+    fn declutter(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        if self.output_dt.is_float() {
+            crate::ops::quant::declutter_quant_sandwich(model, node, 1, |output_dt| {
+                Box::new(LayerNorm { output_dt, ..self.clone() })
+            })
+        } else {
+            Ok(None)
+        }
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::quant::{quantize_linear_u8, DequantizeLinearF32};
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+    use tract_data::internal::QParams::ZpScale;
+
+    fn qu8(zero_point: i32, scale: f32) -> DatumType {
+        DatumType::QU8(ZpScale { zero_point, scale })
+    }
+
+    #[test]
+    fn float() -> TractResult<()> {
+        let op = LayerNorm::new(1, 0.0, f32::datum_type());
+        let input = tensor2(&[[1f32, 2., 3., 4.], [0., 0., 2., 2.]]);
+        let output = op.eval(tvec!(
+            input.into_arc_tensor(),
+            rctensor1(&[1f32, 1., 2., 2.]),
+            rctensor0(1f32)
+        ))?;
+        let a = 1.5 / 1.25f32.sqrt();
+        let b = 0.5 / 1.25f32.sqrt();
+        output[0].close_enough(
+            &tensor2(&[[1. - a, 1. - b, 1. + 2. * b, 1. + 2. * a], [0., 0., 3., 3.]]),
+            Approximation::Close,
+        )
+    }
+
+    #[derive(Debug)]
+    struct QuantLayerNormProblem {
+        data: Vec<u8>,
+        input_dt: DatumType,
+        scale: Vec<f32>,
+        bias: Vec<f32>,
+    }
+
+    impl QuantLayerNormProblem {
+        fn check(&self) -> TractResult<()> {
+            let len = self.scale.len();
+            let mut input = tensor1(&self.data).into_shape(&[self.data.len() / len, len])?;
+            unsafe { input.set_datum_type(self.input_dt) };
+            let inputs = tvec!(
+                input.clone().into_arc_tensor(),
+                rctensor1(&self.scale),
+                rctensor1(&self.bias)
+            );
+            let output_dt = qu8(128, 0.05);
+            let found = LayerNorm::new(1, 1e-5, output_dt).eval(inputs.clone())?;
+            let mut float_inputs = inputs;
+            float_inputs[0] = input.cast_to::<f32>()?.into_owned().into_arc_tensor();
+            let reference = LayerNorm::new(1, 1e-5, f32::datum_type()).eval(float_inputs)?;
+            found[0].close_enough(&reference[0], Approximation::Approximate)
+        }
+    }
+
+    impl Arbitrary for QuantLayerNormProblem {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<QuantLayerNormProblem>;
+        fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+            (1usize..4, 2usize..16)
+                .prop_flat_map(|(rows, len)| {
+                    (
+                        vec(any::<u8>(), rows * len..=rows * len),
+                        (0i32..256, 0.001f32..0.1).prop_map(|(zp, scale)| qu8(zp, scale)),
+                        vec(-1f32..1., len..=len),
+                        vec(-1f32..1., len..=len),
+                    )
+                })
+                .prop_map(|(data, input_dt, scale, bias)| QuantLayerNormProblem {
+                    data,
+                    input_dt,
+                    scale,
+                    bias,
+                })
+                .boxed()
+        }
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn quant_prop(pb in any::<QuantLayerNormProblem>()) {
+            pb.check().unwrap()
+        }
+    }
+
+    #[test]
+    fn quant_constant_row() -> TractResult<()> {
+        QuantLayerNormProblem {
+            data: vec![12, 12, 12, 12],
+            input_dt: qu8(3, 0.01),
+            scale: vec![1.0; 4],
+            bias: vec![0.5, 0.0, -0.5, 1.0],
+        }
+        .check()
+    }
+
+    #[test]
+    fn quant_small_epsilon() -> TractResult<()> {
+        QuantLayerNormProblem {
+            data: vec![163, 164],
+            input_dt: qu8(0, 0.014259321),
+            scale: vec![0.0, -0.7805486],
+            bias: vec![0.0, 0.0],
+        }
+        .check()
+    }
+
+    #[test]
+    fn declutter_quant_sandwich() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let source = model.add_source("source", u8::fact([2, 4]))?;
+        let dequant =
+            model.wire_node("dequant", DequantizeLinearF32::new(0.02, 100), &[source])?[0];
+        let scale = model.add_const("scale", rctensor1(&[1f32, 0.5, 2., 1.]))?;
+        let bias = model.add_const("bias", rctensor1(&[0f32, 0.1, 0.2, 0.3]))?;
+        let norm = model.wire_node(
+            "norm",
+            LayerNorm::new(1, 1e-5, f32::datum_type()),
+            &[dequant, scale, bias],
+        )?;
+        let quant = model.wire_node("quant", quantize_linear_u8(20., 128), &norm)?;
+        model.set_output_outlets(&quant)?;
+        let input = tensor2(&[[0u8, 100, 200, 255], [90, 95, 100, 140]]);
+        let expected = model.clone().into_runnable()?.run(tvec!(input.clone()))?;
+
+        let model = model.into_decluttered()?;
+        assert!(model.nodes().iter().all(|n| !n.op_is::<DequantizeLinearF32>()));
+        let norm = model.nodes().iter().find_map(|n| n.op_as::<LayerNorm>()).unwrap();
+        assert_eq!(norm.output_dt, qu8(128, 0.05));
+        let found = model.into_runnable()?.run(tvec!(input))?;
+        let as_qu8 = |t: &Tensor| -> Tensor {
+            let mut t = t.clone();
+            unsafe { t.set_datum_type(qu8(128, 0.05)) };
+            t
+        };
+        as_qu8(&found[0]).close_enough(&as_qu8(&expected[0]), Approximation::Approximate)
+    }
+}
@@ -1,8 +1,15 @@
+mod attention;
 mod data_formats;
+mod gelu;
+mod layer_norm;
 mod reduce;
 mod softmax;
 
+pub use self::attention::BandedAttention;
 pub use self::data_formats::{BaseDataShape, DataFormat, DataShape, SymDataShape};
+pub use self::gelu::declutter_gelu_erf;
+pub(crate) use self::gelu::declutter_gelu_tanh;
+pub use self::layer_norm::LayerNorm;
 pub use self::reduce::{Reduce, Reducer};
 pub use self::softmax::Softmax;
 
@@ -14,6 +21,25 @@ element_wise!(sigmoid, Sigmoid,
     cost: |dt| {tvec!((Cost::FMA(dt), 11), (Cost::Div(dt), 1))}
 );
 
+element_wise!(gelu, Gelu,
+    [f16] => |_, xs| { (tract_linalg::ops().gelu_f16)().run(xs) },
+    [f32] => |_, xs| { (tract_linalg::ops().gelu_f32)().run(xs) };
+    cost: |dt| {tvec!((Cost::FMA(dt), 8), (Cost::Div(dt), 1))}
+);
+
+fn gelu_approximate_f32(x: f32) -> f32 {
+    0.5 * x * (1.0 + ((2.0 / std::f32::consts::PI).sqrt() * (x + 0.044715 * x.powi(3))).tanh())
+}
+
+element_wise!(gelu_approximate, GeluApproximate,
+    [f16] => |_, xs| {
+        xs.iter_mut().for_each(|x| *x = f16::from_f32(gelu_approximate_f32(x.to_f32())));
+        Ok(())
+    },
+    [f32] => |_, xs| { xs.iter_mut().for_each(|x| *x = gelu_approximate_f32(*x)); Ok(()) };
+    cost: |dt| {tvec!((Cost::FMA(dt), 16), (Cost::Div(dt), 1))}
+);
+
 element_wise!(leaky_relu, LeakyRelu { #[educe(Hash(method = "hash_f32"))] alpha: f32 },
     [f32] => |op, xs| { xs.iter_mut().for_each(|x| *x *= if *x < 0. { op.alpha } else { 1.0 }); Ok(()) }
 );
@@ -67,7 +67,10 @@ impl TypedOp for Softmax {
         inputs: &[&TypedFact],
         _outputs: &[&TypedFact],
     ) -> TractResult<Invariants> {
-        Ok((0..inputs[0].rank()).map(AxisInfo::simple).collect())
+        Ok((0..inputs[0].rank())
+            .filter(|axis| !self.axes.contains(axis))
+            .map(AxisInfo::simple)
+            .collect())
     }
 
     fn change_axes(
@@ -91,6 +94,23 @@ impl TypedOp for Softmax {
         }
     }
 
+    fn declutter(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        if let Some(patch) = super::attention::declutter_banded_attention(model, node)? {
+            return Ok(Some(patch));
+        }
+        if self.output_dt.is_float() {
+            crate::ops::quant::declutter_quant_sandwich(model, node, 1, |output_dt| {
+                Box::new(Softmax { axes: self.axes.clone(), output_dt })
+            })
+        } else {
+            Ok(None)
+        }
+    }
+
     as_op!();
 }
 
@@ -104,9 +124,18 @@ impl EvalOp for Softmax {
         let dt = input.datum_type();
 
         let output = match dt {
-            DatumType::F64 => self.eval_t::<f64>(input)?,
-            DatumType::F32 => self.eval_t::<f32>(input)?,
-            DatumType::F16 => self.eval_t::<f16>(input)?,
+            DatumType::F64 => self.eval_t::<f64>(input, &|xs| {
+                xs.iter_mut().for_each(|x| *x = x.exp());
+                Ok(())
+            })?,
+            DatumType::F32 => {
+                let exp = (tract_linalg::ops().exp_f32)();
+                self.eval_t::<f32>(input, &|xs| exp.run(xs))?
+            }
+            DatumType::F16 => {
+                let exp = (tract_linalg::ops().exp_f16)();
+                self.eval_t::<f16>(input, &|xs| exp.run(xs))?
+            }
             DatumType::QI8(_) | DatumType::QU8(_) => self.eval_quant_t(input)?,
             dt => bail!("Unsupported type {:?}", dt),
         };
@@ -115,7 +144,11 @@ impl EvalOp for Softmax {
 }
 
 impl Softmax {
-    fn eval_t<T>(&self, input: Arc<Tensor>) -> TractResult<TVec<Arc<Tensor>>>
+    fn eval_t<T>(
+        &self,
+        input: Arc<Tensor>,
+        exp: &dyn Fn(&mut [T]) -> TractResult<()>,
+    ) -> TractResult<TVec<Arc<Tensor>>>
     where
         T: Float + Datum + std::iter::Sum,
     {
@@ -136,7 +169,7 @@ impl Softmax {
                     view.collapse_axis(Axis(ix), it_coords[ix]);
                 }
             }
-            softmax_inner(view);
+            softmax_inner(view, exp)?;
         }
 
         Ok(tvec!(output.into_arc_tensor()))
@@ -174,11 +207,23 @@ impl Softmax {
     }
 }
 
-fn softmax_inner<T: Float + Datum + std::iter::Sum, D: Dimension>(mut view: ArrayViewMut<T, D>) {
-    let max = *view.iter().max_by(|i, j| i.partial_cmp(j).unwrap()).unwrap();
-    view.mapv_inplace(|x| (x - max).exp());
+fn softmax_inner<T: Float + Datum + std::iter::Sum, D: Dimension>(
+    mut view: ArrayViewMut<T, D>,
+    exp: &dyn Fn(&mut [T]) -> TractResult<()>,
+) -> TractResult<()> {
+    let max = view.iter().copied().fold(T::neg_infinity(), T::max);
+    view.mapv_inplace(|x| x - max);
+    if let Some(slice) = view.as_slice_mut() {
+        exp(slice)?;
+    } else {
+        // softmax axes are not contiguous, go through a dense buffer for the kernel
+        let mut buffer: Vec<T> = view.iter().copied().collect();
+        exp(&mut buffer)?;
+        view.iter_mut().zip(buffer).for_each(|(x, e)| *x = e);
+    }
     let exp_sum = view.iter().copied().sum();
     view.mapv_inplace(|x| x / exp_sum);
+    Ok(())
 }
 
 fn softmax_quant_inner<D: Dimension>(
@@ -190,7 +235,7 @@ fn softmax_quant_inner<D: Dimension>(
 ) {
     let (_, in_scale) = in_qp.zp_scale();
     let (scale_in_multiplier, scale_in_shift) = convert_scale_to_mult_shift(in_scale).unwrap();
-    let (_, out_scale) = out_qp.zp_scale();
+    let (out_zero_point, out_scale) = out_qp.zp_scale();
     let (scale_out_multiplier, scale_out_shift) = convert_scale_to_mult_shift(out_scale).unwrap();
     let shift = 26 - scale_in_shift;
 
@@ -249,6 +294,8 @@ fn softmax_quant_inner<D: Dimension>(
             }
         };
 
+        let unsat_scaled_output = unsat_scaled_output + out_zero_point;
+
         // Return the final result by clipping the computed value within its range
         // and casting it to u8 in any case.
         if out_is_signed {
@@ -408,7 +455,11 @@ mod test {
             let in_float =
                 self.data.iter().map(|it| (*it as f32 - in_zero_point as f32) * in_scale).collect();
             let mut in_float_array = Array1::from_vec(in_float);
-            softmax_inner(in_float_array.view_mut());
+            softmax_inner(in_float_array.view_mut(), &|xs| {
+                xs.iter_mut().for_each(|x| *x = x.exp());
+                Ok(())
+            })
+            .unwrap();
             let rescaled_output = in_float_array
                 .iter()
                 .map(|it| {
@@ -556,6 +607,45 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_softmax_out_zero_point() -> Result<()> {
+        let input_dt = DatumType::QU8(ZpScale { zero_point: 128, scale: 0.05 });
+        let output_dt = DatumType::QI8(ZpScale { zero_point: -128, scale: 0.00390625 });
+        let mut data = Tensor::from_shape(&[1, 1, 2, 3], &[128_u8, 148, 98, 0, 10, 20])?;
+        unsafe { data.set_datum_type(input_dt) };
+
+        let prob = SoftmaxProblem { data, axes: tvec![3], output_dt };
+        prob.check()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_declutter_quant_sandwich() -> Result<()> {
+        use crate::ops::quant::{quantize_linear_u8, DequantizeLinearF32};
+        let mut model = TypedModel::default();
+        let source = model.add_source("source", u8::fact([2, 3]))?;
+        let dequant = model.wire_node("dequant", DequantizeLinearF32::new(0.05, 128), &[source])?;
+        let softmax =
+            model.wire_node("softmax", Softmax::new(tvec!(1), f32::datum_type()), &dequant)?;
+        let quant = model.wire_node("quant", quantize_linear_u8(256., 0), &softmax)?;
+        model.set_output_outlets(&quant)?;
+        let input = tensor2(&[[128u8, 148, 98], [0, 10, 255]]);
+        let expected = model.clone().into_runnable()?.run(tvec!(input.clone()))?;
+
+        let model = model.into_decluttered()?;
+        assert!(model.nodes().iter().all(|n| !n.op_is::<DequantizeLinearF32>()));
+        let softmax = model.nodes().iter().find_map(|n| n.op_as::<Softmax>()).unwrap();
+        let output_dt = DatumType::QU8(ZpScale { zero_point: 0, scale: 0.00390625 });
+        assert_eq!(softmax.output_dt, output_dt);
+        let found = model.into_runnable()?.run(tvec!(input))?;
+        let as_qu8 = |t: &Tensor| -> Tensor {
+            let mut t = t.clone();
+            unsafe { t.set_datum_type(output_dt) };
+            t
+        };
+        as_qu8(&found[0]).close_enough(&as_qu8(&expected[0]), Approximation::Approximate)
+    }
+
     #[test]
     fn test_inner_softmax_1() -> Result<()> {
         let in_qp = ZpScale { zero_point: 0, scale: 0.03125 };
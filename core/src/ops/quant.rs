@@ -1,10 +1,13 @@
 use crate::internal::*;
+use crate::ops::binary::BinMiniOp;
+use crate::ops::cast::cast;
 use crate::ops::element_wise::ElementWiseOp;
-use crate::ops::math::QScale;
+use crate::ops::math::{Add, Mul, QScale, Sub};
 use num_traits::AsPrimitive;
 use tract_linalg::lut::Lut;
 use tract_linalg::mmm::RoundingPolicy;
-use tract_linalg::Scaler;
+use tract_linalg::{ScaleShiftAndRound, Scaler};
+use tract_ndarray::Axis;
 
 use super::math::round_ties_to_even;
 
@@ -151,17 +154,7 @@ impl TypedOp for DequantizeLinearF32 {
         let mut current = dequant;
         let incoming_dt = model.node_input_facts(dequant.id)?[0].datum_type;
         while let Some(quant) = model.single_succ(current.id)? {
-            let q_params = if let Some(op) = quant.op_as::<ElementWiseOp>() {
-                if let Some(mop) = op.0.downcast_ref::<QuantizeLinearU8>() {
-                    Some((mop.scale, mop.zero_point as i32, u8::datum_type()))
-                } else {
-                    op.0.downcast_ref::<QuantizeLinearI8>()
-                        .map(|mop| (mop.scale, mop.zero_point as i32, i8::datum_type()))
-                }
-            } else {
-                None
-            };
-            if let Some((scale, zero_point, dt)) = q_params {
+            if let Some((scale, zero_point, dt)) = quantize_linear_params(quant) {
                 // first, try Op::quantize() on all ops in the chain
                 let mut patch = TypedModelPatch::default();
                 let mut wire: OutletId = patch.tap_model(model, dequant.inputs[0])?;
@@ -246,6 +239,374 @@ impl TypedOp for DequantizeLinearF32 {
     as_op!();
 }
 
+/// Dequantization with one zero point and scale per slice of the input along `axis`.
+///
+/// Per-axis parameters do not fit in a DatumType, so the quantized tensor stays a plain
+/// integer tensor and is dequantized to f32 by this op. It is not folded into the quantized
+/// matmul and convolution operators: they only receive per-axis parameters as explicit
+/// inputs (ONNX QLinearMatMul, QLinearConv).
+#[derive(Clone, Debug, new, Hash)]
+pub struct DequantizeLinearPerAxis {
+    pub axis: usize,
+    /// i32, one per slice.
+    pub zero_points: Arc<Tensor>,
+    /// f32, one per slice.
+    pub scales: Arc<Tensor>,
+}
+
+impl DequantizeLinearPerAxis {
+    fn eval_t<T: Datum + AsPrimitive<i32>>(&self, input: &Tensor) -> TractResult<Tensor> {
+        let mut output = unsafe { Tensor::uninitialized::<f32>(input.shape())? };
+        let input = input.to_array_view::<T>()?;
+        let zero_points = self.zero_points.as_slice::<i32>()?;
+        let scales = self.scales.as_slice::<f32>()?;
+        for (ix, (x, mut y)) in input
+            .axis_iter(Axis(self.axis))
+            .zip(output.to_array_view_mut::<f32>()?.axis_iter_mut(Axis(self.axis)))
+            .enumerate()
+        {
+            let (zero_point, scale) = (zero_points[ix], scales[ix]);
+            y.zip_mut_with(&x, |y, x| *y = (x.as_() - zero_point) as f32 * scale);
+        }
+        Ok(output)
+    }
+}
+
+impl Op for DequantizeLinearPerAxis {
+    fn name(&self) -> Cow<str> {
+        "DequantizeLinearPerAxis".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "axis: {} zero_points: {:?} scales: {:?}",
+            self.axis, self.zero_points, self.scales
+        )])
+    }
+
+    fn validation(&self) -> Validation {
+        Validation::Accurate
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl_dyn_hash!(DequantizeLinearPerAxis);
+
+impl EvalOp for DequantizeLinearPerAxis {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let output = match inputs[0].datum_type() {
+            DatumType::I8 => self.eval_t::<i8>(&inputs[0])?,
+            DatumType::I32 => self.eval_t::<i32>(&inputs[0])?,
+            DatumType::U8 => self.eval_t::<u8>(&inputs[0])?,
+            dt => bail!("Unsupported type {:?}", dt),
+        };
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for DequantizeLinearPerAxis {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let rank = inputs[0].rank();
+        ensure!(
+            self.axis < rank,
+            "Invalid quantization axis {} for a tensor of rank {}",
+            self.axis,
+            rank
+        );
+        ensure!(self.zero_points.datum_type() == i32::datum_type());
+        ensure!(self.scales.datum_type() == f32::datum_type());
+        ensure!(self.zero_points.len() == self.scales.len());
+        let len = inputs[0].shape[self.axis].to_usize()?;
+        ensure!(
+            len == self.scales.len(),
+            "Expected {} quantization parameters along axis {}, got {}",
+            len,
+            self.axis,
+            self.scales.len()
+        );
+        let mut fact = inputs[0].clone();
+        fact.datum_type = f32::datum_type();
+        Ok(tvec!(fact))
+    }
+
+    as_op!();
+}
+
+/// Scale, zero point and output type of a QuantizeLinearU8 or
+/// QuantizeLinearI8 node. The scale is the inverse of the quantization scale.
+fn quantize_linear_params(node: &TypedNode) -> Option<(f32, i32, DatumType)> {
+    let op = node.op_as::<ElementWiseOp>()?;
+    if let Some(mop) = op.0.downcast_ref::<QuantizeLinearU8>() {
+        Some((mop.scale, mop.zero_point as i32, u8::datum_type()))
+    } else {
+        op.0.downcast_ref::<QuantizeLinearI8>()
+            .map(|mop| (mop.scale, mop.zero_point as i32, i8::datum_type()))
+    }
+}
+
+/// Replace a `DequantizeLinearF32 -> node -> QuantizeLinear` sandwich by the
+/// quantized version of `node`, built by `quantized` for the given output
+/// type.
+///
+/// The first `quantized_inputs` inputs of `node` must come from
+/// dequantizers, and the quantizer must be the only successor of `node`.
+/// Other inputs are kept as they are.
+pub(crate) fn declutter_quant_sandwich(
+    model: &TypedModel,
+    node: &TypedNode,
+    quantized_inputs: usize,
+    quantized: impl Fn(DatumType) -> Box<dyn TypedOp>,
+) -> TractResult<Option<TypedModelPatch>> {
+    let mut input_dts = tvec!();
+    for input in &node.inputs[..quantized_inputs] {
+        let dequant_node = model.node(input.node);
+        let dequant = if let Some(dequant) = dequant_node.op_as::<DequantizeLinearF32>() {
+            dequant
+        } else {
+            return Ok(None);
+        };
+        let qp = QParams::ZpScale { zero_point: dequant.zero_point, scale: dequant.scale };
+        input_dts.push(match model.outlet_fact(dequant_node.inputs[0])?.datum_type {
+            DatumType::U8 => DatumType::QU8(qp),
+            DatumType::I8 => DatumType::QI8(qp),
+            _ => return Ok(None),
+        });
+    }
+    let quant = if let Some(quant) = model.single_succ(node.id)? {
+        quant
+    } else {
+        return Ok(None);
+    };
+    let (scale, zero_point, dt) =
+        if let Some(params) = quantize_linear_params(quant) { params } else { return Ok(None) };
+    let output_qp = QParams::ZpScale { zero_point, scale: scale.recip() };
+    let output_dt =
+        if dt == DatumType::U8 { DatumType::QU8(output_qp) } else { DatumType::QI8(output_qp) };
+    let mut patch = TypedModelPatch::default();
+    let mut wires: TVec<OutletId> = tvec!();
+    for (ix, input) in node.inputs.iter().enumerate() {
+        if let Some(prev) = node.inputs[..ix].iter().position(|i| i == input) {
+            wires.push(wires[prev]);
+        } else if ix < quantized_inputs {
+            let dequant_node = model.node(input.node);
+            let wire = patch.tap_model(model, dequant_node.inputs[0])?;
+            wires.push(patch.wire_node(&*dequant_node.name, cast(input_dts[ix]), &[wire])?[0]);
+        } else {
+            wires.push(patch.tap_model(model, *input)?);
+        }
+    }
+    let wire = patch.wire_node(&*node.name, quantized(output_dt), &wires)?;
+    let wire = patch.wire_node(&*quant.name, cast(dt), &wire)?[0];
+    patch.shunt_outside(model, OutletId::new(quant.id, 0), wire)?;
+    Ok(Some(patch))
+}
+
+/// Add, Sub or Mul between two QU8 or QI8 tensors with their own
+/// quantization parameters, producing `output_dt` without going through f32.
+///
+/// Add and Sub follow TFLite's QuantizedAdd: both operands are centered on
+/// their zero point, shifted left by 20 bits, brought to a common scale, and
+/// the sum is requantized to the output scale. Mul requantizes the product of
+/// the centered operands once.
+#[derive(Debug, Clone, new, Hash)]
+pub struct QBinOp {
+    pub mini_op: Box<dyn BinMiniOp>,
+    pub output_dt: DatumType,
+}
+
+impl_dyn_hash!(QBinOp);
+
+/// Headroom given to Add and Sub operands before rescaling them.
+const QBIN_LEFT_SHIFT: usize = 20;
+
+impl QBinOp {
+    fn eval_t<A, B, C>(&self, a: &Tensor, b: &Tensor, c: &mut Tensor) -> TractResult<()>
+    where
+        A: Datum + AsPrimitive<i32>,
+        B: Datum + AsPrimitive<i32>,
+        C: Datum + Copy,
+        i32: AsPrimitive<C>,
+    {
+        let (a_zp, a_scale) = a.datum_type().zp_scale();
+        let (b_zp, b_scale) = b.datum_type().zp_scale();
+        let (c_zp, c_scale) = self.output_dt.zp_scale();
+        let min: i32 = self.output_dt.unquantized().min_value().cast_to_scalar()?;
+        let max: i32 = self.output_dt.unquantized().max_value().cast_to_scalar()?;
+        let a = a.to_array_view::<A>()?;
+        let b = b.to_array_view::<B>()?;
+        let mut c = c.to_array_view_mut::<C>()?;
+        let zip = ndarray::Zip::from(&mut c).and_broadcast(&a).and_broadcast(&b);
+        let requant = |x: i32, scaler: Scaler| (x.q_scale(scaler) + c_zp).max(min).min(max).as_();
+        if self.mini_op.is::<Mul>() {
+            let scaler = Scaler::new(a_scale * b_scale / c_scale, RoundingPolicy::Away);
+            zip.for_each(|c, a, b| {
+                *c = requant((a.as_() - a_zp) * (b.as_() - b_zp), scaler);
+            });
+        } else {
+            let sign = if self.mini_op.is::<Add>() {
+                1
+            } else if self.mini_op.is::<Sub>() {
+                -1
+            } else {
+                bail!("{} has no quantized form", self.mini_op.name())
+            };
+            let twice_max_scale = 2. * a_scale.max(b_scale);
+            let a_scaler = Scaler::new(a_scale / twice_max_scale, RoundingPolicy::Away);
+            let b_scaler = Scaler::new(b_scale / twice_max_scale, RoundingPolicy::Away);
+            let c_scaler = Scaler::new(
+                twice_max_scale / ((1 << QBIN_LEFT_SHIFT) as f32 * c_scale),
+                RoundingPolicy::Away,
+            );
+            zip.for_each(|c, a, b| {
+                let a = (a.as_() - a_zp).q_shl(QBIN_LEFT_SHIFT).q_scale(a_scaler);
+                let b = (b.as_() - b_zp).q_shl(QBIN_LEFT_SHIFT).q_scale(b_scaler);
+                *c = requant(a + sign * b, c_scaler);
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Op for QBinOp {
+    fn name(&self) -> Cow<str> {
+        format!("Q{}", self.mini_op.name()).into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("output: {:?}", self.output_dt)])
+    }
+
+    fn validation(&self) -> Validation {
+        Validation::Rounding
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for QBinOp {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (a, b) = args_2!(inputs);
+        let shape = crate::broadcast::multi_broadcast(&[a.shape(), b.shape()])
+            .ok_or_else(|| format_err!("Can not broadcast {:?} and {:?}", a, b))?;
+        let mut c = unsafe { Tensor::uninitialized_dt(self.output_dt, &shape)? };
+        use DatumType::{I8, U8};
+        match (
+            a.datum_type().unquantized(),
+            b.datum_type().unquantized(),
+            c.datum_type().unquantized(),
+        ) {
+            (U8, U8, U8) => self.eval_t::<u8, u8, u8>(&a, &b, &mut c)?,
+            (U8, U8, I8) => self.eval_t::<u8, u8, i8>(&a, &b, &mut c)?,
+            (U8, I8, U8) => self.eval_t::<u8, i8, u8>(&a, &b, &mut c)?,
+            (U8, I8, I8) => self.eval_t::<u8, i8, i8>(&a, &b, &mut c)?,
+            (I8, U8, U8) => self.eval_t::<i8, u8, u8>(&a, &b, &mut c)?,
+            (I8, U8, I8) => self.eval_t::<i8, u8, i8>(&a, &b, &mut c)?,
+            (I8, I8, U8) => self.eval_t::<i8, i8, u8>(&a, &b, &mut c)?,
+            (I8, I8, I8) => self.eval_t::<i8, i8, i8>(&a, &b, &mut c)?,
+            (a, b, c) => bail!("{} does not support {:?}, {:?} -> {:?}", self.name(), a, b, c),
+        }
+        Ok(tvec!(c.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for QBinOp {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        ensure!(
+            inputs[0].rank() == inputs[1].rank(),
+            "Rank mismatch in {}: {:?}",
+            self.name(),
+            inputs
+        );
+        for dt in inputs.iter().map(|i| i.datum_type).chain(std::iter::once(self.output_dt)) {
+            ensure!(
+                dt.is_quantized()
+                    && (dt.unquantized() == DatumType::U8 || dt.unquantized() == DatumType::I8),
+                "{} expects QU8 or QI8 operands, got {:?}",
+                self.name(),
+                dt
+            );
+        }
+        let shape = crate::broadcast::multi_broadcast(&[
+            &inputs[0].shape.to_tvec(),
+            &inputs[1].shape.to_tvec(),
+        ])
+        .ok_or_else(|| format_err!("Can not broadcast {:?} and {:?}", inputs[0], inputs[1]))?;
+        Ok(tvec!(self.output_dt.fact(&*shape)))
+    }
+
+    fn invariants(
+        &self,
+        inputs: &[&TypedFact],
+        _outputs: &[&TypedFact],
+    ) -> TractResult<Invariants> {
+        Ok((0..inputs[0].rank())
+            .map(|axis| AxisInfo {
+                inputs: tvec!(Some(axis), Some(axis)),
+                outputs: tvec!(Some(axis)),
+                period: 1,
+                disposable: true,
+            })
+            .collect())
+    }
+
+    fn change_axes(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+        _io: InOut,
+        change: &AxisOp,
+    ) -> TractResult<Option<AxisChangeConsequence>> {
+        Ok(Some(AxisChangeConsequence::new(model, node, None, change)))
+    }
+
+    fn cost(&self, inputs: &[&TypedFact]) -> TractResult<TVec<(Cost, TDim)>> {
+        let count: TDim = self.output_facts(inputs)?[0].shape.iter().product();
+        Ok(tvec!((Cost::FMA(DatumType::I32), count * 3)))
+    }
+
+    as_op!();
+}
+
+/// Declutter rule shared by Add, Sub and Mul: fold the dequantize/quantize
+/// sandwich around the operator, or take over when both operands are
+/// quantized with different parameters, which the plain binary ops do not
+/// support.
+pub(crate) fn declutter_quant_bin(
+    mini_op: &dyn BinMiniOp,
+    model: &TypedModel,
+    node: &TypedNode,
+) -> TractResult<Option<TypedModelPatch>> {
+    let output_dt = node.outputs[0].fact.datum_type;
+    if output_dt.is_float() {
+        return declutter_quant_sandwich(model, node, 2, |output_dt| {
+            Box::new(QBinOp::new(dyn_clone::clone_box(mini_op), output_dt))
+        });
+    }
+    let facts = model.node_input_facts(node.id)?;
+    let is_q8 = |dt: DatumType| {
+        dt.is_quantized() && [DatumType::U8, DatumType::I8].contains(&dt.unquantized())
+    };
+    if facts.iter().all(|f| is_q8(f.datum_type))
+        && is_q8(output_dt)
+        && facts[0].datum_type != facts[1].datum_type
+    {
+        let op = QBinOp::new(dyn_clone::clone_box(mini_op), output_dt);
+        return Ok(Some(TypedModelPatch::replace_single_op(model, node, &node.inputs, op)?));
+    }
+    Ok(None)
+}
+
 element_wise_oop!(lookup_table,
  LookupTable {
      table: Box<dyn Lut>
@@ -467,3 +828,114 @@ impl ElementWiseMiniOp for OffsetU8asI8 {
 pub fn offset_u8_as_i8() -> ElementWiseOp {
     ElementWiseOp(Box::new(OffsetU8asI8 {}))
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops::binary::TypedBinOp;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    #[derive(Debug)]
+    struct QBinProblem {
+        mini_op: Box<dyn BinMiniOp>,
+        a: (Vec<u8>, f32, u8),
+        b: (Vec<u8>, f32, u8),
+        c: (f32, u8),
+    }
+
+    impl QBinProblem {
+        fn check(&self) -> TractResult<()> {
+            let mut model = TypedModel::default();
+            let mut wires = tvec!();
+            for (name, (data, scale, zp)) in [("a", &self.a), ("b", &self.b)] {
+                let source = model.add_source(name, u8::fact([data.len()]))?;
+                let dequant = DequantizeLinearF32::new(*scale, *zp as i32);
+                wires.push(model.wire_node(format!("{}.dequant", name), dequant, &[source])?[0]);
+            }
+            let bin = model.wire_node("bin", TypedBinOp(self.mini_op.clone()), &wires)?;
+            let quant = quantize_linear_u8(self.c.0.recip(), self.c.1);
+            let quant = model.wire_node("quant", quant, &bin)?;
+            model.set_output_outlets(&quant)?;
+            let inputs = tvec!(tensor1(&self.a.0), tensor1(&self.b.0));
+            let expected = model.clone().into_runnable()?.run(inputs.clone())?;
+
+            let model = model.into_decluttered()?;
+            ensure!(model.nodes().iter().any(|n| n.op_is::<QBinOp>()));
+            ensure!(model.nodes().iter().all(|n| !n.op_is::<DequantizeLinearF32>()));
+            let found = model.into_runnable()?.run(inputs)?;
+            let output_dt =
+                DatumType::QU8(QParams::ZpScale { zero_point: self.c.1 as i32, scale: self.c.0 });
+            let as_qu8 = |t: &Tensor| -> Tensor {
+                let mut t = t.clone();
+                unsafe { t.set_datum_type(output_dt) };
+                t
+            };
+            as_qu8(&found[0]).close_enough(&as_qu8(&expected[0]), Approximation::Approximate)
+        }
+    }
+
+    impl Arbitrary for QBinProblem {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<QBinProblem>;
+        fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+            let mini_ops: Vec<Box<dyn BinMiniOp>> =
+                vec![Box::new(Add), Box::new(Sub), Box::new(Mul)];
+            (1usize..10)
+                .prop_flat_map(move |len| {
+                    let q = || (0.001f32..0.1, any::<u8>());
+                    (
+                        proptest::sample::select(mini_ops.clone()),
+                        (vec(any::<u8>(), len..=len), q()),
+                        (vec(any::<u8>(), len..=len), q()),
+                        q(),
+                    )
+                })
+                .prop_map(|(mini_op, a, b, c)| QBinProblem {
+                    mini_op,
+                    a: (a.0, a.1 .0, a.1 .1),
+                    b: (b.0, b.1 .0, b.1 .1),
+                    c,
+                })
+                .boxed()
+        }
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn qbin_prop(pb in any::<QBinProblem>()) {
+            pb.check().unwrap()
+        }
+    }
+
+    #[test]
+    fn qbin_add_residual() -> TractResult<()> {
+        QBinProblem {
+            mini_op: Box::new(Add),
+            a: (vec![0, 12, 128, 200, 255], 0.02, 128),
+            b: (vec![255, 3, 100, 50, 0], 0.05, 10),
+            c: (0.04, 100),
+        }
+        .check()
+    }
+
+    #[test]
+    fn qbin_mismatched_quantized_inputs() -> TractResult<()> {
+        let qu8 = |zero_point, scale| DatumType::QU8(QParams::ZpScale { zero_point, scale });
+        let mut model = TypedModel::default();
+        let a = model.add_source("a", qu8(128, 0.02).fact([3]))?;
+        let b = model.add_source("b", qu8(10, 0.05).fact([3]))?;
+        let add = model.wire_node("add", crate::ops::math::add::bin_typed(), &[a, b])?;
+        model.set_output_outlets(&add)?;
+        let model = model.into_decluttered()?;
+        ensure!(model.nodes().iter().any(|n| n.op_is::<QBinOp>()));
+        let mut ta = tensor1(&[128u8, 178, 78]);
+        unsafe { ta.set_datum_type(qu8(128, 0.02)) };
+        let mut tb = tensor1(&[10u8, 30, 0]);
+        unsafe { tb.set_datum_type(qu8(10, 0.05)) };
+        let found = model.into_runnable()?.run(tvec!(ta, tb))?;
+        let mut expected = tensor1(&[128u8, 228, 53]);
+        unsafe { expected.set_datum_type(qu8(128, 0.02)) };
+        found[0].close_enough(&expected, Approximation::Exact)
+    }
+}
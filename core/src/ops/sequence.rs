@@ -0,0 +1,875 @@
+//! Sequence and optional values.
+//!
+//! Sequences are scalar tensors of `DatumType::Sequence` holding a list of tensors, optionals
+//! are scalar tensors of `DatumType::Optional` holding zero or one tensor. Their facts describe
+//! the held tensors with a `SequenceFact`. When the elements are statically known, declutter
+//! lowers the sequence operators back to plain tensor operators.
+
+use crate::internal::*;
+use crate::num_traits::Zero;
+use crate::ops::array::{Slice, TypedConcat};
+
+fn position(fact: Option<&&TypedFact>) -> TractResult<Option<i64>> {
+    fact.and_then(|f| f.konst.as_ref()).map(|k| k.cast_to_scalar::<i64>()).transpose()
+}
+
+/// Resolve a possibly negative position in a sequence of `len` elements. Insertion
+/// positions can point right after the last element.
+fn resolve_position(position: i64, len: usize, insertion: bool) -> TractResult<usize> {
+    let len = len as i64;
+    let max = if insertion { len } else { len - 1 };
+    ensure!(
+        -len <= position && position <= max,
+        "Position {} out of bounds for a sequence of {} elements",
+        position,
+        len
+    );
+    Ok(if position < 0 { position + len } else { position } as usize)
+}
+
+fn elements_fact(fact: &TypedFact) -> TractResult<&SequenceFact> {
+    ensure!(
+        fact.datum_type == DatumType::Sequence || fact.datum_type == DatumType::Optional,
+        "Expected a sequence or an optional, got {:?}",
+        fact
+    );
+    fact.sequence.as_deref().with_context(|| format!("Unknown elements for {:?}", fact))
+}
+
+fn sequence(tensor: &Tensor) -> TractResult<&[Arc<Tensor>]> {
+    Ok(&tensor.to_scalar::<TensorSequence>()?.0)
+}
+
+/// Tap the elements of the sequence or optional at `outlet` into the patch, if they are
+/// wired by a SequenceConstruct or OptionalConstruct, or are constant.
+fn tap_elements(
+    model: &TypedModel,
+    patch: &mut TypedModelPatch,
+    outlet: OutletId,
+) -> TractResult<Option<TVec<OutletId>>> {
+    let node = model.node(outlet.node);
+    if node.op_is::<SequenceConstruct>() || node.op_is::<OptionalConstruct>() {
+        let taps = node.inputs.iter().map(|i| patch.tap_model(model, *i));
+        return taps.collect::<TractResult<_>>().map(Some);
+    }
+    let konst = if let Some(konst) = &model.outlet_fact(outlet)?.konst {
+        konst
+    } else {
+        return Ok(None);
+    };
+    let elements: Vec<Arc<Tensor>> = match konst.datum_type() {
+        DatumType::Sequence => sequence(konst)?.to_vec(),
+        DatumType::Optional => konst.to_scalar::<TensorOption>()?.0.iter().cloned().collect(),
+        _ => return Ok(None),
+    };
+    let consts = elements
+        .into_iter()
+        .enumerate()
+        .map(|(ix, t)| patch.add_const(format!("{}.{}", node.name, ix), t))
+        .collect::<TractResult<_>>();
+    consts.map(Some)
+}
+
+/// Replace a node by a constant if its output fact is one.
+fn declutter_to_konst(
+    model: &TypedModel,
+    node: &TypedNode,
+) -> TractResult<Option<TypedModelPatch>> {
+    if let Some(konst) = node.outputs[0].fact.konst.clone() {
+        let mut patch = TypedModelPatch::default();
+        let wire = patch.add_const(&node.name, konst)?;
+        patch.shunt_outside(model, node.id.into(), wire)?;
+        return Ok(Some(patch));
+    }
+    Ok(None)
+}
+
+/// Build a sequence from its inputs (ONNX SequenceConstruct and SequenceEmpty).
+#[derive(Debug, Clone, Default, Hash)]
+pub struct SequenceConstruct;
+impl_dyn_hash!(SequenceConstruct);
+
+impl Op for SequenceConstruct {
+    fn name(&self) -> Cow<str> {
+        "SequenceConstruct".into()
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for SequenceConstruct {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        Ok(tvec!(tensor0(TensorSequence(inputs.into_vec())).into_arc_tensor()))
+    }
+}
+
+impl TypedOp for SequenceConstruct {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if let Some(first) = inputs.first() {
+            ensure!(
+                inputs.iter().all(|i| i.datum_type == first.datum_type),
+                "Sequence elements must share a datum type, got {:?}",
+                inputs
+            );
+        }
+        let elements = inputs.iter().map(|f| (*f).clone()).collect();
+        Ok(tvec!(TypedFact::sequence_of(SequenceFact::Known(elements))))
+    }
+
+    as_op!();
+}
+
+/// Element of a sequence at a given position (ONNX SequenceAt).
+#[derive(Debug, Clone, Default, Hash)]
+pub struct SequenceAt;
+impl_dyn_hash!(SequenceAt);
+
+impl Op for SequenceAt {
+    fn name(&self) -> Cow<str> {
+        "SequenceAt".into()
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for SequenceAt {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (seq, pos) = args_2!(inputs);
+        let seq = sequence(&seq)?;
+        let pos = resolve_position(pos.cast_to_scalar::<i64>()?, seq.len(), false)?;
+        Ok(tvec!(seq[pos].clone()))
+    }
+}
+
+impl TypedOp for SequenceAt {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let element = elements_fact(inputs[0])?.element(position(inputs.get(1))?)?;
+        Ok(tvec!(element))
+    }
+
+    fn declutter(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        let inputs = model.node_input_facts(node.id)?;
+        let pos = if let Some(pos) = position(inputs.get(1))? {
+            pos
+        } else {
+            return Ok(None);
+        };
+        let mut patch = TypedModelPatch::default();
+        if let Some(elements) = tap_elements(model, &mut patch, node.inputs[0])? {
+            let pos = resolve_position(pos, elements.len(), false)?;
+            patch.shunt_outside(model, node.id.into(), elements[pos])?;
+            return Ok(Some(patch));
+        }
+        Ok(None)
+    }
+
+    as_op!();
+}
+
+/// Insert a tensor in a sequence, at the end if no position is given (ONNX SequenceInsert).
+#[derive(Debug, Clone, Default, Hash)]
+pub struct SequenceInsert;
+impl_dyn_hash!(SequenceInsert);
+
+impl Op for SequenceInsert {
+    fn name(&self) -> Cow<str> {
+        "SequenceInsert".into()
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for SequenceInsert {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let mut seq = sequence(&inputs[0])?.to_vec();
+        let pos = inputs.get(2).map(|p| p.cast_to_scalar::<i64>()).transpose()?;
+        let pos = resolve_position(pos.unwrap_or(seq.len() as i64), seq.len(), true)?;
+        seq.insert(pos, inputs[1].clone());
+        Ok(tvec!(tensor0(TensorSequence(seq)).into_arc_tensor()))
+    }
+}
+
+impl TypedOp for SequenceInsert {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let elements = elements_fact(inputs[0])?;
+        let inserted = inputs[1].clone();
+        let elements = match elements {
+            SequenceFact::Known(facts) => {
+                let pos = if inputs.len() > 2 {
+                    position(inputs.get(2))?
+                } else {
+                    Some(facts.len() as i64)
+                };
+                let mut facts = facts.clone();
+                if let Some(pos) = pos {
+                    facts.insert(resolve_position(pos, facts.len(), true)?, inserted);
+                } else {
+                    facts.push(inserted);
+                    ensure!(
+                        SequenceFact::Known(facts.clone()).common().is_some(),
+                        "Can not insert {:?} at an unknown position in {:?}",
+                        inputs[1],
+                        elements
+                    );
+                }
+                SequenceFact::Known(facts)
+            }
+            SequenceFact::Dynamic(fact) => {
+                ensure!(
+                    fact.without_value() == inserted.without_value(),
+                    "Can not insert {:?} in {:?}",
+                    inserted,
+                    elements
+                );
+                SequenceFact::Dynamic(fact.clone())
+            }
+        };
+        Ok(tvec!(TypedFact::sequence_of(elements)))
+    }
+
+    fn declutter(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        let inputs = model.node_input_facts(node.id)?;
+        let pos = position(inputs.get(2))?;
+        if node.inputs.len() > 2 && pos.is_none() {
+            return Ok(None);
+        }
+        let mut patch = TypedModelPatch::default();
+        if let Some(mut elements) = tap_elements(model, &mut patch, node.inputs[0])? {
+            let pos = resolve_position(pos.unwrap_or(elements.len() as i64), elements.len(), true)?;
+            elements.insert(pos, patch.tap_model(model, node.inputs[1])?);
+            let wire = patch.wire_node(&node.name, SequenceConstruct, &elements)?;
+            patch.shunt_outside(model, node.id.into(), wire[0])?;
+            return Ok(Some(patch));
+        }
+        Ok(None)
+    }
+
+    as_op!();
+}
+
+/// Remove a tensor from a sequence, the last one if no position is given (ONNX SequenceErase).
+#[derive(Debug, Clone, Default, Hash)]
+pub struct SequenceErase;
+impl_dyn_hash!(SequenceErase);
+
+impl Op for SequenceErase {
+    fn name(&self) -> Cow<str> {
+        "SequenceErase".into()
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for SequenceErase {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let mut seq = sequence(&inputs[0])?.to_vec();
+        let pos = inputs.get(1).map(|p| p.cast_to_scalar::<i64>()).transpose()?;
+        let pos = resolve_position(pos.unwrap_or(-1), seq.len(), false)?;
+        seq.remove(pos);
+        Ok(tvec!(tensor0(TensorSequence(seq)).into_arc_tensor()))
+    }
+}
+
+impl TypedOp for SequenceErase {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let elements = elements_fact(inputs[0])?;
+        let elements = match elements {
+            SequenceFact::Known(facts) => {
+                let pos = if inputs.len() > 1 { position(inputs.get(1))? } else { Some(-1) };
+                let mut facts = facts.clone();
+                if let Some(pos) = pos {
+                    facts.remove(resolve_position(pos, facts.len(), false)?);
+                } else {
+                    ensure!(
+                        elements.common().is_some(),
+                        "Can not erase at an unknown position from {:?}",
+                        elements
+                    );
+                    facts.pop();
+                }
+                SequenceFact::Known(facts)
+            }
+            SequenceFact::Dynamic(_) => elements.clone(),
+        };
+        Ok(tvec!(TypedFact::sequence_of(elements)))
+    }
+
+    fn declutter(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        let inputs = model.node_input_facts(node.id)?;
+        let pos = position(inputs.get(1))?;
+        if node.inputs.len() > 1 && pos.is_none() {
+            return Ok(None);
+        }
+        let mut patch = TypedModelPatch::default();
+        if let Some(mut elements) = tap_elements(model, &mut patch, node.inputs[0])? {
+            elements.remove(resolve_position(pos.unwrap_or(-1), elements.len(), false)?);
+            let wire = patch.wire_node(&node.name, SequenceConstruct, &elements)?;
+            patch.shunt_outside(model, node.id.into(), wire[0])?;
+            return Ok(Some(patch));
+        }
+        Ok(None)
+    }
+
+    as_op!();
+}
+
+/// Number of tensors in a sequence, as a scalar I64 (ONNX SequenceLength).
+#[derive(Debug, Clone, Default, Hash)]
+pub struct SequenceLength;
+impl_dyn_hash!(SequenceLength);
+
+impl Op for SequenceLength {
+    fn name(&self) -> Cow<str> {
+        "SequenceLength".into()
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for SequenceLength {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let seq = args_1!(inputs);
+        Ok(tvec!(tensor0(sequence(&seq)?.len() as i64).into_arc_tensor()))
+    }
+}
+
+impl TypedOp for SequenceLength {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if let Some(len) = elements_fact(inputs[0])?.known_len() {
+            Ok(tvec!(tensor0(len as i64).into()))
+        } else {
+            Ok(tvec!(i64::scalar_fact()))
+        }
+    }
+
+    fn declutter(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        declutter_to_konst(model, node)
+    }
+
+    as_op!();
+}
+
+/// Split a tensor along `axis` into a sequence (ONNX SplitToSequence).
+///
+/// The optional second input gives either a chunk size or the list of chunk lengths.
+/// Without it, the tensor is split in chunks of one, and `keep_dims` tells if the split
+/// axis is kept in the elements.
+#[derive(Debug, Clone, Default, Hash)]
+pub struct SplitToSequence {
+    pub axis: usize,
+    pub keep_dims: bool,
+}
+impl_dyn_hash!(SplitToSequence);
+
+impl SplitToSequence {
+    /// Start and end of each chunk along the axis, if they can be determined.
+    fn chunks(
+        &self,
+        len: &TDim,
+        split: Option<&Tensor>,
+    ) -> TractResult<Option<TVec<(TDim, TDim)>>> {
+        let sizes: TVec<TDim> = match split {
+            Some(split) if split.rank() == 1 => {
+                let sizes = split.cast_to::<i64>()?;
+                let sizes: TVec<TDim> =
+                    sizes.as_slice::<i64>()?.iter().map(|s| s.to_dim()).collect();
+                let total = sizes.iter().sum::<TDim>();
+                ensure!(
+                    total.to_i64().is_err() || len.to_i64().is_err() || &total == len,
+                    "Split {:?} does not add up to {}",
+                    split,
+                    len
+                );
+                sizes
+            }
+            split => {
+                let size = split.map(|s| s.cast_to_scalar::<i64>()).transpose()?.unwrap_or(1);
+                ensure!(size > 0, "Invalid split size {}", size);
+                let len = if let Ok(len) = len.to_i64() { len } else { return Ok(None) };
+                (0..len).step_by(size as usize).map(|s| (len - s).min(size).to_dim()).collect()
+            }
+        };
+        let mut start = TDim::zero();
+        Ok(Some(
+            sizes
+                .into_iter()
+                .map(|size| {
+                    let end = start.clone() + size;
+                    (std::mem::replace(&mut start, end.clone()), end)
+                })
+                .collect(),
+        ))
+    }
+
+    fn squeeze(&self, has_split: bool) -> bool {
+        !has_split && !self.keep_dims
+    }
+}
+
+impl Op for SplitToSequence {
+    fn name(&self) -> Cow<str> {
+        "SplitToSequence".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("axis: {} keep_dims: {}", self.axis, self.keep_dims)])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for SplitToSequence {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = &inputs[0];
+        let len = input.shape()[self.axis].to_dim();
+        let chunks = self.chunks(&len, inputs.get(1).map(|t| &**t))?.context("Unknown chunks")?;
+        let squeeze = self.squeeze(inputs.len() > 1);
+        let elements = chunks
+            .iter()
+            .map(|(start, end)| {
+                let mut chunk = input.slice(self.axis, start.to_usize()?, end.to_usize()?)?;
+                if squeeze {
+                    chunk.remove_axis(self.axis)?;
+                }
+                Ok(chunk.into_arc_tensor())
+            })
+            .collect::<TractResult<_>>()?;
+        Ok(tvec!(tensor0(TensorSequence(elements)).into_arc_tensor()))
+    }
+}
+
+impl TypedOp for SplitToSequence {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let input = inputs[0];
+        ensure!(self.axis < input.rank(), "Invalid axis {} for {:?}", self.axis, input);
+        let squeeze = self.squeeze(inputs.len() > 1);
+        let element = |start: &TDim, end: &TDim| {
+            let mut fact = input.datum_type.fact(input.shape.clone());
+            if squeeze {
+                fact.shape.remove_axis(self.axis)?;
+            } else {
+                fact.shape.set(self.axis, end.clone() - start);
+            }
+            Ok(fact)
+        };
+        let split = if let Some(split) = inputs.get(1) {
+            Some(split.konst.as_deref().context("SplitToSequence needs a constant split")?)
+        } else {
+            None
+        };
+        let elements = if let Some(chunks) = self.chunks(&input.shape[self.axis], split)? {
+            SequenceFact::Known(
+                chunks
+                    .iter()
+                    .map(|(start, end)| element(start, end))
+                    .collect::<TractResult<_>>()?,
+            )
+        } else {
+            ensure!(split.is_none(), "Can not split {:?} with {:?}", input, split);
+            SequenceFact::Dynamic(element(&0.to_dim(), &1.to_dim())?)
+        };
+        Ok(tvec!(TypedFact::sequence_of(elements)))
+    }
+
+    fn declutter(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        let inputs = model.node_input_facts(node.id)?;
+        let split = inputs.get(1).and_then(|f| f.konst.as_deref());
+        let chunks = if let Some(chunks) = self.chunks(&inputs[0].shape[self.axis], split)? {
+            chunks
+        } else {
+            return Ok(None);
+        };
+        let squeeze = self.squeeze(inputs.len() > 1);
+        let mut patch = TypedModelPatch::default();
+        let input = patch.tap_model(model, node.inputs[0])?;
+        let mut elements = tvec!();
+        for (ix, (start, end)) in chunks.into_iter().enumerate() {
+            let name = format!("{}.{}", node.name, ix);
+            let mut wire = patch.wire_node(&name, Slice::new(self.axis, start, end), &[input])?;
+            if squeeze {
+                wire = patch.wire_node(format!("{}.rm", name), AxisOp::Rm(self.axis), &wire)?;
+            }
+            elements.push(wire[0]);
+        }
+        let wire = patch.wire_node(&node.name, SequenceConstruct, &elements)?;
+        patch.shunt_outside(model, node.id.into(), wire[0])?;
+        Ok(Some(patch))
+    }
+
+    as_op!();
+}
+
+/// Concatenate the tensors of a sequence along `axis`, or stack them along a new `axis`
+/// (ONNX ConcatFromSequence).
+#[derive(Debug, Clone, Default, Hash)]
+pub struct ConcatFromSequence {
+    pub axis: usize,
+    pub new_axis: bool,
+}
+impl_dyn_hash!(ConcatFromSequence);
+
+impl Op for ConcatFromSequence {
+    fn name(&self) -> Cow<str> {
+        "ConcatFromSequence".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("axis: {} new_axis: {}", self.axis, self.new_axis)])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for ConcatFromSequence {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let seq = args_1!(inputs);
+        let seq = sequence(&seq)?;
+        ensure!(!seq.is_empty(), "Can not concatenate an empty sequence");
+        let elements = seq
+            .iter()
+            .map(|t| {
+                let mut t = t.clone().into_tensor();
+                if self.new_axis {
+                    t.insert_axis(self.axis)?;
+                }
+                Ok(t)
+            })
+            .collect::<TractResult<Vec<_>>>()?;
+        Ok(tvec!(Tensor::stack_tensors(self.axis, &elements)?.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for ConcatFromSequence {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let facts = match elements_fact(inputs[0])? {
+            SequenceFact::Known(facts) if !facts.is_empty() => facts,
+            elements => bail!("Can not concatenate {:?}", elements),
+        };
+        let mut shape = facts[0].shape.clone();
+        if self.new_axis {
+            shape.insert_axis(self.axis)?;
+            shape.set(self.axis, facts.len().to_dim());
+        } else {
+            ensure!(self.axis < shape.rank(), "Invalid axis {} for {:?}", self.axis, facts[0]);
+            let len = facts.iter().map(|f| f.shape[self.axis].clone()).sum::<TDim>();
+            shape.set(self.axis, len);
+        }
+        Ok(tvec!(facts[0].datum_type.fact(shape)))
+    }
+
+    fn declutter(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        let mut patch = TypedModelPatch::default();
+        let mut elements = if let Some(elements) = tap_elements(model, &mut patch, node.inputs[0])?
+        {
+            elements
+        } else {
+            return Ok(None);
+        };
+        if self.new_axis {
+            for (ix, wire) in elements.iter_mut().enumerate() {
+                let name = format!("{}.add_axis.{}", node.name, ix);
+                *wire = patch.wire_node(name, AxisOp::Add(self.axis), &[*wire])?[0];
+            }
+        }
+        let concat = TypedConcat::concat_vars(self.axis, elements.len());
+        let wire = patch.wire_node(&node.name, concat, &elements)?;
+        patch.shunt_outside(model, node.id.into(), wire[0])?;
+        Ok(Some(patch))
+    }
+
+    as_op!();
+}
+
+/// Build an optional, empty if there is no input (ONNX Optional).
+#[derive(Debug, Clone, Default, Hash)]
+pub struct OptionalConstruct;
+impl_dyn_hash!(OptionalConstruct);
+
+impl Op for OptionalConstruct {
+    fn name(&self) -> Cow<str> {
+        "OptionalConstruct".into()
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for OptionalConstruct {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        Ok(tvec!(tensor0(TensorOption(inputs.into_iter().next())).into_arc_tensor()))
+    }
+}
+
+impl TypedOp for OptionalConstruct {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        ensure!(inputs.len() <= 1, "Optional holds at most one tensor");
+        let elements = inputs.iter().map(|f| (*f).clone()).collect();
+        Ok(tvec!(TypedFact::optional_of(SequenceFact::Known(elements))))
+    }
+
+    as_op!();
+}
+
+/// Tells if an optional holds a tensor, as a scalar Bool (ONNX OptionalHasElement).
+#[derive(Debug, Clone, Default, Hash)]
+pub struct OptionalHasElement;
+impl_dyn_hash!(OptionalHasElement);
+
+impl Op for OptionalHasElement {
+    fn name(&self) -> Cow<str> {
+        "OptionalHasElement".into()
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for OptionalHasElement {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let optional = args_1!(inputs);
+        let has_element = optional.to_scalar::<TensorOption>()?.0.is_some();
+        Ok(tvec!(tensor0(has_element).into_arc_tensor()))
+    }
+}
+
+impl TypedOp for OptionalHasElement {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        ensure!(inputs[0].datum_type == DatumType::Optional, "Expected an optional");
+        if let Some(len) = inputs[0].sequence.as_ref().and_then(|s| s.known_len()) {
+            Ok(tvec!(tensor0(len > 0).into()))
+        } else {
+            Ok(tvec!(bool::scalar_fact()))
+        }
+    }
+
+    fn declutter(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        declutter_to_konst(model, node)
+    }
+
+    as_op!();
+}
+
+/// The tensor held by an optional, failing if it is empty (ONNX OptionalGetElement).
+#[derive(Debug, Clone, Default, Hash)]
+pub struct OptionalGetElement;
+impl_dyn_hash!(OptionalGetElement);
+
+impl Op for OptionalGetElement {
+    fn name(&self) -> Cow<str> {
+        "OptionalGetElement".into()
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for OptionalGetElement {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let optional = args_1!(inputs);
+        let element = optional.to_scalar::<TensorOption>()?.0.clone();
+        Ok(tvec!(element.context("Getting the element of an empty optional")?))
+    }
+}
+
+impl TypedOp for OptionalGetElement {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        ensure!(inputs[0].datum_type == DatumType::Optional, "Expected an optional");
+        let element = match elements_fact(inputs[0])? {
+            SequenceFact::Known(facts) if facts.is_empty() => {
+                bail!("Getting the element of an empty optional")
+            }
+            elements => elements.element(Some(0))?,
+        };
+        Ok(tvec!(element))
+    }
+
+    fn declutter(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        let mut patch = TypedModelPatch::default();
+        if let Some(elements) = tap_elements(model, &mut patch, node.inputs[0])? {
+            patch.shunt_outside(model, node.id.into(), elements[0])?;
+            return Ok(Some(patch));
+        }
+        Ok(None)
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(model: TypedModel, inputs: TVec<Tensor>) -> TractResult<TVec<Arc<Tensor>>> {
+        let plain = model.clone().into_runnable()?.run(inputs.clone())?;
+        let decluttered = model.into_decluttered()?;
+        assert!(decluttered
+            .nodes()
+            .iter()
+            .all(|n| n.outputs[0].fact.datum_type != DatumType::Sequence
+                && n.outputs[0].fact.datum_type != DatumType::Optional));
+        let lowered = decluttered.into_runnable()?.run(inputs)?;
+        assert_eq!(plain, lowered);
+        Ok(plain)
+    }
+
+    #[test]
+    fn split_insert_concat() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", f32::fact([4, 2]))?;
+        let y = model.add_source("y", f32::fact([1, 2]))?;
+        let seq = model.wire_node("split", SplitToSequence { axis: 0, keep_dims: true }, &[x])?;
+        let pos = model.add_const("pos", tensor0(-1i64))?;
+        let seq = model.wire_node("insert", SequenceInsert, &[seq[0], y, pos])?;
+        let seq = model.wire_node("erase", SequenceErase, &[seq[0]])?;
+        let concat =
+            model.wire_node("concat", ConcatFromSequence { axis: 0, new_axis: false }, &seq)?;
+        model.set_output_outlets(&concat)?;
+        let output = run(
+            model,
+            tvec!(tensor2(&[[0f32, 1.], [2., 3.], [4., 5.], [6., 7.]]), tensor2(&[[8f32, 9.]])),
+        )?;
+        output[0].close_enough(
+            &tensor2(&[[0f32, 1.], [2., 3.], [4., 5.], [8., 9.]]),
+            Approximation::Exact,
+        )
+    }
+
+    #[test]
+    fn split_with_lengths_and_stack() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", f32::fact([2, 4]))?;
+        let split = model.add_const("lengths", tensor1(&[2i64, 2]))?;
+        let seq =
+            model.wire_node("split", SplitToSequence { axis: 1, keep_dims: true }, &[x, split])?;
+        let len = model.wire_node("len", SequenceLength, &seq)?;
+        let stack =
+            model.wire_node("stack", ConcatFromSequence { axis: 0, new_axis: true }, &seq)?;
+        model.set_output_outlets(&[stack[0], len[0]])?;
+        assert_eq!(model.outlet_fact(stack[0])?.shape.as_concrete(), Some(&[2, 2, 2][..]));
+        let output = run(model, tvec!(tensor2(&[[0f32, 1., 2., 3.], [4., 5., 6., 7.]])))?;
+        output[0].close_enough(
+            &tensor3(&[[[0f32, 1.], [4., 5.]], [[2., 3.], [6., 7.]]]),
+            Approximation::Exact,
+        )?;
+        assert_eq!(output[1], rctensor0(2i64));
+        Ok(())
+    }
+
+    #[test]
+    fn sequence_at_and_optional() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", f32::fact([3]))?;
+        let seq = model.wire_node("split", SplitToSequence { axis: 0, keep_dims: false }, &[x])?;
+        let pos = model.add_const("pos", tensor0(1i32))?;
+        let at = model.wire_node("at", SequenceAt, &[seq[0], pos])?;
+        let opt = model.wire_node("opt", OptionalConstruct, &at)?;
+        let has = model.wire_node("has", OptionalHasElement, &opt)?;
+        let get = model.wire_node("get", OptionalGetElement, &opt)?;
+        model.set_output_outlets(&[get[0], has[0]])?;
+        let output = run(model, tvec!(tensor1(&[1f32, 2., 3.])))?;
+        assert_eq!(output[0], rctensor0(2f32));
+        assert_eq!(output[1], rctensor0(true));
+        Ok(())
+    }
+
+    #[test]
+    fn dynamic_split() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let s = Symbol::new('S');
+        let x = model.add_source("x", f32::fact(dims!(s, 2)))?;
+        let seq = model.wire_node("split", SplitToSequence { axis: 0, keep_dims: true }, &[x])?;
+        let fact = model.outlet_fact(seq[0])?;
+        assert_eq!(fact.sequence.as_deref(), Some(&SequenceFact::Dynamic(f32::fact([1, 2]))));
+        let pos = model.add_const("pos", tensor0(-1i64))?;
+        let at = model.wire_node("at", SequenceAt, &[seq[0], pos])?;
+        let len = model.wire_node("len", SequenceLength, &seq)?;
+        model.set_output_outlets(&[at[0], len[0]])?;
+        let output = model.into_decluttered()?.into_runnable()?.run(tvec!(tensor2(&[
+            [0f32, 1.],
+            [2., 3.],
+            [4., 5.]
+        ])))?;
+        assert_eq!(output[0], rctensor2(&[[4f32, 5.]]));
+        assert_eq!(output[1], rctensor0(3i64));
+        Ok(())
+    }
+}
@@ -0,0 +1,175 @@
+//! FarmHash "na" 64 bits hash, known to TensorFlow as `Fingerprint64`.
+//!
+//! The values are part of the model semantics (hash buckets computed at
+//! training time), so this must stay bit-exact with the reference
+//! implementation.
+
+use std::convert::TryInto;
+
+const K0: u64 = 0xc3a5c85c97cb3127;
+const K1: u64 = 0xb492b66fbe98f273;
+const K2: u64 = 0x9ae16a3b2f90404f;
+
+fn fetch64(s: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(s[at..at + 8].try_into().unwrap())
+}
+
+fn fetch32(s: &[u8], at: usize) -> u64 {
+    u32::from_le_bytes(s[at..at + 4].try_into().unwrap()) as u64
+}
+
+fn shift_mix(v: u64) -> u64 {
+    v ^ (v >> 47)
+}
+
+fn hash_len_16(u: u64, v: u64, mul: u64) -> u64 {
+    let mut a = (u ^ v).wrapping_mul(mul);
+    a ^= a >> 47;
+    let mut b = (v ^ a).wrapping_mul(mul);
+    b ^= b >> 47;
+    b.wrapping_mul(mul)
+}
+
+fn hash_len_0_to_16(s: &[u8]) -> u64 {
+    let len = s.len();
+    if len >= 8 {
+        let mul = K2.wrapping_add(len as u64 * 2);
+        let a = fetch64(s, 0).wrapping_add(K2);
+        let b = fetch64(s, len - 8);
+        let c = b.rotate_right(37).wrapping_mul(mul).wrapping_add(a);
+        let d = a.rotate_right(25).wrapping_add(b).wrapping_mul(mul);
+        hash_len_16(c, d, mul)
+    } else if len >= 4 {
+        let mul = K2.wrapping_add(len as u64 * 2);
+        let a = fetch32(s, 0);
+        hash_len_16((len as u64).wrapping_add(a << 3), fetch32(s, len - 4), mul)
+    } else if len > 0 {
+        let y = s[0] as u32 + ((s[len >> 1] as u32) << 8);
+        let z = len as u32 + ((s[len - 1] as u32) << 2);
+        shift_mix((y as u64).wrapping_mul(K2) ^ (z as u64).wrapping_mul(K0)).wrapping_mul(K2)
+    } else {
+        K2
+    }
+}
+
+fn hash_len_17_to_32(s: &[u8]) -> u64 {
+    let len = s.len();
+    let mul = K2.wrapping_add(len as u64 * 2);
+    let a = fetch64(s, 0).wrapping_mul(K1);
+    let b = fetch64(s, 8);
+    let c = fetch64(s, len - 8).wrapping_mul(mul);
+    let d = fetch64(s, len - 16).wrapping_mul(K2);
+    hash_len_16(
+        a.wrapping_add(b).rotate_right(43).wrapping_add(c.rotate_right(30)).wrapping_add(d),
+        a.wrapping_add(b.wrapping_add(K2).rotate_right(18)).wrapping_add(c),
+        mul,
+    )
+}
+
+fn hash_len_33_to_64(s: &[u8]) -> u64 {
+    let len = s.len();
+    let mul = K2.wrapping_add(len as u64 * 2);
+    let a = fetch64(s, 0).wrapping_mul(K2);
+    let b = fetch64(s, 8);
+    let c = fetch64(s, len - 8).wrapping_mul(mul);
+    let d = fetch64(s, len - 16).wrapping_mul(K2);
+    let y = a.wrapping_add(b).rotate_right(43).wrapping_add(c.rotate_right(30)).wrapping_add(d);
+    let z =
+        hash_len_16(y, a.wrapping_add(b.wrapping_add(K2).rotate_right(18)).wrapping_add(c), mul);
+    let e = fetch64(s, 16).wrapping_mul(mul);
+    let f = fetch64(s, 24);
+    let g = y.wrapping_add(fetch64(s, len - 32)).wrapping_mul(mul);
+    let h = z.wrapping_add(fetch64(s, len - 24)).wrapping_mul(mul);
+    hash_len_16(
+        e.wrapping_add(f).rotate_right(43).wrapping_add(g.rotate_right(30)).wrapping_add(h),
+        e.wrapping_add(f.wrapping_add(a).rotate_right(18)).wrapping_add(g),
+        mul,
+    )
+}
+
+fn weak_hash_len_32_with_seeds(s: &[u8], at: usize, a: u64, b: u64) -> (u64, u64) {
+    let (w, x, y, z) =
+        (fetch64(s, at), fetch64(s, at + 8), fetch64(s, at + 16), fetch64(s, at + 24));
+    let mut a = a.wrapping_add(w);
+    let mut b = b.wrapping_add(a).wrapping_add(z).rotate_right(21);
+    let c = a;
+    a = a.wrapping_add(x).wrapping_add(y);
+    b = b.wrapping_add(a.rotate_right(44));
+    (a.wrapping_add(z), b.wrapping_add(c))
+}
+
+pub fn fingerprint64(s: &[u8]) -> u64 {
+    let len = s.len();
+    if len <= 16 {
+        return hash_len_0_to_16(s);
+    } else if len <= 32 {
+        return hash_len_17_to_32(s);
+    } else if len <= 64 {
+        return hash_len_33_to_64(s);
+    }
+    let seed = 81u64;
+    let mut x = seed;
+    let mut y = seed.wrapping_mul(K1).wrapping_add(113);
+    let mut z = shift_mix(y.wrapping_mul(K2).wrapping_add(113)).wrapping_mul(K2);
+    let mut v = (0u64, 0u64);
+    let mut w = (0u64, 0u64);
+    x = x.wrapping_mul(K2).wrapping_add(fetch64(s, 0));
+    let end = ((len - 1) / 64) * 64;
+    let last64 = len - 64;
+    let mut at = 0;
+    while at != end {
+        x = x.wrapping_add(y).wrapping_add(v.0).wrapping_add(fetch64(s, at + 8)).rotate_right(37);
+        x = x.wrapping_mul(K1);
+        y = y.wrapping_add(v.1).wrapping_add(fetch64(s, at + 48)).rotate_right(42).wrapping_mul(K1);
+        x ^= w.1;
+        y = y.wrapping_add(v.0).wrapping_add(fetch64(s, at + 40));
+        z = z.wrapping_add(w.0).rotate_right(33).wrapping_mul(K1);
+        v = weak_hash_len_32_with_seeds(s, at, v.1.wrapping_mul(K1), x.wrapping_add(w.0));
+        w = weak_hash_len_32_with_seeds(
+            s,
+            at + 32,
+            z.wrapping_add(w.1),
+            y.wrapping_add(fetch64(s, at + 16)),
+        );
+        std::mem::swap(&mut z, &mut x);
+        at += 64;
+    }
+    let mul = K1.wrapping_add((z & 0xff) << 1);
+    let at = last64;
+    w.0 = w.0.wrapping_add(((len - 1) & 63) as u64);
+    v.0 = v.0.wrapping_add(w.0);
+    w.0 = w.0.wrapping_add(v.0);
+    x = x.wrapping_add(y).wrapping_add(v.0).wrapping_add(fetch64(s, at + 8)).rotate_right(37);
+    x = x.wrapping_mul(mul);
+    y = y.wrapping_add(v.1).wrapping_add(fetch64(s, at + 48)).rotate_right(42).wrapping_mul(mul);
+    x ^= w.1.wrapping_mul(9);
+    y = y.wrapping_add(v.0.wrapping_mul(9)).wrapping_add(fetch64(s, at + 40));
+    z = z.wrapping_add(w.0).rotate_right(33).wrapping_mul(mul);
+    v = weak_hash_len_32_with_seeds(s, at, v.1.wrapping_mul(mul), x.wrapping_add(w.0));
+    w = weak_hash_len_32_with_seeds(
+        s,
+        at + 32,
+        z.wrapping_add(w.1),
+        y.wrapping_add(fetch64(s, at + 16)),
+    );
+    std::mem::swap(&mut z, &mut x);
+    hash_len_16(
+        hash_len_16(v.0, w.0, mul).wrapping_add(shift_mix(y).wrapping_mul(K0)).wrapping_add(z),
+        hash_len_16(v.1, w.1, mul).wrapping_add(x),
+        mul,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_strings() {
+        assert_eq!(fingerprint64(b""), K2);
+        assert_eq!(fingerprint64(b"a"), 12917804110809363939);
+        assert_eq!(fingerprint64(b"b"), 11795596070477164822);
+        assert_eq!(fingerprint64(b"c"), 11430444447143000872);
+        assert_eq!(fingerprint64(b"d"), 4470636696479570465);
+    }
+}
@@ -0,0 +1,103 @@
+use super::fingerprint::fingerprint64;
+use crate::internal::*;
+
+/// Map each string to one of `buckets` integer ids, using the 64 bits
+/// farmhash fingerprint of its bytes.
+///
+/// Accepts both String and Blob inputs, so the buckets match TensorFlow
+/// `StringToHashBucketFast`.
+#[derive(Clone, Debug, Hash)]
+pub struct StringToHashBucket {
+    pub buckets: u64,
+}
+
+impl_dyn_hash!(StringToHashBucket);
+
+impl StringToHashBucket {
+    fn bucket(&self, bytes: &[u8]) -> i64 {
+        (fingerprint64(bytes) % self.buckets) as i64
+    }
+}
+
+impl Op for StringToHashBucket {
+    fn name(&self) -> Cow<str> {
+        "StringToHashBucket".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("buckets:{}", self.buckets)])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for StringToHashBucket {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let output = match input.datum_type() {
+            DatumType::String => {
+                input.to_array_view::<String>()?.mapv(|s| self.bucket(s.as_bytes()))
+            }
+            DatumType::Blob => input.to_array_view::<Blob>()?.mapv(|s| self.bucket(&s)),
+            dt => bail!("{} does not support {:?}", self.name(), dt),
+        };
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for StringToHashBucket {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        ensure!(self.buckets > 0, "{} needs at least one bucket", self.name());
+        ensure!(
+            inputs[0].datum_type == DatumType::String || inputs[0].datum_type == DatumType::Blob,
+            "{} expects strings, got {:?}",
+            self.name(),
+            inputs[0].datum_type
+        );
+        Ok(tvec!(i64::fact(inputs[0].shape.iter())))
+    }
+
+    fn invariants(&self, inputs: &[&TypedFact], outputs: &[&TypedFact]) -> TractResult<Invariants> {
+        Invariants::new_element_wise(inputs, outputs)
+    }
+
+    fn change_axes(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+        _io: InOut,
+        change: &AxisOp,
+    ) -> TractResult<Option<AxisChangeConsequence>> {
+        Ok(Some(AxisChangeConsequence::new(model, node, None, change)))
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tensorflow_buckets() -> TractResult<()> {
+        let op = StringToHashBucket { buckets: 10 };
+        let input =
+            tensor1(&["a", "b", "c", "d"].iter().map(|s| s.to_string()).collect::<Vec<_>>());
+        let output = op.eval(tvec!(input.into_arc_tensor()))?;
+        assert_eq!(*output[0], tensor1(&[9i64, 2, 2, 5]));
+        let op = StringToHashBucket { buckets: 3 };
+        let input = tensor1(&[
+            Blob(b"Hello".to_vec()),
+            Blob(b"TensorFlow".to_vec()),
+            Blob(b"2.x".to_vec()),
+        ]);
+        let output = op.eval(tvec!(input.into_arc_tensor()))?;
+        assert_eq!(*output[0], tensor1(&[0i64, 2, 2]));
+        Ok(())
+    }
+}
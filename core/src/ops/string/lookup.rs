@@ -0,0 +1,185 @@
+use crate::internal::*;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+fn hash_of<T: Hash>(t: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    t.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Static key/value table: each input element is replaced by the value
+/// associated to the matching key, or by `default` if the key is missing.
+///
+/// Keys are typically strings (vocabulary lookup) but any hashable type
+/// works.
+#[derive(Clone, Debug)]
+pub struct LookupTable {
+    pub keys: Arc<Tensor>,
+    pub values: Arc<Tensor>,
+    pub default: Arc<Tensor>,
+    index: HashMap<u64, TVec<usize>>,
+}
+
+impl_dyn_hash!(LookupTable);
+
+impl Hash for LookupTable {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.keys.hash(state);
+        self.values.hash(state);
+        self.default.hash(state);
+    }
+}
+
+impl LookupTable {
+    pub fn new(
+        keys: Arc<Tensor>,
+        values: Arc<Tensor>,
+        default: Arc<Tensor>,
+    ) -> TractResult<LookupTable> {
+        fn index_t<T: Datum + Hash>(keys: &Tensor) -> TractResult<HashMap<u64, TVec<usize>>> {
+            let mut index = HashMap::<u64, TVec<usize>>::default();
+            for (ix, k) in keys.as_slice::<T>()?.iter().enumerate() {
+                index.entry(hash_of(k)).or_default().push(ix);
+            }
+            Ok(index)
+        }
+        ensure!(
+            keys.rank() == 1 && values.rank() == 1 && keys.len() == values.len(),
+            "Keys and values must be vectors of the same length, got {:?} and {:?}",
+            keys,
+            values
+        );
+        ensure!(
+            default.rank() == 0 && default.datum_type() == values.datum_type(),
+            "Default must be a {:?} scalar, got {:?}",
+            values.datum_type(),
+            default
+        );
+        let index = dispatch_hash!(index_t(keys.datum_type())(&keys))?;
+        Ok(LookupTable { keys, values, default, index })
+    }
+
+    fn positions_t<T: Datum + Hash>(&self, input: &Tensor) -> TractResult<Vec<Option<usize>>> {
+        let keys = self.keys.as_slice::<T>()?;
+        Ok(input
+            .as_slice::<T>()?
+            .iter()
+            .map(|needle| {
+                self.index
+                    .get(&hash_of(needle))
+                    .and_then(|candidates| candidates.iter().find(|ix| keys[**ix] == *needle))
+                    .copied()
+            })
+            .collect())
+    }
+
+    fn gather_t<T: Datum>(
+        &self,
+        positions: &[Option<usize>],
+        shape: &[usize],
+    ) -> TractResult<Tensor> {
+        let values = self.values.as_slice::<T>()?;
+        let default = self.default.to_scalar::<T>()?;
+        let output: Vec<T> =
+            positions.iter().map(|p| p.map(|p| &values[p]).unwrap_or(default).clone()).collect();
+        tensor1(&output).into_shape(shape)
+    }
+}
+
+impl Op for LookupTable {
+    fn name(&self) -> Cow<str> {
+        "LookupTable".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "{} {:?} keys to {:?}",
+            self.keys.len(),
+            self.keys.datum_type(),
+            self.values.datum_type()
+        )])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for LookupTable {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let positions = dispatch_hash!(Self::positions_t(self.keys.datum_type())(self, &input))?;
+        let output = dispatch_datum!(Self::gather_t(self.values.datum_type())(
+            self,
+            &positions,
+            input.shape()
+        ))?;
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for LookupTable {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        ensure!(
+            inputs[0].datum_type == self.keys.datum_type(),
+            "Looking up {:?} in a table of {:?} keys",
+            inputs[0].datum_type,
+            self.keys.datum_type()
+        );
+        Ok(tvec!(self.values.datum_type().fact(inputs[0].shape.iter())))
+    }
+
+    fn invariants(&self, inputs: &[&TypedFact], outputs: &[&TypedFact]) -> TractResult<Invariants> {
+        Invariants::new_element_wise(inputs, outputs)
+    }
+
+    fn change_axes(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+        _io: InOut,
+        change: &AxisOp,
+    ) -> TractResult<Option<AxisChangeConsequence>> {
+        Ok(Some(AxisChangeConsequence::new(model, node, None, change)))
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(s: &[&str]) -> Tensor {
+        tensor1(&s.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn vocabulary() -> TractResult<()> {
+        let op = LookupTable::new(
+            strings(&["the", "cat", "sat"]).into_arc_tensor(),
+            rctensor1(&[4i64, 8, 15]),
+            rctensor0(-1i64),
+        )?;
+        let input = strings(&["cat", "on", "the", "mat"]).into_shape(&[2, 2])?;
+        let output = op.eval(tvec!(input.into_arc_tensor()))?;
+        assert_eq!(*output[0], tensor2(&[[8i64, -1], [4, -1]]));
+        Ok(())
+    }
+
+    #[test]
+    fn reverse_vocabulary() -> TractResult<()> {
+        let op = LookupTable::new(
+            rctensor1(&[4i64, 8]),
+            strings(&["the", "cat"]).into_arc_tensor(),
+            rctensor0("<unk>".to_string()),
+        )?;
+        let output = op.eval(tvec!(rctensor1(&[8i64, 5])))?;
+        assert_eq!(*output[0], strings(&["cat", "<unk>"]));
+        Ok(())
+    }
+}
@@ -0,0 +1,12 @@
+//! Operators on string tensors, for text preprocessing graphs.
+
+pub mod fingerprint;
+mod hash;
+mod lookup;
+mod normalizer;
+mod split;
+
+pub use hash::StringToHashBucket;
+pub use lookup::LookupTable;
+pub use normalizer::{CaseChange, StringNormalizer};
+pub use split::{Separator, StringSplit};
@@ -0,0 +1,148 @@
+use crate::internal::*;
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum CaseChange {
+    Lower,
+    Upper,
+}
+
+/// Remove stop words from a list of strings, and optionally change the
+/// case of the remaining ones.
+///
+/// Input is either `[C]` or `[1, C]`. When stop words are removed, the
+/// inner dimension becomes data-dependent. If every string is removed, the
+/// output holds a single empty string.
+#[derive(Clone, Debug, Hash)]
+pub struct StringNormalizer {
+    pub case_change: Option<CaseChange>,
+    pub case_sensitive: bool,
+    pub stopwords: Vec<String>,
+    pub len: Symbol,
+}
+
+impl_dyn_hash!(StringNormalizer);
+
+impl StringNormalizer {
+    pub fn new(
+        case_change: Option<CaseChange>,
+        case_sensitive: bool,
+        stopwords: Vec<String>,
+    ) -> StringNormalizer {
+        let stopwords = if case_sensitive {
+            stopwords
+        } else {
+            stopwords.iter().map(|s| s.to_lowercase()).collect()
+        };
+        StringNormalizer { case_change, case_sensitive, stopwords, len: Symbol::fresh('W') }
+    }
+
+    fn is_stopword(&self, s: &str) -> bool {
+        if self.case_sensitive {
+            self.stopwords.iter().any(|w| w == s)
+        } else {
+            let s = s.to_lowercase();
+            self.stopwords.contains(&s)
+        }
+    }
+
+    fn normalize(&self, s: &str) -> String {
+        match self.case_change {
+            Some(CaseChange::Lower) => s.to_lowercase(),
+            Some(CaseChange::Upper) => s.to_uppercase(),
+            None => s.to_string(),
+        }
+    }
+}
+
+impl Op for StringNormalizer {
+    fn name(&self) -> Cow<str> {
+        "StringNormalizer".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "case_change:{:?} case_sensitive:{} stopwords:{}",
+            self.case_change,
+            self.case_sensitive,
+            self.stopwords.len()
+        )])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for StringNormalizer {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let mut strings: Vec<String> = input
+            .as_slice::<String>()?
+            .iter()
+            .filter(|s| !self.is_stopword(s))
+            .map(|s| self.normalize(s))
+            .collect();
+        if strings.is_empty() {
+            strings.push(String::new());
+        }
+        let mut shape: TVec<usize> = input.shape().into();
+        *shape.last_mut().context("StringNormalizer expects a non-scalar input")? = strings.len();
+        Ok(tvec!(tensor1(&strings).into_shape(&shape)?.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for StringNormalizer {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let fact = inputs[0];
+        ensure!(
+            fact.datum_type == DatumType::String,
+            "{} expects strings, got {:?}",
+            self.name(),
+            fact.datum_type
+        );
+        ensure!(
+            fact.rank() == 1 || (fact.rank() == 2 && fact.shape[0].is_one()),
+            "{} expects a [C] or [1, C] input, got {:?}",
+            self.name(),
+            fact
+        );
+        let mut shape = fact.shape.to_tvec();
+        if !self.stopwords.is_empty() {
+            *shape.last_mut().unwrap() = self.len.to_dim();
+        }
+        Ok(tvec!(String::fact(shape)))
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(s: &[&str]) -> Tensor {
+        tensor1(&s.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn remove_stopwords() -> TractResult<()> {
+        let op = StringNormalizer::new(Some(CaseChange::Upper), false, vec!["The".into()]);
+        let input = strings(&["the", "cat", "THE", "Sat"]).into_shape(&[1, 4])?;
+        let output = op.eval(tvec!(input.into_arc_tensor()))?;
+        assert_eq!(*output[0], strings(&["CAT", "SAT"]).into_shape(&[1, 2])?);
+        let output = op.eval(tvec!(strings(&["the"]).into_arc_tensor()))?;
+        assert_eq!(*output[0], strings(&[""]));
+        Ok(())
+    }
+
+    #[test]
+    fn case_sensitive_stopwords() -> TractResult<()> {
+        let op = StringNormalizer::new(Some(CaseChange::Lower), true, vec!["The".into()]);
+        let output = op.eval(tvec!(strings(&["the", "The", "Cat"]).into_arc_tensor()))?;
+        assert_eq!(*output[0], strings(&["the", "cat"]));
+        Ok(())
+    }
+}
@@ -0,0 +1,200 @@
+use crate::internal::*;
+
+/// How to cut a string into tokens.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum Separator {
+    /// Runs of whitespace, ignoring leading whitespace (python `str.split()`).
+    Whitespace,
+    /// An exact, non-empty, substring. Consecutive separators give empty tokens.
+    Exact(String),
+    /// Any of the characters. An empty set splits into individual characters.
+    AnyOf(String),
+}
+
+impl Separator {
+    /// Split `s`, performing at most `max_split` cuts: the last token holds the
+    /// remainder of the string.
+    pub fn split<'s>(&self, s: &'s str, max_split: Option<usize>) -> Vec<&'s str> {
+        let max_tokens = max_split.map(|m| m + 1).unwrap_or(usize::MAX);
+        match self {
+            Separator::Whitespace => {
+                let mut tokens = vec![];
+                let mut rest = s.trim_start();
+                while !rest.is_empty() {
+                    match rest.find(char::is_whitespace) {
+                        Some(end) if tokens.len() + 1 < max_tokens => {
+                            tokens.push(&rest[..end]);
+                            rest = rest[end..].trim_start();
+                        }
+                        _ => {
+                            tokens.push(rest);
+                            break;
+                        }
+                    }
+                }
+                tokens
+            }
+            Separator::Exact(sep) => s.splitn(max_tokens, &**sep).collect(),
+            Separator::AnyOf(chars) if chars.is_empty() => {
+                let mut tokens: Vec<&str> = vec![];
+                for (ix, c) in s.char_indices() {
+                    if tokens.len() + 1 == max_tokens {
+                        tokens.push(&s[ix..]);
+                        break;
+                    }
+                    tokens.push(&s[ix..ix + c.len_utf8()]);
+                }
+                tokens
+            }
+            Separator::AnyOf(chars) => s.splitn(max_tokens, |c| chars.contains(c)).collect(),
+        }
+    }
+}
+
+/// Split each string of the input into tokens.
+///
+/// The first output has an extra inner axis holding the tokens, padded with
+/// empty strings to the length of the longest split. The second output has
+/// the input shape and holds the token counts as i64.
+#[derive(Clone, Debug, Hash)]
+pub struct StringSplit {
+    pub separator: Separator,
+    pub max_split: Option<usize>,
+    pub tokens: Symbol,
+}
+
+impl_dyn_hash!(StringSplit);
+
+impl StringSplit {
+    pub fn new(separator: Separator, max_split: Option<usize>) -> StringSplit {
+        StringSplit { separator, max_split, tokens: Symbol::fresh('T') }
+    }
+}
+
+impl Op for StringSplit {
+    fn name(&self) -> Cow<str> {
+        "StringSplit".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("separator:{:?} max_split:{:?}", self.separator, self.max_split)])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for StringSplit {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let strings = input.as_slice::<String>()?;
+        let tokens: Vec<Vec<&str>> =
+            strings.iter().map(|s| self.separator.split(s, self.max_split)).collect();
+        let width = tokens.iter().map(|t| t.len()).max().unwrap_or(0);
+        let counts = tokens.iter().map(|t| t.len() as i64).collect::<Vec<_>>();
+        let counts = tensor1(&counts).into_shape(input.shape())?;
+        let mut shape: TVec<usize> = input.shape().into();
+        shape.push(width);
+        let mut padded = tract_ndarray::ArrayD::<String>::default(&*shape);
+        for (lane, t) in padded.as_slice_mut().unwrap().chunks_mut(width.max(1)).zip(tokens.iter())
+        {
+            for (slot, token) in lane.iter_mut().zip(t.iter()) {
+                *slot = token.to_string();
+            }
+        }
+        Ok(tvec!(padded.into_arc_tensor(), counts.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for StringSplit {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        ensure!(
+            inputs[0].datum_type == DatumType::String,
+            "{} expects strings, got {:?}",
+            self.name(),
+            inputs[0].datum_type
+        );
+        if let Separator::Exact(sep) = &self.separator {
+            ensure!(!sep.is_empty(), "Empty separator, use Separator::Whitespace");
+        }
+        let mut shape = inputs[0].shape.to_tvec();
+        let counts = i64::fact(&shape);
+        shape.push(self.tokens.to_dim());
+        Ok(tvec!(String::fact(shape), counts))
+    }
+
+    fn invariants(
+        &self,
+        inputs: &[&TypedFact],
+        _outputs: &[&TypedFact],
+    ) -> TractResult<Invariants> {
+        Ok((0..inputs[0].rank())
+            .map(|axis| AxisInfo {
+                inputs: tvec!(Some(axis)),
+                outputs: tvec!(Some(axis), Some(axis)),
+                period: 1,
+                disposable: true,
+            })
+            .collect())
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_like_python() {
+        let ws = Separator::Whitespace;
+        assert_eq!(ws.split("  a b\t c ", None), vec!["a", "b", "c"]);
+        assert_eq!(ws.split("  a b\t c ", Some(1)), vec!["a", "b\t c "]);
+        assert!(ws.split("   ", None).is_empty());
+        let comma = Separator::Exact(",".into());
+        assert_eq!(comma.split("a,,b", None), vec!["a", "", "b"]);
+        assert_eq!(comma.split("a,,b", Some(1)), vec!["a", ",b"]);
+        assert_eq!(comma.split("", None), vec![""]);
+        let chars = Separator::AnyOf(" ,".into());
+        assert_eq!(chars.split("a b,c", None), vec!["a", "b", "c"]);
+        assert_eq!(Separator::AnyOf("".into()).split("héé", Some(1)), vec!["h", "éé"]);
+    }
+
+    #[test]
+    fn padded_tokens() -> TractResult<()> {
+        let op = StringSplit::new(Separator::Whitespace, None);
+        let input = tensor1(&["hello world".to_string(), "tract".to_string(), "".to_string()]);
+        let output = op.eval(tvec!(input.into_arc_tensor()))?;
+        let expected = ["hello", "world", "tract", "", "", ""];
+        let expected = expected.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(*output[0], tensor1(&expected).into_shape(&[3, 2])?);
+        assert_eq!(*output[1], tensor1(&[2i64, 1, 0]));
+        Ok(())
+    }
+
+    #[test]
+    fn two_splits_in_one_model() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let source = model.add_source("input", String::fact([2]))?;
+        let words = StringSplit::new(Separator::Whitespace, None);
+        let chars = StringSplit::new(Separator::AnyOf("".into()), None);
+        let words = model.wire_node("words", words, &[source])?;
+        let chars = model.wire_node("chars", chars, &[source])?;
+        let concat = crate::ops::array::TypedConcat::concat_vars(1, 2);
+        let concat = model.wire_node("concat", concat, &[words[0], chars[0]])?;
+        model.set_output_outlets(&concat)?;
+        let words_width = &model.outlet_fact(words[0])?.shape[1];
+        let chars_width = &model.outlet_fact(chars[0])?.shape[1];
+        assert_ne!(words_width, chars_width);
+        assert_ne!(words_width, &Symbol::from('T').to_dim());
+
+        let input = tensor1(&["a b".to_string(), "tract".to_string()]);
+        let output = model.into_runnable()?.run(tvec!(input))?;
+        assert_eq!(output[0].shape(), &[2, 7]);
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+use crate::internal::*;
+use crate::ops::binary::{MergeOpUnicast, TypedBinOp, UnaryOp};
+use crate::ops::element_wise::ElementWiseOp;
+use crate::ops::fused_element_wise::{FusedElementWise, FusedStep};
+use crate::optim::OptimizerSession;
+use std::collections::HashSet;
+
+/// Merge chains of element-wise ops into a single FusedElementWise.
+///
+/// A chain link is an ElementWiseOp, a UnaryOp with a scalar constant or a
+/// binary op between two tensors of the same shape, all on the same float
+/// datum type. Intermediate results must feed only the next link.
+#[derive(Clone, Debug)]
+pub struct FuseElementWise;
+
+impl super::TypedPass for FuseElementWise {
+    fn reset(&mut self) -> TractResult<()> {
+        Ok(())
+    }
+
+    fn next(
+        &mut self,
+        _session: &mut OptimizerSession,
+        model: &TypedModel,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        let mut visited = HashSet::<usize>::new();
+        for node in model.eval_order()? {
+            if visited.contains(&node) {
+                continue;
+            }
+            let chain = chain_from(model, node)?;
+            visited.extend(chain.iter().copied());
+            if chain.len() > 1 {
+                return fuse(model, &chain).map(Some);
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Check that node can be a chain link, its accumulator coming from `acc`.
+/// Returns the step and the other input outlet, if any.
+fn as_step(
+    model: &TypedModel,
+    node: &TypedNode,
+    acc: OutletId,
+) -> TractResult<Option<(FusedStep, Option<OutletId>)>> {
+    let acc_fact = model.outlet_fact(acc)?;
+    let dt = acc_fact.datum_type;
+    if !dt.is_float()
+        || node.outputs.len() != 1
+        || node.outputs[0].fact.datum_type != dt
+        || node.outputs[0].fact.shape != acc_fact.shape
+    {
+        return Ok(None);
+    }
+    if let Some(op) = node.op_as::<ElementWiseOp>() {
+        if op.0.output_type(dt).is_none() {
+            return Ok(Some((FusedStep::Map(op.0.clone()), None)));
+        }
+    } else if let Some(op) = node.op_as::<UnaryOp>() {
+        if op.a.len() == 1 && op.a.datum_type() == dt {
+            return Ok(Some((FusedStep::Uniform(op.mini_op.clone(), op.a.clone()), None)));
+        }
+    } else if node.inputs.len() == 2 && node.inputs[0] != node.inputs[1] {
+        let mini_op = if let Some(op) = node.op_as::<TypedBinOp>() {
+            &op.0
+        } else if let Some(op) = node.op_as::<MergeOpUnicast>() {
+            &op.0
+        } else {
+            return Ok(None);
+        };
+        let acc_is_left = node.inputs[0] == acc;
+        let other = node.inputs[acc_is_left as usize];
+        if model.outlet_fact(other)? == acc_fact {
+            // the input index is resolved when the whole chain is known
+            return Ok(Some((FusedStep::WithInput(mini_op.clone(), 0, acc_is_left), Some(other))));
+        }
+    }
+    Ok(None)
+}
+
+/// The longest chain of fusable nodes starting at node.
+fn chain_from(model: &TypedModel, node: usize) -> TractResult<Vec<usize>> {
+    let mut chain = vec![];
+    let mut current = model.node(node);
+    if current.inputs.is_empty() || as_step(model, current, current.inputs[0])?.is_none() {
+        return Ok(chain);
+    }
+    chain.push(node);
+    loop {
+        let outlet = OutletId::new(current.id, 0);
+        let succs = &current.outputs[0].successors;
+        if succs.len() != 1 || model.output_outlets()?.contains(&outlet) {
+            return Ok(chain);
+        }
+        let next = model.node(succs[0].node);
+        if as_step(model, next, outlet)?.is_none() {
+            return Ok(chain);
+        }
+        chain.push(next.id);
+        current = next;
+    }
+}
+
+fn fuse(model: &TypedModel, chain: &[usize]) -> TractResult<TypedModelPatch> {
+    let head = model.node(chain[0]);
+    let mut acc = head.inputs[0];
+    let mut inputs = tvec!(acc);
+    let mut steps = vec![];
+    for &node in chain {
+        let node = model.node(node);
+        let (step, other) = as_step(model, node, acc)?.context("chain link is not fusable")?;
+        let step = match (step, other) {
+            (FusedStep::WithInput(op, _, acc_is_left), Some(other)) => {
+                let ix = if let Some(ix) = inputs.iter().position(|i| *i == other) {
+                    ix
+                } else {
+                    inputs.push(other);
+                    inputs.len() - 1
+                };
+                FusedStep::WithInput(op, ix, acc_is_left)
+            }
+            (step, _) => step,
+        };
+        steps.push(step);
+        acc = OutletId::new(node.id, 0);
+    }
+    let last = model.node(*chain.last().unwrap());
+    let mut patch = TypedModelPatch::default();
+    let taps =
+        inputs.iter().map(|i| patch.tap_model(model, *i)).collect::<TractResult<TVec<_>>>()?;
+    let wire = patch.wire_node(&*last.name, FusedElementWise::new(steps), &taps)?[0];
+    patch.shunt_outside(model, acc, wire)?;
+    Ok(patch.with_context(format!("fused {} element-wise ops", chain.len())))
+}
@@ -4,12 +4,15 @@ use std::fmt::Debug;
 use tract_itertools::Itertools;
 
 pub mod change_axes;
+mod fuse_element_wise;
 mod op_optim;
 mod prop_const;
 mod push_split_down;
 
 use self::change_axes::ChangeAxes;
+use self::fuse_element_wise::FuseElementWise;
 use self::prop_const::PropConst;
+pub(crate) use self::prop_const::symbolic_value;
 use self::push_split_down::PushSplitDown;
 use op_optim::OpOptim;
 
@@ -63,6 +66,7 @@ impl Optimizer {
                 |op, _session, model, node| TypedOp::fuse(op, model, node),
                 0,
             )),
+            Box::new(FuseElementWise),
         ])
     }
 
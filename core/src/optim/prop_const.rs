@@ -1,6 +1,9 @@
 use tract_data::UndeterminedSymbol;
 
 use crate::internal::*;
+use crate::ops::array::{ConcatSlice, Gather, Slice, TypedConcat};
+use crate::ops::binary::{TypedBinOp, UnaryOp};
+use crate::ops::cast::Cast;
 use crate::ops::konst::Const;
 use crate::ops::source::TypedSource;
 use crate::optim::OptimizerSession;
@@ -48,6 +51,21 @@ impl super::TypedPass for PropConst {
                 }
             }
         }
+        let mut symbolic = HashMap::default();
+        for node in model.eval_order()? {
+            let node = model.node(node);
+            if node.outputs.len() != 1 || node.outputs[0].fact.konst.is_some() {
+                continue;
+            }
+            if let Some(value) = symbolic_value(model, node.id.into(), &mut symbolic)? {
+                let dt = node.outputs[0].fact.datum_type;
+                if let Ok(value) = value.cast_to_dt(dt) {
+                    let wire = patch.add_const(format!("{}.0", node.name), value.into_owned())?;
+                    patch.shunt_outside(model, node.id.into(), wire)?;
+                    return Ok(Some(patch));
+                }
+            }
+        }
         for node in model.eval_order()? {
             trace!("Cleanup inputs for {}", model.node(node));
             for i in 0..model.node(node).inputs.len() {
@@ -65,3 +83,143 @@ impl super::TypedPass for PropConst {
         Ok(Some(patch).filter(|p| p.nodes.len() > 0))
     }
 }
+
+/// Shape-like tensors bigger than this are not evaluated symbolically.
+const MAX_SYMBOLIC_VOLUME: usize = 64;
+
+/// Value of an integer outlet as a TDim tensor, evaluated symbolically.
+///
+/// Shape computations (Shape -> Gather -> Concat -> Reshape chains from ONNX exporters) do
+/// not fold to constants when they involve symbols, as casting a symbolic TDim to an integer
+/// type is undetermined. This evaluates the usual shape ops (Cast, Gather, Concat, Slice,
+/// AxisOp and arithmetic) over TDim instead. Returns None if the outlet is not a small
+/// integer tensor computed from constants by such ops.
+pub(crate) fn symbolic_value(
+    model: &TypedModel,
+    outlet: OutletId,
+    cache: &mut HashMap<OutletId, Option<Arc<Tensor>>>,
+) -> TractResult<Option<Arc<Tensor>>> {
+    if let Some(value) = cache.get(&outlet) {
+        return Ok(value.clone());
+    }
+    let value = eval_symbolic(model, outlet, cache)?;
+    cache.insert(outlet, value.clone());
+    Ok(value)
+}
+
+fn eval_symbolic(
+    model: &TypedModel,
+    outlet: OutletId,
+    cache: &mut HashMap<OutletId, Option<Arc<Tensor>>>,
+) -> TractResult<Option<Arc<Tensor>>> {
+    let fact = model.outlet_fact(outlet)?;
+    if !fact.datum_type.is_integer() && fact.datum_type != TDim::datum_type() {
+        return Ok(None);
+    }
+    if !fact
+        .shape
+        .as_concrete()
+        .map(|s| s.iter().product::<usize>() <= MAX_SYMBOLIC_VOLUME)
+        .unwrap_or(false)
+    {
+        return Ok(None);
+    }
+    if let Some(k) = &fact.konst {
+        return Ok(k.cast_to::<TDim>().ok().map(|k| k.into_owned().into_arc_tensor()));
+    }
+    let node = model.node(outlet.node);
+    let op: Box<dyn TypedOp> = if node.op_is::<Cast>() {
+        return symbolic_value(model, node.inputs[0], cache);
+    } else if let Some(op) = node.op_as::<UnaryOp>() {
+        match op.a.cast_to::<TDim>() {
+            Ok(a) => Box::new(UnaryOp::new(op.mini_op.clone(), a.into_owned().into_arc_tensor())),
+            Err(_) => return Ok(None),
+        }
+    } else if let Some(op) = node.op_as::<TypedConcat>() {
+        let mut slices = tvec!();
+        for slice in &op.slices {
+            slices.push(match slice {
+                ConcatSlice::Var => ConcatSlice::Var,
+                ConcatSlice::Const(c) => match c.cast_to::<TDim>() {
+                    Ok(c) => ConcatSlice::Const(c.into_owned().into_arc_tensor()),
+                    Err(_) => return Ok(None),
+                },
+            })
+        }
+        Box::new(TypedConcat::new(op.axis, slices))
+    } else if node.op_is::<Gather>()
+        || node.op_is::<Slice>()
+        || node.op_is::<AxisOp>()
+        || node.op_is::<TypedBinOp>()
+    {
+        node.op.clone()
+    } else {
+        return Ok(None);
+    };
+    let mut inputs = tvec!();
+    for input in &node.inputs {
+        if let Some(value) = symbolic_value(model, *input, cache)? {
+            inputs.push(value)
+        } else {
+            return Ok(None);
+        }
+    }
+    let mut value = match op.eval(inputs) {
+        Ok(mut outputs) if outputs.len() > outlet.slot => outputs.remove(outlet.slot).into_tensor(),
+        _ => return Ok(None),
+    };
+    if value.datum_type() != TDim::datum_type() {
+        return Ok(None);
+    }
+    value.as_slice_mut::<TDim>()?.iter_mut().for_each(|d| *d = d.clone().simplify());
+    Ok(Some(value.into_arc_tensor()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shape_of_input(model: &mut TypedModel) -> TractResult<OutletId> {
+        let s = Symbol::from('S');
+        let x = model.add_source("x", f32::fact(&[s.to_dim(), 3.to_dim(), 4.to_dim()]))?;
+        let shape = tensor1(&model.outlet_fact(x)?.shape.to_tvec());
+        let shape = model.add_const("shape", shape)?;
+        Ok(model.wire_node("shape.cast", Cast::new(i64::datum_type()), &[shape])?[0])
+    }
+
+    #[test]
+    fn symbolic_gather_concat() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let shape = shape_of_input(&mut model)?;
+        let zero = model.add_const("zero", tensor1(&[0i64]))?;
+        let batch = model.wire_node("batch", Gather::new(0), &[shape, zero])?;
+        let target = model.wire_node(
+            "target",
+            TypedConcat::new(0, tvec!(ConcatSlice::Var, ConcatSlice::Const(rctensor1(&[12i64])))),
+            &batch,
+        )?;
+        let double =
+            model.wire_node("double", crate::ops::math::mul::unary(rctensor1(&[2i64])), &target)?;
+        let s = Symbol::from('S');
+        assert_eq!(
+            *model.symbolic_value(double[0])?.unwrap(),
+            tensor1(&[s.to_dim() * 2, 24.to_dim()])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fold_concrete_dims() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let shape = shape_of_input(&mut model)?;
+        let rest = model.wire_node("rest", Slice::new(0, 1, 3), &[shape])?;
+        let one =
+            model.wire_node("one", crate::ops::math::add::unary(rctensor1(&[1i64])), &rest)?;
+        model.set_output_outlets(&one)?;
+        assert!(model.outlet_fact(one[0])?.konst.is_none());
+        let model = model.into_decluttered()?;
+        let output = model.outlet_fact(model.output_outlets()?[0])?;
+        assert_eq!(output.konst.as_deref(), Some(&tensor1(&[4i64, 5])));
+        Ok(())
+    }
+}
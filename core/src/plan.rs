@@ -1,6 +1,8 @@
 use std::borrow::Borrow;
+use std::collections::VecDeque;
 use std::fmt::{Debug, Display};
 use std::marker::PhantomData;
+use std::sync::Mutex;
 
 use crate::internal::*;
 use crate::model::order::eval_order_for_nodes;
@@ -44,6 +46,10 @@ where
     pub order: Vec<usize>,
     pub flush_lists: Vec<TVec<usize>>,
     pub has_unresolved_symbols: bool,
+    /// Shape-specialized variants, used by `SimpleState::run` when the input shapes resolve all
+    /// symbols.
+    #[educe(Hash(ignore))]
+    pub variants: Option<PlanVariants>,
     _casper: PhantomData<(F, O)>,
 }
 
@@ -106,6 +112,7 @@ where
             flush_lists,
             outputs: outputs.to_vec(),
             has_unresolved_symbols: !symbols.is_empty(),
+            variants: None,
             _casper: PhantomData,
         })
     }
@@ -120,6 +127,193 @@ where
     }
 }
 
+impl TypedSimplePlan<TypedModel> {
+    /// This constructor returns a plan for a decluttered model with symbolic input dimensions.
+    ///
+    /// The plan runs an optimized symbolic version of the model, but its states specialize it
+    /// lazily for each new assignment of the input symbols: the model is decluttered once,
+    /// then concretized and optimized for each variant, so kernels are picked for the actual
+    /// shapes. At most `capacity` variants are kept, least recently used ones are evicted
+    /// first. Variants share the constant tensors of the decluttered model, and the packed
+    /// weights of the matrix products computed from them.
+    pub fn new_polymorphic(model: TypedModel, capacity: usize) -> TractResult<Self> {
+        ensure!(capacity > 0, "Polymorphic plan needs room for at least one variant");
+        let mut symbols: Vec<Symbol> = vec![];
+        for input in model.input_outlets()? {
+            for dim in model.outlet_fact(*input)?.shape.iter() {
+                for s in dim.symbols() {
+                    if !symbols.contains(&s) {
+                        symbols.push(s);
+                    }
+                }
+            }
+        }
+        let model = model.into_decluttered()?;
+        let mut plan = SimplePlan::new(model.clone().into_optimized()?)?;
+        if !symbols.is_empty() {
+            let source = Arc::new(model);
+            let packed_a = Arc::new(crate::ops::matmul::PackedACache::default());
+            let specializer: PlanSpecializer = Arc::new(move |values: &SymbolValues| {
+                let model = packed_a.scope(|| source.concretize_dims(values)?.into_optimized())?;
+                SimplePlan::new(model)
+            });
+            plan.variants = Some(PlanVariants::new(symbols, capacity, specializer));
+        }
+        Ok(plan)
+    }
+}
+
+/// Builds the plan of a variant for the given symbol values.
+pub type PlanSpecializer =
+    Arc<dyn Fn(&SymbolValues) -> TractResult<TypedSimplePlan<TypedModel>> + Send + Sync>;
+
+type Variant = (Vec<i64>, Arc<TypedSimplePlan<TypedModel>>);
+
+/// Bounded cache of plans specialized for concrete values of a set of symbols.
+///
+/// Clones share the same cache.
+#[derive(Clone)]
+pub struct PlanVariants {
+    symbols: Vec<Symbol>,
+    capacity: usize,
+    specializer: PlanSpecializer,
+    /// Most recently used first.
+    variants: Arc<Mutex<VecDeque<Variant>>>,
+}
+
+impl PlanVariants {
+    pub fn new(
+        symbols: Vec<Symbol>,
+        capacity: usize,
+        specializer: PlanSpecializer,
+    ) -> PlanVariants {
+        PlanVariants { symbols, capacity, specializer, variants: Default::default() }
+    }
+
+    /// Number of variants currently cached.
+    pub fn len(&self) -> usize {
+        self.variants.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Plan specialized for the values, or None if some symbols are left unresolved.
+    pub fn get(
+        &self,
+        values: &SymbolValues,
+    ) -> TractResult<Option<Arc<TypedSimplePlan<TypedModel>>>> {
+        let key = match self.symbols.iter().map(|s| values[*s]).collect::<Option<Vec<i64>>>() {
+            Some(key) => key,
+            None => return Ok(None),
+        };
+        {
+            let mut variants = self.variants.lock().unwrap();
+            if let Some(pos) = variants.iter().position(|v| v.0 == key) {
+                let variant = variants.remove(pos).unwrap();
+                let plan = variant.1.clone();
+                variants.push_front(variant);
+                return Ok(Some(plan));
+            }
+        }
+        let plan = Arc::new(
+            (self.specializer)(values)
+                .with_context(|| format!("Specializing plan for {:?}", values))?,
+        );
+        let mut variants = self.variants.lock().unwrap();
+        if let Some(pos) = variants.iter().position(|v| v.0 == key) {
+            // built concurrently by another thread, keep the cached one
+            let variant = variants.remove(pos).unwrap();
+            let plan = variant.1.clone();
+            variants.push_front(variant);
+            return Ok(Some(plan));
+        }
+        variants.push_front((key, plan.clone()));
+        variants.truncate(self.capacity);
+        Ok(Some(plan))
+    }
+
+    /// Plan specialized for the symbol values implied by the input shapes.
+    pub fn for_inputs<F, O>(
+        &self,
+        model: &Graph<F, O>,
+        inputs: &[Tensor],
+    ) -> TractResult<Option<Arc<TypedSimplePlan<TypedModel>>>>
+    where
+        F: Fact + Hash + Clone + 'static,
+        O: Debug + Display + AsRef<dyn Op> + AsMut<dyn Op> + Clone + 'static + Hash,
+    {
+        let mut values = SymbolValues::default();
+        for (outlet, input) in model.input_outlets()?.iter().zip(inputs) {
+            if let Ok(fact) = model.outlet_fact(*outlet)?.to_typed_fact() {
+                for (expected, provided) in fact.shape.iter().zip(input.shape()) {
+                    resolve(&mut values, &expected, *provided as i64)
+                        .with_context(|| format!("Input {:?}", outlet))?
+                }
+            }
+        }
+        self.get(&values)
+    }
+}
+
+impl Debug for PlanVariants {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PlanVariants({:?}, {}/{})", self.symbols, self.len(), self.capacity)
+    }
+}
+
+fn resolve(symbols: &mut SymbolValues, expected: &TDim, provided: i64) -> TractResult<()> {
+    match expected {
+        TDim::Sym(s) => match symbols[*s] {
+            Some(value) if value != provided => {
+                bail!("Conflicting values for {:?}: {} and {}", s, value, provided)
+            }
+            _ => symbols[*s] = Some(provided),
+        },
+        TDim::MulInt(x, expr) => {
+            ensure!(provided % *x == 0, "Dimension {} is not a multiple of {}", provided, x);
+            resolve(symbols, expr, provided / *x)?
+        }
+        _ => (),
+    }
+    Ok(())
+}
+
+/// Resolves the symbols of a dimension, replacing the values they had in a previous run.
+fn override_symbols(symbols: &mut SymbolValues, expected: &TDim, provided: i64) -> TractResult<()> {
+    let mut values = SymbolValues::default();
+    resolve(&mut values, expected, provided)?;
+    for s in expected.symbols() {
+        if let Some(v) = values[s] {
+            symbols[s] = Some(v);
+        }
+    }
+    Ok(())
+}
+
+/// Callback fired with the label and the value of a tapped outlet.
+pub type TapCallback = Arc<dyn Fn(&str, &Arc<Tensor>) -> TractResult<()> + Send + Sync>;
+
+/// An outlet watched by a SimpleState during its runs.
+#[derive(Clone)]
+pub struct Tap {
+    pub outlet: OutletId,
+    pub label: String,
+    /// None to collect the value in SimpleState::tapped
+    pub callback: Option<TapCallback>,
+}
+
+impl Debug for Tap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Tap({:?} {:?}", self.outlet, self.label)?;
+        if self.callback.is_some() {
+            write!(f, " with callback")?;
+        }
+        write!(f, ")")
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct SimpleState<F, O, M, P>
 where
@@ -132,6 +326,11 @@ where
     pub states: Vec<Option<Box<dyn OpState>>>,
     pub session_state: SessionState,
     pub values: Vec<Option<TVec<Arc<Tensor>>>>,
+    pub taps: Vec<Tap>,
+    /// Values of the collecting taps from the last run, by outlet label.
+    pub tapped: HashMap<String, Arc<Tensor>>,
+    /// State of the last plan variant `run` dispatched to.
+    variant: Option<Box<TypedSimpleState<TypedModel, Arc<TypedSimplePlan<TypedModel>>>>>,
     _phantom: PhantomData<(M, F, O)>,
 }
 
@@ -151,7 +350,16 @@ where
             .iter()
             .map(|n: &Node<F, O>| n.op().state(&mut session, n.id))
             .collect::<TractResult<_>>()?;
-        Ok(SimpleState { plan, states, session_state: session, values, _phantom: PhantomData })
+        Ok(SimpleState {
+            plan,
+            states,
+            session_state: session,
+            values,
+            taps: vec![],
+            tapped: HashMap::default(),
+            variant: None,
+            _phantom: PhantomData,
+        })
     }
 
     /// Reset wires state.
@@ -173,10 +381,41 @@ where
         Ok(())
     }
 
+    /// Runs the plan on the inputs.
+    ///
+    /// If the plan has shape-specialized variants, and neither stateful ops nor taps tie the
+    /// state to the symbolic model, the run is dispatched to the variant for the input shapes.
+    /// `values` and `session_state` are then left untouched.
     pub fn run(&mut self, inputs: TVec<Tensor>) -> TractResult<TVec<Arc<Tensor>>> {
+        if let Some(variant) = self.variant_for(&inputs)? {
+            let reuse =
+                self.variant.as_ref().map(|v| Arc::ptr_eq(&v.plan, &variant)).unwrap_or(false);
+            if !reuse {
+                self.variant = Some(Box::new(SimpleState::new(variant)?));
+            }
+            return self.variant.as_mut().unwrap().run(inputs);
+        }
         self.run_plan_with_eval(inputs, self::eval)
     }
 
+    fn variant_for(
+        &self,
+        inputs: &[Tensor],
+    ) -> TractResult<Option<Arc<TypedSimplePlan<TypedModel>>>> {
+        let plan = self.plan.borrow();
+        if let Some(variants) = &plan.variants {
+            let stateless = plan
+                .model()
+                .nodes()
+                .iter()
+                .all(|n| n.op().is_stateless() || n.op_is::<crate::ops::source::TypedSource>());
+            if stateless && self.taps.is_empty() {
+                return variants.for_inputs(plan.model(), inputs);
+            }
+        }
+        Ok(None)
+    }
+
     pub fn exec(&mut self) -> TractResult<TVec<Arc<Tensor>>> {
         self.exec_plan_with_eval(self::eval)
     }
@@ -210,12 +449,15 @@ where
         E: Into<anyhow::Error> + Send + Sync + 'static,
     {
         let mut result = tvec!();
+        self.tapped.clear();
         {
             let &mut SimpleState {
                 ref plan,
                 ref mut session_state,
                 ref mut states,
                 ref mut values,
+                ref taps,
+                ref mut tapped,
                 ..
             } = self;
             let plan = plan.borrow();
@@ -268,11 +510,12 @@ where
                     for (o, v) in node.outputs.iter().zip(vs.iter()) {
                         if let Ok(f) = o.fact.to_typed_fact() {
                             for (dim_abstract, dim_concrete) in f.shape.iter().zip(v.shape()) {
-                                Self::resolve(
+                                override_symbols(
                                     &mut session_state.resolved_symbols,
                                     &dim_abstract,
                                     *dim_concrete as i64,
-                                );
+                                )
+                                .with_context(|| format!("Evaluating {}", node))?;
                             }
                         }
                     }
@@ -303,6 +546,16 @@ where
                     }
                 }
 
+                for tap in taps.iter().filter(|tap| tap.outlet.node == node.id) {
+                    let value = &vs[tap.outlet.slot];
+                    if let Some(callback) = &tap.callback {
+                        callback(&tap.label, value)
+                            .with_context(|| format!("Tap {} on {}", tap.label, node))?;
+                    } else {
+                        tapped.insert(tap.label.clone(), value.clone());
+                    }
+                }
+
                 values[node.id] = Some(vs);
             }
             for output in &plan.outputs {
@@ -321,20 +574,21 @@ where
             self.model().inputs.len(),
             inputs.len()
         );
+        let mut values = SymbolValues::default();
+        for (outlet, t) in self.model().input_outlets()?.iter().zip(inputs.iter()) {
+            if let Ok(fact) = self.model().outlet_fact(*outlet)?.to_typed_fact() {
+                for (expected, provided) in fact.shape.iter().zip(t.shape()) {
+                    resolve(&mut values, &expected, *provided as i64)
+                        .with_context(|| format!("Input {:?}", outlet))?
+                }
+            }
+        }
         for (ix, t) in inputs.into_iter().enumerate() {
             self.set_input(ix, t)?
         }
         Ok(())
     }
 
-    fn resolve(symbols: &mut SymbolValues, expected: &TDim, provided: i64) {
-        match expected {
-            TDim::Sym(s) => symbols[*s] = Some(provided),
-            TDim::MulInt(x, expr) => Self::resolve(symbols, expr, provided / *x),
-            _ => (),
-        }
-    }
-
     pub fn set_input(&mut self, input: usize, t: Tensor) -> TractResult<()> {
         let outlet: OutletId = *self
             .model()
@@ -346,7 +600,8 @@ where
         let model = plan.model.borrow();
         if let Ok(fact) = model.outlet_fact(outlet)?.to_typed_fact() {
             for (expected, provided) in fact.shape.iter().zip(t.shape()) {
-                Self::resolve(&mut session_state.resolved_symbols, &expected, *provided as i64)
+                override_symbols(&mut session_state.resolved_symbols, &expected, *provided as i64)
+                    .with_context(|| format!("Setting input {}", input))?
             }
         }
         self.plan
@@ -467,6 +722,106 @@ where
             .collect())
     }
 
+    /// Label of an outlet, as used for taps: its outlet label if it has one, the node name
+    /// for the first output of a node, "name:slot" otherwise.
+    pub fn tap_label(&self, outlet: OutletId) -> String {
+        let model = self.model();
+        if let Some(label) = model.outlet_label(outlet) {
+            label.to_string()
+        } else if outlet.slot == 0 {
+            model.node(outlet.node).name.clone()
+        } else {
+            format!("{}:{}", model.node(outlet.node).name, outlet.slot)
+        }
+    }
+
+    fn add_tap(&mut self, outlet: OutletId, callback: Option<TapCallback>) -> TractResult<String> {
+        let model = self.model();
+        ensure!(
+            outlet.node < model.nodes().len()
+                && outlet.slot < model.node(outlet.node).outputs.len(),
+            "Invalid outlet {:?}",
+            outlet
+        );
+        if !self.plan().order.contains(&outlet.node) {
+            bail!(
+                "{} is not computed by this plan, use SimplePlan::new_for_outputs",
+                model.node(outlet.node)
+            );
+        }
+        if callback.is_none() {
+            if let Some(tap) =
+                self.taps.iter().find(|tap| tap.outlet == outlet && tap.callback.is_none())
+            {
+                return Ok(tap.label.clone());
+            }
+        }
+        let label = self.tap_label(outlet);
+        self.taps.push(Tap { outlet, label: label.clone(), callback });
+        Ok(label)
+    }
+
+    /// Collect the value of an outlet in `tapped` during runs. Returns the tap label. Tapping
+    /// an outlet already collected is a no-op.
+    pub fn tap(&mut self, outlet: OutletId) -> TractResult<String> {
+        self.add_tap(outlet, None)
+    }
+
+    /// Collect the value of an outlet found by outlet label, node name or "name:slot".
+    pub fn tap_by_name(&mut self, name: &str) -> TractResult<String> {
+        let model = self.model();
+        let outlet = if let Some((outlet, _)) =
+            model.outlet_labels.iter().find(|(_, label)| label.as_str() == name)
+        {
+            *outlet
+        } else if let Ok(node) = model.node_by_name(name) {
+            OutletId::new(node.id, 0)
+        } else if let Some((node, slot)) = name.rsplit_once(':') {
+            OutletId::new(model.node_by_name(node)?.id, slot.parse()?)
+        } else {
+            bail!("No node or outlet named {}", name)
+        };
+        self.tap(outlet)
+    }
+
+    /// Call `callback` with the value of an outlet during runs. Returns the tap label.
+    pub fn tap_with<C>(&mut self, outlet: OutletId, callback: C) -> TractResult<String>
+    where
+        C: Fn(&str, &Arc<Tensor>) -> TractResult<()> + Send + Sync + 'static,
+    {
+        self.add_tap(outlet, Some(Arc::new(callback)))
+    }
+
+    /// Collect the values of all outlets computed by the plan.
+    pub fn tap_all(&mut self) -> TractResult<()> {
+        let outlets: Vec<OutletId> = self
+            .plan()
+            .order
+            .iter()
+            .flat_map(|&n| {
+                (0..self.model().node(n).outputs.len()).map(move |slot| OutletId::new(n, slot))
+            })
+            .collect();
+        for outlet in outlets {
+            self.tap(outlet)?;
+        }
+        Ok(())
+    }
+
+    /// Remove all taps, and the collected values.
+    pub fn untap_all(&mut self) {
+        self.taps.clear();
+        self.tapped.clear();
+    }
+
+    /// Collected tap values from the last run, in tap order.
+    pub fn tapped_values(&self) -> impl Iterator<Item = (&str, &Arc<Tensor>)> {
+        self.taps
+            .iter()
+            .filter(|tap| tap.callback.is_none())
+            .filter_map(move |tap| self.tapped.get(&tap.label).map(|t| (&*tap.label, t)))
+    }
+
     pub fn plan(&self) -> &SimplePlan<F, O, M> {
         self.plan.borrow()
     }
@@ -493,3 +848,208 @@ where
     .with_context(|| format!("Evaluating {}", node));
     r
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::math;
+    use std::sync::Mutex;
+
+    fn model() -> TractResult<TypedModel> {
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", f32::fact([2]))?;
+        let y = model.wire_node("y", math::add::unary(rctensor1(&[1f32])), &[x])?;
+        let z = model.wire_node("z", math::mul::unary(rctensor1(&[2f32])), &y)?;
+        model.set_output_outlets(&z)?;
+        Ok(model)
+    }
+
+    #[test]
+    fn tap_intermediate() -> TractResult<()> {
+        let model = model()?;
+        let y = model.node_by_name("y")?.id;
+        let mut state = SimpleState::new(SimplePlan::new(model)?)?;
+        assert_eq!(state.tap(OutletId::new(y, 0))?, "y");
+        let seen = Arc::new(Mutex::new(vec![]));
+        let seen_in_callback = seen.clone();
+        state.tap_with(OutletId::new(y, 0), move |label, value| {
+            seen_in_callback.lock().unwrap().push((label.to_string(), value.clone()));
+            Ok(())
+        })?;
+        let outputs = state.run(tvec!(tensor1(&[1f32, 2.])))?;
+        assert_eq!(*outputs[0], tensor1(&[4f32, 6.]));
+        assert_eq!(*state.tapped["y"], tensor1(&[2f32, 3.]));
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 1);
+        assert_eq!(seen[0].0, "y");
+        assert_eq!(*seen[0].1, tensor1(&[2f32, 3.]));
+        Ok(())
+    }
+
+    #[test]
+    fn tap_all_dedup() -> TractResult<()> {
+        let mut state = SimpleState::new(SimplePlan::new(model()?)?)?;
+        assert_eq!(state.tap_by_name("y")?, "y");
+        state.tap_all()?;
+        assert_eq!(state.tap_by_name("y")?, "y");
+        assert_eq!(state.taps.len(), 3);
+        state.run(tvec!(tensor1(&[1f32, 2.])))?;
+        let labels: Vec<&str> = state.tapped_values().map(|(label, _)| label).collect();
+        assert_eq!(labels, vec!["y", "x", "z"]);
+        assert_eq!(*state.tapped["x"], tensor1(&[1f32, 2.]));
+        Ok(())
+    }
+
+    #[test]
+    fn tap_by_name() -> TractResult<()> {
+        let mut state = SimpleState::new(SimplePlan::new(model()?)?)?;
+        assert_eq!(state.tap_by_name("z:0")?, "z");
+        assert!(state.tap_by_name("w").is_err());
+        assert!(state.tap_by_name("z:1").is_err());
+        Ok(())
+    }
+
+    fn polymorphic_model() -> TractResult<TypedModel> {
+        let mut model = TypedModel::default();
+        let s = Symbol::from('S');
+        let x = model.add_source("x", f32::fact(&[s.to_dim(), 2.to_dim()]))?;
+        let y = model.wire_node("y", math::add::unary(rctensor2(&[[1f32, 2.]])), &[x])?;
+        model.set_output_outlets(&y)?;
+        Ok(model)
+    }
+
+    #[test]
+    fn polymorphic_lru() -> TractResult<()> {
+        let plan = SimplePlan::new_polymorphic(polymorphic_model()?, 2)?;
+        let variants = plan.variants.as_ref().unwrap();
+        for s in 1..=3 {
+            let input = Tensor::zero::<f32>(&[s, 2])?;
+            let output = plan.run(tvec!(input))?;
+            assert_eq!(output[0].shape(), &[s, 2]);
+            assert_eq!(output[0].as_slice::<f32>()?[2 * s - 1], 2f32);
+        }
+        assert_eq!(variants.len(), 2);
+        let s = Symbol::from('S');
+        let three = variants.get(&SymbolValues::default().with(s, 3))?.unwrap();
+        assert!(Arc::ptr_eq(&three, &variants.get(&SymbolValues::default().with(s, 3))?.unwrap()));
+        assert!(!plan.model().node(plan.model().output_outlets()?[0].node).outputs[0]
+            .fact
+            .shape
+            .is_concrete());
+        assert!(three.model().output_fact(0)?.shape.is_concrete());
+        // S=1 was evicted, S=2 is now the least recently used
+        variants.get(&SymbolValues::default().with(s, 1))?;
+        assert_eq!(variants.len(), 2);
+        assert!(variants.get(&SymbolValues::default())?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn polymorphic_variants_share_weights() -> TractResult<()> {
+        let plan = SimplePlan::new_polymorphic(polymorphic_model()?, 4)?;
+        let variants = plan.variants.as_ref().unwrap();
+        let s = Symbol::from('S');
+        let weights = |n: i64| -> TractResult<Arc<Tensor>> {
+            let variant = variants.get(&SymbolValues::default().with(s, n))?.unwrap();
+            let node = variant.model().node_by_name("y")?;
+            Ok(node.op_as::<crate::ops::binary::UnaryOp>().unwrap().a.clone())
+        };
+        assert!(Arc::ptr_eq(&weights(1)?, &weights(5)?));
+        Ok(())
+    }
+
+    #[test]
+    fn polymorphic_variants_share_packed_weights() -> TractResult<()> {
+        use crate::ops::matmul::lir_unary::{LirMatMulUnary, PackedA};
+        let mut model = TypedModel::default();
+        let s = Symbol::from('S');
+        let x = model.add_source("x", f32::fact(&[s.to_dim(), 4.to_dim()]))?;
+        let a = Tensor::from_shape(&[3, 4], &(1..=12).map(|i| i as f32).collect::<Vec<_>>())?;
+        let mm = crate::ops::matmul::MatMulUnary::new(a.into_arc_tensor(), false, true, true);
+        let y = model.wire_node("y", mm, &[x])?;
+        model.set_output_outlets(&y)?;
+        let plan = SimplePlan::new_polymorphic(model, 16)?;
+        let mut state = SimpleState::new(&plan)?;
+        for n in 2..12 {
+            let output = state.run(tvec!(Tensor::zero::<f32>(&[n, 4])?))?;
+            assert_eq!(output[0].shape(), &[n, 3]);
+        }
+        let variants = plan.variants.as_ref().unwrap();
+        assert_eq!(variants.len(), 10);
+        // the kernel, hence the packing, depends on n: compare variants using the same one
+        let mut packed_by_kernel: HashMap<String, Vec<Arc<Tensor>>> = HashMap::default();
+        for n in 2..12 {
+            let variant = variants.get(&SymbolValues::default().with(s, n))?.unwrap();
+            let lir = variant
+                .model()
+                .nodes()
+                .iter()
+                .find_map(|n| n.op_as::<LirMatMulUnary>())
+                .context("no LirMatMulUnary")?;
+            let packed = match &lir.micro_ops.iter().next().unwrap().0 {
+                PackedA::Packed(packed) => packed.clone(),
+                other => bail!("unexpected {:?}", other),
+            };
+            packed_by_kernel.entry(lir.mmm.kernel_name().to_string()).or_default().push(packed);
+        }
+        assert!(packed_by_kernel.values().any(|packed| packed.len() > 1));
+        for packed in packed_by_kernel.values() {
+            assert!(packed.iter().all(|p| Arc::ptr_eq(p, &packed[0])));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn plain_optimization_does_not_share_packed_weights() -> TractResult<()> {
+        use crate::ops::matmul::lir_unary::{LirMatMulUnary, PackedA};
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", f32::fact(&[8, 4]))?;
+        let a = Tensor::from_shape(&[3, 4], &(1..=12).map(|i| i as f32).collect::<Vec<_>>())?;
+        let mm = crate::ops::matmul::MatMulUnary::new(a.into_arc_tensor(), false, true, true);
+        let y = model.wire_node("y", mm, &[x])?;
+        model.set_output_outlets(&y)?;
+        let packed = |model: TypedModel| -> TractResult<Arc<Tensor>> {
+            let model = model.into_optimized()?;
+            let lir = model
+                .nodes()
+                .iter()
+                .find_map(|n| n.op_as::<LirMatMulUnary>())
+                .context("no LirMatMulUnary")?;
+            match &lir.micro_ops.iter().next().unwrap().0 {
+                PackedA::Packed(packed) => Ok(packed.clone()),
+                other => bail!("unexpected {:?}", other),
+            }
+        };
+        assert!(!Arc::ptr_eq(&packed(model.clone())?, &packed(model)?));
+        Ok(())
+    }
+
+    fn polymorphic_two_inputs_model() -> TractResult<TypedModel> {
+        let mut model = TypedModel::default();
+        let s = Symbol::from('S');
+        let x = model.add_source("x", f32::fact(&[s.to_dim() * 2, 2.to_dim()]))?;
+        let y = model.add_source("y", f32::fact(&[s.to_dim(), 2.to_dim()]))?;
+        model.set_output_outlets(&[x, y])?;
+        Ok(model)
+    }
+
+    #[test]
+    fn polymorphic_rejects_non_multiple_dims() -> TractResult<()> {
+        let plan = SimplePlan::new_polymorphic(polymorphic_two_inputs_model()?, 2)?;
+        let inputs = [Tensor::zero::<f32>(&[5, 2])?, Tensor::zero::<f32>(&[2, 2])?];
+        assert!(plan.variants.as_ref().unwrap().for_inputs(plan.model(), &inputs).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn polymorphic_rejects_conflicting_symbols() -> TractResult<()> {
+        let plan = SimplePlan::new_polymorphic(polymorphic_two_inputs_model()?, 2)?;
+        let variants = plan.variants.as_ref().unwrap();
+        let inputs = [Tensor::zero::<f32>(&[6, 2])?, Tensor::zero::<f32>(&[2, 2])?];
+        assert!(variants.for_inputs(plan.model(), &inputs).is_err());
+        assert!(plan.run(inputs.iter().cloned().collect()).is_err());
+        let inputs = [Tensor::zero::<f32>(&[6, 2])?, Tensor::zero::<f32>(&[3, 2])?];
+        assert!(variants.for_inputs(plan.model(), &inputs)?.is_some());
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+use crate::internal::translator::Translate;
+use crate::internal::*;
+use crate::ops::cnn::ConvUnary;
+use crate::ops::matmul::{MatMulUnary, WeightQuantMatMulUnary};
+use crate::ops::scan::Scan;
+pub use tract_linalg::frame::weight_quant::{QuantizedWeights, WeightFormat, WeightQuant};
+
+/// Switch constant f32 matmul and convolution weights to int8 or int4 storage.
+///
+/// Activations stay in f32: weights are dequantized on the fly while packed
+/// for the matrix multiplier. Convolutions are only flagged here, their kernel
+/// is quantized at codegen.
+#[derive(Debug)]
+pub struct WeightQuantTranslator(pub WeightQuant);
+
+impl Translate<TypedFact, Box<dyn TypedOp>, TypedFact, Box<dyn TypedOp>> for WeightQuantTranslator {
+    fn translate_node(
+        &self,
+        _source: &Graph<TypedFact, Box<dyn TypedOp>>,
+        node: &Node<TypedFact, Box<dyn TypedOp>>,
+        target: &mut Graph<TypedFact, Box<dyn TypedOp>>,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        let new_op: Box<dyn TypedOp> = if let Some(op) = node.op_as::<MatMulUnary>() {
+            if let Some(op) = WeightQuantMatMulUnary::from_mat_mul_unary(op, self.0)? {
+                Box::new(op)
+            } else {
+                node.op.clone()
+            }
+        } else if let Some(op) = node.op_as::<ConvUnary>() {
+            if op.kernel.datum_type() == f32::datum_type() && op.q_params.is_none() && op.group == 1
+            {
+                Box::new(ConvUnary { weight_quant: Some(self.0), ..op.clone() })
+            } else {
+                node.op.clone()
+            }
+        } else if let Some(op) = node.op_as::<Scan>() {
+            let mut new = op.clone();
+            new.body = self.translate_model(&op.body)?;
+            Box::new(new)
+        } else {
+            node.op.clone()
+        };
+        target.wire_node(
+            &node.name,
+            new_op,
+            &node.inputs.iter().map(|i| mapping[i]).collect::<TVec<_>>(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops::cnn::{KernelFormat, PaddingSpec, PoolSpec};
+    use crate::ops::nn::DataFormat;
+
+    #[test]
+    fn conv_and_matmul() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let source = model.add_source("input", f32::fact([1, 4, 8]))?;
+        let kernel = Tensor::from_shape(
+            &[6, 4, 3],
+            &(0..72).map(|i| ((i * 5) % 11) as f32 / 4. - 1.).collect::<Vec<_>>(),
+        )?;
+        let conv = ConvUnary::new(
+            PoolSpec::new(DataFormat::NCHW, tvec!(3), PaddingSpec::Valid, None, None, Some(6)),
+            KernelFormat::OIHW,
+            kernel.into_arc_tensor(),
+            1,
+            None,
+            None,
+        );
+        let wire = model.wire_node("conv", conv, &[source])?;
+        let a = Tensor::from_shape(
+            &[1, 5, 6],
+            &(0..30).map(|i| ((i * 7) % 13) as f32 / 6. - 1.).collect::<Vec<_>>(),
+        )?;
+        let matmul = MatMulUnary::new(a.into_arc_tensor(), false, false, false);
+        let wire = model.wire_node("matmul", matmul, &wire)?;
+        model.set_output_outlets(&wire)?;
+
+        let quant = WeightQuant { format: WeightFormat::Int4, group_size: 4 };
+        let quantized = WeightQuantTranslator(quant).translate_model(&model)?;
+        assert!(quantized.node_by_name("matmul")?.op_is::<WeightQuantMatMulUnary>());
+        assert_eq!(
+            quantized.node_by_name("conv")?.op_as::<ConvUnary>().unwrap().weight_quant,
+            Some(quant)
+        );
+
+        let input = Tensor::from_shape(
+            &[1, 4, 8],
+            &(0..32).map(|i| ((i * 3) % 7) as f32 / 3. - 1.).collect::<Vec<_>>(),
+        )?;
+        let reference = model.into_runnable()?.run(tvec!(input.clone()))?.remove(0);
+        let plain = quantized.clone().into_runnable()?.run(tvec!(input.clone()))?.remove(0);
+        let optimized = quantized.into_optimized()?.into_runnable()?.run(tvec!(input))?.remove(0);
+        optimized.close_enough(&plain, true)?;
+        // int4 is coarse: only check we stay in the neighbourhood of f32
+        let reference = reference.as_slice::<f32>()?;
+        let max = reference.iter().fold(0f32, |acc, x| acc.max(x.abs()));
+        for (x, y) in reference.iter().zip(plain.as_slice::<f32>()?) {
+            assert!((x - y).abs() < max / 4., "{} {}", x, y);
+        }
+        Ok(())
+    }
+}
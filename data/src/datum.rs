@@ -4,9 +4,11 @@ use crate::tensor::litteral::*;
 use crate::tensor::Tensor;
 use crate::TVec;
 use half::f16;
+use itertools::Itertools;
 use num_complex::Complex;
 use scan_fmt::scan_fmt;
 use std::hash::Hash;
+use std::sync::Arc;
 use std::{fmt, ops};
 
 use num_traits::AsPrimitive;
@@ -34,6 +36,50 @@ impl std::str::FromStr for Blob {
     }
 }
 
+/// An ordered list of tensors, as manipulated by ONNX sequence operators.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct TensorSequence(pub Vec<Arc<Tensor>>);
+
+impl ops::Deref for TensorSequence {
+    type Target = [Arc<Tensor>];
+    fn deref(&self) -> &[Arc<Tensor>] {
+        &self.0
+    }
+}
+
+impl fmt::Display for TensorSequence {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "Sequence of {} tensors: [{:?}]", self.len(), self.iter().format(", "))
+    }
+}
+
+impl std::str::FromStr for TensorSequence {
+    type Err = ();
+    fn from_str(_s: &str) -> Result<TensorSequence, ()> {
+        Err(())
+    }
+}
+
+/// A tensor that may be absent, as manipulated by ONNX optional operators.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct TensorOption(pub Option<Arc<Tensor>>);
+
+impl fmt::Display for TensorOption {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match &self.0 {
+            Some(t) => write!(fmt, "Some({:?})", t),
+            None => write!(fmt, "None"),
+        }
+    }
+}
+
+impl std::str::FromStr for TensorOption {
+    type Err = ();
+    fn from_str(_s: &str) -> Result<TensorOption, ()> {
+        Err(())
+    }
+}
+
 #[derive(Copy, Clone, PartialEq)]
 pub enum QParams {
     MinMax { min: f32, max: f32 },
@@ -118,6 +164,8 @@ pub enum DatumType {
     TDim,
     Blob,
     String,
+    Sequence,
+    Optional,
     QI8(QParams),
     QU8(QParams),
     QI32(QParams),
@@ -132,7 +180,13 @@ pub enum DatumType {
 impl DatumType {
     pub fn super_types(&self) -> TVec<DatumType> {
         use DatumType::*;
-        if *self == String || *self == TDim || *self == Blob || *self == Bool || self.is_quantized()
+        if *self == String
+            || *self == TDim
+            || *self == Blob
+            || *self == Bool
+            || *self == Sequence
+            || *self == Optional
+            || self.is_quantized()
         {
             tvec!(*self)
         } else if self.is_complex_float() {
@@ -290,6 +344,9 @@ impl DatumType {
         match self {
             DatumType::TDim => std::mem::size_of::<usize>(),
             DatumType::String => std::mem::size_of::<usize>(),
+            DatumType::Blob | DatumType::Sequence | DatumType::Optional => {
+                std::mem::size_of::<usize>()
+            }
             _ => self.size_of(),
         }
     }
@@ -358,6 +415,8 @@ impl std::str::FromStr for DatumType {
                 "Blob" | "blob" => Ok(DatumType::Blob),
                 "String" | "string" => Ok(DatumType::String),
                 "TDim" | "tdim" => Ok(DatumType::TDim),
+                "Sequence" | "sequence" => Ok(DatumType::Sequence),
+                "Optional" | "optional" => Ok(DatumType::Optional),
                 "ComplexI16" | "complexi16" => Ok(DatumType::ComplexI16),
                 "ComplexI32" | "complexi32" => Ok(DatumType::ComplexI32),
                 "ComplexI64" | "complexi64" => Ok(DatumType::ComplexI64),
@@ -459,6 +518,8 @@ datum!(u64, U64);
 datum!(TDim, TDim);
 datum!(String, String);
 datum!(Blob, Blob);
+datum!(TensorSequence, Sequence);
+datum!(TensorOption, Optional);
 datum!(Complex<i16>, ComplexI16);
 datum!(Complex<i32>, ComplexI32);
 datum!(Complex<i64>, ComplexI64);
@@ -18,7 +18,8 @@ impl std::error::Error for UndeterminedSymbol {}
 macro_rules! b( ($e:expr) => { Box::new($e) } );
 
 lazy_static::lazy_static! {
-    static ref SYMBOL_TABLE: std::sync::Mutex<Vec<char>> = std::sync::Mutex::new(Vec::new());
+    // symbol names, and whether they can be looked up by name
+    static ref SYMBOL_TABLE: std::sync::Mutex<Vec<(char, bool)>> = std::sync::Mutex::new(Vec::new());
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash, Debug)]
@@ -27,7 +28,22 @@ pub struct Symbol(char, usize);
 impl Symbol {
     pub fn new(c: char) -> Symbol {
         let mut table = SYMBOL_TABLE.lock().unwrap();
-        table.push(c);
+        table.push((c, true));
+        Symbol(c, table.len() - 1)
+    }
+
+    /// A symbol for a data-dependent dimension, distinct from every other one.
+    ///
+    /// It is named after `hint`, or the first letter not in use yet, and
+    /// `Symbol::from(char)` never resolves to it.
+    pub fn fresh(hint: char) -> Symbol {
+        let mut table = SYMBOL_TABLE.lock().unwrap();
+        let c = std::iter::once(hint)
+            .chain('A'..='Z')
+            .chain('a'..='z')
+            .find(|c| table.iter().all(|(s, _)| s != c))
+            .unwrap_or(hint);
+        table.push((c, false));
         Symbol(c, table.len() - 1)
     }
 
@@ -39,10 +55,10 @@ impl Symbol {
 impl From<char> for Symbol {
     fn from(c: char) -> Symbol {
         let mut table = SYMBOL_TABLE.lock().unwrap();
-        if let Some(pos) = table.iter().position(|s| *s == c) {
+        if let Some(pos) = table.iter().position(|s| *s == (c, true)) {
             Symbol(c, pos)
         } else {
-            table.push(c);
+            table.push((c, true));
             Symbol(c, table.len() - 1)
         }
     }
@@ -77,7 +93,7 @@ impl Debug for SymbolValues {
             self.0
                 .iter()
                 .enumerate()
-                .filter_map(|(ix, v)| v.map(|v| format!("{}={}", table[ix], v)))
+                .filter_map(|(ix, v)| v.map(|v| format!("{}={}", table[ix].0, v)))
                 .join(",")
         )
     }
@@ -278,9 +294,7 @@ impl TDim {
                             (acc.0 * a, acc.1.into_iter().chain(Some(p.as_ref().clone())).collect())
                         }
                         Val(a) => (acc.0 * a, acc.1),
-                        it => {
-                            (acc.0, acc.1.into_iter().chain(Some(it).into_iter()).collect())
-                        }
+                        it => (acc.0, acc.1.into_iter().chain(Some(it).into_iter()).collect()),
                     });
                 if rest.len() == 0 {
                     Val(ints)
@@ -343,9 +357,8 @@ impl TDim {
                                 q
                             )),
                         )
-                    } else if let Some(v) = terms
-                        .iter()
-                        .find_map(|t| if let Val(v) = t { Some(*v) } else { None })
+                    } else if let Some(v) =
+                        terms.iter().find_map(|t| if let Val(v) = t { Some(*v) } else { None })
                     {
                         let offset = if v >= q as i64 {
                             Some(v / q as i64)
@@ -952,4 +965,11 @@ mod tests {
         let e = (s() - 3 + 1).div_ceil(1);
         assert_eq!(e, s() + -2);
     }
+
+    #[test]
+    fn fresh_symbols_are_not_named() {
+        let fresh = Symbol::fresh('F');
+        assert_ne!(fresh, Symbol::fresh('F'));
+        assert_ne!(fresh, Symbol::from(fresh.as_char()));
+    }
 }
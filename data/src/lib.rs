@@ -17,7 +17,9 @@ pub type TractError = anyhow::Error;
 pub type TractResult<T> = anyhow::Result<T>;
 
 pub mod prelude {
-    pub use crate::datum::{round_ties_to_even, Blob, Datum, DatumType, QParams};
+    pub use crate::datum::{
+        round_ties_to_even, Blob, Datum, DatumType, QParams, TensorOption, TensorSequence,
+    };
     pub use crate::dim::{Symbol, SymbolValues, TDim, ToDim};
     pub use crate::tensor::litteral::*;
     pub use crate::tensor::{natural_strides, IntoArcTensor, IntoTensor, Tensor};
@@ -38,6 +38,8 @@ macro_rules! dispatch_datum {
             DatumType::Blob => $($path)::*::<Blob>($($args),*),
             DatumType::TDim => $($path)::*::<TDim>($($args),*),
             DatumType::String => $($path)::*::<String>($($args),*),
+            DatumType::Sequence => $($path)::*::<TensorSequence>($($args),*),
+            DatumType::Optional => $($path)::*::<TensorOption>($($args),*),
             DatumType::QI8(_) => $($path)::*::<i8>($($args),*),
             DatumType::QU8(_) => $($path)::*::<u8>($($args),*),
             DatumType::QI32(_) => $($path)::*::<i32>($($args),*),
@@ -71,6 +73,8 @@ macro_rules! dispatch_datum_by_size {
             DatumType::Blob => $($path)::*::<Blob>($($args),*),
             DatumType::TDim => $($path)::*::<TDim>($($args),*),
             DatumType::String => $($path)::*::<String>($($args),*),
+            DatumType::Sequence => $($path)::*::<TensorSequence>($($args),*),
+            DatumType::Optional => $($path)::*::<TensorOption>($($args),*),
             DatumType::QI8(_)   => $($path)::*::<i8>($($args),*),
             DatumType::QU8(_)   => $($path)::*::<u8>($($args),*),
             DatumType::QI32(_)   => $($path)::*::<i32>($($args),*),
@@ -247,6 +251,8 @@ macro_rules! dispatch_hash {
             DatumType::Blob => $($path)::*::<Blob>($($args),*),
             DatumType::TDim => $($path)::*::<TDim>($($args),*),
             DatumType::String => $($path)::*::<String>($($args),*),
+            DatumType::Sequence => $($path)::*::<TensorSequence>($($args),*),
+            DatumType::Optional => $($path)::*::<TensorOption>($($args),*),
             DatumType::ComplexI16 => $($path)::*::<Complex<i16>>($($args),*),
             DatumType::ComplexI32 => $($path)::*::<Complex<i32>>($($args),*),
             DatumType::ComplexI64 => $($path)::*::<Complex<i64>>($($args),*),
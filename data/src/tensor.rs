@@ -1,5 +1,8 @@
 //! `Tensor`, tract main data object of interest.
-use crate::datum::{round_ties_to_even, scale_by, Blob, ClampCast, Datum, DatumType, QParams};
+use crate::datum::{
+    round_ties_to_even, scale_by, Blob, ClampCast, Datum, DatumType, QParams, TensorOption,
+    TensorSequence,
+};
 use crate::dim::TDim;
 use crate::TVec;
 use half::f16;
@@ -36,6 +39,18 @@ impl From<bool> for Approximation {
     }
 }
 
+impl std::str::FromStr for Approximation {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match &*s.to_lowercase() {
+            "exact" => Ok(Self::Exact),
+            "close" => Ok(Self::Close),
+            "approximate" => Ok(Self::Approximate),
+            _ => anyhow::bail!("Unknown approximation {}, expected exact, close or approximate", s),
+        }
+    }
+}
+
 impl Approximation {
     fn atol_and_rtol(&self, dt: &DatumType) -> (f64, f64) {
         use Approximation::*;
@@ -44,6 +59,7 @@ impl Approximation {
             (Approximate, DatumType::F16) => (1e-3, 5e-3),
             (Exact, _) => (0.0, 0.0),
             (Close, _) => (1e-7, 1e-7),
+            (Approximate, dt) if dt.is_quantized() => (dt.zp_scale().1 as f64, 0.0),
             (Approximate, _) => (1e-4, 5e-4),
         }
     }
@@ -86,6 +102,8 @@ impl Hash for Tensor {
                 TDim => self.as_slice_unchecked::<crate::dim::TDim>().hash(state),
                 String => self.as_slice_unchecked::<std::string::String>().hash(state),
                 Blob => self.as_slice_unchecked::<crate::datum::Blob>().hash(state),
+                Sequence => self.as_slice_unchecked::<TensorSequence>().hash(state),
+                Optional => self.as_slice_unchecked::<TensorOption>().hash(state),
                 QI8(_) => self.as_slice_unchecked::<i8>().hash(state),
                 QU8(_) => self.as_slice_unchecked::<u8>().hash(state),
                 QI32(_) => self.as_slice_unchecked::<i32>().hash(state),
@@ -138,6 +156,22 @@ impl Drop for Tensor {
                     .for_each(|s| std::ptr::drop_in_place(s as *mut TDim));
             }
         }
+        if self.dt == DatumType::Sequence {
+            unsafe {
+                self.as_slice_mut::<TensorSequence>()
+                    .unwrap()
+                    .iter_mut()
+                    .for_each(|s| std::ptr::drop_in_place(s as *mut TensorSequence));
+            }
+        }
+        if self.dt == DatumType::Optional {
+            unsafe {
+                self.as_slice_mut::<TensorOption>()
+                    .unwrap()
+                    .iter_mut()
+                    .for_each(|s| std::ptr::drop_in_place(s as *mut TensorOption));
+            }
+        }
         if !self.data.is_null() && self.layout.size() > 0 {
             unsafe { alloc::dealloc(self.data, self.layout) }
         }
@@ -175,6 +209,10 @@ impl Tensor {
             return Ok(ndarray::ArrayD::<Blob>::default(shape).into());
         } else if dt == TDim::datum_type() {
             return Ok(ndarray::ArrayD::<TDim>::default(shape).into());
+        } else if dt == TensorSequence::datum_type() {
+            return Ok(ndarray::ArrayD::<TensorSequence>::default(shape).into());
+        } else if dt == TensorOption::datum_type() {
+            return Ok(ndarray::ArrayD::<TensorOption>::default(shape).into());
         }
         assert!(dt.is_copy());
         let bytes = shape.iter().cloned().product::<usize>() * dt.size_of();
@@ -366,6 +404,14 @@ impl Tensor {
         content: &[u8],
         align: usize,
     ) -> anyhow::Result<Tensor> {
+        let bytes = shape.iter().try_fold(dt.size_of(), |acc, d| acc.checked_mul(*d));
+        anyhow::ensure!(
+            dt.is_copy() && bytes == Some(content.len()),
+            "Can not build a {:?} tensor of shape {:?} from {} bytes",
+            dt,
+            shape,
+            content.len()
+        );
         let mut tensor = Tensor::uninitialized_aligned_dt(dt, shape, align)?;
         tensor.as_bytes_mut().copy_from_slice(content);
         Ok(tensor)
@@ -994,6 +1040,17 @@ impl Tensor {
                 return Ok(Cow::Owned(ints.cast_to_dt(dst_dt)?.into_owned()));
             }
             let mut result = Self::uninitialized_dt(dst_dt, &self.shape)?;
+            if self.dt == DatumType::Blob && dst_dt == DatumType::String {
+                for (s, d) in self
+                    .as_slice_unchecked::<Blob>()
+                    .iter()
+                    .zip(result.as_slice_mut_unchecked::<String>().iter_mut())
+                {
+                    *d = String::from_utf8(s.0.clone())
+                        .map_err(|_| anyhow::format_err!("Could not parse {} as String", s))?;
+                }
+                return Ok(Cow::Owned(result));
+            }
             if self.dt == DatumType::String {
                 dispatch_datum!(Self::cast_from_string(dst_dt)(self, &mut result))?;
                 return Ok(Cow::Owned(result));
@@ -1238,26 +1295,27 @@ impl Tensor {
     }
 
     pub fn deep_clone(&self) -> Tensor {
-        if self.dt == DatumType::String {
-            let data: Vec<String> = self.as_slice::<String>().unwrap().to_vec();
-            let t = Tensor {
+        fn clone_t<T: Datum>(t: &Tensor) -> Tensor {
+            let data: Vec<T> = t.as_slice::<T>().unwrap().to_vec();
+            let clone = Tensor {
                 data: data.as_ptr() as *mut u8,
-                shape: self.shape.clone(),
-                strides: self.strides.clone(),
-                ..*self
+                shape: t.shape.clone(),
+                strides: t.strides.clone(),
+                ..*t
             };
             std::mem::forget(data);
-            t
+            clone
+        }
+        if self.dt == DatumType::String {
+            clone_t::<String>(self)
         } else if self.dt == DatumType::TDim {
-            let data: Vec<TDim> = self.as_slice::<TDim>().unwrap().to_vec();
-            let t = Tensor {
-                data: data.as_ptr() as *mut u8,
-                shape: self.shape.clone(),
-                strides: self.strides.clone(),
-                ..*self
-            };
-            std::mem::forget(data);
-            t
+            clone_t::<TDim>(self)
+        } else if self.dt == DatumType::Blob {
+            clone_t::<Blob>(self)
+        } else if self.dt == DatumType::Sequence {
+            clone_t::<TensorSequence>(self)
+        } else if self.dt == DatumType::Optional {
+            clone_t::<TensorOption>(self)
         } else {
             unsafe {
                 let tensor = Tensor::uninitialized_dt(self.datum_type(), self.shape()).unwrap();
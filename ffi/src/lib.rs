@@ -0,0 +1,717 @@
+//! C API for tract.
+//!
+//! All functions return a `TRACT_RESULT`. On `TRACT_RESULT_KO`, the error
+//! message is available through `tract_get_last_error()` on the same thread.
+//!
+//! Objects are opaque and heap allocated. They are created by `*_create`,
+//! `*_for_path` or `into_*` functions and must be released with the matching
+//! `*_destroy`. Functions consuming an object take a pointer to the caller
+//! pointer and set it to null.
+#![allow(clippy::missing_safety_doc)]
+
+use std::cell::RefCell;
+use std::convert::{TryFrom, TryInto};
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_void};
+
+use tract_nnef::internal as native;
+use tract_nnef::internal::{bail, TractErrorContext};
+use tract_nnef::prelude::*;
+use tract_onnx::prelude::{InferenceFact, InferenceModel, InferenceModelExt};
+use tract_onnx::WithOnnx;
+
+/// Used as a return type of functions that can encounter errors.
+/// If the function encountered an error, you can retrieve it using the
+/// `tract_get_last_error` function
+#[repr(C)]
+#[allow(non_camel_case_types)]
+#[derive(Debug, PartialEq, Eq)]
+pub enum TRACT_RESULT {
+    /// The function returned successfully
+    TRACT_RESULT_OK = 0,
+    /// The function returned an error
+    TRACT_RESULT_KO = 1,
+}
+
+/// Element type of a value.
+#[repr(C)]
+#[allow(non_camel_case_types)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum DatumType {
+    TRACT_DATUM_TYPE_BOOL = 0x01,
+    TRACT_DATUM_TYPE_U8 = 0x11,
+    TRACT_DATUM_TYPE_U16 = 0x12,
+    TRACT_DATUM_TYPE_U32 = 0x14,
+    TRACT_DATUM_TYPE_U64 = 0x18,
+    TRACT_DATUM_TYPE_I8 = 0x21,
+    TRACT_DATUM_TYPE_I16 = 0x22,
+    TRACT_DATUM_TYPE_I32 = 0x24,
+    TRACT_DATUM_TYPE_I64 = 0x28,
+    TRACT_DATUM_TYPE_F16 = 0x32,
+    TRACT_DATUM_TYPE_F32 = 0x34,
+    TRACT_DATUM_TYPE_F64 = 0x38,
+}
+
+impl From<DatumType> for native::DatumType {
+    fn from(dt: DatumType) -> native::DatumType {
+        use crate::DatumType::*;
+        match dt {
+            TRACT_DATUM_TYPE_BOOL => native::DatumType::Bool,
+            TRACT_DATUM_TYPE_U8 => native::DatumType::U8,
+            TRACT_DATUM_TYPE_U16 => native::DatumType::U16,
+            TRACT_DATUM_TYPE_U32 => native::DatumType::U32,
+            TRACT_DATUM_TYPE_U64 => native::DatumType::U64,
+            TRACT_DATUM_TYPE_I8 => native::DatumType::I8,
+            TRACT_DATUM_TYPE_I16 => native::DatumType::I16,
+            TRACT_DATUM_TYPE_I32 => native::DatumType::I32,
+            TRACT_DATUM_TYPE_I64 => native::DatumType::I64,
+            TRACT_DATUM_TYPE_F16 => native::DatumType::F16,
+            TRACT_DATUM_TYPE_F32 => native::DatumType::F32,
+            TRACT_DATUM_TYPE_F64 => native::DatumType::F64,
+        }
+    }
+}
+
+impl TryFrom<u32> for DatumType {
+    type Error = TractError;
+    fn try_from(dt: u32) -> TractResult<DatumType> {
+        use crate::DatumType::*;
+        Ok(match dt {
+            0x01 => TRACT_DATUM_TYPE_BOOL,
+            0x11 => TRACT_DATUM_TYPE_U8,
+            0x12 => TRACT_DATUM_TYPE_U16,
+            0x14 => TRACT_DATUM_TYPE_U32,
+            0x18 => TRACT_DATUM_TYPE_U64,
+            0x21 => TRACT_DATUM_TYPE_I8,
+            0x22 => TRACT_DATUM_TYPE_I16,
+            0x24 => TRACT_DATUM_TYPE_I32,
+            0x28 => TRACT_DATUM_TYPE_I64,
+            0x32 => TRACT_DATUM_TYPE_F16,
+            0x34 => TRACT_DATUM_TYPE_F32,
+            0x38 => TRACT_DATUM_TYPE_F64,
+            _ => bail!("Invalid datum type {:#x}", dt),
+        })
+    }
+}
+
+impl TryFrom<native::DatumType> for DatumType {
+    type Error = TractError;
+    fn try_from(dt: native::DatumType) -> TractResult<DatumType> {
+        use crate::DatumType::*;
+        Ok(match dt.unquantized() {
+            native::DatumType::Bool => TRACT_DATUM_TYPE_BOOL,
+            native::DatumType::U8 => TRACT_DATUM_TYPE_U8,
+            native::DatumType::U16 => TRACT_DATUM_TYPE_U16,
+            native::DatumType::U32 => TRACT_DATUM_TYPE_U32,
+            native::DatumType::U64 => TRACT_DATUM_TYPE_U64,
+            native::DatumType::I8 => TRACT_DATUM_TYPE_I8,
+            native::DatumType::I16 => TRACT_DATUM_TYPE_I16,
+            native::DatumType::I32 => TRACT_DATUM_TYPE_I32,
+            native::DatumType::I64 => TRACT_DATUM_TYPE_I64,
+            native::DatumType::F16 => TRACT_DATUM_TYPE_F16,
+            native::DatumType::F32 => TRACT_DATUM_TYPE_F32,
+            native::DatumType::F64 => TRACT_DATUM_TYPE_F64,
+            _ => bail!("Datum type {:?} is not supported by the C API", dt),
+        })
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = RefCell::new(None);
+}
+
+fn wrap<F: FnOnce() -> TractResult<()>>(func: F) -> TRACT_RESULT {
+    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(func)) {
+        Ok(Ok(_)) => TRACT_RESULT::TRACT_RESULT_OK,
+        Ok(Err(e)) => {
+            let msg = format!("{:?}", e);
+            if std::env::var("TRACT_ERROR_STDERR").is_ok() {
+                eprintln!("{}", msg);
+            }
+            LAST_ERROR.with(|p| {
+                *p.borrow_mut() = Some(CString::new(msg).unwrap_or_else(|_| {
+                    CString::new("tract error message contains 0, can't convert to CString")
+                        .unwrap()
+                }))
+            });
+            TRACT_RESULT::TRACT_RESULT_KO
+        }
+        Err(_) => {
+            LAST_ERROR.with(|p| {
+                *p.borrow_mut() = Some(CString::new("tract panicked").unwrap());
+            });
+            TRACT_RESULT::TRACT_RESULT_KO
+        }
+    }
+}
+
+macro_rules! check_not_null {
+    ($($ptr:ident),*) => {
+        $(
+            if $ptr.is_null() {
+                bail!(concat!("Unexpected null pointer ", stringify!($ptr)));
+            }
+         )*
+    }
+}
+
+unsafe fn str_arg<'a>(ptr: *const c_char) -> TractResult<&'a str> {
+    check_not_null!(ptr);
+    Ok(CStr::from_ptr(ptr).to_str()?)
+}
+
+/// Retrieve the last error that happened in this thread. A function encountered an error if
+/// its return type is of type `TRACT_RESULT` and it returned `TRACT_RESULT_KO`.
+///
+/// # Return value
+///  It returns a pointer to a null-terminated UTF-8 string that will contain the error description.
+///  Rust side keeps ownership of the buffer. It will be valid as long as no other tract calls is
+///  performed by the thread.
+///  If no error occured, null is returned.
+#[no_mangle]
+pub extern "C" fn tract_get_last_error() -> *const c_char {
+    LAST_ERROR.with(|msg| msg.borrow().as_ref().map(|s| s.as_ptr()).unwrap_or(std::ptr::null()))
+}
+
+/// Returns a pointer to a static buffer containing a null-terminated version string.
+///
+/// The returned pointer must not be freed.
+#[no_mangle]
+pub extern "C" fn tract_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr() as *const c_char
+}
+
+/// Frees a string allocated by libtract.
+#[no_mangle]
+pub unsafe extern "C" fn tract_free_cstring(ptr: *mut c_char) {
+    if !ptr.is_null() {
+        let _ = CString::from_raw(ptr);
+    }
+}
+
+unsafe fn release<T>(ptr: *mut *mut T) -> TractResult<()> {
+    check_not_null!(ptr);
+    if !(*ptr).is_null() {
+        let _ = Box::from_raw(*ptr);
+        *ptr = std::ptr::null_mut();
+    }
+    Ok(())
+}
+
+unsafe fn take<T>(ptr: *mut *mut T) -> TractResult<T> {
+    check_not_null!(ptr);
+    if (*ptr).is_null() {
+        bail!("Unexpected null object");
+    }
+    let object = Box::from_raw(*ptr);
+    *ptr = std::ptr::null_mut();
+    Ok(*object)
+}
+
+unsafe fn give<T>(object: T, ptr: *mut *mut T) {
+    *ptr = Box::into_raw(Box::new(object));
+}
+
+// NNEF
+pub struct TractNnef(tract_nnef::framework::Nnef);
+
+/// Creates an instance of an NNEF framework and parser that can be used to load models.
+///
+/// The returned object should be destroyed with `tract_nnef_destroy` once the model
+/// has been loaded.
+#[no_mangle]
+pub unsafe extern "C" fn tract_nnef_create(nnef: *mut *mut TractNnef) -> TRACT_RESULT {
+    wrap(|| {
+        check_not_null!(nnef);
+        give(TractNnef(tract_nnef::nnef().with_tract_core().with_onnx()), nnef);
+        Ok(())
+    })
+}
+
+/// Destroy the NNEF parser. It is safe to destroy the NNEF parser once the model had been loaded.
+#[no_mangle]
+pub unsafe extern "C" fn tract_nnef_destroy(nnef: *mut *mut TractNnef) -> TRACT_RESULT {
+    wrap(|| release(nnef))
+}
+
+/// Parse and load an NNEF model as a tract TypedModel.
+///
+/// `path` is a null-terminated utf-8 string pointer to the `.tar`, `.tar.gz` or directory
+/// containing the model.
+///
+/// The returned model must be later freed using `tract_model_destroy`.
+#[no_mangle]
+pub unsafe extern "C" fn tract_nnef_model_for_path(
+    nnef: *const TractNnef,
+    path: *const c_char,
+    model: *mut *mut TractModel,
+) -> TRACT_RESULT {
+    wrap(|| {
+        check_not_null!(nnef, model);
+        *model = std::ptr::null_mut();
+        let path = str_arg(path)?;
+        let m =
+            (*nnef).0.model_for_path(path).with_context(|| format!("opening file {:?}", path))?;
+        give(TractModel(m), model);
+        Ok(())
+    })
+}
+
+// ONNX
+pub struct TractOnnx(tract_onnx::Onnx);
+
+/// Creates an instance of an ONNX framework and parser that can be used to load models.
+///
+/// The returned object should be destroyed with `tract_onnx_destroy` once the model
+/// has been loaded.
+#[no_mangle]
+pub unsafe extern "C" fn tract_onnx_create(onnx: *mut *mut TractOnnx) -> TRACT_RESULT {
+    wrap(|| {
+        check_not_null!(onnx);
+        give(TractOnnx(tract_onnx::onnx()), onnx);
+        Ok(())
+    })
+}
+
+/// Destroy the ONNX parser. It is safe to destroy the ONNX parser once the model had been loaded.
+#[no_mangle]
+pub unsafe extern "C" fn tract_onnx_destroy(onnx: *mut *mut TractOnnx) -> TRACT_RESULT {
+    wrap(|| release(onnx))
+}
+
+/// Parse and load an ONNX model as a tract InferenceModel.
+///
+/// `path` is a null-terminated utf-8 string pointer to the `.onnx` file.
+///
+/// The returned model must be later freed using `tract_inference_model_destroy`.
+#[no_mangle]
+pub unsafe extern "C" fn tract_onnx_model_for_path(
+    onnx: *const TractOnnx,
+    path: *const c_char,
+    model: *mut *mut TractInferenceModel,
+) -> TRACT_RESULT {
+    wrap(|| {
+        check_not_null!(onnx, model);
+        *model = std::ptr::null_mut();
+        let path = str_arg(path)?;
+        let m = (*onnx).0.model_for_path(path)?;
+        give(TractInferenceModel(m), model);
+        Ok(())
+    })
+}
+
+// TENSORFLOW
+pub struct TractTensorflow(tract_tensorflow::Tensorflow);
+
+/// Creates an instance of a TensorFlow framework and parser that can be used to load
+/// frozen graphs.
+///
+/// The returned object should be destroyed with `tract_tensorflow_destroy` once the model
+/// has been loaded.
+#[no_mangle]
+pub unsafe extern "C" fn tract_tensorflow_create(tf: *mut *mut TractTensorflow) -> TRACT_RESULT {
+    wrap(|| {
+        check_not_null!(tf);
+        give(TractTensorflow(tract_tensorflow::tensorflow()), tf);
+        Ok(())
+    })
+}
+
+/// Destroy the TensorFlow parser. It is safe to destroy the parser once the model had been
+/// loaded.
+#[no_mangle]
+pub unsafe extern "C" fn tract_tensorflow_destroy(tf: *mut *mut TractTensorflow) -> TRACT_RESULT {
+    wrap(|| release(tf))
+}
+
+/// Parse and load a TensorFlow frozen graph as a tract InferenceModel.
+///
+/// `path` is a null-terminated utf-8 string pointer to the `.pb` file.
+///
+/// The returned model must be later freed using `tract_inference_model_destroy`.
+#[no_mangle]
+pub unsafe extern "C" fn tract_tensorflow_model_for_path(
+    tf: *const TractTensorflow,
+    path: *const c_char,
+    model: *mut *mut TractInferenceModel,
+) -> TRACT_RESULT {
+    wrap(|| {
+        check_not_null!(tf, model);
+        *model = std::ptr::null_mut();
+        let path = str_arg(path)?;
+        let m = (*tf).0.model_for_path(path)?;
+        give(TractInferenceModel(m), model);
+        Ok(())
+    })
+}
+
+// INFERENCE MODEL
+pub struct TractInferenceModel(InferenceModel);
+
+/// Query an InferenceModel input and output counts.
+///
+/// The two pointers are optional, null can be used to skip one of them.
+#[no_mangle]
+pub unsafe extern "C" fn tract_inference_model_nbio(
+    model: *const TractInferenceModel,
+    inputs: *mut usize,
+    outputs: *mut usize,
+) -> TRACT_RESULT {
+    wrap(|| {
+        check_not_null!(model);
+        let model = &(*model).0;
+        if !inputs.is_null() {
+            *inputs = model.inputs.len();
+        }
+        if !outputs.is_null() {
+            *outputs = model.outputs.len();
+        }
+        Ok(())
+    })
+}
+
+/// Set an InferenceModel input fact from a null-terminated string.
+///
+/// The fact is formatted like the command line `-i` option: comma separated dimensions,
+/// optionally followed by a datum type, for instance "1,3,224,224,f32" or "N,16000,f32".
+/// A dimension can be an integer, a single letter symbol, or `_` if unknown.
+#[no_mangle]
+pub unsafe extern "C" fn tract_inference_model_set_input_fact(
+    model: *mut TractInferenceModel,
+    input_id: usize,
+    fact: *const c_char,
+) -> TRACT_RESULT {
+    wrap(|| {
+        check_not_null!(model);
+        let fact: InferenceFact = str_arg(fact)?.parse()?;
+        (*model).0.set_input_fact(input_id, fact)
+    })
+}
+
+/// Convert an InferenceModel to a TypedModel, running type and shape analysis.
+///
+/// This function consumes the InferenceModel: `*inference` is set to null. The resulting
+/// model must be freed using `tract_model_destroy`.
+#[no_mangle]
+pub unsafe extern "C" fn tract_inference_model_into_typed(
+    inference: *mut *mut TractInferenceModel,
+    typed: *mut *mut TractModel,
+) -> TRACT_RESULT {
+    wrap(|| {
+        check_not_null!(typed);
+        *typed = std::ptr::null_mut();
+        let model = take(inference)?.0.into_typed()?;
+        give(TractModel(model), typed);
+        Ok(())
+    })
+}
+
+/// Convert an InferenceModel to an optimized TypedModel.
+///
+/// This function consumes the InferenceModel: `*inference` is set to null. The resulting
+/// model must be freed using `tract_model_destroy`.
+#[no_mangle]
+pub unsafe extern "C" fn tract_inference_model_into_optimized(
+    inference: *mut *mut TractInferenceModel,
+    optimized: *mut *mut TractModel,
+) -> TRACT_RESULT {
+    wrap(|| {
+        check_not_null!(optimized);
+        *optimized = std::ptr::null_mut();
+        let model = take(inference)?.0.into_optimized()?;
+        give(TractModel(model), optimized);
+        Ok(())
+    })
+}
+
+/// Destroy an InferenceModel.
+#[no_mangle]
+pub unsafe extern "C" fn tract_inference_model_destroy(
+    model: *mut *mut TractInferenceModel,
+) -> TRACT_RESULT {
+    wrap(|| release(model))
+}
+
+// TYPED MODEL
+pub struct TractModel(TypedModel);
+
+/// Query a model input and output counts.
+///
+/// The two pointers are optional, null can be used to skip one of them.
+#[no_mangle]
+pub unsafe extern "C" fn tract_model_nbio(
+    model: *const TractModel,
+    inputs: *mut usize,
+    outputs: *mut usize,
+) -> TRACT_RESULT {
+    wrap(|| {
+        check_not_null!(model);
+        let model = &(*model).0;
+        if !inputs.is_null() {
+            *inputs = model.inputs.len();
+        }
+        if !outputs.is_null() {
+            *outputs = model.outputs.len();
+        }
+        Ok(())
+    })
+}
+
+/// Query the name of a model input.
+///
+/// The returned name must be freed by the caller using `tract_free_cstring`.
+#[no_mangle]
+pub unsafe extern "C" fn tract_model_input_name(
+    model: *const TractModel,
+    input: usize,
+    name: *mut *mut c_char,
+) -> TRACT_RESULT {
+    wrap(|| {
+        check_not_null!(model, name);
+        *name = std::ptr::null_mut();
+        let model = &(*model).0;
+        let outlet = model.input_outlets()?.get(input).context("Invalid input index")?;
+        *name = CString::new(&*model.node(outlet.node).name)?.into_raw();
+        Ok(())
+    })
+}
+
+/// Query the name of a model output.
+///
+/// The returned name must be freed by the caller using `tract_free_cstring`.
+#[no_mangle]
+pub unsafe extern "C" fn tract_model_output_name(
+    model: *const TractModel,
+    output: usize,
+    name: *mut *mut c_char,
+) -> TRACT_RESULT {
+    wrap(|| {
+        check_not_null!(model, name);
+        *name = std::ptr::null_mut();
+        let model = &(*model).0;
+        let outlet = model.output_outlets()?.get(output).context("Invalid output index")?;
+        *name = CString::new(&*model.node(outlet.node).name)?.into_raw();
+        Ok(())
+    })
+}
+
+/// Substitute symbols in a model by integer values.
+///
+/// `symbols` and `values` are arrays of `nb_symbols` items. Each symbol is a null-terminated
+/// string containing a single letter. The model is modified in place.
+#[no_mangle]
+pub unsafe extern "C" fn tract_model_concretize_symbols(
+    model: *mut TractModel,
+    nb_symbols: usize,
+    symbols: *const *const c_char,
+    values: *const i64,
+) -> TRACT_RESULT {
+    wrap(|| {
+        check_not_null!(model);
+        let mut table = SymbolValues::default();
+        if nb_symbols > 0 {
+            check_not_null!(symbols, values);
+        }
+        for i in 0..nb_symbols {
+            let name = str_arg(*symbols.add(i))?;
+            let mut chars = name.chars();
+            let symbol = match (chars.next(), chars.next()) {
+                (Some(c), None) => Symbol::from(c),
+                _ => bail!("Symbols are single letters, got {:?}", name),
+            };
+            table.set(symbol, *values.add(i));
+        }
+        let model = &mut (*model).0;
+        *model = model.concretize_dims(&table)?;
+        Ok(())
+    })
+}
+
+/// Declutter a TypedModel in-place.
+#[no_mangle]
+pub unsafe extern "C" fn tract_model_declutter(model: *mut TractModel) -> TRACT_RESULT {
+    wrap(|| {
+        check_not_null!(model);
+        (*model).0.declutter()
+    })
+}
+
+/// Optimize a TypedModel in-place.
+#[no_mangle]
+pub unsafe extern "C" fn tract_model_optimize(model: *mut TractModel) -> TRACT_RESULT {
+    wrap(|| {
+        check_not_null!(model);
+        // optimize a copy so the model is left untouched if optimization fails
+        let model = &mut (*model).0;
+        *model = model.clone().into_optimized()?;
+        Ok(())
+    })
+}
+
+/// Convert a TypedModel into a TypedRunnableModel.
+///
+/// This function consumes the model: `*model` is set to null. The runnable model must be
+/// freed using `tract_runnable_destroy`.
+#[no_mangle]
+pub unsafe extern "C" fn tract_model_into_runnable(
+    model: *mut *mut TractModel,
+    runnable: *mut *mut TractRunnable,
+) -> TRACT_RESULT {
+    wrap(|| {
+        check_not_null!(runnable);
+        *runnable = std::ptr::null_mut();
+        let plan = take(model)?.0.into_runnable()?;
+        give(TractRunnable(plan), runnable);
+        Ok(())
+    })
+}
+
+/// Destroy a TypedModel.
+#[no_mangle]
+pub unsafe extern "C" fn tract_model_destroy(model: *mut *mut TractModel) -> TRACT_RESULT {
+    wrap(|| release(model))
+}
+
+// RUNNABLE MODEL
+pub struct TractRunnable(TypedRunnableModel<TypedModel>);
+
+/// Query a runnable model input and output counts.
+///
+/// The two pointers are optional, null can be used to skip one of them.
+#[no_mangle]
+pub unsafe extern "C" fn tract_runnable_nbio(
+    runnable: *const TractRunnable,
+    inputs: *mut usize,
+    outputs: *mut usize,
+) -> TRACT_RESULT {
+    wrap(|| {
+        check_not_null!(runnable);
+        let model = (*runnable).0.model();
+        if !inputs.is_null() {
+            *inputs = model.inputs.len();
+        }
+        if !outputs.is_null() {
+            *outputs = model.outputs.len();
+        }
+        Ok(())
+    })
+}
+
+/// Run a runnable model.
+///
+/// `inputs` is an array of as many values as the model has inputs, they are borrowed and
+/// not consumed. `outputs` must point to an array of as many value pointers as the model
+/// has outputs. On success, each of them is set to a new value that the caller must free
+/// using `tract_value_destroy`.
+#[no_mangle]
+pub unsafe extern "C" fn tract_runnable_run(
+    runnable: *const TractRunnable,
+    inputs: *const *const TractValue,
+    outputs: *mut *mut TractValue,
+) -> TRACT_RESULT {
+    wrap(|| {
+        check_not_null!(runnable, inputs, outputs);
+        let plan = &(*runnable).0;
+        let input_count = plan.model().inputs.len();
+        let output_count = plan.model().outputs.len();
+        for i in 0..output_count {
+            *outputs.add(i) = std::ptr::null_mut();
+        }
+        let mut values = tvec!();
+        for i in 0..input_count {
+            let value = *inputs.add(i);
+            if value.is_null() {
+                bail!("Input {} is null", i);
+            }
+            values.push((*value).0.clone().into_tensor());
+        }
+        let results = plan.run(values)?;
+        for (i, result) in results.into_iter().enumerate() {
+            give(TractValue(result), outputs.add(i));
+        }
+        Ok(())
+    })
+}
+
+/// Destroy a runnable model.
+#[no_mangle]
+pub unsafe extern "C" fn tract_runnable_destroy(runnable: *mut *mut TractRunnable) -> TRACT_RESULT {
+    wrap(|| release(runnable))
+}
+
+// VALUE
+pub struct TractValue(Arc<Tensor>);
+
+/// Create a value from a datum type, a shape and a pointer to the (densely packed, row major)
+/// data. The data is copied, the caller keeps ownership of the buffer.
+///
+/// `datum_type` is one of the `DatumType` constants, other values are rejected.
+///
+/// The value must be freed using `tract_value_destroy`.
+#[no_mangle]
+pub unsafe extern "C" fn tract_value_create(
+    datum_type: u32,
+    rank: usize,
+    shape: *const usize,
+    data: *const c_void,
+    value: *mut *mut TractValue,
+) -> TRACT_RESULT {
+    wrap(|| {
+        check_not_null!(value);
+        *value = std::ptr::null_mut();
+        let shape: &[usize] = if rank == 0 {
+            &[]
+        } else {
+            check_not_null!(shape);
+            std::slice::from_raw_parts(shape, rank)
+        };
+        let dt: native::DatumType = DatumType::try_from(datum_type)?.into();
+        let len = shape.iter().product::<usize>() * dt.size_of();
+        let bytes: &[u8] = if len == 0 {
+            &[]
+        } else {
+            check_not_null!(data);
+            std::slice::from_raw_parts(data as *const u8, len)
+        };
+        let tensor = Tensor::from_raw_dt(dt, shape, bytes)?;
+        give(TractValue(tensor.into_arc_tensor()), value);
+        Ok(())
+    })
+}
+
+/// Inspect a value.
+///
+/// All output pointers are optional, use null to skip one of them. `shape` and `data` point
+/// to buffers owned by the value, valid until it is destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn tract_value_inspect(
+    value: *const TractValue,
+    datum_type: *mut DatumType,
+    rank: *mut usize,
+    shape: *mut *const usize,
+    data: *mut *const c_void,
+) -> TRACT_RESULT {
+    wrap(|| {
+        check_not_null!(value);
+        let tensor = &(*value).0;
+        if !datum_type.is_null() {
+            *datum_type = tensor.datum_type().try_into()?;
+        }
+        if !rank.is_null() {
+            *rank = tensor.rank();
+        }
+        if !shape.is_null() {
+            *shape = tensor.shape().as_ptr();
+        }
+        if !data.is_null() {
+            *data = tensor.as_bytes().as_ptr() as *const c_void;
+        }
+        Ok(())
+    })
+}
+
+/// Destroy a value.
+#[no_mangle]
+pub unsafe extern "C" fn tract_value_destroy(value: *mut *mut TractValue) -> TRACT_RESULT {
+    wrap(|| release(value))
+}
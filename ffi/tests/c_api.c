@@ -0,0 +1,80 @@
+#include <assert.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "tract.h"
+
+#define check(call)                                                                                \
+    {                                                                                              \
+        TRACT_RESULT result = call;                                                                \
+        if (result == TRACT_RESULT_KO) {                                                           \
+            fprintf(stderr, "Error calling tract: %s", tract_get_last_error());                    \
+            return 1;                                                                              \
+        }                                                                                          \
+    }
+
+int main(int argc, char **argv) {
+    assert(argc == 2);
+    assert(tract_version() != NULL);
+
+    /* errors are reported through the thread-local last error */
+    TractNnef *nnef = NULL;
+    TractModel *model = NULL;
+    check(tract_nnef_create(&nnef));
+    assert(tract_nnef_model_for_path(nnef, "/no/such/model", &model) == TRACT_RESULT_KO);
+    assert(model == NULL);
+    assert(tract_get_last_error() != NULL && strlen(tract_get_last_error()) > 0);
+    assert(tract_model_declutter(NULL) == TRACT_RESULT_KO);
+
+    check(tract_nnef_model_for_path(nnef, argv[1], &model));
+    check(tract_nnef_destroy(&nnef));
+    assert(nnef == NULL);
+
+    size_t inputs, outputs;
+    check(tract_model_nbio(model, &inputs, &outputs));
+    assert(inputs == 1 && outputs == 1);
+    char *name = NULL;
+    check(tract_model_input_name(model, 0, &name));
+    assert(strcmp(name, "input") == 0);
+    tract_free_cstring(name);
+
+    const char *symbols[] = {"N"};
+    const int64_t values[] = {3};
+    check(tract_model_concretize_symbols(model, 1, symbols, values));
+    check(tract_model_optimize(model));
+
+    TractRunnable *runnable = NULL;
+    check(tract_model_into_runnable(&model, &runnable));
+    assert(model == NULL);
+
+    float data[12];
+    for (int i = 0; i < 12; i++) {
+        data[i] = (float)i;
+    }
+    size_t shape[] = {3, 4};
+    TractValue *input = NULL;
+    assert(tract_value_create(0x99, 2, shape, data, &input) == TRACT_RESULT_KO);
+    assert(input == NULL);
+    check(tract_value_create(TRACT_DATUM_TYPE_F32, 2, shape, data, &input));
+
+    TractValue *output = NULL;
+    check(tract_runnable_run(runnable, (const TractValue *const *)&input, &output));
+
+    DatumType dt;
+    size_t rank;
+    const size_t *output_shape;
+    const float *output_data;
+    check(tract_value_inspect(output, &dt, &rank, &output_shape, (const void **)&output_data));
+    assert(dt == TRACT_DATUM_TYPE_F32);
+    assert(rank == 2 && output_shape[0] == 3 && output_shape[1] == 4);
+    for (int i = 0; i < 12; i++) {
+        assert(output_data[i] == 2.0f * i);
+    }
+
+    check(tract_value_destroy(&input));
+    check(tract_value_destroy(&output));
+    check(tract_runnable_destroy(&runnable));
+    assert(runnable == NULL);
+    printf("ok\n");
+    return 0;
+}
@@ -0,0 +1,48 @@
+#![cfg(target_os = "linux")]
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// Integration tests run from target/<profile>/deps, next to libtract.so
+fn lib_dir() -> PathBuf {
+    let exe = std::env::current_exe().unwrap();
+    let deps = exe.parent().unwrap();
+    if deps.join("libtract.so").exists() {
+        deps.to_path_buf()
+    } else {
+        deps.parent().unwrap().to_path_buf()
+    }
+}
+
+#[test]
+fn c_api() {
+    let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let lib_dir = lib_dir();
+    assert!(lib_dir.join("libtract.so").exists(), "libtract.so not found in {:?}", lib_dir);
+    let exe = Path::new(env!("CARGO_TARGET_TMPDIR")).join("c_api");
+    let cc = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let status = Command::new(cc)
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg("-I")
+        .arg(crate_dir)
+        .arg(crate_dir.join("tests/c_api.c"))
+        .arg("-o")
+        .arg(&exe)
+        .arg("-L")
+        .arg(&lib_dir)
+        .arg("-ltract")
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .status()
+        .expect("Failed to run C compiler");
+    assert!(status.success());
+    let output = Command::new(&exe)
+        .arg(crate_dir.join("tests/nnef-mul"))
+        .output()
+        .expect("Failed to run C test program");
+    assert!(
+        output.status.success(),
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,315 @@
+#ifndef TRACT_H
+#define TRACT_H
+
+/* Warning, this file is autogenerated by cbindgen. Don't modify this manually. */
+
+#include <stdint.h>
+#include <stddef.h>
+
+/**
+ * Used as a return type of functions that can encounter errors.
+ * If the function encountered an error, you can retrieve it using the
+ * `tract_get_last_error` function
+ */
+typedef enum {
+  /**
+   * The function returned successfully
+   */
+  TRACT_RESULT_OK = 0,
+  /**
+   * The function returned an error
+   */
+  TRACT_RESULT_KO = 1,
+} TRACT_RESULT;
+
+/**
+ * Element type of a value.
+ */
+typedef enum {
+  TRACT_DATUM_TYPE_BOOL = 0x01,
+  TRACT_DATUM_TYPE_U8 = 0x11,
+  TRACT_DATUM_TYPE_U16 = 0x12,
+  TRACT_DATUM_TYPE_U32 = 0x14,
+  TRACT_DATUM_TYPE_U64 = 0x18,
+  TRACT_DATUM_TYPE_I8 = 0x21,
+  TRACT_DATUM_TYPE_I16 = 0x22,
+  TRACT_DATUM_TYPE_I32 = 0x24,
+  TRACT_DATUM_TYPE_I64 = 0x28,
+  TRACT_DATUM_TYPE_F16 = 0x32,
+  TRACT_DATUM_TYPE_F32 = 0x34,
+  TRACT_DATUM_TYPE_F64 = 0x38,
+} DatumType;
+
+typedef struct TractNnef TractNnef;
+
+typedef struct TractOnnx TractOnnx;
+
+typedef struct TractTensorflow TractTensorflow;
+
+typedef struct TractInferenceModel TractInferenceModel;
+
+typedef struct TractModel TractModel;
+
+typedef struct TractRunnable TractRunnable;
+
+typedef struct TractValue TractValue;
+
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Retrieve the last error that happened in this thread. A function encountered an error if
+ * its return type is of type `TRACT_RESULT` and it returned `TRACT_RESULT_KO`.
+ *
+ * # Return value
+ *  It returns a pointer to a null-terminated UTF-8 string that will contain the error description.
+ *  Rust side keeps ownership of the buffer. It will be valid as long as no other tract calls is
+ *  performed by the thread.
+ *  If no error occured, null is returned.
+ */
+const char *tract_get_last_error(void);
+
+/**
+ * Returns a pointer to a static buffer containing a null-terminated version string.
+ *
+ * The returned pointer must not be freed.
+ */
+const char *tract_version(void);
+
+/**
+ * Frees a string allocated by libtract.
+ */
+void tract_free_cstring(char *ptr);
+
+/**
+ * Creates an instance of an NNEF framework and parser that can be used to load models.
+ *
+ * The returned object should be destroyed with `tract_nnef_destroy` once the model
+ * has been loaded.
+ */
+TRACT_RESULT tract_nnef_create(TractNnef **nnef);
+
+/**
+ * Destroy the NNEF parser. It is safe to destroy the NNEF parser once the model had been loaded.
+ */
+TRACT_RESULT tract_nnef_destroy(TractNnef **nnef);
+
+/**
+ * Parse and load an NNEF model as a tract TypedModel.
+ *
+ * `path` is a null-terminated utf-8 string pointer to the `.tar`, `.tar.gz` or directory
+ * containing the model.
+ *
+ * The returned model must be later freed using `tract_model_destroy`.
+ */
+TRACT_RESULT tract_nnef_model_for_path(const TractNnef *nnef, const char *path, TractModel **model);
+
+/**
+ * Creates an instance of an ONNX framework and parser that can be used to load models.
+ *
+ * The returned object should be destroyed with `tract_onnx_destroy` once the model
+ * has been loaded.
+ */
+TRACT_RESULT tract_onnx_create(TractOnnx **onnx);
+
+/**
+ * Destroy the ONNX parser. It is safe to destroy the ONNX parser once the model had been loaded.
+ */
+TRACT_RESULT tract_onnx_destroy(TractOnnx **onnx);
+
+/**
+ * Parse and load an ONNX model as a tract InferenceModel.
+ *
+ * `path` is a null-terminated utf-8 string pointer to the `.onnx` file.
+ *
+ * The returned model must be later freed using `tract_inference_model_destroy`.
+ */
+TRACT_RESULT tract_onnx_model_for_path(const TractOnnx *onnx,
+                                       const char *path,
+                                       TractInferenceModel **model);
+
+/**
+ * Creates an instance of a TensorFlow framework and parser that can be used to load
+ * frozen graphs.
+ *
+ * The returned object should be destroyed with `tract_tensorflow_destroy` once the model
+ * has been loaded.
+ */
+TRACT_RESULT tract_tensorflow_create(TractTensorflow **tf);
+
+/**
+ * Destroy the TensorFlow parser. It is safe to destroy the parser once the model had been
+ * loaded.
+ */
+TRACT_RESULT tract_tensorflow_destroy(TractTensorflow **tf);
+
+/**
+ * Parse and load a TensorFlow frozen graph as a tract InferenceModel.
+ *
+ * `path` is a null-terminated utf-8 string pointer to the `.pb` file.
+ *
+ * The returned model must be later freed using `tract_inference_model_destroy`.
+ */
+TRACT_RESULT tract_tensorflow_model_for_path(const TractTensorflow *tf,
+                                             const char *path,
+                                             TractInferenceModel **model);
+
+/**
+ * Query an InferenceModel input and output counts.
+ *
+ * The two pointers are optional, null can be used to skip one of them.
+ */
+TRACT_RESULT tract_inference_model_nbio(const TractInferenceModel *model,
+                                        size_t *inputs,
+                                        size_t *outputs);
+
+/**
+ * Set an InferenceModel input fact from a null-terminated string.
+ *
+ * The fact is formatted like the command line `-i` option: comma separated dimensions,
+ * optionally followed by a datum type, for instance "1,3,224,224,f32" or "N,16000,f32".
+ * A dimension can be an integer, a single letter symbol, or `_` if unknown.
+ */
+TRACT_RESULT tract_inference_model_set_input_fact(TractInferenceModel *model,
+                                                  size_t input_id,
+                                                  const char *fact);
+
+/**
+ * Convert an InferenceModel to a TypedModel, running type and shape analysis.
+ *
+ * This function consumes the InferenceModel: `*inference` is set to null. The resulting
+ * model must be freed using `tract_model_destroy`.
+ */
+TRACT_RESULT tract_inference_model_into_typed(TractInferenceModel **inference, TractModel **typed);
+
+/**
+ * Convert an InferenceModel to an optimized TypedModel.
+ *
+ * This function consumes the InferenceModel: `*inference` is set to null. The resulting
+ * model must be freed using `tract_model_destroy`.
+ */
+TRACT_RESULT tract_inference_model_into_optimized(TractInferenceModel **inference,
+                                                  TractModel **optimized);
+
+/**
+ * Destroy an InferenceModel.
+ */
+TRACT_RESULT tract_inference_model_destroy(TractInferenceModel **model);
+
+/**
+ * Query a model input and output counts.
+ *
+ * The two pointers are optional, null can be used to skip one of them.
+ */
+TRACT_RESULT tract_model_nbio(const TractModel *model, size_t *inputs, size_t *outputs);
+
+/**
+ * Query the name of a model input.
+ *
+ * The returned name must be freed by the caller using `tract_free_cstring`.
+ */
+TRACT_RESULT tract_model_input_name(const TractModel *model, size_t input, char **name);
+
+/**
+ * Query the name of a model output.
+ *
+ * The returned name must be freed by the caller using `tract_free_cstring`.
+ */
+TRACT_RESULT tract_model_output_name(const TractModel *model, size_t output, char **name);
+
+/**
+ * Substitute symbols in a model by integer values.
+ *
+ * `symbols` and `values` are arrays of `nb_symbols` items. Each symbol is a null-terminated
+ * string containing a single letter. The model is modified in place.
+ */
+TRACT_RESULT tract_model_concretize_symbols(TractModel *model,
+                                            size_t nb_symbols,
+                                            const char *const *symbols,
+                                            const int64_t *values);
+
+/**
+ * Declutter a TypedModel in-place.
+ */
+TRACT_RESULT tract_model_declutter(TractModel *model);
+
+/**
+ * Optimize a TypedModel in-place.
+ */
+TRACT_RESULT tract_model_optimize(TractModel *model);
+
+/**
+ * Convert a TypedModel into a TypedRunnableModel.
+ *
+ * This function consumes the model: `*model` is set to null. The runnable model must be
+ * freed using `tract_runnable_destroy`.
+ */
+TRACT_RESULT tract_model_into_runnable(TractModel **model, TractRunnable **runnable);
+
+/**
+ * Destroy a TypedModel.
+ */
+TRACT_RESULT tract_model_destroy(TractModel **model);
+
+/**
+ * Query a runnable model input and output counts.
+ *
+ * The two pointers are optional, null can be used to skip one of them.
+ */
+TRACT_RESULT tract_runnable_nbio(const TractRunnable *runnable, size_t *inputs, size_t *outputs);
+
+/**
+ * Run a runnable model.
+ *
+ * `inputs` is an array of as many values as the model has inputs, they are borrowed and
+ * not consumed. `outputs` must point to an array of as many value pointers as the model
+ * has outputs. On success, each of them is set to a new value that the caller must free
+ * using `tract_value_destroy`.
+ */
+TRACT_RESULT tract_runnable_run(const TractRunnable *runnable,
+                                const TractValue *const *inputs,
+                                TractValue **outputs);
+
+/**
+ * Destroy a runnable model.
+ */
+TRACT_RESULT tract_runnable_destroy(TractRunnable **runnable);
+
+/**
+ * Create a value from a datum type, a shape and a pointer to the (densely packed, row major)
+ * data. The data is copied, the caller keeps ownership of the buffer.
+ *
+ * `datum_type` is one of the `DatumType` constants, other values are rejected.
+ *
+ * The value must be freed using `tract_value_destroy`.
+ */
+TRACT_RESULT tract_value_create(uint32_t datum_type,
+                                size_t rank,
+                                const size_t *shape,
+                                const void *data,
+                                TractValue **value);
+
+/**
+ * Inspect a value.
+ *
+ * All output pointers are optional, use null to skip one of them. `shape` and `data` point
+ * to buffers owned by the value, valid until it is destroyed.
+ */
+TRACT_RESULT tract_value_inspect(const TractValue *value,
+                                 DatumType *datum_type,
+                                 size_t *rank,
+                                 const size_t **shape,
+                                 const void **data);
+
+/**
+ * Destroy a value.
+ */
+TRACT_RESULT tract_value_destroy(TractValue **value);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* TRACT_H */
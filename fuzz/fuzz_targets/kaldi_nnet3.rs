@@ -0,0 +1,9 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use tract_hir::prelude::*;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(proto) = tract_kaldi::parser::nnet3(data) {
+        let _ = tract_kaldi::kaldi().model_for_proto_model(&proto);
+    }
+});
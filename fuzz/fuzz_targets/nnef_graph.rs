@@ -0,0 +1,13 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use tract_nnef::prelude::*;
+use tract_nnef::ProtoModel;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(text) = std::str::from_utf8(data) {
+        if let Ok(doc) = tract_nnef::ast::parse::parse_document(text) {
+            let proto = ProtoModel { doc, tensors: vec![], quantization: None };
+            let _ = tract_nnef::nnef().model_for_proto_model(&proto);
+        }
+    }
+});
@@ -0,0 +1,7 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use tract_nnef::prelude::*;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = tract_nnef::nnef().model_for_read(&mut &*data);
+});
@@ -0,0 +1,11 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use tract_onnx::prelude::*;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(model) = tract_onnx::onnx().model_for_read(&mut &*data) {
+        if let Ok(model) = model.into_typed() {
+            let _ = model.into_decluttered();
+        }
+    }
+});
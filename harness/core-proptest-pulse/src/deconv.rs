@@ -51,12 +51,6 @@ impl Arbitrary for DeconvOp {
                 Just(cnn::PaddingSpec::SameLower)
             ],
         )
-            .prop_filter(
-                "Same padding geometry constraint",
-                |(stride, dilation, _adj, ker, padding)| {
-                    padding == &cnn::PaddingSpec::Valid || ((ker.len() - 1) * dilation > stride - 1)
-                },
-            )
             .prop_map(|(stride, dilation, adj, ker, padding)| DeconvOp {
                 stride,
                 dilation,
@@ -15,6 +15,7 @@ mod conv_plus_conv;
 mod deconv;
 mod delay_plus_pool;
 mod pad_plus_conv;
+mod stft;
 
 #[allow(dead_code)]
 fn setup_test_logger() {
@@ -26,6 +27,18 @@ fn proptest_regular_against_pulse(
     pulse: usize,
     input_array: ArrayD<f32>,
     axis: usize,
+) -> TestCaseResult {
+    proptest_regular_against_chunks(model, pulse.to_dim(), &[pulse], input_array, axis)
+}
+
+/// Run the model pulsified with `pulse`, feeding it chunks of the successive
+/// lengths in `chunks` (cycling), and compare against the regular model.
+fn proptest_regular_against_chunks(
+    model: TypedModel,
+    pulse: TDim,
+    chunks: &[usize],
+    input_array: ArrayD<f32>,
+    axis: usize,
 ) -> TestCaseResult {
     setup_test_logger();
     let s = stream_symbol();
@@ -64,7 +77,8 @@ fn proptest_regular_against_pulse(
     debug!("Run pulsing model");
     //dbg!(pulsed_plan.model());
     let mut written = 0;
-    loop {
+    for chunk_ix in 0.. {
+        let pulse = chunks[chunk_ix % chunks.len()];
         let to_write_in_chunk = pulse.min(input_array.shape()[axis].saturating_sub(written));
         let mut chunk: ArrayD<f32> = input_array
             .slice_axis(Axis(axis), (written..written + to_write_in_chunk).into())
@@ -244,3 +258,19 @@ fn test_pad_before_2() {
     let input = arr1(&[1.0, 2.0]);
     proptest_regular_against_pulse(model, 2, input.into_dyn(), 0).unwrap();
 }
+
+#[test]
+fn test_softmax_off_streaming_axis() {
+    use tract_hir::tract_core::ops::array::{Pad, PadMode};
+    use tract_hir::tract_core::ops::nn::Softmax;
+    let s = tract_pulse::internal::stream_dim();
+    let mut model = TypedModel::default();
+    let a = model.add_source("a", f32::fact(dims!(s, 3))).unwrap();
+    let pad = Pad::new(vec![(2, 0), (0, 0)], PadMode::Constant(rctensor0(0f32)));
+    let padded = model.wire_node("pad", pad, &[a]).unwrap();
+    model.wire_node("softmax", Softmax::new(tvec!(1), f32::datum_type()), &padded).unwrap();
+    model.auto_outputs().unwrap();
+
+    let input = arr2(&[[1.0f32, 2.0, 3.0], [0.0, 0.0, 0.0], [-1.0, 5.0, 1.0]]);
+    proptest_regular_against_pulse(model, 2, input.into_dyn(), 0).unwrap();
+}
@@ -62,7 +62,7 @@ impl Arbitrary for PadPlusConvProblem {
 }
 
 impl PadPlusConvProblem {
-    pub fn run(&self) -> TestCaseResult {
+    fn model(&self) -> TypedModel {
         use tract_hir::ops::cnn::*;
         let mut model = InferenceModel::default();
         let mut wire = model
@@ -86,14 +86,42 @@ impl PadPlusConvProblem {
         let kernel = model.add_const("kernel", self.ker.clone()).unwrap();
         let conv = model.wire_node("conv", expand(conv), &[wire, kernel]).unwrap();
         model.set_output_outlets(&conv).unwrap();
-        let model = model.into_typed().unwrap();
-        proptest_regular_against_pulse(model, self.pulse as _, self.input.clone().into_dyn(), 2)
+        model.into_typed().unwrap()
+    }
+
+    pub fn run(&self) -> TestCaseResult {
+        proptest_regular_against_pulse(
+            self.model(),
+            self.pulse as _,
+            self.input.clone().into_dyn(),
+            2,
+        )
+    }
+
+    pub fn run_variable_pulse(&self, chunks: &[usize]) -> TestCaseResult {
+        let chunks: Vec<usize> = chunks.iter().map(|c| c * self.stride).collect();
+        proptest_regular_against_chunks(
+            self.model(),
+            tract_pulse::internal::pulse_dim() * self.stride,
+            &chunks,
+            self.input.clone().into_dyn(),
+            2,
+        )
     }
 }
 
 proptest! {
     #[test]
     fn proptest_conv(pb in PadPlusConvProblem::arbitrary()) { pb.run().unwrap() }
+
+    #[test]
+    fn proptest_conv_variable_pulse(
+        pb in PadPlusConvProblem::arbitrary()
+            .prop_filter("edge padding requires a fixed pulse", |pb| pb.pad_mode != PadMode::Edge),
+        chunks in proptest::collection::vec(1usize..4, 1..4)
+    ) {
+        pb.run_variable_pulse(&chunks).unwrap()
+    }
 }
 
 #[test]
@@ -0,0 +1,102 @@
+use proptest::proptest;
+use proptest::test_runner::TestCaseResult;
+use tract_hir::internal::*;
+use tract_hir::tract_core::ops::array::Slice;
+use tract_hir::tract_core::ops::fft::{Fft, Stft};
+use tract_hir::tract_core::ops::math::{ComplexToInnerDim, InnerDimToComplex};
+
+use super::*;
+
+#[derive(Debug, Clone)]
+struct StftProblem {
+    input: Vec<f32>,
+    pulse: usize,
+    delay: usize,
+    frame: usize,
+    stride: usize,
+    window: Option<Vec<f32>>,
+}
+
+impl Arbitrary for StftProblem {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> BoxedStrategy<Self> {
+        (1usize..6, 1usize..4, 1usize..4, 0usize..4)
+            .prop_flat_map(|(frame, stride, factor, delay)| {
+                let min_input = delay + frame;
+                (
+                    vec((min_input..min_input + 10).prop_map(|samples| 2 * samples)),
+                    Just(factor * stride),
+                    Just(delay),
+                    Just(frame),
+                    Just(stride),
+                    proptest::option::of(vec(frame..frame + 1)),
+                )
+            })
+            .prop_map(|(input, pulse, delay, frame, stride, window)| StftProblem {
+                input,
+                pulse,
+                delay,
+                frame,
+                stride,
+                window,
+            })
+            .boxed()
+    }
+}
+
+impl StftProblem {
+    pub fn run(&self) -> TestCaseResult {
+        let mut model = TypedModel::default();
+        let s = tract_pulse::internal::stream_dim();
+        let a = model.add_source("a", f32::fact(dims!(s.clone(), 2))).unwrap();
+        let delayed = model.wire_node("delay", Slice::new(0, self.delay, s), &[a]).unwrap();
+        let complex = model.wire_node("complex", InnerDimToComplex, &delayed).unwrap();
+        let stft = Stft {
+            axis: 0,
+            frame: self.frame,
+            stride: self.stride,
+            window: self.window.as_ref().map(|w| rctensor1(w)),
+        };
+        let frames = model.wire_node("stft", stft, &complex).unwrap();
+        let fft = model.wire_node("fft", Fft { axis: 1, inverse: false }, &frames).unwrap();
+        let output = model.wire_node("real", ComplexToInnerDim, &fft).unwrap();
+        model.set_output_outlets(&output).unwrap();
+        let input = arr1(&self.input).into_shape((self.input.len() / 2, 2)).unwrap().into_dyn();
+        proptest_regular_against_pulse(model, self.pulse, input, 0)
+    }
+}
+
+proptest! {
+    #[test]
+    fn proptest(pb in StftProblem::arbitrary()) { pb.run().unwrap() }
+}
+
+#[test]
+fn test_overlapping_frames() {
+    StftProblem {
+        input: (0..20).map(|i| i as f32).collect(),
+        pulse: 2,
+        delay: 0,
+        frame: 4,
+        stride: 2,
+        window: None,
+    }
+    .run()
+    .unwrap()
+}
+
+#[test]
+fn test_misaligned_delay() {
+    StftProblem {
+        input: (0..20).map(|i| i as f32).collect(),
+        pulse: 3,
+        delay: 1,
+        frame: 2,
+        stride: 3,
+        window: Some(vec![0.5, 2.0]),
+    }
+    .run()
+    .unwrap()
+}
@@ -17,6 +17,8 @@ pub struct LstmProblem {
     pub b_icfo: Array1<f32>,
     pub h0: Array2<f32>,
     pub c0: Array2<f32>,
+    /// peepholes, as rows for the input, forget and output gates
+    pub p_ifo: Option<Array2<f32>>,
 }
 
 impl LstmProblem {
@@ -49,6 +51,7 @@ impl LstmProblem {
             optional_bias_input: Some(3),
             optional_initial_h_input: Some(4),
             optional_initial_c_input: Some(5),
+            optional_p_input: self.p_ifo.as_ref().map(|_| 6),
             ..Default::default()
         };
         let w = model.add_const("w", w_iofc)?;
@@ -56,7 +59,15 @@ impl LstmProblem {
         let b = model.add_const("b", b_iofc)?;
         let h0 = model.add_const("h0", self.h0.clone().insert_axis(Axis(0)))?;
         let c0 = model.add_const("c0", self.c0.clone().insert_axis(Axis(0)))?;
-        let lstm = model.wire_node("lstm", expand(op), &[x, w, r, b, h0, c0]).unwrap();
+        let mut inputs = vec![x, w, r, b, h0, c0];
+        if let Some(p_ifo) = &self.p_ifo {
+            // onnx P is [num_directions, 3*hidden_size], in iof order
+            let p_iof =
+                tract_ndarray::concatenate(Axis(0), &[p_ifo.row(0), p_ifo.row(2), p_ifo.row(1)])?;
+            let p_iof = p_iof.into_shape((1, 3 * s))?;
+            inputs.push(model.add_const("p", p_iof)?);
+        }
+        let lstm = model.wire_node("lstm", expand(op), &inputs).unwrap();
         model.set_output_outlets(&lstm).unwrap();
         model.analyse(false)?;
         model.into_typed()
@@ -95,9 +106,14 @@ impl LstmProblem {
         )?[0];
         let seq_length = model.add_const("seq_length", tensor0(self.length as i64))?;
         let w = model.add_const("w", self.w_xh_icfo.clone())?;
-        let wc1 = model.add_const("wc1", tensor1(&[0f32]))?;
-        let wc2 = model.add_const("wc2", tensor1(&[0f32]))?;
-        let wc3 = model.add_const("wc3", tensor1(&[0f32]))?;
+        let (wci, wcf, wco) = if let Some(p_ifo) = &self.p_ifo {
+            (p_ifo.row(0).to_owned(), p_ifo.row(1).to_owned(), p_ifo.row(2).to_owned())
+        } else {
+            (arr1(&[0f32]), arr1(&[0f32]), arr1(&[0f32]))
+        };
+        let wc1 = model.add_const("wc1", wci)?;
+        let wc2 = model.add_const("wc2", wcf)?;
+        let wc3 = model.add_const("wc3", wco)?;
         let b = model.add_const("b", self.b_icfo.clone())?;
 
         let lstm = model
@@ -107,7 +123,7 @@ impl LstmProblem {
                     0.0,
                     -1.0,
                     f32::datum_type(),
-                    false,
+                    self.p_ifo.is_some(),
                 )),
                 &[seq_length, x, cs, h, w, wc1, wc2, wc3, b],
             )
@@ -219,9 +235,13 @@ fn strat() -> BoxedStrategy<LstmProblem> {
                 proptest::collection::vec((-3..3).prop_map(|a| a as f32), 4 * cell_size),
                 proptest::collection::vec((-3..3).prop_map(|a| a as f32), cell_size * batch_size),
                 proptest::collection::vec((-3..3).prop_map(|a| a as f32), cell_size * batch_size),
+                proptest::option::of(proptest::collection::vec(
+                    (-3..3).prop_map(|a| a as f32),
+                    3 * cell_size,
+                )),
             )
         })
-        .prop_map(|((length, batch_size, cell_size), x, w_xh_icfo, b_icfo, h0, c0)| {
+        .prop_map(|((length, batch_size, cell_size), x, w_xh_icfo, b_icfo, h0, c0, p_ifo)| {
             let x = Array3::from_shape_vec((length, batch_size, cell_size), x)
                 .unwrap()
                 .into_arc_tensor();
@@ -230,7 +250,8 @@ fn strat() -> BoxedStrategy<LstmProblem> {
             let b_icfo = Array1::from_shape_vec(cell_size * 4, b_icfo).unwrap();
             let h0 = Array2::from_shape_vec((batch_size, cell_size), h0).unwrap();
             let c0 = Array2::from_shape_vec((batch_size, cell_size), c0).unwrap();
-            LstmProblem { length, batch_size, cell_size, x, w_xh_icfo, b_icfo, h0, c0 }
+            let p_ifo = p_ifo.map(|p| Array2::from_shape_vec((3, cell_size), p).unwrap());
+            LstmProblem { length, batch_size, cell_size, x, w_xh_icfo, b_icfo, h0, c0, p_ifo }
         })
         .boxed()
 }
@@ -255,6 +276,7 @@ fn test_x() {
         b_icfo: arr1(&[0.0f32, 0.0, 0.0, 0.0]),
         h0: arr2(&[[0.0f32]]),
         c0: arr2(&[[0.0f32]]),
+        p_ifo: None,
     };
     let o = pb.onnx_run().unwrap();
     let t = pb.tf_run().unwrap();
@@ -272,6 +294,7 @@ fn test_seq() {
         b_icfo: arr1(&[0.0f32, 0.0, 0.0, 0.0]),
         h0: arr2(&[[0.0f32]]),
         c0: arr2(&[[0.0f32]]),
+        p_ifo: None,
     };
     let o = pb.onnx_run().unwrap();
     let t = pb.tf_run().unwrap();
@@ -289,6 +312,7 @@ fn test_c0() {
         b_icfo: arr1(&[0.0f32, 0.0, 0.0, 0.0]),
         h0: arr2(&[[0.0f32]]),
         c0: arr2(&[[1.0f32]]),
+        p_ifo: None,
     };
     let o = pb.onnx_run().unwrap();
     let t = pb.tf_run().unwrap();
@@ -306,6 +330,7 @@ fn test_b() {
         b_icfo: arr1(&[0.0f32, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0]),
         h0: arr2(&[[0.0f32, 0.0]]),
         c0: arr2(&[[0.0f32, 0.0]]),
+        p_ifo: None,
     };
     let o = pb.onnx_run().unwrap();
     let t = pb.tf_run().unwrap();
@@ -328,8 +353,32 @@ fn test_w() {
         b_icfo: arr1(&[0.0f32, 0.0, -3.0, -1.0, -1.0, 0.0, 2.0, -2.0]),
         h0: arr2(&[[1.0f32, 0.0]]),
         c0: arr2(&[[-1.0f32, -2.0]]),
+        p_ifo: None,
     };
     let o = pb.onnx_run().unwrap();
     let t = pb.tf_run().unwrap();
     assert_eq!(o, t)
 }
+
+#[test]
+fn test_peepholes() {
+    let pb = LstmProblem {
+        length: 2,
+        batch_size: 1,
+        cell_size: 2,
+        x: rctensor3(&[[[2.0f32, 1.0]], [[0.0, -3.0]]]),
+        w_xh_icfo: arr2(&[
+            [0f32, -2.0, 0.0, 0.0, -2.0, -2.0, -3.0, -3.0],
+            [0.0, -3.0, 2.0, 0.0, -2.0, 2.0, 1.0, -3.0],
+            [0.0, 2.0, 1.0, 0.0, -3.0, -2.0, -3.0, -1.0],
+            [0.0, 0.0, -3.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+        ]),
+        b_icfo: arr1(&[0.0f32, 0.0, -3.0, -1.0, -1.0, 0.0, 2.0, -2.0]),
+        h0: arr2(&[[1.0f32, 0.0]]),
+        c0: arr2(&[[-1.0f32, -2.0]]),
+        p_ifo: Some(arr2(&[[1.0f32, -2.0], [0.5, 2.0], [-1.0, 3.0]])),
+    };
+    let o = pb.onnx_run().unwrap();
+    let t = pb.tf_run().unwrap();
+    assert!(o.close_enough(&t, true).is_ok(), "\nonnx:{:?}\n tf :{:?}\n", o, t);
+}
@@ -0,0 +1,8 @@
+#!/bin/sh
+
+cd `dirname $0`
+set -x
+
+: ${TRACT_RUN:=cargo run -p tract $CARGO_OPTS --}
+
+$TRACT_RUN . dump -q --assert-op-count BandedAttention 1 --assert-op-count Softmax 0
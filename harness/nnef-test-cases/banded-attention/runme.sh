@@ -0,0 +1,9 @@
+#!/bin/sh
+
+cd `dirname $0`
+set -x
+
+: ${TRACT_RUN:=cargo run -p tract $CARGO_OPTS --}
+
+$TRACT_RUN . --nnef-tract-core --pulse 2 stream-check
+$TRACT_RUN . --nnef-tract-core --pulse 5 stream-check
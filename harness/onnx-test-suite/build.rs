@@ -77,6 +77,7 @@ enum Mode {
     Plain,
     Optim,
     Nnef,
+    Onnx,
 }
 
 pub fn make_test_file(root: &mut fs::File, tests_set: &str, onnx_tag: &str) {
@@ -117,7 +118,7 @@ pub fn make_test_file(root: &mut fs::File, tests_set: &str, onnx_tag: &str) {
         .collect();
     tests.sort();
     writeln!(rs, "mod {} {{", tests_set_ver).unwrap();
-    for &mode in &[Plain, Optim, Nnef] {
+    for &mode in &[Plain, Optim, Nnef, Onnx] {
         writeln!(rs, "mod {} {{", format!("{:?}", mode).to_lowercase()).unwrap();
         writeln!(rs, "use tract_core::internal::*;").unwrap();
         writeln!(rs, "use crate::onnx::{{run_one, Mode}};").unwrap();
@@ -132,6 +133,7 @@ pub fn make_test_file(root: &mut fs::File, tests_set: &str, onnx_tag: &str) {
                         pair.as_ref().unwrap().1.contains(&"not-typable".to_string())
                             || pair.as_ref().unwrap().1.contains(&"not-nnef".to_string())
                     }
+                    Mode::Onnx => !pair.as_ref().unwrap().1.contains(&"onnx-export".to_string()),
                 };
             if ignore {
                 writeln!(rs, "#[ignore]").unwrap();
@@ -2,6 +2,9 @@ use std::collections::HashMap;
 use std::io::{BufRead, Write};
 
 const SETS: &[&str] = &["node", "real", "simple", "pytorch-operator", "pytorch-converted"];
+// flag set on top of the level when the ONNX export round trip works
+const ONNX_EXPORT: usize = 4;
+
 const VERSIONS: &[&str] = &["1.4.1", "1.5.0", "1.6.0", "1.7.0", "1.8.1", "1.9.0", "1.10.1"];
 
 // const SETS: &[&str] = &["node"];
@@ -23,14 +26,18 @@ fn run_set(set: &str, ver: &str) -> HashMap<String, usize> {
             let test_id =
                 line.split_whitespace().nth(1).unwrap().split("::").nth(2).unwrap().to_string();
             let level = line.split_whitespace().nth(1).unwrap().split("::").nth(1).unwrap();
+            let entry = unexpected.entry(test_id.clone()).or_insert(0);
+            if level == "onnx" {
+                *entry |= ONNX_EXPORT;
+                continue;
+            }
             let level = match level {
                 "nnef" => 3,
                 "optim" => 2,
                 "plain" => 1,
                 _ => panic!(),
             };
-            let entry = unexpected.entry(test_id.clone()).or_insert(0);
-            *entry = (*entry).max(level);
+            *entry = (*entry & ONNX_EXPORT) | (*entry & !ONNX_EXPORT).max(level);
         }
     }
     unexpected
@@ -52,6 +59,10 @@ fn process_unexpected(set: &str, ver: &str, unexpected: HashMap<String, usize>)
         let spec = specs
             .entry(test_id.to_string())
             .or_insert_with(|| format!("{} not-nnef not-typable", test_id));
+        if level & ONNX_EXPORT != 0 && !spec.split_whitespace().any(|t| t == "onnx-export") {
+            spec.push_str(" onnx-export");
+        }
+        let level = level & !ONNX_EXPORT;
         if level >= 3 {
             *spec =
                 spec.split_whitespace().filter(|t| t != &"not-nnef").collect::<Vec<_>>().join(" ");
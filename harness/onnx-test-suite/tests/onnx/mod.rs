@@ -37,6 +37,7 @@ pub enum Mode {
     Plain,
     Optim,
     Nnef,
+    Onnx,
 }
 
 pub fn run_one<P: AsRef<path::Path>>(
@@ -172,6 +173,16 @@ pub fn run_one<P: AsRef<path::Path>>(
                     let reloaded = nnef.model_for_read(&mut &*buffer).unwrap();
                     run_model(reloaded, inputs, &data_path)
                 }
+                Onnx => {
+                    let model = model.into_typed().unwrap();
+                    info!("Declutter");
+                    let decluttered = model.into_decluttered().unwrap();
+                    info!("Store to ONNX");
+                    let buffer = onnx.write(&decluttered, vec![]).unwrap();
+                    info!("Reload from ONNX");
+                    let reloaded = onnx.model_for_read(&mut &*buffer).unwrap();
+                    run_model(reloaded.into_optimized().unwrap(), inputs, &data_path)
+                }
             }
             info!("Test model (mode: {:?}) {:#?} OK.", mode, path);
         }
@@ -159,7 +159,8 @@ impl<'a> TryFrom<&'a InferenceFact> for TypedFact {
             let shape = ShapeFact::from_dims(shape);
             let konst = fact.value.concretize();
             let uniform = konst.as_ref().and_then(|k| k.as_uniform()).map(Arc::new);
-            Ok(TypedFact { datum_type, shape, konst, uniform })
+            let sequence = konst.clone().and_then(|k| TypedFact::from(k).sequence);
+            Ok(TypedFact { datum_type, shape, konst, uniform, sequence })
         } else {
             bail!("Can not make a TypedFact out of {:?}", fact)
         }
@@ -213,3 +214,61 @@ impl<'a> From<&'a Tensor> for InferenceFact {
         InferenceFact::from(t.clone())
     }
 }
+
+/// Parses the command line fact syntax: comma separated dimensions, optionally followed by a
+/// datum type, for instance "1,3,224,224,f32" or "N,16000". `_` stands for an unknown
+/// dimension.
+impl std::str::FromStr for InferenceFact {
+    type Err = TractError;
+
+    fn from_str(spec: &str) -> TractResult<InferenceFact> {
+        if spec.is_empty() {
+            return Ok(InferenceFact::default());
+        }
+        let splits: Vec<&str> = spec.split(',').map(|s| s.trim()).collect();
+        let (dt, dims) = if let Ok(dt) = DatumType::from_str(splits.last().unwrap()) {
+            (Some(dt), &splits[..splits.len() - 1])
+        } else {
+            (None, &*splits)
+        };
+        let shape = ShapeFactoid::closed(
+            dims.iter()
+                .map(|d| {
+                    Ok(if *d == "_" {
+                        GenericFactoid::Any
+                    } else {
+                        GenericFactoid::Only(d.parse()?)
+                    })
+                })
+                .collect::<TractResult<TVec<DimFact>>>()
+                .with_context(|| format!("parsing fact {:?}", spec))?,
+        );
+        Ok(if let Some(dt) = dt {
+            InferenceFact::dt_shape(dt, shape)
+        } else {
+            InferenceFact::shape(shape)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() -> TractResult<()> {
+        assert_eq!("".parse::<InferenceFact>()?, InferenceFact::default());
+        assert_eq!("1,3,f32".parse::<InferenceFact>()?, f32::fact([1, 3]).into());
+        let fact: InferenceFact = "N, _".parse()?;
+        assert_eq!(fact.datum_type, GenericFactoid::Any);
+        assert_eq!(
+            fact.shape,
+            ShapeFactoid::closed(tvec!(
+                GenericFactoid::Only(Symbol::from('N').to_dim()),
+                GenericFactoid::Any
+            ))
+        );
+        assert!("1,3,f33".parse::<InferenceFact>().is_err());
+        Ok(())
+    }
+}
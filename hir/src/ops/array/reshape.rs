@@ -35,7 +35,12 @@ impl Expansion for Reshape {
         model: &mut TypedModel,
         inputs: &[OutletId],
     ) -> TractResult<TVec<OutletId>> {
-        if let Some(ref shape) = model.outlet_fact(inputs[1])?.konst {
+        let shape = if let Some(shape) = model.outlet_fact(inputs[1])?.konst.clone() {
+            Some(shape)
+        } else {
+            model.symbolic_value(inputs[1])?
+        };
+        if let Some(ref shape) = shape {
             let input_shape: TVec<TDim> = model.outlet_fact(inputs[0])?.shape.to_tvec();
             let shape = shape.cast_to::<TDim>()?;
             let shape = shape.as_slice::<TDim>()?;
@@ -224,4 +229,26 @@ mod tests {
             &[Rm(0), Add(1), r!(3 ; 5,7 => 35 ), Add(4)]
         )
     }
+
+    #[test]
+    fn symbolic_shape_chain() -> TractResult<()> {
+        use crate::ops::array::{Concat, Gather, Shape};
+        use crate::ops::expandable::expand;
+        let mut model = InferenceModel::default();
+        let x = model.add_source("x", f32::fact(&[stream(), 3.to_dim(), 4.to_dim()]).into())?;
+        let shape = model.wire_node("shape", expand(Shape::new(i64::datum_type())), &[x])?;
+        let zero = model.add_const("zero", tensor1(&[0i64]))?;
+        let batch = model.wire_node("batch", expand(Gather::new(0)), &[shape[0], zero])?;
+        let twelve = model.add_const("twelve", tensor1(&[12i64]))?;
+        let target = model.wire_node("target", expand(Concat::new(0)), &[batch[0], twelve])?;
+        let y = model.wire_node("reshape", expand(super::Reshape::new()), &[x, target[0]])?;
+        model.set_output_outlets(&y)?;
+        let typed = model.into_typed()?.into_decluttered()?;
+        assert_eq!(typed.nodes().len(), 2);
+        assert_eq!(
+            typed.node(typed.output_outlets()?[0].node).op_as::<AxisOp>(),
+            Some(&r!(1 ; 3, 4 => 12))
+        );
+        Ok(())
+    }
 }
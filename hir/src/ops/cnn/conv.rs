@@ -79,11 +79,11 @@ impl Conv {
     }
 
     pub fn output_shape<D: DimLike>(&self, ishape: &[D], kshape: &[usize]) -> TractResult<TVec<D>> {
-        debug_assert_eq!(
+        ensure!(
             ishape.len()
                 + (self.data_format == DataFormat::HWC || self.data_format == DataFormat::CHW)
-                    as usize,
-            kshape.len(),
+                    as usize
+                == kshape.len(),
             "Input and kernel ranks are inconsistent"
         );
         let mut result: TVec<D> = ishape.into();
@@ -96,7 +96,7 @@ impl Conv {
             kernel_spatial_shape,
             self.dilations.as_ref().unwrap_or(&ones),
             self.strides.as_ref().unwrap_or(&ones),
-        );
+        )?;
         let channels_out = match self.kernel_fmt {
             KernelFormat::OIHW => kshape[0],
             KernelFormat::HWIO => kshape[kshape.len() - 1] * self.group.unwrap_or(1),
@@ -420,4 +420,13 @@ mod test {
             .unwrap();
         assert_eq!(result, tvec!(rctensor3(&[[[2.0f32]]])));
     }
+
+    #[test]
+    fn test_infer_zero_stride() {
+        let mut op = expand(Conv::default().hwc().hwio().strides(tvec!(0)));
+        let ifact = f32::fact(&[2, 1]).into();
+        let kfact = f32::fact(&[1, 1, 1]).into();
+        let ofact = InferenceFact::default();
+        assert!(op.infer_facts(tvec!(&ifact, &kfact), tvec!(&ofact), tvec!()).is_err());
+    }
 }
@@ -60,7 +60,7 @@ pub fn rules_for_shape<'r, 'p: 'r, 's: 'r>(
             &*pool_spec.kernel_shape,
             pool_spec.dilations.as_ref().unwrap_or(&ones),
             pool_spec.strides.as_ref().unwrap_or(&ones),
-        );
+        )?;
         for o in outputs {
             for (ix, d) in computed.iter().enumerate() {
                 s.equals(&o.shape[ix + ishape.h_axis()], &d.convoluted)?;
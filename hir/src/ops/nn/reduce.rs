@@ -121,27 +121,17 @@ pub struct Reduce {
 impl_dyn_hash!(Reduce);
 
 impl Reduce {
-    pub fn must_reduce(&self, ax: usize, rank: usize) -> bool {
-        let resolved_axes: Option<Vec<usize>> = match &self.axes {
-            None => None,
-            Some(original_axes) => {
-                let mut ans: Vec<usize> = vec![];
-                for or_ax in original_axes.iter() {
-                    ans.push(Self::resolve_axis(*or_ax, rank).unwrap());
-                }
-                Some(ans)
-            }
-        };
-
-        resolved_axes.as_ref().map(|axes| axes.contains(&ax)).unwrap_or(true)
+    pub fn must_reduce(&self, ax: usize, rank: usize) -> TractResult<bool> {
+        Ok(self.resolve_axes(rank)?.contains(&ax))
     }
 
-    pub fn output_shape(&self, shape: &[TDim]) -> TVec<TDim> {
-        shape
+    pub fn output_shape(&self, shape: &[TDim]) -> TractResult<TVec<TDim>> {
+        let axes = self.resolve_axes(shape.len())?;
+        Ok(shape
             .iter()
             .enumerate()
             .filter_map(|(ix, d)| {
-                if self.must_reduce(ix, shape.len()) {
+                if axes.contains(&ix) {
                     if self.keep_dims {
                         Some(1.to_dim())
                     } else {
@@ -151,7 +141,7 @@ impl Reduce {
                     Some(d.clone())
                 }
             })
-            .collect()
+            .collect())
     }
 
     fn resolve_axis(axis: i64, rank: usize) -> TractResult<usize> {
@@ -170,6 +160,7 @@ impl Reduce {
             Some(axis) => axis.iter().map(|&a| Self::resolve_axis(a, input_rank)).collect(),
         }?;
         axes.sort();
+        ensure!(axes.windows(2).all(|w| w[0] != w[1]), "Duplicate axes in {:?}", self.axes);
         Ok(axes)
     }
 }
@@ -204,7 +195,7 @@ impl Expansion for Reduce {
             s.equals(&outputs[0].rank, 0)?;
         }
         s.given(&inputs[0].shape, move |s, shape| {
-            let out_shape = self.output_shape(&*shape);
+            let out_shape = self.output_shape(&*shape)?;
             s.equals(&outputs[0].shape, out_shape)
         })
     }
@@ -239,3 +230,18 @@ impl Expansion for Reduce {
         Ok(tvec!(wire))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn invalid_axes_are_errors() {
+        for axes in [vec![2], vec![-3], vec![0, -2]] {
+            let mut op = expand(Reduce::new(Some(axes), false, Reducer::Sum));
+            let ifact = f32::fact(&[2, 3]).into();
+            let ofact = InferenceFact::default();
+            assert!(op.infer_facts(tvec!(&ifact), tvec!(&ofact), tvec!()).is_err());
+        }
+    }
+}
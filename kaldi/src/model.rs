@@ -9,6 +9,12 @@ pub struct KaldiProtoModel {
     pub adjust_final_offset: isize,
 }
 
+impl KaldiProtoModel {
+    pub fn component(&self, name: &str) -> TractResult<&Component> {
+        self.components.get(name).with_context(|| format!("Component {} not found", name))
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ConfigLines {
     pub input_name: String,
@@ -67,6 +73,11 @@ impl GeneralDescriptor {
                     _ => return None,
                 }
             }
+            match offsets.len() {
+                0 => return None,
+                1 => return Some((1, 1)),
+                _ => (),
+            }
             let dilation = offsets[1] - offsets[0];
             if offsets.windows(2).all(|pair| pair[1] - pair[0] == dilation) {
                 return Some((offsets.len(), dilation as usize));
@@ -203,7 +214,7 @@ impl Framework<KaldiProtoModel, InferenceModel> for Kaldi {
         for (name, node) in &proto_model.config_lines.nodes {
             match node {
                 NodeLine::Component(line) => {
-                    let component = &proto_model.components[&line.component];
+                    let component = proto_model.component(&line.component)?;
                     if crate::ops::AFFINE.contains(&&*component.klass)
                         && line.input.as_conv_shape_dilation().is_some()
                     {
@@ -239,11 +250,11 @@ impl Framework<KaldiProtoModel, InferenceModel> for Kaldi {
                     }
                 }
                 NodeLine::DimRange(line) => {
-                    let op = tract_hir::ops::array::Slice::new(
-                        1,
-                        line.offset as usize,
-                        (line.offset + line.dim) as usize,
-                    );
+                    let end = line
+                        .offset
+                        .checked_add(line.dim)
+                        .with_context(|| format!("Invalid dim-range-node {}", name))?;
+                    let op = tract_hir::ops::array::Slice::new(1, line.offset, end);
                     let id =
                         model.add_node(name.to_string(), op, tvec!(InferenceFact::default()))?;
                     line.input.wire(
@@ -10,13 +10,20 @@ pub fn affine_component(ctx: &ParsingContext, name: &str) -> TractResult<Box<dyn
     } else {
         bail!("Could not find component {}", name);
     };
-    let component = &ctx.proto_model.components[&line.component];
+    let component = ctx.proto_model.component(&line.component)?;
     let (kernel_len, dilation) = line.input.as_conv_shape_dilation().unwrap_or((1, 1));
     let kernel: &Tensor =
         component.attributes.get("LinearParams").context("missing attribute LinearParams")?;
     let bias = component.attributes.get("BiasParams").context("missing attribute BiasParams")?;
     // O•TI -> t -> TI•O -> T•I•O = HWIO
     let o_ti = kernel.to_array_view::<f32>()?;
+    ensure!(
+        o_ti.ndim() == 2 && bias.len() > 0 && kernel.len() % (kernel_len * bias.len()) == 0,
+        "Inconsistent LinearParams {:?} and BiasParams {:?} in {}",
+        kernel.shape(),
+        bias.shape(),
+        name
+    );
     let t_i_o_shape = (kernel_len, kernel.len() / kernel_len / bias.len(), bias.len());
     let t_i_o =
         tract_ndarray::Array::from_shape_vec(t_i_o_shape, o_ti.t().iter().cloned().collect())?;
@@ -103,6 +110,7 @@ impl Expansion for Affine {
                 group: 1,
                 bias: Some(self.bias_params.clone().into_arc_tensor()),
                 q_params: None,
+                weight_quant: None,
             },
             inputs,
         )
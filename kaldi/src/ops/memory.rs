@@ -31,7 +31,7 @@ impl EvalOp for Memory {
         _session: &mut SessionState,
         _id: usize,
     ) -> TractResult<Option<Box<dyn OpState>>> {
-        unimplemented!()
+        bail!("Memory ops must be turned into loops before evaluation")
     }
 }
 
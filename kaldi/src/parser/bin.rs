@@ -11,6 +11,10 @@ pub fn attributes<'a>(i: &'a [u8], klass: &str) -> IResult<&'a [u8], HashMap<Str
 
 fn attribute<'a>(i: &'a [u8], klass: &str) -> IResult<&'a [u8], (String, Arc<Tensor>)> {
     let (i, name) = super::open_any(i)?;
-    let (i, value) = COMPONENTS[klass][name].parse_bin(i)?;
+    let kind = COMPONENTS
+        .get(klass)
+        .and_then(|attributes| attributes.get(name))
+        .ok_or_else(|| nom::Err::Error(nom::error::Error::new(i, nom::error::ErrorKind::Verify)))?;
+    let (i, value) = kind.parse_bin(i)?;
     Ok((i, (name.to_string(), value.into_arc_tensor())))
 }
@@ -41,20 +41,37 @@ impl KaldiAttributeKind {
         alt((preceded(tag([4]), le_f32), map(preceded(tag([8]), le_f64), |f| f as f32)))(i)
     }
 
+    fn invalid(i: &[u8]) -> nom::Err<nom::error::Error<&[u8]>> {
+        nom::Err::Failure(nom::error::Error::new(i, nom::error::ErrorKind::Verify))
+    }
+
+    /// Validates an f32 item count against the remaining input.
+    fn check_len(i: &[u8], len: i64) -> Result<usize, nom::Err<nom::error::Error<&[u8]>>> {
+        if len < 0 || len as u64 > (i.len() / 4) as u64 {
+            Err(Self::invalid(i))
+        } else {
+            Ok(len as usize)
+        }
+    }
+
     fn parse_float_vector(i: &[u8]) -> IResult<&[u8], Tensor> {
         let (i, len) = super::integer(true)(i)?;
+        let len = Self::check_len(i, len as i64)?;
         // FIXME pending merge of https://github.com/Geal/nom/pull/995
         if len == 0 {
             Ok((i, tensor1(&[0.0f32])))
         } else {
-            map(many_m_n(len as usize, len as usize, le_f32), |data| tensor1(&*data))(i)
+            map(many_m_n(len, len, le_f32), |data| tensor1(&*data))(i)
         }
     }
 
     fn parse_float_matrix(i: &[u8]) -> IResult<&[u8], Tensor> {
         let (i, rows) = super::integer(true)(i)?;
         let (i, cols) = super::integer(true)(i)?;
-        let len = (rows * cols) as usize;
+        if rows < 0 || cols < 0 {
+            return Err(Self::invalid(i));
+        }
+        let len = Self::check_len(i, rows as i64 * cols as i64)?;
         // FIXME pending merge of https://github.com/Geal/nom/pull/995
         if len == 0 {
             Ok((i, tensor2(&[[0.0f32; 0]; 0])))
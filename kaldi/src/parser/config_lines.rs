@@ -47,7 +47,7 @@ pub fn parse_config(s: &str) -> TractResult<ConfigLines> {
             _ => bail!("Unknown config line {}", line_kind),
         }
     }
-    let (input_name, input_dim) = input_node.unwrap();
+    let (input_name, input_dim) = input_node.context("No input-node in config")?;
     Ok(ConfigLines { input_dim, input_name, nodes, outputs })
 }
 
@@ -100,7 +100,7 @@ pub fn identifier(i: &str) -> IResult<&str, &str> {
 }
 
 pub fn uinteger(i: &str) -> IResult<&str, usize> {
-    map(digit1, |s: &str| s.parse().unwrap())(i)
+    map_res(digit1, |s: &str| s.parse())(i)
 }
 
 #[cfg(test)]
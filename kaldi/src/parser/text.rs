@@ -40,11 +40,14 @@ pub fn matrix(i: &[u8]) -> IResult<&[u8], Tensor> {
         separated_list0(spaced(tag("\n")), separated_list0(space1, float)),
         multispaced(tag("]")),
     )(i)?;
+    let invalid = || nom::Err::Failure(nom::error::Error::new(i, nom::error::ErrorKind::Verify));
     let lines = v.len();
+    let cols = v.first().map(|l| l.len()).unwrap_or(0);
+    if v.iter().any(|l| l.len() != cols) {
+        return Err(invalid());
+    }
     let data: Vec<_> = v.into_iter().flat_map(|v| v.into_iter()).collect();
-    let cols = data.len() / lines;
-    let t = tract_ndarray::Array1::from(data);
-    let t = t.into_shape((lines, cols)).unwrap();
+    let t = tract_ndarray::Array2::from_shape_vec((lines, cols), data).map_err(|_| invalid())?;
     Ok((i, t.into_tensor()))
 }
 
@@ -89,6 +92,28 @@ output-node name=output input=fixed1
         );
     }
 
+    #[test]
+    fn test_ragged_matrix() {
+        let slice = r#"[
+            1.0 2.0 3.0
+            4.0 5.0 ]"#;
+        assert!(tensor(slice.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_nnet3_missing_component() {
+        let slice = r#"<Nnet3>
+
+input-node name=input dim=3
+component-node name=fixed1 input=input component=fixed1
+output-node name=output input=fixed1
+
+<NumComponents> 0
+</Nnet3>"#;
+        let proto = nnet3(slice.as_bytes()).unwrap();
+        assert!(proto.component("fixed1").is_err());
+    }
+
     #[test]
     fn fixed_affine_40x10_T40_S3() {
         let slice = std::fs::read("test_cases/fixed_affine_40x10_T40_S3/model.raw.txt").unwrap();
@@ -90,6 +90,10 @@ fn main() {
                         let _ = fs::remove_file("fma_mmm_i32_8x8.asm");
                         let _ = fs::remove_file("fma_sigmoid_f32.asm");
                         let _ = fs::remove_file("fma_tanh_f32.asm");
+                        let _ = fs::remove_file("fma_exp_f32.asm");
+                        let _ = fs::remove_file("fma_ln_f32.asm");
+                        let _ = fs::remove_file("fma_erf_f32.asm");
+                        let _ = fs::remove_file("fma_gelu_f32.asm");
                     }
                 }
                 "macos" => {
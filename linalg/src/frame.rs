@@ -1,14 +1,24 @@
 #[macro_use]
 pub mod element_wise;
 #[macro_use]
+pub mod erf;
+#[macro_use]
+pub mod exp;
+#[macro_use]
+pub mod gelu;
+#[macro_use]
+pub mod log;
+#[macro_use]
 pub mod lut;
 #[macro_use]
 pub mod mmm;
 pub mod pack;
 #[macro_use]
 pub mod sigmoid;
+pub mod sparse;
 #[macro_use]
 pub mod tanh;
+pub mod weight_quant;
 
 pub use pack::Packer;
 pub use pack::PackingWriter;
@@ -0,0 +1,92 @@
+macro_rules! erf_impl {
+    ($ti: ident, $func: ident, $nr: expr, $alignment_items: expr, $cond: expr) => {
+        ew_impl!($ti, $func, $nr, $alignment_items);
+        #[cfg(test)]
+        paste! {
+            mod [<test_ $func>] {
+                use super::*;
+                erf_frame_tests!($cond, $ti, $func);
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+#[macro_use]
+pub mod test {
+    use crate::{frame::element_wise::*, LADatum};
+    use num_traits::{AsPrimitive, Float};
+    use proptest::test_runner::TestCaseResult;
+
+    #[macro_export]
+    macro_rules! erf_frame_tests {
+        ($cond:expr, $t: ty, $ker:ty) => {
+            proptest::proptest! {
+                #[test]
+                fn erf(xs in proptest::collection::vec(-6f32..6.0, 0..100)) {
+                    if $cond {
+                        $crate::frame::erf::test::test_erf::<$ker, $t>(&*xs).unwrap()
+                    }
+                }
+            }
+
+            #[test]
+            fn erf_4_magic() {
+                if $cond {
+                    $crate::frame::erf::test::test_erf::<$ker, $t>(&[0f32, -1.0, 1.0, 3.0]).unwrap()
+                }
+            }
+
+            #[test]
+            fn erf_20_ones() {
+                if $cond {
+                    $crate::frame::erf::test::test_erf::<$ker, $t>(&[1.0; 20]).unwrap();
+                }
+            }
+
+            #[test]
+            fn erf_special_values() {
+                use $crate::frame::element_wise::*;
+                if $cond {
+                    let mut input: Vec<$t> = [0f32, -100f32, 100f32, f32::INFINITY]
+                        .iter()
+                        .map(|x| <f32 as num_traits::AsPrimitive<$t>>::as_(*x))
+                        .collect();
+                    <$ker>::ew().run(&mut input).unwrap();
+                    let found: Vec<f32> =
+                        input.iter().map(|x| num_traits::AsPrimitive::<f32>::as_(*x)).collect();
+                    assert_eq!(found[0], 0.0);
+                    assert_eq!(found[1], -1.0);
+                    assert_eq!(found[2], 1.0);
+                    assert_eq!(found[3], 1.0);
+                }
+            }
+        };
+    }
+
+    pub fn test_erf<K: ElementWiseKer<T>, T: LADatum + Float>(values: &[f32]) -> TestCaseResult
+    where
+        f32: AsPrimitive<T>,
+        T: AsPrimitive<f32>,
+    {
+        let values: Vec<T> = values.iter().copied().map(|x| x.as_()).collect();
+        crate::frame::element_wise::test::test_element_wise::<K, _, _>(&values, |x| {
+            (reference_erf(x.as_() as f64) as f32).as_()
+        })
+    }
+
+    /// Taylor series evaluated in f64, good to a few ulps of f32 over the
+    /// range where erf is not saturated.
+    pub fn reference_erf(x: f64) -> f64 {
+        if x.abs() > 5.0 {
+            return x.signum();
+        }
+        let mut term = x;
+        let mut sum = x;
+        for n in 1..200 {
+            term *= -x * x / n as f64;
+            sum += term / (2 * n + 1) as f64;
+        }
+        sum * std::f64::consts::FRAC_2_SQRT_PI
+    }
+}
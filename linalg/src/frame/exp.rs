@@ -0,0 +1,93 @@
+macro_rules! exp_impl {
+    ($ti: ident, $func: ident, $nr: expr, $alignment_items: expr, $cond: expr) => {
+        ew_impl!($ti, $func, $nr, $alignment_items);
+        #[cfg(test)]
+        paste! {
+            mod [<test_ $func>] {
+                use super::*;
+                exp_frame_tests!($cond, $ti, $func);
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+#[macro_use]
+pub mod test {
+    use crate::{frame::element_wise::*, LADatum};
+    use num_traits::{AsPrimitive, Float};
+    use proptest::test_runner::TestCaseResult;
+
+    #[macro_export]
+    macro_rules! exp_frame_tests {
+        ($cond:expr, $t: ty, $ker:ty) => {
+            proptest::proptest! {
+                #[test]
+                fn exp(xs in proptest::collection::vec(-20f32..10.0, 0..100)) {
+                    if $cond {
+                        $crate::frame::exp::test::test_exp::<$ker, $t>(&*xs).unwrap()
+                    }
+                }
+            }
+
+            #[test]
+            fn exp_4_magic() {
+                if $cond {
+                    $crate::frame::exp::test::test_exp::<$ker, $t>(&[0f32, -20.0, 10.0, 1.0])
+                        .unwrap()
+                }
+            }
+
+            #[test]
+            fn exp_20_ones() {
+                if $cond {
+                    $crate::frame::exp::test::test_exp::<$ker, $t>(&[1.0; 20]).unwrap();
+                }
+            }
+
+            #[test]
+            fn exp_special_values_like_std() {
+                if $cond {
+                    $crate::frame::exp::test::test_exp::<$ker, $t>(&[
+                        f32::NAN,
+                        f32::INFINITY,
+                        f32::NEG_INFINITY,
+                        0.0,
+                        -0.0,
+                        1e-40,
+                        -1e-40,
+                        f32::MIN_POSITIVE,
+                    ])
+                    .unwrap()
+                }
+            }
+
+            #[test]
+            fn exp_special_values() {
+                use $crate::frame::element_wise::*;
+                if $cond {
+                    let mut input: Vec<$t> = [-100f32, 100f32, 0f32, f32::NEG_INFINITY]
+                        .iter()
+                        .map(|x| <f32 as num_traits::AsPrimitive<$t>>::as_(*x))
+                        .collect();
+                    <$ker>::ew().run(&mut input).unwrap();
+                    let found: Vec<f32> =
+                        input.iter().map(|x| num_traits::AsPrimitive::<f32>::as_(*x)).collect();
+                    assert_eq!(found[0], 0.0);
+                    assert_eq!(found[1], f32::INFINITY);
+                    assert_eq!(found[2], 1.0);
+                    assert_eq!(found[3], 0.0);
+                }
+            }
+        };
+    }
+
+    pub fn test_exp<K: ElementWiseKer<T>, T: LADatum + Float>(values: &[f32]) -> TestCaseResult
+    where
+        f32: AsPrimitive<T>,
+        T: AsPrimitive<f32>,
+    {
+        let values: Vec<T> = values.iter().copied().map(|x| x.as_()).collect();
+        crate::frame::element_wise::test::test_element_wise::<K, _, _>(&values, |x| x.exp())
+    }
+}
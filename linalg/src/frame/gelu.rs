@@ -0,0 +1,82 @@
+macro_rules! gelu_impl {
+    ($ti: ident, $func: ident, $nr: expr, $alignment_items: expr, $cond: expr) => {
+        ew_impl!($ti, $func, $nr, $alignment_items);
+        #[cfg(test)]
+        paste! {
+            mod [<test_ $func>] {
+                use super::*;
+                gelu_frame_tests!($cond, $ti, $func);
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+#[macro_use]
+pub mod test {
+    use crate::{frame::element_wise::*, LADatum};
+    use num_traits::{AsPrimitive, Float};
+    use proptest::test_runner::TestCaseResult;
+
+    #[macro_export]
+    macro_rules! gelu_frame_tests {
+        ($cond:expr, $t: ty, $ker:ty) => {
+            proptest::proptest! {
+                #[test]
+                fn gelu(xs in proptest::collection::vec(-10f32..10.0, 0..100)) {
+                    if $cond {
+                        $crate::frame::gelu::test::test_gelu::<$ker, $t>(&*xs).unwrap()
+                    }
+                }
+            }
+
+            #[test]
+            fn gelu_4_magic() {
+                if $cond {
+                    $crate::frame::gelu::test::test_gelu::<$ker, $t>(&[0f32, -1.0, 1.0, 3.0])
+                        .unwrap()
+                }
+            }
+
+            #[test]
+            fn gelu_20_ones() {
+                if $cond {
+                    $crate::frame::gelu::test::test_gelu::<$ker, $t>(&[1.0; 20]).unwrap();
+                }
+            }
+
+            #[test]
+            fn gelu_special_values() {
+                use $crate::frame::element_wise::*;
+                if $cond {
+                    let mut input: Vec<$t> = [0f32, -100f32, 100f32, 1f32]
+                        .iter()
+                        .map(|x| <f32 as num_traits::AsPrimitive<$t>>::as_(*x))
+                        .collect();
+                    <$ker>::ew().run(&mut input).unwrap();
+                    let found: Vec<f32> =
+                        input.iter().map(|x| num_traits::AsPrimitive::<f32>::as_(*x)).collect();
+                    assert_eq!(found[0], 0.0);
+                    assert_eq!(found[1], 0.0);
+                    assert_eq!(found[2], 100.0);
+                }
+            }
+        };
+    }
+
+    pub fn test_gelu<K: ElementWiseKer<T>, T: LADatum + Float>(values: &[f32]) -> TestCaseResult
+    where
+        f32: AsPrimitive<T>,
+        T: AsPrimitive<f32>,
+    {
+        let values: Vec<T> = values.iter().copied().map(|x| x.as_()).collect();
+        crate::frame::element_wise::test::test_element_wise::<K, _, _>(&values, |x| {
+            let x: f64 = x.as_() as f64;
+            let y = 0.5
+                * x
+                * (1.0
+                    + crate::frame::erf::test::reference_erf(x * std::f64::consts::FRAC_1_SQRT_2));
+            (y as f32).as_()
+        })
+    }
+}
@@ -0,0 +1,94 @@
+macro_rules! ln_impl {
+    ($ti: ident, $func: ident, $nr: expr, $alignment_items: expr, $cond: expr) => {
+        ew_impl!($ti, $func, $nr, $alignment_items);
+        #[cfg(test)]
+        paste! {
+            mod [<test_ $func>] {
+                use super::*;
+                ln_frame_tests!($cond, $ti, $func);
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+#[macro_use]
+pub mod test {
+    use crate::{frame::element_wise::*, LADatum};
+    use num_traits::{AsPrimitive, Float};
+    use proptest::test_runner::TestCaseResult;
+
+    #[macro_export]
+    macro_rules! ln_frame_tests {
+        ($cond:expr, $t: ty, $ker:ty) => {
+            proptest::proptest! {
+                #[test]
+                fn ln(xs in proptest::collection::vec(0.001f32..10000.0, 0..100)) {
+                    if $cond {
+                        $crate::frame::log::test::test_ln::<$ker, $t>(&*xs).unwrap()
+                    }
+                }
+            }
+
+            #[test]
+            fn ln_4_magic() {
+                if $cond {
+                    $crate::frame::log::test::test_ln::<$ker, $t>(&[1f32, 0.5, 2.0, 1000.0])
+                        .unwrap()
+                }
+            }
+
+            #[test]
+            fn ln_20_ones() {
+                if $cond {
+                    $crate::frame::log::test::test_ln::<$ker, $t>(&[1.0; 20]).unwrap();
+                }
+            }
+
+            #[test]
+            fn ln_special_values_like_std() {
+                if $cond {
+                    $crate::frame::log::test::test_ln::<$ker, $t>(&[
+                        f32::NAN,
+                        f32::INFINITY,
+                        f32::NEG_INFINITY,
+                        0.0,
+                        -0.0,
+                        1e-40,
+                        -1e-40,
+                        1e-45,
+                        f32::MIN_POSITIVE,
+                    ])
+                    .unwrap()
+                }
+            }
+
+            #[test]
+            fn ln_special_values() {
+                use $crate::frame::element_wise::*;
+                if $cond {
+                    let mut input: Vec<$t> = [0f32, -1f32, f32::INFINITY, 1f32]
+                        .iter()
+                        .map(|x| <f32 as num_traits::AsPrimitive<$t>>::as_(*x))
+                        .collect();
+                    <$ker>::ew().run(&mut input).unwrap();
+                    let found: Vec<f32> =
+                        input.iter().map(|x| num_traits::AsPrimitive::<f32>::as_(*x)).collect();
+                    assert_eq!(found[0], f32::NEG_INFINITY);
+                    assert!(found[1].is_nan());
+                    assert_eq!(found[2], f32::INFINITY);
+                    assert_eq!(found[3], 0.0);
+                }
+            }
+        };
+    }
+
+    pub fn test_ln<K: ElementWiseKer<T>, T: LADatum + Float>(values: &[f32]) -> TestCaseResult
+    where
+        f32: AsPrimitive<T>,
+        T: AsPrimitive<f32>,
+    {
+        let values: Vec<T> = values.iter().copied().map(|x| x.as_()).collect();
+        crate::frame::element_wise::test::test_element_wise::<K, _, _>(&values, |x| x.ln())
+    }
+}
@@ -1,3 +1,4 @@
+pub mod autotune;
 pub mod cost_model;
 #[macro_use]
 pub(crate) mod fuse;
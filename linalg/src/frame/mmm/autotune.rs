@@ -0,0 +1,339 @@
+//! Profile-guided selection of f32 matrix multiplication kernels.
+//!
+//! In tuning mode, every candidate kernel from `Ops::mmm_f32_impls` is
+//! benchmarked for each (m, k, n) product the first time it is requested, and
+//! the winner is stored in an on-disk profile specific to the CPU model. In
+//! cached mode, the profile is only consulted. The mode defaults to the value
+//! of `TRACT_MMM_AUTOTUNE` ("tune" or "cached", off otherwise), and the profile
+//! location to `TRACT_MMM_PROFILE`, or a file in the user cache directory.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use tract_data::anyhow::Context;
+use tract_data::internal::*;
+
+use super::{FusedSpec, MatMatMul};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AutotuneMode {
+    Off,
+    Cached,
+    Tune,
+}
+
+impl AutotuneMode {
+    fn from_env() -> AutotuneMode {
+        match std::env::var("TRACT_MMM_AUTOTUNE").map(|s| s.to_lowercase()).as_deref() {
+            Ok("tune") => AutotuneMode::Tune,
+            Ok("cached") => AutotuneMode::Cached,
+            _ => AutotuneMode::Off,
+        }
+    }
+}
+
+impl std::str::FromStr for AutotuneMode {
+    type Err = TractError;
+    fn from_str(s: &str) -> TractResult<AutotuneMode> {
+        match &*s.to_lowercase() {
+            "off" => Ok(AutotuneMode::Off),
+            "cached" => Ok(AutotuneMode::Cached),
+            "tune" => Ok(AutotuneMode::Tune),
+            _ => bail!("Unknown autotune mode {}, expected one of off, cached, tune", s),
+        }
+    }
+}
+
+/// Fastest kernel per (m, k, n) for a given CPU model.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MmmProfile {
+    pub cpu: String,
+    pub winners: HashMap<(usize, usize, usize), String>,
+}
+
+impl MmmProfile {
+    pub fn new(cpu: impl Into<String>) -> MmmProfile {
+        MmmProfile { cpu: cpu.into(), winners: HashMap::default() }
+    }
+
+    pub fn parse(s: &str) -> TractResult<MmmProfile> {
+        let mut lines = s.lines().filter(|l| !l.trim().is_empty());
+        let cpu = lines
+            .next()
+            .and_then(|l| l.strip_prefix("cpu "))
+            .context("Profile must start with a cpu line")?;
+        let mut profile = MmmProfile::new(cpu.trim());
+        for line in lines {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            if tokens.len() != 4 {
+                bail!("Invalid profile line: {}", line);
+            }
+            let mkn = (tokens[0].parse()?, tokens[1].parse()?, tokens[2].parse()?);
+            profile.winners.insert(mkn, tokens[3].to_string());
+        }
+        Ok(profile)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> TractResult<MmmProfile> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Reading mmm profile {:?}", path))?;
+        MmmProfile::parse(&content).with_context(|| format!("Parsing mmm profile {:?}", path))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> TractResult<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_string())
+            .with_context(|| format!("Writing mmm profile {:?}", path))?;
+        Ok(())
+    }
+
+    /// The kernel recorded for this product, if it is one of `impls`.
+    pub fn kernel(
+        &self,
+        impls: &[Box<dyn MatMatMul>],
+        m: usize,
+        k: usize,
+        n: usize,
+    ) -> Option<Box<dyn MatMatMul>> {
+        let name = self.winners.get(&(m, k, n))?;
+        impls.iter().find(|mmm| mmm.kernel_name() == name).cloned()
+    }
+
+    /// Benchmark all `impls` on this product, record and return the fastest.
+    pub fn tune(
+        &mut self,
+        impls: &[Box<dyn MatMatMul>],
+        m: usize,
+        k: usize,
+        n: usize,
+    ) -> TractResult<Box<dyn MatMatMul>> {
+        let best = fastest(impls, m, k, n)?;
+        self.winners.insert((m, k, n), best.kernel_name().to_string());
+        Ok(best)
+    }
+}
+
+/// Benchmark all `impls` on this product and return the fastest.
+pub fn fastest(
+    impls: &[Box<dyn MatMatMul>],
+    m: usize,
+    k: usize,
+    n: usize,
+) -> TractResult<Box<dyn MatMatMul>> {
+    let mut best: Option<(f64, &Box<dyn MatMatMul>)> = None;
+    for mmm in impls {
+        let time = bench(&**mmm, m, k, n)?;
+        log::debug!("mmm autotune {}x{}x{}: {} {:.3e}s", m, k, n, mmm.kernel_name(), time);
+        if best.map(|b| time < b.0).unwrap_or(true) {
+            best = Some((time, mmm));
+        }
+    }
+    let best = best.context("No kernel to tune")?.1;
+    log::info!("mmm autotune {}x{}x{}: picked {}", m, k, n, best.kernel_name());
+    Ok(best.clone())
+}
+
+impl fmt::Display for MmmProfile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "cpu {}", self.cpu)?;
+        let mut winners: Vec<_> = self.winners.iter().collect();
+        winners.sort();
+        for ((m, k, n), kernel) in winners {
+            writeln!(f, "{} {} {} {}", m, k, n, kernel)?;
+        }
+        Ok(())
+    }
+}
+
+/// A description of the CPU, used to key the profiles.
+pub fn cpu_model() -> String {
+    std::fs::read_to_string("/proc/cpuinfo")
+        .ok()
+        .and_then(|info| {
+            info.lines()
+                .find(|l| l.starts_with("model name") || l.starts_with("CPU part"))
+                .and_then(|l| l.split_once(':'))
+                .map(|(_, model)| model.trim().to_string())
+        })
+        .map(|model| format!("{} {}", std::env::consts::ARCH, model))
+        .unwrap_or_else(|| std::env::consts::ARCH.to_string())
+}
+
+/// Where the profile for this CPU is loaded from and saved to.
+pub fn default_profile_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("TRACT_MMM_PROFILE") {
+        return Some(path.into());
+    }
+    let cache = std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+    let cpu: String =
+        cpu_model().chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect();
+    Some(cache.join("tract").join(format!("mmm-{}.txt", cpu)))
+}
+
+/// Time per product for `mmm` on m, k, n, in seconds.
+pub fn bench(mmm: &dyn MatMatMul, m: usize, k: usize, n: usize) -> TractResult<f64> {
+    let dt = mmm.internal_type();
+    unsafe {
+        let a = Tensor::zero_aligned_dt(dt, &[mmm.a_pack().len(k, m)], mmm.a_pack().alignment())?;
+        let b = Tensor::zero_aligned_dt(dt, &[mmm.b_pack().len(k, n)], mmm.b_pack().alignment())?;
+        let c = Tensor::zero_dt(dt, &[m, n])?;
+        let pa = mmm.a_packed(dt.size_of(), k).wrap(&a.view());
+        let pb = mmm.b_packed(dt.size_of(), k).wrap(&b.view())?;
+        let pc = mmm.c_view(0, 1).wrap(&c.view());
+        let mut scratch = mmm.allocate_scratch_space();
+        let ops = [FusedSpec::AddMatMul { a: pa, b: pb, k }, FusedSpec::Store(pc)];
+        mmm.run_with_scratch_space(m, n, scratch.as_mut(), &ops)?;
+        let mut best = f64::MAX;
+        for _round in 0..3 {
+            let start = Instant::now();
+            let mut iters = 0;
+            while iters < 1000 && start.elapsed() < Duration::from_millis(10) {
+                mmm.run_with_scratch_space(m, n, scratch.as_mut(), &ops)?;
+                iters += 1;
+            }
+            best = best.min(start.elapsed().as_secs_f64() / iters as f64);
+        }
+        Ok(best)
+    }
+}
+
+struct Autotuner {
+    mode: AutotuneMode,
+    path: Option<PathBuf>,
+    profile: MmmProfile,
+}
+
+impl Autotuner {
+    fn from_env() -> Autotuner {
+        let mut tuner = Autotuner {
+            mode: AutotuneMode::from_env(),
+            path: None,
+            profile: MmmProfile::new(cpu_model()),
+        };
+        tuner.set_path(default_profile_path());
+        tuner
+    }
+
+    fn set_path(&mut self, path: Option<PathBuf>) {
+        self.profile = MmmProfile::new(cpu_model());
+        if let Some(p) = path.as_ref().filter(|p| p.exists()) {
+            match MmmProfile::load(p) {
+                Ok(profile) if profile.cpu == self.profile.cpu => self.profile = profile,
+                Ok(profile) => log::warn!(
+                    "Ignoring mmm profile {:?} recorded for {}, running on {}",
+                    p,
+                    profile.cpu,
+                    self.profile.cpu
+                ),
+                Err(e) => log::warn!("{:?}", e),
+            }
+        }
+        self.path = path;
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref AUTOTUNER: Mutex<Autotuner> = Mutex::new(Autotuner::from_env());
+}
+
+// the tuner state stays consistent even if a thread panicked while holding it
+fn autotuner() -> MutexGuard<'static, Autotuner> {
+    AUTOTUNER.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+pub fn mode() -> AutotuneMode {
+    autotuner().mode
+}
+
+pub fn set_mode(mode: AutotuneMode) {
+    autotuner().mode = mode;
+}
+
+/// Use the profile at `path` (None to keep it in memory only).
+pub fn set_profile_path(path: Option<PathBuf>) {
+    autotuner().set_path(path)
+}
+
+pub fn profile() -> MmmProfile {
+    autotuner().profile.clone()
+}
+
+/// The f32 kernel to use for this product according to the current mode and
+/// profile, or None to fall back to the static choice.
+///
+/// Benchmarks run without holding the tuner lock, so other products can be
+/// looked up meanwhile. Two threads may tune the same product concurrently,
+/// the last one to finish is recorded.
+pub fn mmm_f32(m: usize, k: usize, n: usize) -> Option<Box<dyn MatMatMul>> {
+    let impls = crate::ops().mmm_f32_impls();
+    {
+        let tuner = autotuner();
+        if tuner.mode == AutotuneMode::Off {
+            return None;
+        }
+        if let Some(mmm) = tuner.profile.kernel(impls, m, k, n) {
+            return Some(mmm);
+        }
+        if tuner.mode != AutotuneMode::Tune {
+            return None;
+        }
+    }
+    let mmm = match fastest(impls, m, k, n) {
+        Ok(mmm) => mmm,
+        Err(e) => {
+            log::warn!("mmm autotune failed for {}x{}x{}: {:?}", m, k, n, e);
+            return None;
+        }
+    };
+    let mut tuner = autotuner();
+    tuner.profile.winners.insert((m, k, n), mmm.kernel_name().to_string());
+    if let Some(path) = &tuner.path {
+        if let Err(e) = tuner.profile.save(path) {
+            log::warn!("{:?}", e);
+        }
+    }
+    Some(mmm)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn profile_roundtrip() {
+        let mut profile = MmmProfile::new("x86_64 Some CPU @ 3GHz");
+        profile.winners.insert((64, 32, 7), "generic_f32_4x4".to_string());
+        profile.winners.insert((8, 8, 8), "fma_mmm_f32_8x8".to_string());
+        assert_eq!(MmmProfile::parse(&profile.to_string()).unwrap(), profile);
+    }
+
+    #[test]
+    fn tune_picks_a_candidate() {
+        let impls = crate::ops().mmm_f32_impls();
+        let mut profile = MmmProfile::new(cpu_model());
+        let picked = profile.tune(impls, 16, 8, 12).unwrap();
+        assert_eq!(profile.kernel(impls, 16, 8, 12).unwrap().kernel_name(), picked.kernel_name());
+        assert!(profile.kernel(impls, 16, 8, 13).is_none());
+    }
+
+    #[test]
+    fn survives_poisoning() {
+        let _ = std::thread::spawn(|| {
+            let _tuner = autotuner();
+            panic!("poisoning the tuner");
+        })
+        .join();
+        assert!(AUTOTUNER.is_poisoned());
+        let _ = mode();
+        let _ = profile();
+    }
+}
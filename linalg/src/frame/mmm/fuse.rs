@@ -1,6 +1,8 @@
 use std::fmt::Debug;
+use std::sync::Arc;
 
-use super::{InputStore, OutputStore, OutputStoreKer, PackedStore};
+use super::{InputStore, OutputStore, OutputStoreKer};
+use crate::frame::sparse::BlockSparseWeights;
 use tract_data::internal::*;
 
 #[repr(usize)]
@@ -47,19 +49,19 @@ pub enum FusedSpec<'t> {
     RoundingShiftRight(usize, RoundingPolicy),
     ShiftLeft(usize),
     Store(OutputStore),
-    AddMatMul { k: usize, a: PackedStore, b: InputStore },
+    AddMatMul { k: usize, a: InputStore, b: InputStore },
+    AddSparseMatMul { a: Arc<BlockSparseWeights>, b: InputStore },
 }
 
 impl<'t> FusedSpec<'t> {
     pub fn prefer_col_outer(&self) -> bool {
-        if let FusedSpec::AddMatMul { b, .. } = self {
-            match b {
-                InputStore::Packed { .. } => false,
-                InputStore::VirtualPacking { .. } => true,
-                InputStore::LatePacking { .. } => true,
+        match self {
+            // keep the outer loop on the operand that is expensive to (re)pack
+            FusedSpec::AddMatMul { a, b, .. } => {
+                matches!(a, InputStore::Packed { .. }) && !matches!(b, InputStore::Packed { .. })
             }
-        } else {
-            false
+            FusedSpec::AddSparseMatMul { b, .. } => !matches!(b, InputStore::Packed { .. }),
+            _ => false,
         }
     }
 }
@@ -39,13 +39,9 @@ pub struct PackedStoreSpec {
 impl InputStoreSpec {
     #[inline]
     pub unsafe fn wrap(&self, tensor: &TensorView) -> TractResult<InputStore> {
-        use InputStore::*;
         use InputStoreSpec as S;
         match self {
-            S::Prepacked(PackedStoreSpec { panel_bytes }) => Ok(Packed(PackedStore {
-                ptr: tensor.as_ptr_unchecked::<u8>() as _,
-                panel_bytes: *panel_bytes as isize,
-            })),
+            S::Prepacked(packed) => Ok(packed.wrap(tensor)),
             S::LatePacking { packer, k_axis, mn_axis } => Ok(InputStore::LatePacking {
                 packer: packer.clone(),
                 ptr: tensor.as_ptr_unchecked::<u8>() as _,
@@ -77,11 +73,11 @@ impl fmt::Display for InputStoreSpec {
 
 impl PackedStoreSpec {
     #[inline]
-    pub unsafe fn wrap(&self, tensor: &TensorView) -> PackedStore {
-        PackedStore {
+    pub unsafe fn wrap(&self, tensor: &TensorView) -> InputStore {
+        InputStore::Packed(PackedStore {
             ptr: tensor.as_ptr_unchecked::<u8>() as _,
             panel_bytes: self.panel_bytes as isize,
-        }
+        })
     }
 }
 
@@ -125,7 +121,7 @@ impl InputStore {
     }
 
     #[inline]
-    pub(super) unsafe fn panel_b(&self, i: usize, buffer: Option<*const u8>) -> *const u8 {
+    pub(super) unsafe fn panel(&self, i: usize, buffer: Option<*const u8>) -> *const u8 {
         match self {
             InputStore::Packed(packed) => packed.panel(i),
             InputStore::LatePacking { packer, ptr, dt, k, mn, mn_stride, k_stride } => {
@@ -1,11 +1,13 @@
 use super::ScratchSpaceFusedNonLinear;
 use super::*;
+use crate::frame::weight_quant::{QuantizedWeights, QuantizedWeightsInput};
 use crate::frame::Packer;
 use crate::LADatum;
 use anyhow::Context;
 use std::fmt;
 use std::fmt::Debug;
 use std::marker::PhantomData;
+use std::sync::Arc;
 use tract_data::anyhow;
 use tract_data::internal::*;
 
@@ -22,6 +24,7 @@ pub trait MatMatMul:
     fn internal_type(&self) -> DatumType;
 
     unsafe fn a_packed(&self, item_size: usize, k: usize) -> PackedStoreSpec;
+    unsafe fn a_quantized_weights(&self, weights: Arc<QuantizedWeights>) -> InputStore;
 
     unsafe fn b_packed(&self, item_size: usize, k: usize) -> InputStoreSpec;
     unsafe fn b_late_packing(&self) -> InputStoreSpec {
@@ -161,6 +164,15 @@ where
         PackedStoreSpec { panel_bytes: (k * K::mr() * item_size) }
     }
 
+    unsafe fn a_quantized_weights(&self, weights: Arc<QuantizedWeights>) -> InputStore {
+        InputStore::VirtualPacking {
+            packer: self.a_pack(),
+            k: weights.k,
+            input: Box::new(QuantizedWeightsInput(weights)),
+            dt: f32::datum_type(),
+        }
+    }
+
     unsafe fn b_packed(&self, item_size: usize, k: usize) -> InputStoreSpec {
         let panel_bytes = k * K::nr() * item_size;
         InputStoreSpec::Prepacked(PackedStoreSpec { panel_bytes })
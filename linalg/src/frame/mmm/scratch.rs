@@ -2,6 +2,7 @@ use std::alloc::Layout;
 use std::fmt::Debug;
 use tract_data::internal::*;
 
+use crate::frame::sparse::BlockSparseWeights;
 use crate::LADatum;
 
 use super::{BinOp, FusedKerSpec, FusedSpec, MatMatMulKer, OutputStoreKer};
@@ -35,7 +36,8 @@ struct LocDependant {
     spec: usize,
     uspec: usize,
     loc: *const u8,
-    buffer: Option<*const u8>,
+    buffer_a: Option<*const u8>,
+    buffer_b: Option<*const u8>,
 }
 
 impl<TI: LADatum> ScratchSpace for ScratchSpaceFusedNonLinear<TI> {}
@@ -51,7 +53,28 @@ impl<TI: LADatum> Drop for ScratchSpaceFusedNonLinear<TI> {
     }
 }
 
-struct AddMatMulTemp(*const u8, usize);
+struct AddMatMulTemp {
+    ptr_a: *const u8,
+    panel_a_id: usize,
+    ptr_b: *const u8,
+    panel_b_id: usize,
+}
+
+#[inline]
+unsafe fn sparse_tile<TI: LADatum, K: MatMatMulKer<TI>>(
+    a: &BlockSparseWeights,
+    down: usize,
+    pb: *const u8,
+    tile: *const u8,
+) -> FusedKerSpec<TI> {
+    a.compute_tile(down, pb as _, K::nr(), tile as _);
+    FusedKerSpec::AddUnicast(OutputStoreKer {
+        ptr: tile as _,
+        row_byte_stride: std::mem::size_of::<TI>() as isize,
+        col_byte_stride: (std::mem::size_of::<TI>() * K::mr()) as isize,
+        item_size: std::mem::size_of::<TI>(),
+    })
+}
 
 impl<TI: LADatum> ScratchSpaceFusedNonLinear<TI> {
     pub unsafe fn prepare<K: MatMatMulKer<TI>>(&mut self, specs: &[FusedSpec]) {
@@ -64,7 +87,7 @@ impl<TI: LADatum> ScratchSpaceFusedNonLinear<TI> {
         let mut offset = 0;
         let mut align = 1;
         fn ld(spec: usize, uspec: usize, loc: *const u8) -> LocDependant {
-            LocDependant { spec, uspec, loc, buffer: None }
+            LocDependant { spec, uspec, loc, buffer_a: None, buffer_b: None }
         }
         // we're cheating here, storing offset as the buf pointer first
         for (ix, spec) in specs.iter().enumerate() {
@@ -100,13 +123,41 @@ impl<TI: LADatum> ScratchSpaceFusedNonLinear<TI> {
                     offset += TI::datum_type().size_of() * K::mr() * K::nr();
                     FusedKerSpec::Done
                 }
-                FS::AddMatMul { b, .. } => {
+                FS::AddMatMul { a, b, .. } => {
                     let mut ld = ld(ix, self.uspecs.len(), offset as _);
                     offset += std::mem::size_of::<AddMatMulTemp>();
+                    if let Some(tmp) = a.scratch_panel_buffer_layout() {
+                        align = tmp.align().lcm(&align);
+                        offset = Integer::next_multiple_of(&offset, &tmp.align());
+                        ld.buffer_a = Some(offset as _);
+                        offset += tmp.size();
+                    }
                     if let Some(tmp) = b.scratch_panel_buffer_layout() {
                         align = tmp.align().lcm(&align);
                         offset = Integer::next_multiple_of(&offset, &tmp.align());
-                        ld.buffer = Some(offset as _);
+                        ld.buffer_b = Some(offset as _);
+                        offset += tmp.size();
+                    }
+                    self.loc_dependant.push(ld);
+                    FusedKerSpec::Done
+                }
+                FS::AddSparseMatMul { a, b } => {
+                    assert!(
+                        TI::datum_type() == f32::datum_type() && a.mr == K::mr(),
+                        "Sparse A does not match kernel"
+                    );
+                    let mut ld = ld(ix, self.uspecs.len(), offset as _);
+                    offset += std::mem::size_of::<AddMatMulTemp>();
+                    // the sparse product is computed in buffer_a, then added as a unicast tile
+                    let tile = Layout::array::<TI>(K::mr() * K::nr()).unwrap();
+                    align = tile.align().lcm(&align);
+                    offset = Integer::next_multiple_of(&offset, &tile.align());
+                    ld.buffer_a = Some(offset as _);
+                    offset += tile.size();
+                    if let Some(tmp) = b.scratch_panel_buffer_layout() {
+                        align = tmp.align().lcm(&align);
+                        offset = Integer::next_multiple_of(&offset, &tmp.align());
+                        ld.buffer_b = Some(offset as _);
                         offset += tmp.size();
                     }
                     self.loc_dependant.push(ld);
@@ -123,17 +174,18 @@ impl<TI: LADatum> ScratchSpaceFusedNonLinear<TI> {
             self.layout = Layout::from_size_align_unchecked(offset, align);
             self.buffer = std::alloc::alloc(self.layout);
         }
-        for LocDependant { loc, buffer, spec, .. } in &mut self.loc_dependant {
+        for LocDependant { loc, buffer_a, buffer_b, spec, .. } in &mut self.loc_dependant {
             *loc = self.buffer.offset(*loc as _);
-            if let Some(b) = buffer {
+            for b in buffer_a.iter_mut().chain(buffer_b.iter_mut()) {
                 *b = self.buffer.offset(*b as _);
             }
             let spec = specs.get_unchecked(*spec);
             #[allow(clippy::single_match)]
             match spec {
-                FS::AddMatMul { .. } => {
+                FS::AddMatMul { .. } | FS::AddSparseMatMul { .. } => {
                     let scratch = *loc as *mut AddMatMulTemp;
-                    (*scratch).1 = usize::MAX;
+                    (*scratch).panel_a_id = usize::MAX;
+                    (*scratch).panel_b_id = usize::MAX;
                 }
                 _ => (),
             };
@@ -151,7 +203,7 @@ impl<TI: LADatum> ScratchSpaceFusedNonLinear<TI> {
         use FusedSpec as FS;
         let ScratchSpaceFusedNonLinear { uspecs, loc_dependant, .. } = self;
         debug_assert!(specs.len() + 2 == uspecs.len());
-        for LocDependant { spec, uspec, loc, buffer } in loc_dependant.iter_mut() {
+        for LocDependant { spec, uspec, loc, buffer_a, buffer_b } in loc_dependant.iter_mut() {
             let spec = specs.get_unchecked(*spec);
             *uspecs.get_unchecked_mut(*uspec) = match spec {
                 FS::BinPerRow(v, op) => {
@@ -184,14 +236,30 @@ impl<TI: LADatum> ScratchSpaceFusedNonLinear<TI> {
                 FS::AddUnicast(store) => FKS::AddUnicast(store.tile_c(down, right)),
                 FS::Store(c_store) => FKS::Store(c_store.tile_c(down, right)),
                 FS::AddMatMul { k, a, b } => {
-                    let pa = a.panel(down);
-                    K::prefetch(pa as _, 512);
                     let scratch = *loc as *mut AddMatMulTemp;
-                    if (*scratch).1 != right {
-                        (*scratch).0 = b.panel_b(right, *buffer);
-                        (*scratch).1 = right;
+                    if (*scratch).panel_a_id != down {
+                        (*scratch).ptr_a = a.panel(down, *buffer_a);
+                        (*scratch).panel_a_id = down;
+                    }
+                    K::prefetch((*scratch).ptr_a as _, 512);
+                    if (*scratch).panel_b_id != right {
+                        (*scratch).ptr_b = b.panel(right, *buffer_b);
+                        (*scratch).panel_b_id = right;
+                    }
+                    FKS::AddMatMul {
+                        k: *k,
+                        pa: (*scratch).ptr_a,
+                        pb: (*scratch).ptr_b,
+                        cpu_variant: 0,
+                    }
+                }
+                FS::AddSparseMatMul { a, b } => {
+                    let scratch = *loc as *mut AddMatMulTemp;
+                    if (*scratch).panel_b_id != right {
+                        (*scratch).ptr_b = b.panel(right, *buffer_b);
+                        (*scratch).panel_b_id = right;
                     }
-                    FKS::AddMatMul { k: *k, pa, pb: (*scratch).0, cpu_variant: 0 }
+                    sparse_tile::<TI, K>(a, down, (*scratch).ptr_b, buffer_a.unwrap())
                 }
                 _ => std::hint::unreachable_unchecked(),
             };
@@ -209,7 +277,7 @@ impl<TI: LADatum> ScratchSpaceFusedNonLinear<TI> {
         use FusedSpec as FS;
         let ScratchSpaceFusedNonLinear { uspecs, loc_dependant, .. } = self;
         debug_assert!(specs.len() + 2 == uspecs.len());
-        for LocDependant { spec, uspec, loc, buffer } in loc_dependant.iter_mut() {
+        for LocDependant { spec, uspec, loc, buffer_a, buffer_b } in loc_dependant.iter_mut() {
             let spec = specs.get_unchecked(*spec);
             *uspecs.get_unchecked_mut(*uspec) = match spec {
                 FS::BinPerRow(v, op) => {
@@ -271,8 +339,7 @@ impl<TI: LADatum> ScratchSpaceFusedNonLinear<TI> {
                     let have = rows.len() - down * K::mr();
                     let row_ptr = if have < K::mr() {
                         r.get_unchecked_mut(..have).copy_from_slice(
-                            rows
-                                .as_slice_unchecked()
+                            rows.as_slice_unchecked()
                                 .get_unchecked(down * K::mr()..)
                                 .get_unchecked(..have),
                         );
@@ -283,15 +350,11 @@ impl<TI: LADatum> ScratchSpaceFusedNonLinear<TI> {
                     } else {
                         rows.as_ptr_unchecked::<TI>().add(down * K::mr())
                     };
-                    let c = std::slice::from_raw_parts_mut(
-                        (*loc as *mut TI).add(K::mr()),
-                        K::nr(),
-                    );
+                    let c = std::slice::from_raw_parts_mut((*loc as *mut TI).add(K::mr()), K::nr());
                     let have = cols.len() - right * K::nr();
                     let col_ptr = if have < K::nr() {
                         c.get_unchecked_mut(..have).copy_from_slice(
-                            cols
-                                .as_slice_unchecked()
+                            cols.as_slice_unchecked()
                                 .get_unchecked(right * K::nr()..)
                                 .get_unchecked(..have),
                         );
@@ -342,14 +405,30 @@ impl<TI: LADatum> ScratchSpaceFusedNonLinear<TI> {
                     FKS::Store(tmpc)
                 }
                 FS::AddMatMul { k, a, b } => {
-                    let pa = a.panel(down);
-                    K::prefetch(pa as _, 512);
                     let scratch = *loc as *mut AddMatMulTemp;
-                    if (*scratch).1 != right {
-                        (*scratch).0 = b.panel_b(right, *buffer);
-                        (*scratch).1 = right;
+                    if (*scratch).panel_a_id != down {
+                        (*scratch).ptr_a = a.panel(down, *buffer_a);
+                        (*scratch).panel_a_id = down;
+                    }
+                    K::prefetch((*scratch).ptr_a as _, 512);
+                    if (*scratch).panel_b_id != right {
+                        (*scratch).ptr_b = b.panel(right, *buffer_b);
+                        (*scratch).panel_b_id = right;
+                    }
+                    FKS::AddMatMul {
+                        k: *k,
+                        pa: (*scratch).ptr_a,
+                        pb: (*scratch).ptr_b,
+                        cpu_variant: 0,
+                    }
+                }
+                FS::AddSparseMatMul { a, b } => {
+                    let scratch = *loc as *mut AddMatMulTemp;
+                    if (*scratch).panel_b_id != right {
+                        (*scratch).ptr_b = b.panel(right, *buffer_b);
+                        (*scratch).panel_b_id = right;
                     }
-                    FKS::AddMatMul { k: *k, pa, pb: (*scratch).0, cpu_variant: 0 }
+                    sparse_tile::<TI, K>(a, down, (*scratch).ptr_b, buffer_a.unwrap())
                 }
                 _ => std::hint::unreachable_unchecked(),
             };
@@ -0,0 +1,312 @@
+use std::hash::{Hash, Hasher};
+use tract_data::internal::*;
+use tract_ndarray::{s, ArrayView2};
+
+/// Default minimum fraction of empty blocks for a constant operand to be
+/// worth storing as `BlockSparseWeights`.
+pub const DEFAULT_SPARSITY_THRESHOLD: f32 = 0.6;
+
+/// Minimum fraction of empty blocks for a constant operand to be stored sparse.
+///
+/// Can be overriden with the TRACT_SPARSITY_THRESHOLD environment variable. A
+/// value above 1 disables sparse storage.
+pub fn sparsity_threshold() -> f32 {
+    std::env::var("TRACT_SPARSITY_THRESHOLD")
+        .ok()
+        .and_then(|s| s.parse::<f32>().ok())
+        .unwrap_or(DEFAULT_SPARSITY_THRESHOLD)
+}
+
+/// A m×k f32 matrix stored as compressed sparse rows of `mr`-high blocks.
+///
+/// Rows are grouped in panels of `mr`, like the A panels of a matrix multiplier
+/// with the same `mr`. In each panel, only the columns where at least one of
+/// the `mr` rows is not zero are kept, as a block of `mr` values. Rows past `m`
+/// in the last panel are padded with zeroes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockSparseWeights {
+    pub m: usize,
+    pub k: usize,
+    pub mr: usize,
+    /// index of the first block of each panel in `cols`, plus the block count
+    pub panel_offsets: Vec<usize>,
+    /// k coordinate of each block
+    pub cols: Vec<u32>,
+    /// `mr` values for each block
+    pub values: Vec<f32>,
+}
+
+impl Hash for BlockSparseWeights {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.m.hash(state);
+        self.k.hash(state);
+        self.mr.hash(state);
+        self.panel_offsets.hash(state);
+        self.cols.hash(state);
+        self.values.iter().for_each(|v| v.to_bits().hash(state));
+    }
+}
+
+impl BlockSparseWeights {
+    /// Fraction of the `mr`×1 blocks of a m×k matrix which are all zeroes.
+    pub fn block_sparsity(mr: usize, a: ArrayView2<f32>) -> f32 {
+        let (m, k) = a.dim();
+        if m == 0 || k == 0 {
+            return 0.0;
+        }
+        let panels = m.divceil(mr);
+        let empty = (0..panels)
+            .map(|p| {
+                let rows = a.slice(s![p * mr..((p + 1) * mr).min(m), ..]);
+                rows.columns().into_iter().filter(|col| col.iter().all(|v| *v == 0.0)).count()
+            })
+            .sum::<usize>();
+        empty as f32 / (panels * k) as f32
+    }
+
+    /// Compress a m×k matrix.
+    pub fn from_dense(mr: usize, a: ArrayView2<f32>) -> BlockSparseWeights {
+        let (m, k) = a.dim();
+        let mut panel_offsets = vec![0];
+        let mut cols = vec![];
+        let mut values = vec![];
+        for p in 0..m.divceil(mr) {
+            let rows = a.slice(s![p * mr..((p + 1) * mr).min(m), ..]);
+            for (ix, col) in rows.columns().into_iter().enumerate() {
+                if col.iter().any(|v| *v != 0.0) {
+                    cols.push(ix as u32);
+                    values.extend(col.iter());
+                    values.resize(cols.len() * mr, 0.0);
+                }
+            }
+            panel_offsets.push(cols.len());
+        }
+        BlockSparseWeights { m, k, mr, panel_offsets, cols, values }
+    }
+
+    pub fn panels(&self) -> usize {
+        self.panel_offsets.len() - 1
+    }
+
+    pub fn blocks(&self) -> usize {
+        self.cols.len()
+    }
+
+    /// Expand back to a m×k f32 tensor.
+    pub fn to_dense(&self) -> Tensor {
+        let mut a = tract_ndarray::Array2::<f32>::zeros((self.m, self.k));
+        for p in 0..self.panels() {
+            for block in self.panel_offsets[p]..self.panel_offsets[p + 1] {
+                let col = self.cols[block] as usize;
+                for (x, v) in self.values[block * self.mr..][..self.mr].iter().enumerate() {
+                    if p * self.mr + x < self.m {
+                        a[(p * self.mr + x, col)] = *v;
+                    }
+                }
+            }
+        }
+        a.into_tensor()
+    }
+
+    /// Compute the product of a panel by a packed B panel into a column-major
+    /// `mr`×`nr` tile.
+    ///
+    /// `pb` is a B panel as packed for a kernel with `nr` columns: `nr` values
+    /// for each k.
+    pub unsafe fn compute_tile(&self, panel: usize, pb: *const f32, nr: usize, tile: *mut f32) {
+        #[cfg(target_arch = "x86_64")]
+        {
+            if is_x86_feature_detected!("fma") && is_x86_feature_detected!("avx") {
+                return self.compute_tile_fma(panel, pb, nr, tile);
+            }
+        }
+        self.compute_tile_default(panel, pb, nr, tile)
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[target_feature(enable = "avx,fma")]
+    unsafe fn compute_tile_fma(&self, panel: usize, pb: *const f32, nr: usize, tile: *mut f32) {
+        self.compute_tile_default(panel, pb, nr, tile)
+    }
+
+    #[inline(always)]
+    unsafe fn compute_tile_default(&self, panel: usize, pb: *const f32, nr: usize, tile: *mut f32) {
+        macro_rules! dispatch {
+            ($r: expr, $($nr: expr),*) => {
+                match nr {
+                    $($nr => return self.tile_chunks::<$r, $nr>(panel, pb, tile),)*
+                    _ => (),
+                }
+            }
+        }
+        if self.mr % 8 == 0 {
+            dispatch!(8, 1, 2, 3, 4, 5, 6, 8);
+        } else if self.mr % 4 == 0 {
+            dispatch!(4, 1, 2, 3, 4, 5, 6, 8);
+        }
+        self.tile_generic(panel, pb, nr, tile)
+    }
+
+    // split the panel in chunks of R rows, small enough for the R×NR
+    // accumulators to stay in registers
+    #[inline(always)]
+    unsafe fn tile_chunks<const R: usize, const NR: usize>(
+        &self,
+        panel: usize,
+        pb: *const f32,
+        tile: *mut f32,
+    ) {
+        let mr = self.mr;
+        let blocks =
+            *self.panel_offsets.get_unchecked(panel)..*self.panel_offsets.get_unchecked(panel + 1);
+        for chunk in 0..mr / R {
+            let mut acc = [[0f32; R]; NR];
+            for block in blocks.clone() {
+                let col = *self.cols.get_unchecked(block) as usize;
+                let a = &*(self.values.as_ptr().add(block * mr + chunk * R) as *const [f32; R]);
+                let b = &*(pb.add(col * NR) as *const [f32; NR]);
+                for c in 0..NR {
+                    for r in 0..R {
+                        acc[c][r] += a[r] * b[c];
+                    }
+                }
+            }
+            for (c, acc) in acc.iter().enumerate() {
+                std::ptr::copy_nonoverlapping(acc.as_ptr(), tile.add(c * mr + chunk * R), R);
+            }
+        }
+    }
+
+    unsafe fn tile_generic(&self, panel: usize, pb: *const f32, nr: usize, tile: *mut f32) {
+        let mr = self.mr;
+        let tile = std::slice::from_raw_parts_mut(tile, mr * nr);
+        tile.iter_mut().for_each(|x| *x = 0.0);
+        let blocks =
+            *self.panel_offsets.get_unchecked(panel)..*self.panel_offsets.get_unchecked(panel + 1);
+        for block in blocks {
+            let col = *self.cols.get_unchecked(block) as usize;
+            let values = self.values.get_unchecked(block * mr..).get_unchecked(..mr);
+            let b = std::slice::from_raw_parts(pb.add(col * nr), nr);
+            for (c, b) in tile.chunks_exact_mut(mr).zip(b.iter()) {
+                for (c, a) in c.iter_mut().zip(values.iter()) {
+                    *c += a * b;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::frame::mmm::{BinOp, FusedSpec};
+    use std::sync::Arc;
+    use tract_ndarray::Array2;
+
+    // zero out most (row panel, column) blocks for a given mr
+    fn pruned(m: usize, k: usize, mr: usize) -> Array2<f32> {
+        Array2::from_shape_fn((m, k), |(r, c)| {
+            if (r / mr * 7 + c * 3) % 5 < 3 {
+                0.0
+            } else {
+                ((r * 5 + c * 11) % 13) as f32 / 4.0 - 1.5
+            }
+        })
+    }
+
+    #[test]
+    fn roundtrip() {
+        let a = pruned(11, 9, 4);
+        let sparse = BlockSparseWeights::from_dense(4, a.view());
+        assert_eq!(sparse.panels(), 3);
+        assert_eq!(sparse.values.len(), 4 * sparse.blocks());
+        assert_eq!(sparse.to_dense(), a.clone().into_tensor());
+        let sparsity = BlockSparseWeights::block_sparsity(4, a.view());
+        assert_eq!(sparsity, 1.0 - sparse.blocks() as f32 / (3 * 9) as f32);
+    }
+
+    #[test]
+    fn dense_has_no_sparsity() {
+        let a = Array2::from_shape_fn((5, 3), |(r, c)| (r + c + 1) as f32);
+        assert_eq!(BlockSparseWeights::block_sparsity(2, a.view()), 0.0);
+    }
+
+    fn tile(mr: usize, nr: usize) {
+        let (m, k) = (2 * mr - 1, 13);
+        let a = pruned(m, k, mr);
+        let sparse = BlockSparseWeights::from_dense(mr, a.view());
+        let pb: Vec<f32> = (0..k * nr).map(|i| (i % 7) as f32 - 3.0).collect();
+        let b = Array2::from_shape_vec((k, nr), pb.clone()).unwrap();
+        let expected = a.dot(&b);
+        for panel in 0..2 {
+            let mut tile = vec![f32::NAN; mr * nr];
+            unsafe { sparse.compute_tile(panel, pb.as_ptr(), nr, tile.as_mut_ptr()) };
+            for r in 0..mr.min(m - panel * mr) {
+                for c in 0..nr {
+                    assert_eq!(tile[c * mr + r], expected[(panel * mr + r, c)]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tile_chunks() {
+        tile(16, 6);
+        tile(12, 8);
+    }
+
+    #[test]
+    fn tile_generic() {
+        tile(3, 2);
+        tile(8, 7);
+    }
+
+    fn matmul(m: usize, k: usize, n: usize) {
+        let mmm = crate::ops()
+            .mmm(f32::datum_type(), f32::datum_type(), f32::datum_type(), Some(m), Some(k), Some(n))
+            .unwrap();
+        let a = pruned(m, k, mmm.mr());
+        let sparse = Arc::new(BlockSparseWeights::from_dense(mmm.mr(), a.view()));
+        let b = Array2::from_shape_fn((k, n), |(k, n)| (k + 2 * n) as f32 / 10.0 - 1.0);
+        let bias = tract_ndarray::Array1::from_shape_fn(m, |m| m as f32 - 2.0);
+        let mut expected = a.dot(&b);
+        expected.outer_iter_mut().zip(bias.iter()).for_each(|(mut row, bias)| {
+            row.mapv_inplace(|x| (x + bias).max(0.0));
+        });
+        let b = b.into_tensor();
+        let bias = bias.into_tensor();
+        let zero = tensor0(0f32);
+        let mut packed_b =
+            Tensor::zero_aligned::<f32>(&[mmm.b_pack().len(k, n)], mmm.b_pack().alignment())
+                .unwrap();
+        let mut c = Tensor::zero::<f32>(&[m, n]).unwrap();
+        unsafe {
+            mmm.b_pack().pack(packed_b.view_mut(), b.view(), 0, 1);
+            mmm.run(
+                m,
+                n,
+                &[
+                    FusedSpec::AddSparseMatMul {
+                        a: sparse,
+                        b: mmm.b_packed(4, k).wrap(&packed_b.view()).unwrap(),
+                    },
+                    FusedSpec::BinPerRow(&bias, BinOp::Add),
+                    FusedSpec::BinScalar(&zero, BinOp::Max),
+                    FusedSpec::Store(mmm.c_view(0, 1).wrap(&c.view_mut())),
+                ],
+            )
+            .unwrap();
+        }
+        c.close_enough(&expected.into_tensor(), true).unwrap();
+    }
+
+    #[test]
+    fn matmul_with_bias_and_relu() {
+        matmul(37, 29, 11)
+    }
+
+    #[test]
+    fn matvec_with_bias_and_relu() {
+        matmul(19, 40, 1)
+    }
+}
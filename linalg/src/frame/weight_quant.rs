@@ -0,0 +1,320 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+use std::sync::Arc;
+use tract_data::anyhow::Context;
+use tract_data::internal::*;
+
+use crate::frame::mmm::VirtualInput;
+use crate::frame::Packer;
+
+/// Storage format for weight-only quantized matrices.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WeightFormat {
+    /// one signed byte per weight, in -127..=127
+    Int8,
+    /// two weights per byte, in -7..=7, stored offset by 8, low nibble first
+    Int4,
+}
+
+impl WeightFormat {
+    fn max(&self) -> f32 {
+        match self {
+            WeightFormat::Int8 => 127.0,
+            WeightFormat::Int4 => 7.0,
+        }
+    }
+}
+
+impl fmt::Display for WeightFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WeightFormat::Int8 => write!(f, "i8"),
+            WeightFormat::Int4 => write!(f, "i4"),
+        }
+    }
+}
+
+/// Weight quantization scheme: symmetric quantization with one f32 scale for
+/// each group of `group_size` consecutive weights along the k axis.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct WeightQuant {
+    pub format: WeightFormat,
+    pub group_size: usize,
+}
+
+impl fmt::Display for WeightQuant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/g{}", self.format, self.group_size)
+    }
+}
+
+impl std::str::FromStr for WeightQuant {
+    type Err = TractError;
+    /// Parse "i8" or "i4", optionally followed by a group size like in "i4/g64".
+    fn from_str(s: &str) -> TractResult<WeightQuant> {
+        let (format, group_size) = s.split_once('/').unwrap_or((s, "g32"));
+        let format = match format {
+            "i8" => WeightFormat::Int8,
+            "i4" => WeightFormat::Int4,
+            _ => bail!("Unknown weight format {}, expected i8 or i4", format),
+        };
+        let group_size = group_size
+            .strip_prefix('g')
+            .and_then(|g| g.parse::<usize>().ok())
+            .filter(|g| *g > 0)
+            .with_context(|| format!("Invalid group size in {}, expected g<n>", s))?;
+        Ok(WeightQuant { format, group_size })
+    }
+}
+
+/// A m×k f32 matrix stored as int8 or int4 values and per-group scales.
+///
+/// Values are dequantized on the fly when packed for the matrix multiplier.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QuantizedWeights {
+    pub quant: WeightQuant,
+    pub m: usize,
+    pub k: usize,
+    pub data: Vec<u8>,
+    pub scales: Vec<f32>,
+}
+
+impl Hash for QuantizedWeights {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.quant.hash(state);
+        self.m.hash(state);
+        self.k.hash(state);
+        self.data.hash(state);
+        self.scales.iter().for_each(|s| s.to_bits().hash(state));
+    }
+}
+
+impl QuantizedWeights {
+    /// Quantize a row-major m×k matrix.
+    pub fn quantize(quant: WeightQuant, m: usize, k: usize, weights: &[f32]) -> TractResult<Self> {
+        if quant.group_size == 0 {
+            bail!("Weight quantization group size must be positive")
+        }
+        if weights.len() != m * k {
+            bail!("Expected {}x{} weights, got {}", m, k, weights.len())
+        }
+        let groups = k.divceil(quant.group_size);
+        let mut scales = Vec::with_capacity(m * groups);
+        let mut values = Vec::with_capacity(m * k);
+        for row in weights.chunks(k.max(1)).take(m) {
+            for group in row.chunks(quant.group_size) {
+                let max = group.iter().fold(0f32, |acc, w| acc.max(w.abs()));
+                let scale = if max > 0.0 { max / quant.format.max() } else { 1.0 };
+                scales.push(scale);
+                values.extend(group.iter().map(|w| {
+                    (w / scale).round().clamp(-quant.format.max(), quant.format.max()) as i8
+                }));
+            }
+        }
+        let data = match quant.format {
+            WeightFormat::Int8 => values.iter().map(|v| *v as u8).collect(),
+            WeightFormat::Int4 => values
+                .chunks(2)
+                .map(|pair| {
+                    let lo = (pair[0] + 8) as u8;
+                    let hi = pair.get(1).map(|v| (v + 8) as u8).unwrap_or(8);
+                    lo | (hi << 4)
+                })
+                .collect(),
+        };
+        Ok(QuantizedWeights { quant, m, k, data, scales })
+    }
+
+    /// Rebuild from serialized data and scales, checking sizes.
+    pub fn from_parts(
+        quant: WeightQuant,
+        m: usize,
+        k: usize,
+        data: Vec<u8>,
+        scales: Vec<f32>,
+    ) -> TractResult<Self> {
+        if quant.group_size == 0 {
+            bail!("Weight quantization group size must be positive")
+        }
+        let weights = QuantizedWeights { quant, m, k, data, scales };
+        if weights.data.len() != weights.data_len() {
+            bail!("Expected {} bytes of weights, got {}", weights.data_len(), weights.data.len())
+        }
+        if weights.scales.len() != m * weights.groups_per_row() {
+            bail!("Expected {} scales, got {}", m * weights.groups_per_row(), weights.scales.len())
+        }
+        Ok(weights)
+    }
+
+    fn data_len(&self) -> usize {
+        match self.quant.format {
+            WeightFormat::Int8 => self.m * self.k,
+            WeightFormat::Int4 => (self.m * self.k).divceil(2),
+        }
+    }
+
+    pub fn groups_per_row(&self) -> usize {
+        self.k.divceil(self.quant.group_size)
+    }
+
+    #[inline]
+    fn value(&self, ix: usize) -> i8 {
+        match self.quant.format {
+            WeightFormat::Int8 => self.data[ix] as i8,
+            WeightFormat::Int4 => {
+                let byte = self.data[ix / 2];
+                let nibble = if ix % 2 == 0 { byte & 0x0F } else { byte >> 4 };
+                nibble as i8 - 8
+            }
+        }
+    }
+
+    #[inline]
+    fn weight(&self, row: usize, col: usize) -> f32 {
+        let scale = self.scales[row * self.groups_per_row() + col / self.quant.group_size];
+        self.value(row * self.k + col) as f32 * scale
+    }
+
+    /// Dequantize to a m×k f32 tensor.
+    pub fn dequantize(&self) -> Tensor {
+        let data: Vec<f32> = (0..self.m)
+            .flat_map(|row| (0..self.k).map(move |col| (row, col)))
+            .map(|(r, c)| self.weight(r, c))
+            .collect();
+        tract_ndarray::Array2::from_shape_vec((self.m, self.k), data).unwrap().into_tensor()
+    }
+
+    /// Dequantize the `mn` rows and `k` columns range into a single f32 A panel.
+    ///
+    /// Rows past `m` are padded with zeroes.
+    pub unsafe fn pack_panel(
+        &self,
+        packer: &Packer,
+        pa: *mut f32,
+        k_range: Range<usize>,
+        mn_range: Range<usize>,
+    ) {
+        debug_assert!(mn_range.len() <= packer.r);
+        let r = packer.r;
+        let panel = std::slice::from_raw_parts_mut(pa, k_range.len() * r);
+        for (x, row) in mn_range.enumerate() {
+            if row < self.m {
+                for (kx, col) in k_range.clone().enumerate() {
+                    *panel.get_unchecked_mut(kx * r + x) = self.weight(row, col);
+                }
+            } else {
+                for kx in 0..k_range.len() {
+                    *panel.get_unchecked_mut(kx * r + x) = 0.0;
+                }
+            }
+        }
+    }
+}
+
+/// Virtual A input dequantizing weights panel by panel.
+#[derive(Clone, Debug)]
+pub struct QuantizedWeightsInput(pub Arc<QuantizedWeights>);
+
+impl VirtualInput for QuantizedWeightsInput {
+    fn input(&self, packer: &Packer, packed_output: *mut u8, k: Range<usize>, mn: Range<usize>) {
+        unsafe { self.0.pack_panel(packer, packed_output as *mut f32, k, mn) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::frame::mmm::FusedSpec;
+
+    fn weights(m: usize, k: usize) -> Vec<f32> {
+        (0..m * k).map(|i| ((i * 7 % 13) as f32 - 6.0) / 3.0).collect()
+    }
+
+    fn roundtrip(format: WeightFormat, group_size: usize) {
+        let (m, k) = (5, 11);
+        let w = weights(m, k);
+        let quant = WeightQuant { format, group_size };
+        let q = QuantizedWeights::quantize(quant, m, k, &w).unwrap();
+        let tolerance = w.iter().fold(0f32, |a, w| a.max(w.abs())) / format.max() / 2.0 + 1e-6;
+        let deq = q.dequantize();
+        for (a, b) in w.iter().zip(deq.as_slice::<f32>().unwrap()) {
+            assert!((a - b).abs() <= tolerance, "{} vs {}", a, b);
+        }
+        let q2 =
+            QuantizedWeights::from_parts(quant, m, k, q.data.clone(), q.scales.clone()).unwrap();
+        assert_eq!(q, q2);
+    }
+
+    #[test]
+    fn roundtrip_i8() {
+        roundtrip(WeightFormat::Int8, 4)
+    }
+
+    #[test]
+    fn roundtrip_i4() {
+        roundtrip(WeightFormat::Int4, 3)
+    }
+
+    #[test]
+    fn parse() {
+        let i4: WeightQuant = "i4/g64".parse().unwrap();
+        assert_eq!(i4, WeightQuant { format: WeightFormat::Int4, group_size: 64 });
+        assert_eq!(i4.to_string().parse::<WeightQuant>().unwrap(), i4);
+        assert_eq!("i8".parse::<WeightQuant>().unwrap().group_size, 32);
+        assert!("i2".parse::<WeightQuant>().is_err());
+        assert!("i8/g0".parse::<WeightQuant>().is_err());
+    }
+
+    #[test]
+    fn from_parts_checks_sizes() {
+        let quant = WeightQuant { format: WeightFormat::Int4, group_size: 2 };
+        assert!(QuantizedWeights::from_parts(quant, 2, 3, vec![0; 3], vec![1.0; 4]).is_ok());
+        assert!(QuantizedWeights::from_parts(quant, 2, 3, vec![0; 4], vec![1.0; 4]).is_err());
+        assert!(QuantizedWeights::from_parts(quant, 2, 3, vec![0; 3], vec![1.0; 3]).is_err());
+    }
+
+    fn matmul(format: WeightFormat, m: usize, k: usize, n: usize) {
+        let mmm = crate::ops()
+            .mmm(f32::datum_type(), f32::datum_type(), f32::datum_type(), Some(m), Some(k), Some(n))
+            .unwrap();
+        let quant = WeightQuant { format, group_size: 8 };
+        let q = Arc::new(QuantizedWeights::quantize(quant, m, k, &weights(m, k)).unwrap());
+        let a: tract_ndarray::Array2<f32> =
+            q.dequantize().into_array::<f32>().unwrap().into_dimensionality().unwrap();
+        let b = tract_ndarray::Array2::from_shape_fn((k, n), |(k, n)| (k + 2 * n) as f32 / 10.0);
+        let expected = a.dot(&b);
+        let b = b.into_tensor();
+        let mut packed_b =
+            Tensor::zero_aligned::<f32>(&[mmm.b_pack().len(k, n)], mmm.b_pack().alignment())
+                .unwrap();
+        let mut c = Tensor::zero::<f32>(&[m, n]).unwrap();
+        unsafe {
+            mmm.b_pack().pack(packed_b.view_mut(), b.view(), 0, 1);
+            mmm.run(
+                m,
+                n,
+                &[
+                    FusedSpec::AddMatMul {
+                        k,
+                        a: mmm.a_quantized_weights(q),
+                        b: mmm.b_packed(4, k).wrap(&packed_b.view()).unwrap(),
+                    },
+                    FusedSpec::Store(mmm.c_view(0, 1).wrap(&c.view_mut())),
+                ],
+            )
+            .unwrap();
+        }
+        c.close_enough(&expected.into_tensor(), true).unwrap();
+    }
+
+    #[test]
+    fn matmul_i8() {
+        matmul(WeightFormat::Int8, 13, 21, 9)
+    }
+
+    #[test]
+    fn matmul_i4() {
+        matmul(WeightFormat::Int4, 7, 16, 5)
+    }
+}
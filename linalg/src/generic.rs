@@ -1,9 +1,17 @@
+pub mod erf;
+pub mod exp;
+pub mod gelu;
+pub mod log;
 pub mod lut;
 pub mod mmm;
 pub mod rounding;
 pub mod sigmoid;
 pub mod tanh;
 
+pub use self::erf::{HErf8, SErf4};
+pub use self::exp::{HExp8, SExp4};
+pub use self::gelu::{HGelu8, SGelu4};
+pub use self::log::{HLn8, SLn4};
 pub use self::lut::GenericLut8;
 pub use self::mmm::GenericMmm4x1;
 pub use self::mmm::GenericMmm4x4;
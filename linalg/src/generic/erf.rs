@@ -0,0 +1,96 @@
+#![allow(clippy::excessive_precision)]
+use crate::frame::element_wise::ElementWiseKer;
+use tract_data::internal::*;
+
+/// Abramowitz and Stegun 7.1.28 approximation, maximum error around 3e-7.
+#[allow(non_upper_case_globals)]
+pub fn serf(x: f32) -> f32 {
+    const a1: f32 = 0.0705230784;
+    const a2: f32 = 0.0422820123;
+    const a3: f32 = 0.0092705272;
+    const a4: f32 = 0.0001520143;
+    const a5: f32 = 0.0002765672;
+    const a6: f32 = 0.0000430638;
+
+    let signum = x.signum();
+    let x = x.abs();
+    let y = a6 * x;
+    let y = (a5 + y) * x;
+    let y = (a4 + y) * x;
+    let y = (a3 + y) * x;
+    let y = (a2 + y) * x;
+    let y = (a1 + y) * x;
+    let y = 1.0 - (y + 1.0).powi(16).recip();
+
+    y.copysign(signum)
+}
+
+pub fn herf(x: f16) -> f16 {
+    f16::from_f32(serf(x.to_f32()))
+}
+
+#[derive(Clone, Debug)]
+pub struct SErf4;
+
+impl ElementWiseKer<f32> for SErf4 {
+    fn name() -> &'static str {
+        "generic"
+    }
+
+    fn alignment_bytes() -> usize {
+        16
+    }
+
+    fn alignment_items() -> usize {
+        4
+    }
+
+    fn nr() -> usize {
+        4
+    }
+
+    fn run(x: &mut [f32]) {
+        debug_assert!(x.len() % Self::nr() == 0);
+        debug_assert!(x.as_ptr() as usize % Self::alignment_bytes() == 0);
+        x.iter_mut().for_each(|px| *px = serf(*px))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct HErf8;
+
+impl ElementWiseKer<f16> for HErf8 {
+    fn name() -> &'static str {
+        "generic"
+    }
+
+    fn alignment_bytes() -> usize {
+        16
+    }
+
+    fn alignment_items() -> usize {
+        4
+    }
+
+    fn nr() -> usize {
+        8
+    }
+
+    fn run(x: &mut [f16]) {
+        debug_assert!(x.len() % Self::nr() == 0);
+        debug_assert!(x.as_ptr() as usize % Self::alignment_bytes() == 0);
+        x.iter_mut().for_each(|px| *px = herf(*px))
+    }
+}
+
+#[cfg(test)]
+#[macro_use]
+pub mod s {
+    erf_frame_tests!(true, f32, crate::generic::erf::SErf4);
+}
+
+#[cfg(test)]
+#[macro_use]
+pub mod h {
+    erf_frame_tests!(true, tract_data::internal::f16, crate::generic::erf::HErf8);
+}
@@ -0,0 +1,113 @@
+#![allow(clippy::excessive_precision)]
+use crate::frame::element_wise::ElementWiseKer;
+use tract_data::internal::*;
+
+/// Cephes-style exponential: range reduction on ln(2), degree 5 polynomial on
+/// the remainder, then scaling by 2^n through the exponent bits.
+pub fn sexp(x: f32) -> f32 {
+    const OVERFLOW: f32 = 88.72283;
+    const LOW: f32 = -88.0;
+    const HIGH: f32 = 88.37;
+
+    const LOG2_E: f32 = std::f32::consts::LOG2_E;
+    const LN2_HI: f32 = 0.693359375;
+    const LN2_LO: f32 = -2.12194440e-4;
+
+    const P5: f32 = 1.9875691500e-4;
+    const P4: f32 = 1.3981999507e-3;
+    const P3: f32 = 8.3334519073e-3;
+    const P2: f32 = 4.1665795894e-2;
+    const P1: f32 = 1.6666665459e-1;
+    const P0: f32 = 5.0000001201e-1;
+
+    if x.is_nan() {
+        return x;
+    } else if x > OVERFLOW {
+        return f32::INFINITY;
+    }
+    let x = x.max(LOW).min(HIGH);
+
+    let n = (x * LOG2_E).round();
+    let r = x - n * LN2_HI - n * LN2_LO;
+    let r2 = r * r;
+
+    let p = P5;
+    let p = r * p + P4;
+    let p = r * p + P3;
+    let p = r * p + P2;
+    let p = r * p + P1;
+    let p = r * p + P0;
+    let p = p * r2 + r + 1.0;
+
+    p * f32::from_bits(((n as i32 + 127) as u32) << 23)
+}
+
+pub fn hexp(x: f16) -> f16 {
+    f16::from_f32(sexp(x.to_f32()))
+}
+
+#[derive(Clone, Debug)]
+pub struct SExp4;
+
+impl ElementWiseKer<f32> for SExp4 {
+    fn name() -> &'static str {
+        "generic"
+    }
+
+    fn alignment_bytes() -> usize {
+        16
+    }
+
+    fn alignment_items() -> usize {
+        4
+    }
+
+    fn nr() -> usize {
+        4
+    }
+
+    fn run(x: &mut [f32]) {
+        debug_assert!(x.len() % Self::nr() == 0);
+        debug_assert!(x.as_ptr() as usize % Self::alignment_bytes() == 0);
+        x.iter_mut().for_each(|px| *px = sexp(*px))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct HExp8;
+
+impl ElementWiseKer<f16> for HExp8 {
+    fn name() -> &'static str {
+        "generic"
+    }
+
+    fn alignment_bytes() -> usize {
+        16
+    }
+
+    fn alignment_items() -> usize {
+        4
+    }
+
+    fn nr() -> usize {
+        8
+    }
+
+    fn run(x: &mut [f16]) {
+        debug_assert!(x.len() % Self::nr() == 0);
+        debug_assert!(x.as_ptr() as usize % Self::alignment_bytes() == 0);
+        x.iter_mut().for_each(|px| *px = hexp(*px))
+    }
+}
+
+#[cfg(test)]
+#[macro_use]
+pub mod s {
+    exp_frame_tests!(true, f32, crate::generic::exp::SExp4);
+}
+
+#[cfg(test)]
+#[macro_use]
+pub mod h {
+    exp_frame_tests!(true, tract_data::internal::f16, crate::generic::exp::HExp8);
+}
@@ -0,0 +1,77 @@
+use crate::frame::element_wise::ElementWiseKer;
+use tract_data::internal::*;
+
+/// Exact (erf based) GELU: 0.5 * x * (1 + erf(x / sqrt(2))).
+pub fn sgelu(x: f32) -> f32 {
+    0.5 * x * (1.0 + super::erf::serf(x * std::f32::consts::FRAC_1_SQRT_2))
+}
+
+pub fn hgelu(x: f16) -> f16 {
+    f16::from_f32(sgelu(x.to_f32()))
+}
+
+#[derive(Clone, Debug)]
+pub struct SGelu4;
+
+impl ElementWiseKer<f32> for SGelu4 {
+    fn name() -> &'static str {
+        "generic"
+    }
+
+    fn alignment_bytes() -> usize {
+        16
+    }
+
+    fn alignment_items() -> usize {
+        4
+    }
+
+    fn nr() -> usize {
+        4
+    }
+
+    fn run(x: &mut [f32]) {
+        debug_assert!(x.len() % Self::nr() == 0);
+        debug_assert!(x.as_ptr() as usize % Self::alignment_bytes() == 0);
+        x.iter_mut().for_each(|px| *px = sgelu(*px))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct HGelu8;
+
+impl ElementWiseKer<f16> for HGelu8 {
+    fn name() -> &'static str {
+        "generic"
+    }
+
+    fn alignment_bytes() -> usize {
+        16
+    }
+
+    fn alignment_items() -> usize {
+        4
+    }
+
+    fn nr() -> usize {
+        8
+    }
+
+    fn run(x: &mut [f16]) {
+        debug_assert!(x.len() % Self::nr() == 0);
+        debug_assert!(x.as_ptr() as usize % Self::alignment_bytes() == 0);
+        x.iter_mut().for_each(|px| *px = hgelu(*px))
+    }
+}
+
+#[cfg(test)]
+#[macro_use]
+pub mod s {
+    gelu_frame_tests!(true, f32, crate::generic::gelu::SGelu4);
+}
+
+#[cfg(test)]
+#[macro_use]
+pub mod h {
+    gelu_frame_tests!(true, tract_data::internal::f16, crate::generic::gelu::HGelu8);
+}
@@ -0,0 +1,128 @@
+#![allow(clippy::excessive_precision)]
+use crate::frame::element_wise::ElementWiseKer;
+use tract_data::internal::*;
+
+/// Cephes-style natural logarithm: the exponent is extracted from the bits,
+/// the mantissa is brought to [sqrt(1/2), sqrt(2)) and fed to a degree 8
+/// polynomial.
+pub fn sln(x: f32) -> f32 {
+    const SQRT_HALF: f32 = std::f32::consts::FRAC_1_SQRT_2;
+    const LN2_HI: f32 = 0.693359375;
+    const LN2_LO: f32 = -2.12194440e-4;
+
+    const P8: f32 = 7.0376836292e-2;
+    const P7: f32 = -1.1514610310e-1;
+    const P6: f32 = 1.1676998740e-1;
+    const P5: f32 = -1.2420140846e-1;
+    const P4: f32 = 1.4249322787e-1;
+    const P3: f32 = -1.6668057665e-1;
+    const P2: f32 = 2.0000714765e-1;
+    const P1: f32 = -2.4999993993e-1;
+    const P0: f32 = 3.3333331174e-1;
+
+    #[allow(clippy::neg_cmp_op_on_partial_ord)]
+    if !(x >= 0.0) {
+        return f32::NAN;
+    } else if x == f32::INFINITY {
+        return f32::INFINITY;
+    } else if x == 0.0 {
+        return f32::NEG_INFINITY;
+    }
+
+    // subnormals are brought back to normals, the scaling is removed from the exponent
+    let (x, e_offset) = if x < f32::MIN_POSITIVE { (x * 8388608.0, 23.0) } else { (x, 0.0) };
+    let bits = x.to_bits();
+    let mut e = ((bits >> 23) as i32 - 126) as f32 - e_offset;
+    let mut m = f32::from_bits((bits & 0x807FFFFF) | 0x3F000000);
+    if m < SQRT_HALF {
+        e -= 1.0;
+        m = m + m - 1.0;
+    } else {
+        m -= 1.0;
+    }
+    let z = m * m;
+
+    let p = P8;
+    let p = m * p + P7;
+    let p = m * p + P6;
+    let p = m * p + P5;
+    let p = m * p + P4;
+    let p = m * p + P3;
+    let p = m * p + P2;
+    let p = m * p + P1;
+    let p = m * p + P0;
+    let p = p * m * z;
+
+    let y = p + e * LN2_LO - 0.5 * z;
+    m + y + e * LN2_HI
+}
+
+pub fn hln(x: f16) -> f16 {
+    f16::from_f32(sln(x.to_f32()))
+}
+
+#[derive(Clone, Debug)]
+pub struct SLn4;
+
+impl ElementWiseKer<f32> for SLn4 {
+    fn name() -> &'static str {
+        "generic"
+    }
+
+    fn alignment_bytes() -> usize {
+        16
+    }
+
+    fn alignment_items() -> usize {
+        4
+    }
+
+    fn nr() -> usize {
+        4
+    }
+
+    fn run(x: &mut [f32]) {
+        debug_assert!(x.len() % Self::nr() == 0);
+        debug_assert!(x.as_ptr() as usize % Self::alignment_bytes() == 0);
+        x.iter_mut().for_each(|px| *px = sln(*px))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct HLn8;
+
+impl ElementWiseKer<f16> for HLn8 {
+    fn name() -> &'static str {
+        "generic"
+    }
+
+    fn alignment_bytes() -> usize {
+        16
+    }
+
+    fn alignment_items() -> usize {
+        4
+    }
+
+    fn nr() -> usize {
+        8
+    }
+
+    fn run(x: &mut [f16]) {
+        debug_assert!(x.len() % Self::nr() == 0);
+        debug_assert!(x.as_ptr() as usize % Self::alignment_bytes() == 0);
+        x.iter_mut().for_each(|px| *px = hln(*px))
+    }
+}
+
+#[cfg(test)]
+#[macro_use]
+pub mod s {
+    ln_frame_tests!(true, f32, crate::generic::log::SLn4);
+}
+
+#[cfg(test)]
+#[macro_use]
+pub mod h {
+    ln_frame_tests!(true, tract_data::internal::f16, crate::generic::log::HLn8);
+}
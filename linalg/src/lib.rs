@@ -54,6 +54,14 @@ pub struct Ops {
     pub sigmoid_f32: Box<dyn Fn() -> Box<dyn element_wise::ElementWise<f32>> + Send + Sync>,
     pub tanh_f16: Box<dyn Fn() -> Box<dyn element_wise::ElementWise<f16>> + Send + Sync>,
     pub tanh_f32: Box<dyn Fn() -> Box<dyn element_wise::ElementWise<f32>> + Send + Sync>,
+    pub exp_f16: Box<dyn Fn() -> Box<dyn element_wise::ElementWise<f16>> + Send + Sync>,
+    pub exp_f32: Box<dyn Fn() -> Box<dyn element_wise::ElementWise<f32>> + Send + Sync>,
+    pub ln_f16: Box<dyn Fn() -> Box<dyn element_wise::ElementWise<f16>> + Send + Sync>,
+    pub ln_f32: Box<dyn Fn() -> Box<dyn element_wise::ElementWise<f32>> + Send + Sync>,
+    pub erf_f16: Box<dyn Fn() -> Box<dyn element_wise::ElementWise<f16>> + Send + Sync>,
+    pub erf_f32: Box<dyn Fn() -> Box<dyn element_wise::ElementWise<f32>> + Send + Sync>,
+    pub gelu_f16: Box<dyn Fn() -> Box<dyn element_wise::ElementWise<f16>> + Send + Sync>,
+    pub gelu_f32: Box<dyn Fn() -> Box<dyn element_wise::ElementWise<f32>> + Send + Sync>,
     pub lut_u8: Box<dyn Fn(&[u8]) -> Box<dyn lut::Lut> + Send + Sync>,
 }
 
@@ -103,6 +111,14 @@ pub fn generic() -> Ops {
         sigmoid_f32: Box::new(|| generic::SSigmoid4::ew()),
         tanh_f16: Box::new(|| generic::HTanh8::ew()),
         tanh_f32: Box::new(|| generic::STanh4::ew()),
+        exp_f16: Box::new(|| generic::HExp8::ew()),
+        exp_f32: Box::new(|| generic::SExp4::ew()),
+        ln_f16: Box::new(|| generic::HLn8::ew()),
+        ln_f32: Box::new(|| generic::SLn4::ew()),
+        erf_f16: Box::new(|| generic::HErf8::ew()),
+        erf_f32: Box::new(|| generic::SErf4::ew()),
+        gelu_f16: Box::new(|| generic::HGelu8::ew()),
+        gelu_f32: Box::new(|| generic::SGelu4::ew()),
         lut_u8: Box::new(|table: &[u8]| Box::new(lut::LutImpl::<generic::GenericLut8>::new(table))),
     }
 }
@@ -8,6 +8,15 @@ mod intel;
 
 tanh_impl!(f32, fma_tanh_f32, 8, 8, is_x86_feature_detected!("fma"));
 sigmoid_impl!(f32, fma_sigmoid_f32, 8, 8, is_x86_feature_detected!("fma"));
+exp_impl!(f32, fma_exp_f32, 8, 8, fma_and_avx2());
+ln_impl!(f32, fma_ln_f32, 8, 8, fma_and_avx2());
+erf_impl!(f32, fma_erf_f32, 8, 8, fma_and_avx2());
+gelu_impl!(f32, fma_gelu_f32, 8, 8, fma_and_avx2());
+
+// exp, ln, erf and gelu kernels use avx2 integer ops on ymm registers
+fn fma_and_avx2() -> bool {
+    is_x86_feature_detected!("fma") && is_x86_feature_detected!("avx2")
+}
 
 pub fn plug(ops: &mut Ops) {
     if is_x86_feature_detected!("fma") {
@@ -92,4 +101,11 @@ pub fn plug(ops: &mut Ops) {
         ops.qmmm_i32 = Box::new(|_, _, _| mmm::avx2_mmm_i32_8x8::mmm());
         log::info!("mmm_i8_i8 and mmm_i8_i32: x86_64/avx2 activated");
     }
+    if fma_and_avx2() {
+        ops.exp_f32 = Box::new(|| fma_exp_f32::ew());
+        ops.ln_f32 = Box::new(|| fma_ln_f32::ew());
+        ops.erf_f32 = Box::new(|| fma_erf_f32::ew());
+        ops.gelu_f32 = Box::new(|| fma_gelu_f32::ew());
+        log::info!("exp_f32, ln_f32, erf_f32, gelu_f32: x86_64/fma activated");
+    }
 }
@@ -18,11 +18,50 @@ impl ProtoModel {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug)]
 pub enum QuantFormat {
-    Linear { params: QParams, bits: i8, signed: bool },
+    Linear {
+        params: QParams,
+        bits: i8,
+        signed: bool,
+    },
+    /// One zero point and scale per slice of the tensor along `axis`.
+    LinearPerAxis {
+        axis: usize,
+        zero_points: Vec<i32>,
+        scales: Vec<f32>,
+        bits: i8,
+        signed: bool,
+    },
+}
+
+// scales are compared bitwise so that equality stays reflexive (and Eq sound) with NaNs
+impl PartialEq for QuantFormat {
+    fn eq(&self, other: &Self) -> bool {
+        use QuantFormat::*;
+        match (self, other) {
+            (
+                Linear { params: p1, bits: b1, signed: s1 },
+                Linear { params: p2, bits: b2, signed: s2 },
+            ) => p1 == p2 && b1 == b2 && s1 == s2,
+            (
+                LinearPerAxis { axis: a1, zero_points: z1, scales: sc1, bits: b1, signed: s1 },
+                LinearPerAxis { axis: a2, zero_points: z2, scales: sc2, bits: b2, signed: s2 },
+            ) => {
+                a1 == a2
+                    && z1 == z2
+                    && b1 == b2
+                    && s1 == s2
+                    && sc1.len() == sc2.len()
+                    && sc1.iter().zip(sc2.iter()).all(|(x, y)| x.to_bits() == y.to_bits())
+            }
+            _ => false,
+        }
+    }
 }
 
+impl Eq for QuantFormat {}
+
 impl QuantFormat {
     pub fn from_dt(datum_type: DatumType) -> Option<QuantFormat> {
         if let Some(params) = datum_type.qparams() {
@@ -37,14 +76,23 @@ impl QuantFormat {
         }
     }
 
-    pub fn datum_type(&self) -> DatumType {
+    pub fn datum_type(&self) -> TractResult<DatumType> {
         match self {
             QuantFormat::Linear { params, bits, signed } => match (bits, signed) {
-                (8, true) => DatumType::QI8(*params),
-                (8, false) => DatumType::QU8(*params),
-                (32, true) => DatumType::I32,
-                (32, false) => DatumType::U32,
-                _ => todo!(),
+                (8, true) => Ok(DatumType::QI8(*params)),
+                (8, false) => Ok(DatumType::QU8(*params)),
+                (32, true) => Ok(DatumType::I32),
+                (32, false) => Ok(DatumType::U32),
+                _ => bail!("Unsupported quantization: {} bits", bits),
+            },
+            // per-axis parameters do not fit in the type, the tensor is stored
+            // raw and dequantized after loading
+            QuantFormat::LinearPerAxis { bits, signed, .. } => match (bits, signed) {
+                (8, true) => Ok(DatumType::I8),
+                (8, false) => Ok(DatumType::U8),
+                (32, true) => Ok(DatumType::I32),
+                (32, false) => Ok(DatumType::U32),
+                _ => bail!("Unsupported quantization: {} bits", bits),
             },
         }
     }
@@ -412,7 +412,7 @@ fn space_and_comments(i: &str) -> IResult<&str, ()> {
     )(i)
 }
 
-fn spaced<'s, O, F>(it: F) -> impl FnMut(&'s str) -> IResult<&'s str, O>
+pub(super) fn spaced<'s, O, F>(it: F) -> impl FnMut(&'s str) -> IResult<&'s str, O>
 where
     F: FnMut(&'s str) -> IResult<&'s str, O>,
 {
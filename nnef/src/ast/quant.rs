@@ -2,16 +2,19 @@ use std::str::FromStr;
 
 use nom::branch::permutation;
 use nom::character::complete::digit1;
-use nom::combinator::map_res;
+use nom::combinator::{map, map_res, recognize};
+use nom::sequence::{delimited, pair};
 use tract_core::internal::*;
+use tract_itertools::Itertools;
 
+use nom::error::ErrorKind;
 use nom::{bytes::complete::*, multi::*};
 use nom::{combinator::all_consuming, IResult};
 use nom::{combinator::opt, number::complete::float};
 
 use crate::ast::*;
 
-use super::parse::{identifier, logical_literal, stag, translate_error};
+use super::parse::{identifier, logical_literal, spaced, stag, translate_error};
 
 #[inline(never)]
 pub fn parse_quantization(doc: &str) -> TractResult<Vec<(String, QuantFormat)>> {
@@ -39,31 +42,98 @@ fn qparam(i: &str) -> IResult<&str, QuantFormat> {
     let (i, id) =
         nom::branch::alt((stag("linear_quantize"), stag("zero_point_linear_quantize")))(i)?;
     let (i, _) = stag("(")(i)?;
-    let (i, params, bits, signed) = match id {
+    let (i, format) = match id {
         "linear_quantize" => {
             let (i, (bits, max, min)) =
                 permutation((arg("bits", integer_numeric), arg("max", float), arg("min", float)))(
                     i,
                 )?;
 
-            (i, QParams::MinMax { min, max }, bits, true)
+            (i, QuantFormat::Linear { params: QParams::MinMax { min, max }, bits, signed: true })
         }
         "zero_point_linear_quantize" => {
-            let (i, (zero_point, scale, bits, signed, _)) = permutation((
-                arg("zero_point", integer_numeric),
-                arg("scale", float),
-                arg("bits", integer_numeric),
-                arg("signed", logical_literal),
-                opt(arg("symmetric", logical_literal)),
-            ))(i)?;
-            (i, QParams::ZpScale { zero_point, scale }, bits, signed)
+            let (i, args) = zero_point_args(i)?;
+            let missing = |_| nom::Err::Failure(nom::error::Error::new(i, ErrorKind::Verify));
+            let zero_points = args.zero_points.ok_or(()).map_err(missing)?;
+            let scales = args.scales.ok_or(()).map_err(missing)?;
+            let bits = args.bits.ok_or(()).map_err(missing)?;
+            let signed = args.signed.ok_or(()).map_err(missing)?;
+            if let Some(axis) = args.axis {
+                let zero_points = if zero_points.len() == 1 {
+                    vec![zero_points[0]; scales.len()]
+                } else {
+                    zero_points
+                };
+                (i, QuantFormat::LinearPerAxis { axis, zero_points, scales, bits, signed })
+            } else if zero_points.len() == 1 && scales.len() == 1 {
+                let params = QParams::ZpScale { zero_point: zero_points[0], scale: scales[0] };
+                (i, QuantFormat::Linear { params, bits, signed })
+            } else {
+                // per-axis parameters require an axis
+                return Err(missing(()));
+            }
         }
         _ => unreachable!(),
     };
 
     let (i, _) = stag(")")(i)?;
-    Ok((i, QuantFormat::Linear { params, bits, signed }))
+    Ok((i, format))
+}
+
+#[derive(Default)]
+struct ZeroPointArgs {
+    zero_points: Option<Vec<i32>>,
+    scales: Option<Vec<f32>>,
+    bits: Option<i8>,
+    signed: Option<bool>,
+    axis: Option<usize>,
+}
+
+// zero_point_linear_quantize arguments, in any order. symmetric and axis are
+// optional.
+fn zero_point_args(mut i: &str) -> IResult<&str, ZeroPointArgs> {
+    let mut args = ZeroPointArgs::default();
+    loop {
+        if let Ok((rest, zp)) = arg("zero_point", scalar_or_array(signed_integer_numeric))(i) {
+            args.zero_points = Some(zp);
+            i = rest;
+        } else if let Ok((rest, scale)) = arg("scale", scalar_or_array(float))(i) {
+            args.scales = Some(scale);
+            i = rest;
+        } else if let Ok((rest, bits)) = arg("bits", integer_numeric)(i) {
+            args.bits = Some(bits);
+            i = rest;
+        } else if let Ok((rest, signed)) = arg("signed", logical_literal)(i) {
+            args.signed = Some(signed);
+            i = rest;
+        } else if let Ok((rest, _)) = arg("symmetric", logical_literal)(i) {
+            i = rest;
+        } else if let Ok((rest, axis)) = arg("axis", integer_numeric)(i) {
+            args.axis = Some(axis);
+            i = rest;
+        } else {
+            return Ok((i, args));
+        }
+    }
+}
+
+fn signed_integer_numeric(i: &str) -> IResult<&str, i32> {
+    map_res(recognize(pair(opt(tag("-")), digit1)), |s: &str| s.parse::<i32>())(i)
+}
+
+// <scalar_or_array>(<f>) ::= <f> | "[" <f> ("," <f>)* "]"
+fn scalar_or_array<'s, T, F>(f: F) -> impl Fn(&'s str) -> IResult<&'s str, Vec<T>>
+where
+    F: Fn(&'s str) -> IResult<&'s str, T> + Copy,
+{
+    move |i: &str| {
+        nom::branch::alt((
+            delimited(stag("["), separated_list1(stag(","), spaced(f)), stag("]")),
+            map(f, |x| vec![x]),
+        ))(i)
+    }
 }
+
 // <arg>(<id>, <f>) ::= <id> "=" <f> ","
 fn arg<'s, T, F>(name: &'static str, f: F) -> impl Fn(&'s str) -> IResult<&'s str, T>
 where
@@ -90,6 +160,11 @@ pub(crate) fn write_quant_format(
         QuantFormat::Linear {
             params: QParams::MinMax {min, max}, bits, signed: _
         } => writeln!(w, "\"{}\": linear_quantize(max = {:.9}, min = {:.9}, bits = {});", name, max, min, bits)?,
+        QuantFormat::LinearPerAxis { axis, zero_points, scales, bits, signed } => {
+            let zero_points = zero_points.iter().map(|z| z.to_string()).join(", ");
+            let scales = scales.iter().map(|s| format!("{:.9}", s)).join(", ");
+            writeln!(w, "\"{}\": zero_point_linear_quantize(zero_point = [{}], scale = [{}], axis = {}, bits = {}, signed = {});", name, zero_points, scales, axis, bits, signed)?
+        }
     }
     Ok(())
 }
@@ -128,6 +203,75 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_qparam_per_axis() {
+        assert_eq!(
+            p(
+                qparam,
+                "zero_point_linear_quantize(zero_point = [0, -3], scale = [0.5, 0.25], axis = 0, bits = 8, signed = true)"
+            ),
+            QuantFormat::LinearPerAxis {
+                axis: 0,
+                zero_points: vec![0, -3],
+                scales: vec![0.5, 0.25],
+                bits: 8,
+                signed: true
+            }
+        );
+        assert_eq!(
+            p(
+                qparam,
+                "zero_point_linear_quantize(zero_point = 0, scale = [0.5, 0.25, 1.0], axis = 1, bits = 8, signed = true, symmetric = true)"
+            ),
+            QuantFormat::LinearPerAxis {
+                axis: 1,
+                zero_points: vec![0, 0, 0],
+                scales: vec![0.5, 0.25, 1.0],
+                bits: 8,
+                signed: true
+            }
+        );
+        assert!(all_consuming(qparam)(
+            "zero_point_linear_quantize(zero_point = 0, scale = [0.5, 0.25], bits = 8, signed = true)"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_per_axis_eq_is_reflexive() {
+        let format = QuantFormat::LinearPerAxis {
+            axis: 0,
+            zero_points: vec![0, 0],
+            scales: vec![f32::NAN, 0.25],
+            bits: 8,
+            signed: true,
+        };
+        assert_eq!(format, format.clone());
+        let other = QuantFormat::LinearPerAxis {
+            axis: 0,
+            zero_points: vec![0, 0],
+            scales: vec![0.5, 0.25],
+            bits: 8,
+            signed: true,
+        };
+        assert_ne!(format, other);
+    }
+
+    #[test]
+    fn test_write_per_axis() {
+        let format = QuantFormat::LinearPerAxis {
+            axis: 2,
+            zero_points: vec![1, -2],
+            scales: vec![0.125, 3.5],
+            bits: 8,
+            signed: false,
+        };
+        let mut buffer = vec![];
+        write_quant_format(&mut buffer, "w".to_string(), format.clone()).unwrap();
+        let parsed = parse_quantization(std::str::from_utf8(&buffer).unwrap()).unwrap();
+        assert_eq!(parsed, vec![("w".to_string(), format)]);
+    }
+
     #[test]
     fn test_quantization() {
         assert_eq!(
@@ -3,6 +3,9 @@ use std::ops::ControlFlow;
 use crate::ast::*;
 use crate::internal::*;
 
+/// Bound on nested fragment invocations, to reject recursive fragments.
+const MAX_FRAGMENT_DEPTH: usize = 128;
+
 pub struct ModelBuilder<'a> {
     pub framework: &'a Nnef,
     pub registries: Vec<String>,
@@ -30,6 +33,9 @@ impl<'mb> ModelBuilder<'mb> {
         'ext: for ext in &self.proto_model.doc.extension {
             match &*ext[0] {
                 "tract_registry" => {
+                    if ext.len() != 2 {
+                        bail!("tract_registry expects a registry name: example: \"extension tract_registry tract_core;\"")
+                    }
                     if self.framework.registries.iter().any(|reg| reg.id == ext[1]) {
                         self.registries.push(ext[1].to_string())
                     } else if let Some(reg) =
@@ -123,15 +129,18 @@ impl<'mb> ModelBuilder<'mb> {
         // todo: can i relax the outlet id constraint ?
         for assignment in body {
             let identifiers = assignment.left.to_identifiers()?;
+            ensure!(!identifiers.is_empty(), "Assignment with no identifier");
             let datum_types = identifiers
                 .iter()
                 .map(|s| {
                     self.proto_model
                         .quantization
                         .as_ref()
-                        .and_then(|qm| qm.get(*s).map(|q| q.datum_type()))
+                        .and_then(|qm| qm.get(*s))
+                        .map(|q| q.datum_type())
+                        .transpose()
                 })
-                .collect::<Vec<_>>();
+                .collect::<TractResult<Vec<_>>>()?;
             self.naming_scopes.push(identifiers[0].to_string());
             let mut values = if identifiers.len() == 1 {
                 let value: OutletId = assignment
@@ -172,6 +181,14 @@ impl<'mb> ModelBuilder<'mb> {
                     }
                 }
             }
+            let proto_model = self.proto_model;
+            for (id, value) in identifiers.iter().zip(values.iter_mut()) {
+                if let Some(QuantFormat::LinearPerAxis { axis, zero_points, scales, .. }) =
+                    proto_model.quantization.as_ref().and_then(|qm| qm.get(*id))
+                {
+                    *value = self.wire_per_axis_dequantize(*value, *axis, zero_points, scales)?;
+                }
+            }
             self.model.node_mut(values[0].node).name = self.naming_scopes.join(".").to_string();
             for (id, outlet) in identifiers.iter().zip(values.iter()) {
                 self.scopes.last_mut().unwrap().insert(id.to_string(), Value::Wire(*outlet));
@@ -181,6 +198,23 @@ impl<'mb> ModelBuilder<'mb> {
         Ok(())
     }
 
+    fn wire_per_axis_dequantize(
+        &mut self,
+        value: OutletId,
+        axis: usize,
+        zero_points: &[i32],
+        scales: &[f32],
+    ) -> TractResult<OutletId> {
+        let scope = self.naming_scopes.join(".");
+        self.model.node_mut(value.node).name = format!("{}.raw", scope);
+        let op = tract_core::ops::quant::DequantizeLinearPerAxis::new(
+            axis,
+            rctensor1(zero_points),
+            rctensor1(scales),
+        );
+        Ok(self.model.wire_node(scope, op, &[value])?[0])
+    }
+
     pub fn wire_invocation(
         &mut self,
         invocation: &Invocation,
@@ -219,6 +253,11 @@ impl<'mb> ModelBuilder<'mb> {
         decl: &FragmentDecl,
         body: &[Assignment],
     ) -> TractResult<Value> {
+        ensure!(
+            self.naming_scopes.len() < MAX_FRAGMENT_DEPTH,
+            "Fragment invocations nested too deep (recursive fragment {} ?)",
+            decl.id
+        );
         let mut inner_scope = HashMap::new();
         for par in invocation.default_params.iter() {
             inner_scope
@@ -230,7 +269,14 @@ impl<'mb> ModelBuilder<'mb> {
         self.naming_scopes.pop();
         let inner_scope = self.scopes.pop().unwrap();
         Ok(Value::Tuple(
-            decl.results.iter().map(|res| inner_scope.get(&res.id).unwrap()).cloned().collect(),
+            decl.results
+                .iter()
+                .map(|res| {
+                    inner_scope.get(&res.id).cloned().with_context(|| {
+                        format!("Fragment {} does not assign its result {}", decl.id, res.id)
+                    })
+                })
+                .collect::<TractResult<_>>()?,
         ))
     }
 
@@ -442,7 +488,7 @@ impl RValue {
                     .map(|(i, dt)| RValue::Literal(i.clone()).resolve(builder, &[*dt]))
                     .collect::<TractResult<_>>()?,
             )),
-            _ => panic!("{:?}", self),
+            _ => bail!("Can not resolve {:?}", self),
         }
     }
 }
@@ -1,10 +1,13 @@
 use crate::internal::*;
 use tract_core::ops;
 
+mod attention;
 mod broadcast;
 mod cast;
 mod downsample;
+mod fft;
 mod gather;
+mod layer_norm;
 mod one_hot;
 mod qconv;
 mod qmatmul;
@@ -14,9 +17,13 @@ mod scan;
 mod scatter;
 mod shape_of;
 mod source;
+mod weight_quant;
 
 pub fn register(registry: &mut Registry) {
     registry.register_unit_element_wise("tract_core_round_even", &ops::math::RoundHalfToEven {});
+    registry.register_unit_element_wise("tract_core_gelu", &ops::nn::Gelu {});
+    registry
+        .register_unit_element_wise("tract_core_gelu_approximate", &ops::nn::GeluApproximate {});
 
     registry.register_binary("tract_core_xor", &ops::logic::Xor {});
 
@@ -30,10 +37,13 @@ pub fn register(registry: &mut Registry) {
         &ops::math::ShiftRight,
         &ops::math::FlippedShiftRight,
     );
+    attention::register(registry);
     broadcast::register(registry);
     cast::register(registry);
     downsample::register(registry);
+    fft::register(registry);
     gather::register(registry);
+    layer_norm::register(registry);
     one_hot::register(registry);
     qconv::register(registry);
     qmatmul::register(registry);
@@ -43,4 +53,5 @@ pub fn register(registry: &mut Registry) {
     shape_of::register(registry);
     source::register(registry);
     range::register(registry);
+    weight_quant::register(registry);
 }
@@ -0,0 +1,47 @@
+use crate::internal::*;
+use crate::ser::*;
+use tract_core::ops::nn::BandedAttention;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<BandedAttention>(), ser_banded_attention);
+    registry.register_primitive(
+        "tract_core_banded_attention",
+        &[
+            TypeName::Scalar.tensor().named("query"),
+            TypeName::Scalar.tensor().named("key"),
+            TypeName::Scalar.tensor().named("value"),
+            TypeName::Integer.named("left_context"),
+            TypeName::Integer.named("right_context").default(0),
+            TypeName::Scalar.named("scale"),
+        ],
+        de_banded_attention,
+    );
+}
+
+fn ser_banded_attention(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op().downcast_ref::<BandedAttention>().unwrap();
+    let inputs: TVec<Arc<RValue>> =
+        node.inputs.iter().map(|i| ast.mapping[i].clone()).collect();
+    Ok(Some(invocation(
+        "tract_core_banded_attention",
+        &inputs,
+        &[
+            ("left_context", numeric(op.left_context)),
+            ("right_context", numeric(op.right_context)),
+            ("scale", numeric(op.scale)),
+        ],
+    )))
+}
+
+fn de_banded_attention(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let query = invocation.named_arg_as(builder, "query")?;
+    let key = invocation.named_arg_as(builder, "key")?;
+    let value = invocation.named_arg_as(builder, "value")?;
+    let left_context = invocation.named_arg_as(builder, "left_context")?;
+    let right_context = invocation.named_arg_as(builder, "right_context")?;
+    let scale = invocation.named_arg_as(builder, "scale")?;
+    builder.wire(BandedAttention { left_context, right_context, scale }, &[query, key, value])
+}
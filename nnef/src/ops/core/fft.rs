@@ -0,0 +1,120 @@
+use crate::internal::*;
+use crate::ser::*;
+use tract_core::ops::fft::{Fft, Stft};
+use tract_core::ops::math::{ComplexToInnerDim, InnerDimToComplex};
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<Fft>(), ser_fft);
+    registry.register_primitive(
+        "tract_core_fft",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::Integer.named("axis"),
+            TypeName::Logical.named("inverse"),
+        ],
+        de_fft,
+    );
+    registry.register_dumper(TypeId::of::<Stft>(), ser_stft);
+    registry.register_primitive(
+        "tract_core_stft",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::Integer.named("axis"),
+            TypeName::Integer.named("frame"),
+            TypeName::Integer.named("stride"),
+            TypeName::Scalar.tensor().named("window"),
+        ],
+        de_stft,
+    );
+    registry.register_dumper(TypeId::of::<InnerDimToComplex>(), ser_inner_dim_to_complex);
+    registry.register_primitive(
+        "tract_core_inner_dim_to_complex",
+        &[TypeName::Scalar.tensor().named("input")],
+        de_inner_dim_to_complex,
+    );
+    registry.register_dumper(TypeId::of::<ComplexToInnerDim>(), ser_complex_to_inner_dim);
+    registry.register_primitive(
+        "tract_core_complex_to_inner_dim",
+        &[TypeName::Scalar.tensor().named("input")],
+        de_complex_to_inner_dim,
+    );
+}
+
+fn ser_fft(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<Fft>().unwrap();
+    let wire = ast.mapping[&node.inputs[0]].clone();
+    Ok(Some(invocation(
+        "tract_core_fft",
+        &[wire],
+        &[("axis", numeric(op.axis)), ("inverse", logical(op.inverse))],
+    )))
+}
+
+fn de_fft(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let wire = invocation.named_arg_as(builder, "input")?;
+    let axis = invocation.named_arg_as(builder, "axis")?;
+    let inverse = invocation.named_arg_as(builder, "inverse")?;
+    builder.wire(Fft { axis, inverse }, &[wire])
+}
+
+fn ser_stft(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<Stft>().unwrap();
+    let wire = ast.mapping[&node.inputs[0]].clone();
+    let mut named = vec![
+        ("axis", numeric(op.axis)),
+        ("frame", numeric(op.frame)),
+        ("stride", numeric(op.stride)),
+    ];
+    if let Some(window) = &op.window {
+        let window = ast.konst(format!("{}.window", node.name), window)?;
+        named.push(("window", (*window).clone()));
+    }
+    Ok(Some(invocation("tract_core_stft", &[wire], &named)))
+}
+
+fn de_stft(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let wire = invocation.named_arg_as(builder, "input")?;
+    let axis = invocation.named_arg_as(builder, "axis")?;
+    let frame = invocation.named_arg_as(builder, "frame")?;
+    let stride = invocation.named_arg_as(builder, "stride")?;
+    let window = invocation.named_arg_as::<Arc<Tensor>>(builder, "window").ok();
+    builder.wire(Stft { axis, frame, stride, window }, &[wire])
+}
+
+fn ser_inner_dim_to_complex(
+    ast: &mut IntoAst,
+    node: &TypedNode,
+) -> TractResult<Option<Arc<RValue>>> {
+    let wire = ast.mapping[&node.inputs[0]].clone();
+    Ok(Some(invocation("tract_core_inner_dim_to_complex", &[wire], &[])))
+}
+
+fn de_inner_dim_to_complex(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let wire = invocation.named_arg_as(builder, "input")?;
+    builder.wire(InnerDimToComplex, &[wire])
+}
+
+fn ser_complex_to_inner_dim(
+    ast: &mut IntoAst,
+    node: &TypedNode,
+) -> TractResult<Option<Arc<RValue>>> {
+    let wire = ast.mapping[&node.inputs[0]].clone();
+    Ok(Some(invocation("tract_core_complex_to_inner_dim", &[wire], &[])))
+}
+
+fn de_complex_to_inner_dim(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let wire = invocation.named_arg_as(builder, "input")?;
+    builder.wire(ComplexToInnerDim, &[wire])
+}
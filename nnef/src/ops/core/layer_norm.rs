@@ -0,0 +1,46 @@
+use crate::internal::*;
+use crate::ser::*;
+use tract_core::ops::nn::LayerNorm;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<LayerNorm>(), ser_layer_norm);
+    registry.register_primitive(
+        "tract_core_layer_norm",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::Scalar.tensor().named("scale"),
+            TypeName::Scalar.tensor().named("bias"),
+            TypeName::Integer.named("axis"),
+            TypeName::Scalar.named("epsilon"),
+        ],
+        de_layer_norm,
+    );
+}
+
+fn ser_layer_norm(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op().downcast_ref::<LayerNorm>().unwrap();
+    let inputs: TVec<Arc<RValue>> = node.inputs.iter().map(|i| ast.mapping[i].clone()).collect();
+    Ok(Some(invocation(
+        "tract_core_layer_norm",
+        &inputs,
+        &[("axis", numeric(op.axis)), ("epsilon", numeric(op.epsilon))],
+    )))
+}
+
+fn de_layer_norm(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let scale = invocation.named_arg_as(builder, "scale")?;
+    let bias = invocation.named_arg_as(builder, "bias")?;
+    let axis = invocation.named_arg_as(builder, "axis")?;
+    let epsilon = invocation.named_arg_as(builder, "epsilon")?;
+    let output_dt = invocation
+        .dt_from_quant_file
+        .first()
+        .cloned()
+        .flatten()
+        .unwrap_or(builder.model.outlet_fact(input)?.datum_type);
+    builder.wire(LayerNorm { axis, epsilon, output_dt }, &[input, scale, bias])
+}
@@ -34,6 +34,9 @@ fn qconv_parameters() -> Vec<Parameter> {
 
 fn qconv_unary_dump(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
     let op = node.op_as::<ConvUnary>().unwrap();
+    if op.weight_quant.is_some() {
+        return super::weight_quant::ser_weight_quant_conv(ast, node, op);
+    }
     if op.q_params.is_none() || node.outputs[0].fact.datum_type.is_quantized() {
         return Ok(None);
     }
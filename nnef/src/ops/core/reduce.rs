@@ -38,7 +38,7 @@ fn de_reduce(
         "tract_core_argmin_reduce_last" => ops::nn::Reducer::ArgMin(true),
         "tract_core_argmax_reduce_last" => ops::nn::Reducer::ArgMax(true),
         "tract_core_product_reduce" => ops::nn::Reducer::Prod,
-        _ => panic!(),
+        _ => bail!("Unknown reducer {}", invocation.invocation.id),
     };
     let axes = invocation.named_arg_as(builder, "axes")?;
     let reduce = ops::nn::Reduce { axes, reducer };
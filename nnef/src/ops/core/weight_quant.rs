@@ -0,0 +1,228 @@
+use crate::internal::*;
+use crate::ops::nnef::deser::read_conv_parameters;
+use crate::ops::nnef::ser::{conv_like_input, conv_like_output, make_conv_named_args};
+use crate::ser::*;
+use tract_core::ops::cnn::{ConvUnary, KernelFormat};
+use tract_core::ops::matmul::WeightQuantMatMulUnary;
+use tract_core::weight_quant::{QuantizedWeights, WeightFormat, WeightQuant};
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<WeightQuantMatMulUnary>(), ser_weight_quant_matmul);
+    registry.register_primitive(
+        "tract_core_weight_quant_matmul",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::Integer.tensor().named("weights"),
+            TypeName::Scalar.tensor().named("scales"),
+            TypeName::String.named("format"),
+            TypeName::Integer.named("group_size"),
+            TypeName::Integer.named("k"),
+            TypeName::Integer.named("rank"),
+            TypeName::Logical.named("transposeB"),
+            TypeName::Logical.named("transposeC"),
+        ],
+        de_weight_quant_matmul,
+    );
+    registry.register_primitive(
+        "tract_core_weight_quant_conv",
+        &[
+            TypeName::Scalar.tensor().named("input"),
+            TypeName::Integer.tensor().named("weights"),
+            TypeName::Scalar.tensor().named("scales"),
+            TypeName::Scalar.tensor().named("bias").default(0),
+            TypeName::Integer.array().named("kernel_shape"),
+            TypeName::String.named("format"),
+            TypeName::Integer.named("group_size"),
+            TypeName::Integer.spec().named("groups"),
+            TypeName::Integer.array().named("dilation"),
+            TypeName::Integer.array().named("stride"),
+            TypeName::Integer.array().array().named("padding"),
+            TypeName::String.spec().named("border"),
+        ],
+        de_weight_quant_conv,
+    );
+}
+
+fn format_name(quant: &WeightQuant) -> &'static str {
+    match quant.format {
+        WeightFormat::Int8 => "i8",
+        WeightFormat::Int4 => "i4",
+    }
+}
+
+fn read_format(format: &str) -> TractResult<WeightFormat> {
+    match format {
+        "i8" => Ok(WeightFormat::Int8),
+        "i4" => Ok(WeightFormat::Int4),
+        _ => bail!("Unknown weight format {}", format),
+    }
+}
+
+fn read_quantized_weights(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+    k: usize,
+) -> TractResult<QuantizedWeights> {
+    let weights: Arc<Tensor> = invocation.named_arg_as(builder, "weights")?;
+    let scales: Arc<Tensor> = invocation.named_arg_as(builder, "scales")?;
+    let format: String = invocation.named_arg_as(builder, "format")?;
+    let group_size = invocation.named_arg_as(builder, "group_size")?;
+    ensure!(scales.rank() == 2, "Expected scales as a m×groups matrix, got {:?}", scales);
+    QuantizedWeights::from_parts(
+        WeightQuant { format: read_format(&format)?, group_size },
+        scales.shape()[0],
+        k,
+        weights.cast_to::<u8>()?.as_slice::<u8>()?.to_vec(),
+        scales.cast_to::<f32>()?.as_slice::<f32>()?.to_vec(),
+    )
+}
+
+fn weights_and_scales(
+    ast: &mut IntoAst,
+    node: &TypedNode,
+    weights: &QuantizedWeights,
+) -> TractResult<[Arc<RValue>; 2]> {
+    let data = tensor1(&weights.data).into_arc_tensor();
+    let data = ast.konst_variable(format!("{}_weights", node.name), &data)?;
+    let scales = Tensor::from_shape(&[weights.m, weights.groups_per_row()], &weights.scales)?;
+    let scales = ast.konst_variable(format!("{}_scales", node.name), &scales.into_arc_tensor())?;
+    Ok([data, scales])
+}
+
+fn ser_weight_quant_matmul(
+    ast: &mut IntoAst,
+    node: &TypedNode,
+) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op().downcast_ref::<WeightQuantMatMulUnary>().unwrap();
+    let input = ast.mapping[&node.inputs[0]].clone();
+    let [weights, scales] = weights_and_scales(ast, node, &op.a)?;
+    Ok(Some(invocation(
+        "tract_core_weight_quant_matmul",
+        &[input, weights, scales],
+        &[
+            ("format", string(format_name(&op.a.quant))),
+            ("group_size", numeric(op.a.quant.group_size)),
+            ("k", numeric(op.a.k)),
+            ("rank", numeric(op.a_rank)),
+            ("transposeB", logical(op.b_trans)),
+            ("transposeC", logical(op.c_trans)),
+        ],
+    )))
+}
+
+fn de_weight_quant_matmul(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let k = invocation.named_arg_as(builder, "k")?;
+    let rank = invocation.named_arg_as(builder, "rank")?;
+    let b_trans = invocation.named_arg_as(builder, "transposeB")?;
+    let c_trans = invocation.named_arg_as(builder, "transposeC")?;
+    let weights = read_quantized_weights(builder, invocation, k)?;
+    builder.wire(WeightQuantMatMulUnary::new(Arc::new(weights), rank, b_trans, c_trans), &[input])
+}
+
+pub fn ser_weight_quant_conv(
+    ast: &mut IntoAst,
+    node: &TypedNode,
+    op: &ConvUnary,
+) -> TractResult<Option<Arc<RValue>>> {
+    let quant = op.weight_quant.unwrap();
+    ensure!(op.group == 1, "Weight quantized convolution only support group = 1");
+    let input = conv_like_input(ast, node, &op.pool_spec)?;
+    let kernel = op.kernel_as_group_o_ihw()?;
+    let (m, k) = (kernel.shape()[1], kernel.shape()[2]);
+    let weights = QuantizedWeights::quantize(quant, m, k, kernel.as_slice::<f32>()?)?;
+    let [weights, scales] = weights_and_scales(ast, node, &weights)?;
+    let mut inputs = tvec![input, weights, scales];
+    if let Some(bias) = op.bias.as_ref() {
+        inputs.push(ast.konst(format!("{}_bias", node.name), bias)?);
+    }
+    let ci = k / op.pool_spec.kernel_shape.iter().product::<usize>();
+    let mut kernel_shape = tvec!(m, ci);
+    kernel_shape.extend(op.pool_spec.kernel_shape.iter().copied());
+    let mut named_args = make_conv_named_args(node, &op.pool_spec, op.group, false, None)?;
+    named_args.push(("kernel_shape", ints(&kernel_shape)));
+    named_args.push(("format", string(format_name(&quant))));
+    named_args.push(("group_size", numeric(quant.group_size)));
+    let wire = invocation("tract_core_weight_quant_conv", &inputs, &named_args);
+    Ok(Some(conv_like_output(ast, node, &op.pool_spec, "conv", wire)?))
+}
+
+fn de_weight_quant_conv(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let kernel_shape: TVec<usize> = invocation.named_arg_as(builder, "kernel_shape")?;
+    let input_fact = builder.model.outlet_fact(input)?.clone();
+    if input_fact.rank() != kernel_shape.len() {
+        bail!(
+            "Convolution input expected as NCHW, kernel as OIHW. Got {:?} and {:?}.",
+            input_fact,
+            kernel_shape
+        );
+    }
+    let k = kernel_shape[1..].iter().product();
+    let weights = read_quantized_weights(builder, invocation, k)?;
+    let quant = weights.quant;
+    let kernel = weights.dequantize().into_shape(&kernel_shape)?;
+    let (group, pool_spec) = read_conv_parameters(builder, invocation, &kernel_shape, &input_fact)?;
+    let bias: Arc<Tensor> = invocation.named_arg_as(builder, "bias")?;
+    let bias: Option<Arc<Tensor>> =
+        if bias.is_uniform() && bias.cast_to_scalar::<f32>()? == 0.0 { None } else { Some(bias) };
+    let op = ConvUnary {
+        weight_quant: Some(quant),
+        ..ConvUnary::new(pool_spec, KernelFormat::OIHW, kernel.into_arc_tensor(), group, bias, None)
+    };
+    builder.wire(op, &[input])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tract_core::ops::cnn::{PaddingSpec, PoolSpec};
+    use tract_core::ops::nn::DataFormat;
+    use tract_core::model::translator::Translate;
+    use tract_core::weight_quant::WeightQuantTranslator;
+
+    #[test]
+    fn conv_round_trip() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let source = model.add_source("input", f32::fact([8, 4]))?;
+        let kernel = Tensor::from_shape(
+            &[6, 4, 3],
+            &(0..72).map(|i| ((i * 5) % 11) as f32 / 4. - 1.).collect::<Vec<_>>(),
+        )?;
+        let conv = ConvUnary::new(
+            PoolSpec::new(DataFormat::HWC, tvec!(3), PaddingSpec::Valid, None, None, Some(6)),
+            KernelFormat::OIHW,
+            kernel.into_arc_tensor(),
+            1,
+            Some(rctensor1(&[0.5f32; 6])),
+            None,
+        );
+        let wire = model.wire_node("conv", conv, &[source])?;
+        model.set_output_outlets(&wire)?;
+        let quant = WeightQuant { format: WeightFormat::Int4, group_size: 4 };
+        let quantized = WeightQuantTranslator(quant).translate_model(&model)?;
+
+        let nnef = crate::nnef().with_tract_core();
+        let buffer = nnef.write_to_tar(&quantized, vec![])?;
+        let proto = nnef.proto_model_for_read(&mut &*buffer)?;
+        let (_, weights) = proto.tensors.iter().find(|(name, _)| name == "conv_weights").unwrap();
+        assert_eq!(weights.datum_type(), u8::datum_type());
+        let reloaded = nnef.model_for_proto_model(&proto)?;
+        let conv = reloaded.nodes().iter().find_map(|n| n.op_as::<ConvUnary>()).unwrap();
+        assert_eq!(conv.weight_quant, Some(quant));
+
+        let input = Tensor::from_shape(
+            &[8, 4],
+            &(0..32).map(|i| ((i * 3) % 7) as f32 / 3. - 1.).collect::<Vec<_>>(),
+        )?;
+        let expected = quantized.into_runnable()?.run(tvec!(input.clone()))?.remove(0);
+        let found = reloaded.into_runnable()?.run(tvec!(input))?.remove(0);
+        found.close_enough(&expected, true)
+    }
+}
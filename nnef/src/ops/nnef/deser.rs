@@ -1,6 +1,6 @@
 use crate::ast::*;
 use tract_core::internal::*;
-use tract_core::ops::cnn::deconv::adjustments;
+use tract_core::ops::cnn::deconv::padding_and_adjustments;
 use tract_core::ops::cnn::PaddingSpec;
 use tract_core::ops::cnn::PoolSpec;
 use tract_core::ops::matmul::MatMulQParams;
@@ -27,7 +27,7 @@ pub fn external(
     } else if type_name == TypeName::Integer {
         i64::datum_type()
     } else {
-        todo!()
+        bail!("Unsupported external type {:?}", type_name)
     };
     let shape: TVec<TDim> = invocation.named_arg_as(builder, "shape")?;
     Ok(tvec!(builder.model.add_source("", dt.fact(&shape))?))
@@ -323,7 +323,7 @@ pub fn conv_or_deconv(
         );
     }
 
-    let (group, pool_spec) =
+    let (group, mut pool_spec) =
         read_conv_parameters(builder, invocation, kernel.shape(), &input_fact)?;
 
     let output_dt =
@@ -346,7 +346,14 @@ pub fn conv_or_deconv(
                 .shape
                 .as_concrete()
                 .context("symbolic dimension not supported in deconv")?[2..];
-            adjustments(&pool_spec, input_shape, &output_shape)?
+            let (padding, adjustments) = padding_and_adjustments(
+                &pool_spec,
+                input_shape,
+                &output_shape,
+                &tvec!(0; pool_spec.rank()),
+            )?;
+            pool_spec.padding = padding;
+            adjustments
         } else {
             tvec!(0; pool_spec.rank())
         };
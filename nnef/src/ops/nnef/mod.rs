@@ -25,6 +25,7 @@ pub fn tract_nnef() -> Registry {
     dumper!(ops::source::TypedSource, ser::source);
     primitive(&mut registry, "variable", deser::variable);
     dumper!(ops::konst::Const, ser::konst);
+    dumper!(ops::quant::DequantizeLinearPerAxis, ser::dequantize_linear_per_axis);
 
     primitive(&mut registry, "reshape", deser::reshape);
     primitive(&mut registry, "transpose", deser::transpose);
@@ -31,6 +31,31 @@ pub fn konst(
     Ok(Some(ast.konst(&node.name, &op.0)?))
 }
 
+/// The per-axis parameters go to graph.quant, attached to an alias of the raw input.
+pub fn dequantize_linear_per_axis(
+    ast: &mut IntoAst,
+    node: &TypedNode,
+    op: &ops::quant::DequantizeLinearPerAxis,
+) -> TractResult<Option<Arc<RValue>>> {
+    let (bits, signed) = match ast.model.outlet_fact(node.inputs[0])?.datum_type {
+        DatumType::I8 => (8, true),
+        DatumType::U8 => (8, false),
+        DatumType::I32 => (32, true),
+        _ => return Ok(None),
+    };
+    let input = ast.mapping[&node.inputs[0]].clone();
+    let input = ast.force_variable(format!("{}_raw", node.name), &input);
+    let format = QuantFormat::LinearPerAxis {
+        axis: op.axis,
+        zero_points: op.zero_points.as_slice::<i32>()?.to_vec(),
+        scales: op.scales.as_slice::<f32>()?.to_vec(),
+        bits,
+        signed,
+    };
+    ast.quantization.insert(ast.scoped_id(&node.name), format);
+    Ok(Some(input))
+}
+
 pub fn concat(
     ast: &mut IntoAst,
     node: &TypedNode,
@@ -141,7 +166,32 @@ pub fn make_conv_named_args<'a>(
 ) -> TractResult<TVec<(&'a str, RValue)>> {
     use tract_core::ops::cnn::PaddingSpec;
     let output_shape = pool_spec.data_format.shape(node.outputs[0].fact.shape.to_tvec())?;
-    let padding = match &pool_spec.padding {
+    // SAME deconvolution is serialized as explicit padding and output shape, so adjustments and
+    // SAME_LOWER survive the round trip
+    let same_deconv =
+        deconv && matches!(pool_spec.padding, PaddingSpec::SameUpper | PaddingSpec::SameLower);
+    let padding_spec = if same_deconv {
+        let adjustments = adjustments.unwrap();
+        let (before, after) = (0..pool_spec.rank())
+            .map(|ax| {
+                let padded = pool_spec.padding.compute_one_for_deconv(
+                    ax,
+                    &1usize,
+                    pool_spec.kernel_shape[ax],
+                    pool_spec.dilation(ax),
+                    pool_spec.stride(ax),
+                    adjustments[ax],
+                )?;
+                Ok((padded.pad_before, padded.pad_after))
+            })
+            .collect::<TractResult<Vec<_>>>()?
+            .into_iter()
+            .unzip();
+        PaddingSpec::Explicit(before, after, false)
+    } else {
+        pool_spec.padding.clone()
+    };
+    let padding = match &padding_spec {
         PaddingSpec::Explicit(bef, after, _) => array(
             &bef.iter()
                 .zip(after.iter())
@@ -161,7 +211,7 @@ pub fn make_conv_named_args<'a>(
         ("groups", numeric(group)),
         ("padding", padding),
     ];
-    if deconv && adjustments.unwrap().iter().any(|a| *a != 0) {
+    if deconv && (same_deconv || adjustments.unwrap().iter().any(|a| *a != 0)) {
         let output_shape = output_shape
             .hw_dims()
             .iter()
@@ -172,17 +222,12 @@ pub fn make_conv_named_args<'a>(
     Ok(named_args)
 }
 
-#[allow(clippy::too_many_arguments)]
-pub fn conv_or_deconv(
+/// Moves the input of a convolution-like operator to the NCHW layout nnef expects.
+pub fn conv_like_input(
     ast: &mut IntoAst,
     node: &TypedNode,
     pool_spec: &PoolSpec,
-    weights: Tensor,
-    bias: &Option<Arc<Tensor>>,
-    group: usize,
-    deconv: bool,
-    adjustments: Option<&[usize]>,
-) -> TractResult<Option<Arc<RValue>>> {
+) -> TractResult<Arc<RValue>> {
     let mut wire = ast.mapping[&node.inputs[0]].clone();
     let data_format = pool_spec.data_format;
     if !data_format.has_n() {
@@ -193,24 +238,24 @@ pub fn conv_or_deconv(
         perm.insert(1, pool_spec.rank() + 1);
         wire = invocation("transpose", &[wire], &[("axes", ints(&perm))]);
     }
-    wire = ast.force_variable(format!("{}_input", node.name), &wire);
-
-    let mut inputs = tvec![wire];
-    inputs.push(ast.konst_variable(format!("{}_weigths", node.name), &weights.into_arc_tensor())?);
-    if let Some(bias) = bias.as_ref() {
-        inputs.push(ast.konst(format!("{}_bias", node.name), bias)?);
-    }
-
-    let named_args = make_conv_named_args(node, pool_spec, group, deconv, adjustments)?;
+    Ok(ast.force_variable(format!("{}_input", node.name), &wire))
+}
 
-    let name = if deconv { "deconv" } else { "conv" };
-    wire = invocation(name, &inputs, &named_args);
+/// Stores the NCHW output of a convolution-like operator and moves it back to the node layout.
+pub fn conv_like_output(
+    ast: &mut IntoAst,
+    node: &TypedNode,
+    pool_spec: &PoolSpec,
+    name: &str,
+    wire: Arc<RValue>,
+) -> TractResult<Arc<RValue>> {
+    let data_format = pool_spec.data_format;
     // need to force quantization storage as output code may miss it
     let var_name = format!("{}_{}", node.name, name);
     if let Some(qp) = QuantFormat::from_dt(node.outputs[0].fact.datum_type) {
         ast.quantization.insert(var_name.clone(), qp);
     }
-    wire = ast.force_variable(var_name, &wire);
+    let mut wire = ast.force_variable(var_name, &wire);
 
     if data_format.c_is_last() {
         let mut perm: TVec<usize> = (0..pool_spec.rank() + 2).collect();
@@ -221,8 +266,33 @@ pub fn conv_or_deconv(
     if !data_format.has_n() {
         wire = invocation("squeeze", &[wire], &[("axes", ints(&[0]))]);
     }
+    Ok(wire)
+}
 
-    Ok(Some(wire))
+#[allow(clippy::too_many_arguments)]
+pub fn conv_or_deconv(
+    ast: &mut IntoAst,
+    node: &TypedNode,
+    pool_spec: &PoolSpec,
+    weights: Tensor,
+    bias: &Option<Arc<Tensor>>,
+    group: usize,
+    deconv: bool,
+    adjustments: Option<&[usize]>,
+) -> TractResult<Option<Arc<RValue>>> {
+    let wire = conv_like_input(ast, node, pool_spec)?;
+
+    let mut inputs = tvec![wire];
+    inputs.push(ast.konst_variable(format!("{}_weigths", node.name), &weights.into_arc_tensor())?);
+    if let Some(bias) = bias.as_ref() {
+        inputs.push(ast.konst(format!("{}_bias", node.name), bias)?);
+    }
+
+    let named_args = make_conv_named_args(node, pool_spec, group, deconv, adjustments)?;
+
+    let name = if deconv { "deconv" } else { "conv" };
+    let wire = invocation(name, &inputs, &named_args);
+    Ok(Some(conv_like_output(ast, node, pool_spec, name, wire)?))
 }
 
 pub fn conv(
@@ -230,7 +300,10 @@ pub fn conv(
     node: &TypedNode,
     op: &ops::cnn::conv::ConvUnary,
 ) -> TractResult<Option<Arc<RValue>>> {
-    if op.q_params.is_some() && !node.outputs[0].fact.datum_type.is_quantized() {
+    // weight quantized convolutions are dumped by the tract_core extension
+    if (op.q_params.is_some() && !node.outputs[0].fact.datum_type.is_quantized())
+        || op.weight_quant.is_some()
+    {
         return Ok(None);
     }
     // tract HWIO: H W I/g O
@@ -528,3 +601,44 @@ pub fn softmax(
         &[("axes", RValue::Literal(crate::ast::Literal::Array(litteral_axes)))],
     )))
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tract_core::ops::quant::DequantizeLinearPerAxis;
+
+    #[test]
+    fn per_axis_quantization_round_trip() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let source = model.add_source("input", f32::fact([2, 3]))?;
+        let weights = model.add_const("weights", tensor2(&[[1i8, -2, 3], [4, 5, -6]]))?;
+        let op = DequantizeLinearPerAxis::new(0, rctensor1(&[0i32, 2]), rctensor1(&[0.5f32, 2.]));
+        let weights = model.wire_node("dequant", op, &[weights])?;
+        let wire = model.wire_node("mul", ops::math::mul::bin_typed(), &[source, weights[0]])?;
+        model.set_output_outlets(&wire)?;
+
+        let nnef = crate::nnef();
+        let buffer = nnef.write_to_tar(&model, vec![])?;
+        let proto = nnef.proto_model_for_read(&mut &*buffer)?;
+        let format = proto.quantization.as_ref().and_then(|q| q.get("dequant")).unwrap();
+        assert_eq!(
+            format,
+            &QuantFormat::LinearPerAxis {
+                axis: 0,
+                zero_points: vec![0, 2],
+                scales: vec![0.5, 2.],
+                bits: 8,
+                signed: true
+            }
+        );
+        let reloaded = nnef.model_for_proto_model(&proto)?;
+        assert!(reloaded.nodes().iter().any(|n| n.op_is::<DequantizeLinearPerAxis>()));
+
+        let input = tensor2(&[[1f32, 2., 3.], [4., 5., 6.]]);
+        let expected = model.into_runnable()?.run(tvec!(input.clone()))?.remove(0);
+        assert_eq!(*expected, tensor2(&[[0.5f32, -2., 4.5], [16., 30., -96.]]));
+        let found = reloaded.into_runnable()?.run(tvec!(input))?.remove(0);
+        assert_eq!(found, expected);
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 use byteorder::{ReadBytesExt, WriteBytesExt, LE};
+use std::io::Read;
 use tract_core::internal::*;
 
 const TRACT_ITEM_TYPE_VENDOR: u16 = (b'T' as u16) << 8u16 | b'R' as u16;
@@ -19,7 +20,7 @@ struct Header {
     padding: [u32; 11],
 }
 
-pub fn read_tensor<R: std::io::Read>(mut reader: R) -> TractResult<Tensor> {
+pub fn read_tensor<R: Read>(mut reader: R) -> TractResult<Tensor> {
     unsafe {
         let mut header: Header = std::mem::zeroed();
         let buffer: &mut [u8; 128] = std::mem::transmute(&mut header);
@@ -35,9 +36,13 @@ pub fn read_tensor<R: std::io::Read>(mut reader: R) -> TractResult<Tensor> {
         }
         let shape: TVec<usize> =
             header.dims[0..header.rank as usize].iter().map(|d| *d as _).collect();
-        let len = shape.iter().product::<usize>();
+        let len = shape
+            .iter()
+            .try_fold(1usize, |acc, d| acc.checked_mul(*d))
+            .with_context(|| format!("Invalid tensor shape {:?}", shape))?;
         if header.bits_per_item != 0xFFFFFFFF
-            && len * (header.bits_per_item as usize / 8) != header.data_size_bytes as usize
+            && len.checked_mul(header.bits_per_item as usize / 8)
+                != Some(header.data_size_bytes as usize)
         {
             bail!(
                 "Shape and len mismatch: shape:{:?}, bits_per_item:{}, bytes:{} ",
@@ -69,22 +74,26 @@ pub fn read_tensor<R: std::io::Read>(mut reader: R) -> TractResult<Tensor> {
             ),
         };
         if dt.is_copy() {
-            let mut tensor = Tensor::uninitialized_dt(dt, &shape)?;
-            reader.read_exact(tensor.as_bytes_mut())?;
-            Ok(tensor)
+            // read before allocating the tensor: the header alone must not trigger a huge
+            // allocation
+            let mut data = vec![];
+            (&mut reader).take(header.data_size_bytes as u64).read_to_end(&mut data)?;
+            ensure!(data.len() == header.data_size_bytes as usize, "Truncated tensor data");
+            Tensor::from_raw_dt(dt, &shape, &data)
         } else if dt == DatumType::String {
-            let mut tensor = Tensor::zero_dt(dt, &shape)?;
-            for item in tensor.as_slice_mut_unchecked::<String>() {
+            // strings are read before building the tensor, so a bogus shape can not trigger a
+            // huge allocation
+            let mut strings = vec![];
+            for _ in 0..len {
                 let len: u32 = reader.read_u32::<LE>()?;
-                let mut bytes = Vec::with_capacity(len as usize);
-                #[allow(clippy::uninit_vec)]
-                bytes.set_len(len as usize);
-                reader.read_exact(&mut bytes)?;
-                *item = String::from_utf8(bytes)?;
+                let mut bytes = vec![];
+                (&mut reader).take(len as u64).read_to_end(&mut bytes)?;
+                ensure!(bytes.len() == len as usize, "Truncated string in tensor data");
+                strings.push(String::from_utf8(bytes)?);
             }
-            Ok(tensor)
+            Ok(tract_ndarray::ArrayD::from_shape_vec(&*shape, strings)?.into_tensor())
         } else {
-            todo!()
+            bail!("Unsupported tensor type {:?}", dt)
         }
     }
 }
@@ -144,4 +153,23 @@ mod test {
     fn header_is_128_bytes() {
         assert_eq!(std::mem::size_of::<Header>(), 128);
     }
+
+    #[test]
+    fn truncated_data() {
+        let mut buffer = vec![];
+        write_tensor(&mut buffer, &tensor1(&[1f32, 2., 3.])).unwrap();
+        assert!(read_tensor(&buffer[..buffer.len() - 1]).is_err());
+        assert!(read_tensor(&buffer[..100]).is_err());
+        assert_eq!(read_tensor(&*buffer).unwrap(), tensor1(&[1f32, 2., 3.]));
+    }
+
+    #[test]
+    fn huge_shape() {
+        let mut buffer = vec![];
+        write_tensor(&mut buffer, &tensor1(&[1f32, 2., 3.])).unwrap();
+        // rank 2, with both dims set to u32::MAX
+        buffer[8..12].copy_from_slice(&2u32.to_le_bytes());
+        buffer[12..20].copy_from_slice(&[0xFF; 8]);
+        assert!(read_tensor(&*buffer).is_err());
+    }
 }
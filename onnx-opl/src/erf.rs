@@ -1,32 +1,40 @@
 use tract_nnef::internal::*;
 
 tract_core::element_wise!(erf, Erf,
-    [f32] => |_, xs| {
-        xs.iter_mut().for_each(|x| *x = erf_f32(*x));
-        Ok(())
-    };
+    [f16] => |_, xs| { (tract_linalg::ops().erf_f16)().run(xs) },
+    [f32] => |_, xs| { (tract_linalg::ops().erf_f32)().run(xs) };
+    declutter: tract_core::ops::nn::declutter_gelu_erf;
     prefix: "onnx."
 );
 
-#[allow(non_upper_case_globals)]
-#[allow(clippy::excessive_precision)]
-fn erf_f32(x: f32) -> f32 {
-    const a1: f32 = 0.0705230784;
-    const a2: f32 = 0.0422820123;
-    const a3: f32 = 0.0092705272;
-    const a4: f32 = 0.0001520143;
-    const a5: f32 = 0.0002765672;
-    const a6: f32 = 0.0000430638;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tract_core::ops::element_wise::ElementWiseOp;
+    use tract_core::ops::math;
 
-    let signum = x.signum();
-    let x = x.abs();
-    let y = a6 * x;
-    let y = (a5 + y) * x;
-    let y = (a4 + y) * x;
-    let y = (a3 + y) * x;
-    let y = (a2 + y) * x;
-    let y = (a1 + y) * x;
-    let y = 1.0 - (y + 1.0).powi(16).recip();
+    #[test]
+    fn decomposed_erf_gelu_is_fused() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", f32::fact([2, 3]))?;
+        let scale = rctensor2(&[[std::f32::consts::FRAC_1_SQRT_2]]);
+        let scaled = model.wire_node("scaled", math::mul::unary(scale), &[x])?;
+        let erf = model.wire_node("erf", super::erf(), &scaled)?;
+        let one_plus = model.wire_node("one_plus", math::add::unary(rctensor2(&[[1f32]])), &erf)?;
+        let half = model.wire_node("half", math::mul::unary(rctensor2(&[[0.5f32]])), &[x])?;
+        let y = model.wire_node("y", math::mul::bin_typed(), &[half[0], one_plus[0]])?;
+        model.set_output_outlets(&y)?;
 
-    y.copysign(signum)
+        let decluttered = model.clone().into_decluttered()?;
+        assert_eq!(decluttered.nodes().len(), 2);
+        assert!(decluttered.nodes()[1]
+            .op_as::<ElementWiseOp>()
+            .map(|ew| ew.0.is::<tract_core::ops::nn::Gelu>())
+            .unwrap_or(false));
+
+        let input = tvec!(tensor2(&[[-3f32, -1., -0.1], [0., 0.5, 2.]]));
+        let expected = model.into_runnable()?.run(input.clone())?;
+        let found = decluttered.into_runnable()?.run(input)?;
+        found[0].close_enough(&expected[0], Approximation::Close)
+    }
 }
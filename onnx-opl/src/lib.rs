@@ -15,6 +15,7 @@ pub mod lrn;
 pub mod ml;
 pub mod non_max_suppression;
 pub mod multinomial;
+pub mod string;
 
 pub trait WithOnnx {
     fn with_onnx(self) -> Self;
@@ -33,6 +34,7 @@ fn onnx_opl_registry() -> Registry {
     ml::register(&mut registry);
     non_max_suppression::register(&mut registry);
     multinomial::register(&mut registry);
+    string::register(&mut registry);
     registry.register_unit_element_wise("tract_onnx_erf", &erf::Erf {});
     registry.register_element_wise(
         "tract_onnx_isinf",
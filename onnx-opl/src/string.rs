@@ -0,0 +1,341 @@
+use regex::Regex;
+use tract_itertools::Itertools;
+use tract_nnef::internal::*;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_primitive(
+        "tract_onnx_regex_full_match",
+        &parameters_regex_full_match(),
+        load_regex_full_match,
+    );
+    registry.register_primitive("tract_onnx_tokenizer", &parameters_tokenizer(), load_tokenizer);
+    registry.register_dumper(TypeId::of::<RegexFullMatch>(), dump_regex_full_match);
+    registry.register_dumper(TypeId::of::<Tokenizer>(), dump_tokenizer);
+}
+
+fn full_match_regex(pattern: &str) -> TractResult<Regex> {
+    Regex::new(&format!("^(?:{})$", pattern))
+        .with_context(|| format!("Invalid regular expression {:?}", pattern))
+}
+
+/// https://github.com/onnx/onnx/blob/main/docs/Operators.md#RegexFullMatch
+#[derive(Clone, Debug, Educe)]
+#[educe(Hash)]
+pub struct RegexFullMatch {
+    pub pattern: String,
+    #[educe(Hash(ignore))]
+    regex: Regex,
+}
+
+impl_dyn_hash!(RegexFullMatch);
+
+impl RegexFullMatch {
+    pub fn new(pattern: impl Into<String>) -> TractResult<RegexFullMatch> {
+        let pattern = pattern.into();
+        let regex = full_match_regex(&pattern)?;
+        Ok(RegexFullMatch { pattern, regex })
+    }
+}
+
+impl Op for RegexFullMatch {
+    fn name(&self) -> Cow<str> {
+        "RegexFullMatch".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("pattern: {:?}", self.pattern)])
+    }
+
+    op_onnx!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for RegexFullMatch {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let output = input.to_array_view::<String>()?.mapv(|s| self.regex.is_match(&s));
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for RegexFullMatch {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        ensure!(inputs[0].datum_type == DatumType::String, "RegexFullMatch expects strings");
+        Ok(tvec!(bool::fact(inputs[0].shape.iter())))
+    }
+
+    fn invariants(&self, inputs: &[&TypedFact], outputs: &[&TypedFact]) -> TractResult<Invariants> {
+        Invariants::new_element_wise(inputs, outputs)
+    }
+
+    fn change_axes(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+        _io: InOut,
+        change: &AxisOp,
+    ) -> TractResult<Option<AxisChangeConsequence>> {
+        Ok(Some(AxisChangeConsequence::new(model, node, None, change)))
+    }
+
+    as_op!();
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum TokenPattern {
+    /// One token per character.
+    Chars,
+    /// Tokens are what lies between matches of any of these expressions.
+    Separators(Vec<String>),
+    /// Tokens are the matches of this expression.
+    Tokens(String),
+}
+
+/// Microsoft contrib Tokenizer.
+///
+/// Splits each string in tokens of at least `min_chars` characters, padding
+/// the new inner axis with `pad_value`. With `mark`, each token list is
+/// surrounded with the 0x02 and 0x03 characters.
+#[derive(Clone, Debug, Educe)]
+#[educe(Hash)]
+pub struct Tokenizer {
+    pub pattern: TokenPattern,
+    pub mark: bool,
+    pub pad_value: String,
+    pub min_chars: usize,
+    pub len: Symbol,
+    #[educe(Hash(ignore))]
+    regex: Option<Regex>,
+}
+
+impl_dyn_hash!(Tokenizer);
+
+impl Tokenizer {
+    pub fn new(
+        pattern: TokenPattern,
+        mark: bool,
+        pad_value: String,
+        min_chars: usize,
+    ) -> TractResult<Tokenizer> {
+        let regex = match &pattern {
+            TokenPattern::Chars => None,
+            TokenPattern::Separators(seps) => {
+                ensure!(
+                    seps.iter().all(|s| !s.is_empty()),
+                    "Empty separator (use TokenPattern::Chars)"
+                );
+                let alternatives = seps.iter().map(|s| format!("(?:{})", s)).join("|");
+                Some(Regex::new(&alternatives)?)
+            }
+            TokenPattern::Tokens(exp) => Some(Regex::new(exp)?),
+        };
+        Ok(Tokenizer { pattern, mark, pad_value, min_chars, len: Symbol::new('K'), regex })
+    }
+
+    pub fn tokenize<'s>(&self, s: &'s str) -> Vec<&'s str> {
+        let tokens: Vec<&str> = match (&self.pattern, &self.regex) {
+            (TokenPattern::Separators(_), Some(regex)) => regex.split(s).collect(),
+            (TokenPattern::Tokens(_), Some(regex)) => {
+                regex.find_iter(s).map(|m| m.as_str()).collect()
+            }
+            _ => s.char_indices().map(|(ix, c)| &s[ix..ix + c.len_utf8()]).collect(),
+        };
+        let mut tokens: Vec<&str> = tokens
+            .into_iter()
+            .filter(|t| !t.is_empty() && t.chars().count() >= self.min_chars)
+            .collect();
+        if self.mark {
+            tokens.insert(0, "\u{2}");
+            tokens.push("\u{3}");
+        }
+        tokens
+    }
+}
+
+impl Op for Tokenizer {
+    fn name(&self) -> Cow<str> {
+        "Tokenizer".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "pattern: {:?} mark: {} pad_value: {:?} min_chars: {}",
+            self.pattern, self.mark, self.pad_value, self.min_chars
+        )])
+    }
+
+    op_onnx!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Tokenizer {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let tokens: Vec<Vec<&str>> =
+            input.as_slice::<String>()?.iter().map(|s| self.tokenize(s)).collect();
+        let width = tokens.iter().map(|t| t.len()).max().unwrap_or(0);
+        let mut shape: TVec<usize> = input.shape().into();
+        shape.push(width);
+        let mut output = tract_ndarray::ArrayD::from_elem(&*shape, self.pad_value.clone());
+        for (lane, t) in output.as_slice_mut().unwrap().chunks_mut(width.max(1)).zip(tokens.iter())
+        {
+            for (slot, token) in lane.iter_mut().zip(t.iter()) {
+                *slot = token.to_string();
+            }
+        }
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for Tokenizer {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        ensure!(inputs[0].datum_type == DatumType::String, "Tokenizer expects strings");
+        ensure!(
+            inputs[0].rank() == 1 || inputs[0].rank() == 2,
+            "Tokenizer expects a [C] or [N, C] input, got {:?}",
+            inputs[0]
+        );
+        let mut shape = inputs[0].shape.to_tvec();
+        shape.push(self.len.to_dim());
+        Ok(tvec!(String::fact(shape)))
+    }
+
+    fn invariants(
+        &self,
+        inputs: &[&TypedFact],
+        _outputs: &[&TypedFact],
+    ) -> TractResult<Invariants> {
+        Ok((0..inputs[0].rank()).map(AxisInfo::simple).collect())
+    }
+
+    as_op!();
+}
+
+fn parameters_regex_full_match() -> Vec<Parameter> {
+    vec![TypeName::String.tensor().named("input"), TypeName::String.named("pattern")]
+}
+
+fn dump_regex_full_match(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<RegexFullMatch>().context("wrong op")?;
+    let input = ast.mapping[&node.inputs[0]].clone();
+    Ok(Some(invocation(
+        "tract_onnx_regex_full_match",
+        &[input],
+        &[("pattern", string(&op.pattern))],
+    )))
+}
+
+fn load_regex_full_match(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let pattern: String = invocation.named_arg_as(builder, "pattern")?;
+    builder.wire(RegexFullMatch::new(pattern)?, &[input])
+}
+
+fn parameters_tokenizer() -> Vec<Parameter> {
+    vec![
+        TypeName::String.tensor().named("input"),
+        TypeName::String.named("mode"),
+        TypeName::String.array().named("patterns"),
+        TypeName::Logical.named("mark"),
+        TypeName::String.named("pad_value"),
+        TypeName::Integer.named("min_chars").default(1),
+    ]
+}
+
+fn dump_tokenizer(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<Tokenizer>().context("wrong op")?;
+    let input = ast.mapping[&node.inputs[0]].clone();
+    let (mode, patterns) = match &op.pattern {
+        TokenPattern::Chars => ("chars", vec![]),
+        TokenPattern::Separators(seps) => ("separators", seps.iter().map(string).collect()),
+        TokenPattern::Tokens(exp) => ("tokens", vec![string(exp)]),
+    };
+    Ok(Some(invocation(
+        "tract_onnx_tokenizer",
+        &[input],
+        &[
+            ("mode", string(mode)),
+            ("patterns", RValue::Array(patterns)),
+            ("mark", logical(op.mark)),
+            ("pad_value", string(&op.pad_value)),
+            ("min_chars", numeric(op.min_chars)),
+        ],
+    )))
+}
+
+fn load_tokenizer(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let mode: String = invocation.named_arg_as(builder, "mode")?;
+    let mut patterns: TVec<String> = invocation.named_arg_as(builder, "patterns")?;
+    let pattern = match &*mode {
+        "chars" => TokenPattern::Chars,
+        "separators" => TokenPattern::Separators(patterns.into_vec()),
+        "tokens" if patterns.len() == 1 => TokenPattern::Tokens(patterns.remove(0)),
+        _ => bail!("Invalid tokenizer mode {:?} with patterns {:?}", mode, patterns),
+    };
+    let mark = invocation.named_arg_as(builder, "mark")?;
+    let pad_value = invocation.named_arg_as(builder, "pad_value")?;
+    let min_chars = invocation.named_arg_as::<i64>(builder, "min_chars")? as usize;
+    builder.wire(Tokenizer::new(pattern, mark, pad_value, min_chars)?, &[input])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(s: &[&str]) -> Tensor {
+        tensor1(&s.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn full_match_only() -> TractResult<()> {
+        let op = RegexFullMatch::new("[a-z]+@[a-z]+\\.com|www")?;
+        let output = op.eval(tvec!(strings(&["me@tract.com", "me@tract.com!", "www"]).into()))?;
+        assert_eq!(*output[0], tensor1(&[true, false, true]));
+        Ok(())
+    }
+
+    #[test]
+    fn tokenize_with_separators() -> TractResult<()> {
+        let op = Tokenizer::new(
+            TokenPattern::Separators(vec![" ".into(), "-".into()]),
+            true,
+            "#".into(),
+            2,
+        )?;
+        let output = op.eval(tvec!(strings(&["a tiny-test", "tract"]).into()))?;
+        let expected = strings(&["\u{2}", "tiny", "test", "\u{3}", "\u{2}", "tract", "\u{3}", "#"]);
+        assert_eq!(*output[0], expected.into_shape(&[2, 4])?);
+        Ok(())
+    }
+
+    #[test]
+    fn tokenize_with_expression() -> TractResult<()> {
+        let op = Tokenizer::new(TokenPattern::Tokens("[a-z]+".into()), false, "".into(), 1)?;
+        let output = op.eval(tvec!(strings(&["ab1cd", "x"]).into()))?;
+        assert_eq!(*output[0], strings(&["ab", "cd", "x", ""]).into_shape(&[2, 2])?);
+        Ok(())
+    }
+
+    #[test]
+    fn tokenize_chars() -> TractResult<()> {
+        let op = Tokenizer::new(TokenPattern::Chars, false, "".into(), 1)?;
+        let output = op.eval(tvec!(strings(&["hé"]).into()))?;
+        assert_eq!(*output[0], strings(&["h", "é"]).into_shape(&[1, 2])?);
+        Ok(())
+    }
+}
@@ -21,6 +21,7 @@ pub mod pb {
 }
 
 pub mod pb_helpers;
+pub mod ser;
 pub mod tensor;
 
 pub use model::Onnx;
@@ -48,5 +49,7 @@ pub fn for_reader<R: std::io::Read>(mut r: R) -> TractResult<InferenceModel> {
 pub fn onnx() -> Onnx {
     let mut ops = crate::model::OnnxOpRegister::default();
     ops::register_all_ops(&mut ops);
-    Onnx { op_register: ops, ..Onnx::default() }
+    let mut dumpers = crate::ser::OnnxDumpRegister::default();
+    ser::dumpers::register_all_dumpers(&mut dumpers);
+    Onnx { op_register: ops, dump_register: dumpers, ..Onnx::default() }
 }
@@ -93,7 +93,11 @@ impl<'a> ParsingContext<'a> {
                 let id = model.add_const(input.name.to_owned(), init)?;
                 outlets_by_name.insert(input.name.to_owned(), id);
             } else {
-                let fact = input.r#type.as_ref().unwrap().value.as_ref().unwrap();
+                let fact = input
+                    .r#type
+                    .as_ref()
+                    .and_then(|t| t.value.as_ref())
+                    .with_context(|| format!("Input {} has no type", input.name))?;
                 #[allow(irrefutable_let_patterns)]
                 let fact: InferenceFact = if let pb::type_proto::Value::TensorType(fact) = fact {
                     translate_inference_fact(fact, &mut symbol_map)?
@@ -183,7 +187,9 @@ impl<'a> ParsingContext<'a> {
                     fact = translate_inference_fact(f, &mut symbol_map)?
                 };
             }
-            let outlet = outlets_by_name[&*output.name];
+            let outlet = *outlets_by_name
+                .get(&*output.name)
+                .with_context(|| format!("Output {} is not computed by any node", output.name))?;
             outputs.push(outlet);
             model.set_outlet_label(outlet, output.name.clone())?;
             model.set_outlet_fact(outlet, fact)?;
@@ -209,6 +215,7 @@ impl OnnxOpRegister {
 #[derive(Clone, Default)]
 pub struct Onnx {
     pub op_register: OnnxOpRegister,
+    pub dump_register: crate::ser::OnnxDumpRegister,
     pub ignore_output_shapes: bool,
 }
 
@@ -243,6 +250,18 @@ impl Onnx {
         Self { ignore_output_shapes: ignore, ..self }
     }
 
+    /// Translate a decluttered model to an ONNX protobuf model.
+    pub fn model_to_proto_model(&self, model: &TypedModel) -> TractResult<pb::ModelProto> {
+        crate::ser::to_proto_model(self, model).context("Translating model to ONNX")
+    }
+
+    /// Write a decluttered model in ONNX protobuf format.
+    pub fn write<W: std::io::Write>(&self, model: &TypedModel, mut w: W) -> TractResult<W> {
+        let proto = self.model_to_proto_model(model)?;
+        w.write_all(&proto.encode_to_vec())?;
+        Ok(w)
+    }
+
     pub fn determinize(model: &mut InferenceModel) -> TractResult<()> {
         use crate::ops::multinomial::Multinomial;
         for node in model.nodes_mut() {
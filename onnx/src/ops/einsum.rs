@@ -116,7 +116,7 @@ impl Expansion for EinSum {
                     .collect();
                 for axis in self.expr.iter_all_axes() {
                     if axis.repr == '*' {
-                        todo!()
+                        bail!("Ellipsis axes are not supported in EinSum inference rules")
                     } else {
                         let mut axes = vec![];
                         if let Some(mut result) = axis.result {
@@ -0,0 +1,616 @@
+use crate::model::{optional_inputs, OnnxOpRegister, ParsingContext};
+use crate::pb::*;
+use std::f64::consts::PI;
+use tract_hir::internal::*;
+use tract_hir::tract_core::ops::array::{Pad, PadMode, Slice};
+use tract_hir::tract_core::ops::fft::{Fft, Stft};
+use tract_hir::tract_core::ops::math::{ComplexToInnerDim, InnerDimToComplex};
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert("DFT", dft);
+    reg.insert("STFT", stft);
+    reg.insert("HannWindow", |_, node| window(node, WindowKind::Hann));
+    reg.insert("HammingWindow", |_, node| window(node, WindowKind::Hamming));
+    reg.insert("BlackmanWindow", |_, node| window(node, WindowKind::Blackman));
+    reg.insert("MelWeightMatrix", mel_weight_matrix);
+}
+
+fn dft(ctx: &ParsingContext, node: &NodeProto) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let inverse = node.get_attr_opt::<i64>("inverse")?.unwrap_or(0) != 0;
+    let onesided = node.get_attr_opt::<i64>("onesided")?.unwrap_or(0) != 0;
+    if inverse && onesided {
+        bail!("DFT with both inverse and onesided set is not supported");
+    }
+    let mut options = optional_inputs(node).skip(1);
+    let length_input = options.next().unwrap();
+    let (axis, axis_input) = if ctx.onnx_operator_set_version >= 20 {
+        (-2, options.next().unwrap())
+    } else {
+        (node.get_attr_opt("axis")?.unwrap_or(1), None)
+    };
+    Ok((expand(Dft { axis, axis_input, length_input, inverse, onesided }), vec![]))
+}
+
+/// Resolve an ONNX signal axis, which can not be the trailing (real, imaginary) axis.
+fn resolve_axis(axis: i64, rank: usize) -> TractResult<usize> {
+    let resolved = if axis < 0 { axis + rank as i64 } else { axis };
+    if resolved < 0 || resolved as usize + 1 >= rank {
+        bail!("Invalid signal axis {} for a rank {} input", axis, rank);
+    }
+    Ok(resolved as usize)
+}
+
+/// Wire a [.., 1] real or [.., 2] complex ONNX signal to a complex tensor.
+fn wire_to_complex(prefix: &str, model: &mut TypedModel, input: OutletId) -> TractResult<OutletId> {
+    let fact = model.outlet_fact(input)?.clone();
+    let rank = fact.rank();
+    ensure!(rank > 0, "Expected a signal with a trailing axis, got {:?}", fact);
+    let mut wire = input;
+    if fact.shape[rank - 1] == 1.to_dim() {
+        let mut pads = vec![(0, 0); rank];
+        pads[rank - 1] = (0, 1);
+        let zero = Tensor::zero_scalar_dt(fact.datum_type)?.into_arc_tensor();
+        let op = Pad { pads, mode: PadMode::Constant(zero) };
+        wire = model.wire_node(format!("{}.imaginary", prefix), op, &[wire])?[0];
+    }
+    model.wire_node(format!("{}.to_complex", prefix), InnerDimToComplex, &[wire]).map(|w| w[0])
+}
+
+fn wire_onesided(
+    prefix: &str,
+    model: &mut TypedModel,
+    wire: OutletId,
+    axis: usize,
+) -> TractResult<OutletId> {
+    let len = model.outlet_fact(wire)?.shape[axis].clone();
+    let op = Slice { axis, start: 0.to_dim(), end: len / 2 + 1 };
+    Ok(model.wire_node(format!("{}.onesided", prefix), op, &[wire])?[0])
+}
+
+fn const_input(model: &TypedModel, input: OutletId, what: &str) -> TractResult<i64> {
+    model
+        .outlet_fact(input)?
+        .konst
+        .as_ref()
+        .with_context(|| format!("Expected {} to be a constant", what))?
+        .cast_to_scalar::<i64>()
+}
+
+#[derive(Debug, Clone, Hash)]
+struct Dft {
+    axis: i64,
+    axis_input: Option<usize>,
+    length_input: Option<usize>,
+    inverse: bool,
+    onesided: bool,
+}
+
+impl_dyn_hash!(Dft);
+
+impl Dft {
+    fn rules_with_axis<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+        rank: usize,
+        axis: i64,
+    ) -> InferenceResult {
+        let axis = resolve_axis(axis, rank)?;
+        s.equals(&outputs[0].shape[rank - 1], 2.to_dim())?;
+        for ax in 0..rank - 1 {
+            if ax != axis {
+                s.equals(&outputs[0].shape[ax], &inputs[0].shape[ax])?;
+            }
+        }
+        let onesided = self.onesided;
+        let output_len = move |len: TDim| if onesided { len / 2 + 1 } else { len };
+        if let Some(length) = self.length_input {
+            s.given(&inputs[length].value, move |s, len| {
+                let len = len.cast_to_scalar::<i64>()?;
+                s.equals(&outputs[0].shape[axis], output_len(len.to_dim()))
+            })
+        } else {
+            s.given(&inputs[0].shape[axis], move |s, len| {
+                s.equals(&outputs[0].shape[axis], output_len(len))
+            })
+        }
+    }
+}
+
+impl Expansion for Dft {
+    fn name(&self) -> Cow<str> {
+        "DFT".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(
+            inputs,
+            1 + self.length_input.is_some() as usize + self.axis_input.is_some() as usize,
+        )?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        s.equals(&outputs[0].rank, &inputs[0].rank)?;
+        if let Some(axis) = self.axis_input {
+            s.given_2(&inputs[0].rank, &inputs[axis].value, move |s, rank, axis| {
+                let axis = axis.cast_to_scalar::<i64>()?;
+                self.rules_with_axis(s, inputs, outputs, rank as usize, axis)
+            })
+        } else {
+            s.given(&inputs[0].rank, move |s, rank| {
+                self.rules_with_axis(s, inputs, outputs, rank as usize, self.axis)
+            })
+        }
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let fact = model.outlet_fact(inputs[0])?.clone();
+        let axis = if let Some(axis) = self.axis_input {
+            const_input(model, inputs[axis], "DFT axis")?
+        } else {
+            self.axis
+        };
+        let axis = resolve_axis(axis, fact.rank())?;
+        let mut wire = inputs[0];
+        if let Some(length) = self.length_input {
+            let length = const_input(model, inputs[length], "DFT length")? as usize;
+            let current = fact.shape[axis].to_usize().context("DFT length over a symbolic axis")?;
+            if length < current {
+                let op = Slice { axis, start: 0.to_dim(), end: length.to_dim() };
+                wire = model.wire_node(format!("{}.truncate", prefix), op, &[wire])?[0];
+            } else if length > current {
+                let mut pads = vec![(0, 0); fact.rank()];
+                pads[axis] = (0, length - current);
+                let zero = Tensor::zero_scalar_dt(fact.datum_type)?.into_arc_tensor();
+                let op = Pad { pads, mode: PadMode::Constant(zero) };
+                wire = model.wire_node(format!("{}.zero_pad", prefix), op, &[wire])?[0];
+            }
+        }
+        wire = wire_to_complex(prefix, model, wire)?;
+        let op = Fft { axis, inverse: self.inverse };
+        wire = model.wire_node(format!("{}.fft", prefix), op, &[wire])?[0];
+        if self.onesided {
+            wire = wire_onesided(prefix, model, wire, axis)?;
+        }
+        model.wire_node(prefix, ComplexToInnerDim, &[wire])
+    }
+}
+
+fn stft(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let onesided = node.get_attr_opt::<i64>("onesided")?.unwrap_or(1) != 0;
+    let mut options = optional_inputs(node).skip(2);
+    let window_input = options.next().unwrap();
+    let frame_length_input = options.next().unwrap();
+    if window_input.is_none() && frame_length_input.is_none() {
+        bail!("STFT needs a window or a frame length");
+    }
+    Ok((expand(StftExpansion { onesided, window_input, frame_length_input }), vec![]))
+}
+
+#[derive(Debug, Clone, Hash)]
+struct StftExpansion {
+    onesided: bool,
+    window_input: Option<usize>,
+    frame_length_input: Option<usize>,
+}
+
+impl_dyn_hash!(StftExpansion);
+
+impl StftExpansion {
+    fn rules_with_frame<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+        frame: TDim,
+    ) -> InferenceResult {
+        let bins = if self.onesided { frame.clone() / 2 + 1 } else { frame.clone() };
+        s.equals(&outputs[0].shape[2], bins)?;
+        s.given_2(&inputs[0].shape[1], &inputs[1].value, move |s, len, step| {
+            let step = step.cast_to_scalar::<i64>()? as usize;
+            s.equals(&outputs[0].shape[1], (len - frame.clone()) / step + 1)
+        })
+    }
+}
+
+impl Expansion for StftExpansion {
+    fn name(&self) -> Cow<str> {
+        "STFT".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(
+            inputs,
+            2 + self.window_input.is_some() as usize + self.frame_length_input.is_some() as usize,
+        )?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        s.equals(&inputs[0].rank, 3)?;
+        s.equals(&outputs[0].rank, 4)?;
+        s.equals(&outputs[0].shape[0], &inputs[0].shape[0])?;
+        s.equals(&outputs[0].shape[3], 2.to_dim())?;
+        if let Some(frame_length) = self.frame_length_input {
+            s.given(&inputs[frame_length].value, move |s, frame| {
+                let frame = frame.cast_to_scalar::<i64>()?.to_dim();
+                self.rules_with_frame(s, inputs, outputs, frame)
+            })
+        } else {
+            let window = self.window_input.unwrap();
+            s.given(&inputs[window].shape[0], move |s, frame| {
+                self.rules_with_frame(s, inputs, outputs, frame)
+            })
+        }
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let stride = const_input(model, inputs[1], "STFT frame step")? as usize;
+        let window = if let Some(window) = self.window_input {
+            Some(
+                model
+                    .outlet_fact(inputs[window])?
+                    .konst
+                    .clone()
+                    .context("Expected STFT window to be a constant")?,
+            )
+        } else {
+            None
+        };
+        let frame = if let Some(frame_length) = self.frame_length_input {
+            const_input(model, inputs[frame_length], "STFT frame length")? as usize
+        } else {
+            window.as_ref().unwrap().len()
+        };
+        let mut wire = wire_to_complex(prefix, model, inputs[0])?;
+        let real_dt = model.outlet_fact(inputs[0])?.datum_type;
+        let window = window
+            .map(|w| -> TractResult<Arc<Tensor>> {
+                Ok(w.cast_to_dt(real_dt)?.into_owned().into_arc_tensor())
+            })
+            .transpose()?;
+        wire = model.wire_node(
+            format!("{}.frames", prefix),
+            Stft { axis: 1, frame, stride, window },
+            &[wire],
+        )?[0];
+        wire =
+            model.wire_node(format!("{}.fft", prefix), Fft { axis: 2, inverse: false }, &[wire])?
+                [0];
+        if self.onesided {
+            wire = wire_onesided(prefix, model, wire, 2)?;
+        }
+        model.wire_node(prefix, ComplexToInnerDim, &[wire])
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash)]
+enum WindowKind {
+    Hann,
+    Hamming,
+    Blackman,
+}
+
+fn window(node: &NodeProto, kind: WindowKind) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let periodic = node.get_attr_opt::<i64>("periodic")?.unwrap_or(1) != 0;
+    let dt = node.get_attr_opt("output_datatype")?.unwrap_or(DatumType::F32);
+    Ok((expand(Window { kind, periodic, dt }), vec![]))
+}
+
+#[derive(Debug, Clone, Hash)]
+struct Window {
+    kind: WindowKind,
+    periodic: bool,
+    dt: DatumType,
+}
+
+impl_dyn_hash!(Window);
+
+impl Window {
+    fn values(&self, size: usize) -> TractResult<Tensor> {
+        let n = if self.periodic { size } else { size.saturating_sub(1) };
+        let values: Vec<f64> = (0..size)
+            .map(|i| {
+                if n == 0 {
+                    return 1.0;
+                }
+                let x = 2.0 * PI * i as f64 / n as f64;
+                match self.kind {
+                    WindowKind::Hann => 0.5 - 0.5 * x.cos(),
+                    WindowKind::Hamming => 25.0 / 46.0 - 21.0 / 46.0 * x.cos(),
+                    WindowKind::Blackman => 0.42 - 0.5 * x.cos() + 0.08 * (2.0 * x).cos(),
+                }
+            })
+            .collect();
+        Ok(tensor1(&values).cast_to_dt(self.dt)?.into_owned())
+    }
+}
+
+impl Expansion for Window {
+    fn name(&self) -> Cow<str> {
+        format!("{:?}Window", self.kind).into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 1)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&inputs[0].rank, 0)?;
+        s.equals(&outputs[0].datum_type, self.dt)?;
+        s.equals(&outputs[0].rank, 1)?;
+        s.given(&inputs[0].value, move |s, size| {
+            let size = size.cast_to_scalar::<i64>()?;
+            s.equals(&outputs[0].shape[0], size.to_dim())
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let size = const_input(model, inputs[0], "window size")?;
+        ensure!(size >= 0, "Invalid window size {}", size);
+        let values = self.values(size as usize)?;
+        Ok(tvec!(model.add_const(prefix, values)?))
+    }
+}
+
+fn mel_weight_matrix(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let dt = node.get_attr_opt("output_datatype")?.unwrap_or(DatumType::F32);
+    Ok((expand(MelWeightMatrix { dt }), vec![]))
+}
+
+#[derive(Debug, Clone, Hash)]
+struct MelWeightMatrix {
+    dt: DatumType,
+}
+
+impl_dyn_hash!(MelWeightMatrix);
+
+impl MelWeightMatrix {
+    fn values(
+        &self,
+        mel_bins: usize,
+        dft_length: usize,
+        sample_rate: f64,
+        lower_hertz: f64,
+        upper_hertz: f64,
+    ) -> TractResult<Tensor> {
+        ensure!(sample_rate > 0.0, "Invalid sample rate {}", sample_rate);
+        let spectrogram_bins = dft_length / 2 + 1;
+        let hertz_to_mel = |hz: f64| 2595.0 * (1.0 + hz / 700.0).log10();
+        let lower_mel = hertz_to_mel(lower_hertz);
+        let upper_mel = hertz_to_mel(upper_hertz);
+        let mel_step = (upper_mel - lower_mel) / (mel_bins + 1) as f64;
+        let bins: Vec<usize> = (0..mel_bins + 2)
+            .map(|i| {
+                let hz = 700.0 * (10f64.powf((i as f64 * mel_step + lower_mel) / 2595.0) - 1.0);
+                (((dft_length + 1) as f64 * hz) / sample_rate).floor().max(0.0) as usize
+            })
+            .collect();
+        ensure!(
+            bins[mel_bins + 1] < spectrogram_bins + 1,
+            "Upper edge frequency {} is above the spectrogram range",
+            upper_hertz
+        );
+        let mut matrix = tract_ndarray::Array2::<f64>::zeros((spectrogram_bins, mel_bins));
+        for i in 0..mel_bins {
+            let (low, center, high) = (bins[i], bins[i + 1], bins[i + 2]);
+            if center == low {
+                if center < spectrogram_bins {
+                    matrix[(center, i)] = 1.0;
+                }
+            } else {
+                for j in low..=center.min(spectrogram_bins - 1) {
+                    matrix[(j, i)] = (j - low) as f64 / (center - low) as f64;
+                }
+            }
+            for j in center..high.min(spectrogram_bins) {
+                matrix[(j, i)] = (high - j) as f64 / (high - center) as f64;
+            }
+        }
+        Ok(matrix.into_tensor().cast_to_dt(self.dt)?.into_owned())
+    }
+}
+
+impl Expansion for MelWeightMatrix {
+    fn name(&self) -> Cow<str> {
+        "MelWeightMatrix".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 5)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&outputs[0].datum_type, self.dt)?;
+        s.equals(&outputs[0].rank, 2)?;
+        s.given(&inputs[0].value, move |s, mel_bins| {
+            let mel_bins = mel_bins.cast_to_scalar::<i64>()?;
+            s.equals(&outputs[0].shape[1], mel_bins.to_dim())
+        })?;
+        s.given(&inputs[1].value, move |s, dft_length| {
+            let dft_length = dft_length.cast_to_scalar::<i64>()?;
+            s.equals(&outputs[0].shape[0], (dft_length / 2 + 1).to_dim())
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let mel_bins = const_input(model, inputs[0], "number of mel bins")?;
+        let dft_length = const_input(model, inputs[1], "DFT length")?;
+        ensure!(mel_bins >= 0 && dft_length >= 0, "Invalid MelWeightMatrix dimensions");
+        let mut floats = tvec!();
+        for (ix, what) in [(2, "sample rate"), (3, "lower edge"), (4, "upper edge")].iter() {
+            let konst = model
+                .outlet_fact(inputs[*ix])?
+                .konst
+                .clone()
+                .with_context(|| format!("Expected {} to be a constant", what))?;
+            floats.push(konst.cast_to_scalar::<f64>()?);
+        }
+        let values =
+            self.values(mel_bins as usize, dft_length as usize, floats[0], floats[1], floats[2])?;
+        Ok(tvec!(model.add_const(prefix, values)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryInto;
+
+    fn run(
+        nodes: Vec<NodeProto>,
+        initializers: &[(&str, Tensor)],
+        input: Tensor,
+    ) -> TractResult<Arc<Tensor>> {
+        let initializer = initializers
+            .iter()
+            .map(|(name, t)| {
+                let mut proto: TensorProto = t.try_into()?;
+                proto.name = name.to_string();
+                Ok(proto)
+            })
+            .collect::<TractResult<_>>()?;
+        let output = nodes.last().unwrap().output[0].clone();
+        let graph = GraphProto {
+            node: nodes,
+            input: vec![crate::ser::value_info("x", &f32::fact(input.shape()))?],
+            output: vec![ValueInfoProto { name: output, ..ValueInfoProto::default() }],
+            initializer,
+            ..GraphProto::default()
+        };
+        let proto = ModelProto {
+            ir_version: 8,
+            opset_import: vec![OperatorSetIdProto { domain: String::new(), version: 17 }],
+            graph: Some(graph),
+            ..ModelProto::default()
+        };
+        let model = crate::onnx().model_for_proto_model(&proto)?.into_optimized()?;
+        Ok(model.into_runnable()?.run(tvec!(input))?.remove(0))
+    }
+
+    fn node(
+        op_type: &str,
+        inputs: &[&str],
+        output: &str,
+        attribute: Vec<AttributeProto>,
+    ) -> NodeProto {
+        NodeProto {
+            op_type: op_type.to_string(),
+            input: inputs.iter().map(|s| s.to_string()).collect(),
+            output: vec![output.to_string()],
+            attribute,
+            ..NodeProto::default()
+        }
+    }
+
+    fn int_attr(name: &str, i: i64) -> AttributeProto {
+        AttributeProto {
+            name: name.to_string(),
+            r#type: attribute_proto::AttributeType::Int as i32,
+            i,
+            ..AttributeProto::default()
+        }
+    }
+
+    #[test]
+    fn dft_real_onesided() -> TractResult<()> {
+        let dft = node("DFT", &["x"], "y", vec![int_attr("onesided", 1)]);
+        let output = run(vec![dft], &[], tensor3(&[[[1f32], [2.], [3.], [4.]]]))?;
+        output.close_enough(&tensor3(&[[[10f32, 0.], [-2., 2.], [-2., 0.]]]), true)
+    }
+
+    #[test]
+    fn dft_inverse_with_length() -> TractResult<()> {
+        let dft = node("DFT", &["x", "len"], "y", vec![int_attr("inverse", 1)]);
+        let input = tensor3(&[[[10f32, 0.], [-2., 2.], [-2., 0.], [-2., -2.], [7., 7.]]]);
+        let output = run(vec![dft], &[("len", tensor0(4i64))], input)?;
+        output.close_enough(&tensor3(&[[[1f32, 0.], [2., 0.], [3., 0.], [4., 0.]]]), true)
+    }
+
+    #[test]
+    fn stft_with_hann_window() -> TractResult<()> {
+        let window = node("HannWindow", &["size"], "window", vec![]);
+        let stft = node("STFT", &["x", "step", "window"], "y", vec![]);
+        let initializers = [("size", tensor0(4i64)), ("step", tensor0(2i64))];
+        let input = tensor3(&[[[1f32], [2.], [3.], [4.], [5.], [6.]]]);
+        let output = run(vec![window, stft], &initializers, input)?;
+        output.close_enough(
+            &tensor4(&[[[[6f32, 0.], [-3., 1.], [0., 0.]], [[10., 0.], [-5., 1.], [0., 0.]]]]),
+            true,
+        )
+    }
+
+    #[test]
+    fn hann_window() -> TractResult<()> {
+        let window = Window { kind: WindowKind::Hann, periodic: true, dt: DatumType::F32 };
+        window.values(4)?.close_enough(&tensor1(&[0f32, 0.5, 1., 0.5]), true)?;
+        let window = Window { periodic: false, ..window };
+        window.values(5)?.close_enough(&tensor1(&[0f32, 0.5, 1., 0.5, 0.]), true)
+    }
+
+    #[test]
+    fn mel_weight_matrix() -> TractResult<()> {
+        let matrix = MelWeightMatrix { dt: DatumType::F32 }.values(8, 16, 8192., 0., 4096.)?;
+        let expected = tensor2(&[
+            [1f32, 1., 0., 0., 0., 0., 0., 0.],
+            [0., 0., 1., 1., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 1., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 1., 0., 0.],
+            [0., 0., 0., 0., 0., 0.5, 0.5, 0.],
+            [0., 0., 0., 0., 0., 0., 1., 0.],
+            [0., 0., 0., 0., 0., 0., 0., 1.],
+            [0., 0., 0., 0., 0., 0., 0., 0.5],
+            [0., 0., 0., 0., 0., 0., 0., 0.],
+        ]);
+        assert_eq!(matrix, expected);
+        Ok(())
+    }
+}
@@ -16,6 +16,7 @@ mod cast;
 mod cumsum;
 mod d2s;
 mod einsum;
+mod fft;
 mod logic;
 mod math;
 mod ml;
@@ -26,6 +27,8 @@ mod resize;
 mod non_max_suppression;
 pub mod multinomial;
 mod s2d;
+mod sequence;
+mod string;
 
 pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     reg.insert("Cast", cast::cast);
@@ -38,6 +41,7 @@ pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     array::register_all_ops(reg);
     cumsum::register_all_ops(reg);
     d2s::register_all_ops(reg);
+    fft::register_all_ops(reg);
     logic::register_all_ops(reg);
     math::register_all_ops(reg);
     ml::register_all_ops(reg);
@@ -45,6 +49,8 @@ pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     quant::register_all_ops(reg);
     rec::register_all_ops(reg);
     s2d::register_all_ops(reg);
+    sequence::register_all_ops(reg);
+    string::register_all_ops(reg);
 }
 
 fn konst(
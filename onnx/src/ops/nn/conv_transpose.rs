@@ -2,7 +2,7 @@ use std::str;
 
 use crate::model::ParsingContext;
 use crate::pb::NodeProto;
-use tract_core::ops::cnn::deconv::adjustments;
+use tract_core::ops::cnn::deconv::padding_and_adjustments;
 use tract_core::ops::cnn::KernelFormat;
 use tract_hir::ops::cnn::PaddingSpec;
 use tract_hir::ops::nn::DataFormat;
@@ -135,47 +135,35 @@ impl Expansion for ConvTranspose {
                 None
             };
 
-            let op = if let Some(output_shape) = &self.output_shape {
+            let mut pool_spec = PoolSpec::new(
+                DataFormat::NCHW,
+                kernel.shape()[2..].into(),
+                self.padding_spec.clone(),
+                self.dilations.clone(),
+                self.strides.clone(),
+                Some(kernel.shape()[0] * self.group),
+            );
+            let adjustments = if let Some(output_shape) = &self.output_shape {
                 let x_shape = &target.outlet_fact(inputs[0])?.shape;
-                let pool_spec = PoolSpec::new(
-                    DataFormat::NCHW,
-                    kernel.shape()[2..].into(),
-                    self.padding_spec.clone(),
-                    self.dilations.clone(),
-                    self.strides.clone(),
-                    Some(kernel.shape()[0] * self.group),
-                );
-                let adjustments = adjustments(
+                let (padding, adjustments) = padding_and_adjustments(
                     &pool_spec,
                     &x_shape.as_concrete().context("expects concrete dim for deconv")?[2..],
                     &**output_shape,
+                    self.adjustments.as_deref().unwrap_or(&zeros),
                 )?;
-                tract_core::ops::cnn::DeconvUnary::new(
-                    pool_spec,
-                    KernelFormat::OIHW,
-                    kernel.into_arc_tensor(),
-                    bias,
-                    adjustments,
-                    self.group,
-                )
+                pool_spec.padding = padding;
+                adjustments
             } else {
-                let pool_spec = PoolSpec::new(
-                    DataFormat::NCHW,
-                    kernel.shape()[2..].into(),
-                    self.padding_spec.clone(),
-                    self.dilations.clone(),
-                    self.strides.clone(),
-                    Some(kernel.shape()[0] * self.group),
-                );
-                tract_core::ops::cnn::DeconvUnary::new(
-                    pool_spec,
-                    KernelFormat::OIHW,
-                    kernel.into_arc_tensor(),
-                    bias,
-                    self.adjustments.clone().unwrap_or(zeros),
-                    self.group,
-                )
+                self.adjustments.clone().unwrap_or(zeros)
             };
+            let op = tract_core::ops::cnn::DeconvUnary::new(
+                pool_spec,
+                KernelFormat::OIHW,
+                kernel.into_arc_tensor(),
+                bias,
+                adjustments,
+                self.group,
+            );
             target.wire_node(prefix, op, &[inputs[0]])
         } else {
             bail!("Kernel values are expected to be constant.")
@@ -0,0 +1,68 @@
+use crate::model::ParsingContext;
+use crate::pb::NodeProto;
+use tract_hir::internal::*;
+
+pub fn layer_normalization(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let axis = node.get_attr_opt("axis")?.unwrap_or(-1);
+    let epsilon = node.get_attr_opt("epsilon")?.unwrap_or(1e-5);
+    if node.output.iter().skip(1).any(|o| !o.is_empty()) {
+        bail!("LayerNormalization Mean and InvStdDev outputs are not supported");
+    }
+    let has_bias = node.input.get(2).map(|b| !b.is_empty()).unwrap_or(false);
+    Ok((expand(LayerNormalization::new(axis, epsilon, has_bias)), vec![]))
+}
+
+#[derive(Debug, Clone, new, Default, Educe)]
+#[educe(Hash)]
+pub struct LayerNormalization {
+    axis: i64,
+    #[educe(Hash(method = "hash_f32"))]
+    epsilon: f32,
+    has_bias: bool,
+}
+
+impl_dyn_hash!(LayerNormalization);
+
+impl Expansion for LayerNormalization {
+    fn name(&self) -> Cow<str> {
+        "LayerNormalization".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 2 + self.has_bias as usize)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        name: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let fact = model.outlet_fact(inputs[0])?.clone();
+        let axis = if self.axis < 0 { self.axis + fact.rank() as i64 } else { self.axis };
+        let mut inputs: TVec<OutletId> = inputs.into();
+        if !self.has_bias {
+            inputs.push(model.add_const(format!("{}.bias", name), rctensor0(0f32))?);
+        }
+        let op = tract_hir::tract_core::ops::nn::LayerNorm::new(
+            axis as usize,
+            self.epsilon,
+            fact.datum_type,
+        );
+        model.wire_node(name, op, &inputs)
+    }
+}
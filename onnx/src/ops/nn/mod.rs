@@ -10,6 +10,7 @@ mod batch_norm;
 mod conv_transpose;
 mod dropout;
 mod instance_norm;
+mod layer_norm;
 mod lrn;
 mod reduce;
 
@@ -38,12 +39,14 @@ pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     reg.insert("ConvTranspose", conv_transpose::conv_transpose);
     reg.insert("Dropout", dropout::dropout);
     reg.insert("Elu", elu);
+    reg.insert("Gelu", gelu);
     reg.insert("GlobalAveragePool", |_, _| Ok((expand(ops::nn::GlobalAvgPool), vec![])));
     reg.insert("GlobalLpPool", global_lp_pool);
     reg.insert("GlobalMaxPool", |_, _| Ok((expand(ops::nn::GlobalMaxPool), vec![])));
     reg.insert("Hardmax", layer_hard_max);
     reg.insert("HardSigmoid", hard_sigmoid);
     reg.insert("InstanceNormalization", instance_norm::instance_normalization);
+    reg.insert("LayerNormalization", layer_norm::layer_normalization);
     reg.insert("LeakyRelu", leaky_relu);
     reg.insert("LogSoftmax", layer_log_soft_max);
     reg.insert("LRN", lrn::lrn);
@@ -266,6 +269,17 @@ pub fn layer_soft_max(
     }
 }
 
+pub fn gelu(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    match node.get_attr_opt("approximate")?.unwrap_or("none") {
+        "none" => Ok((Box::new(ops::nn::gelu()), vec![])),
+        "tanh" => Ok((Box::new(ops::nn::gelu_approximate()), vec![])),
+        other => bail!("Unsupported Gelu approximation: {}", other),
+    }
+}
+
 pub fn leaky_relu(
     _ctx: &ParsingContext,
     node: &NodeProto,
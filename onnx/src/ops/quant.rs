@@ -14,7 +14,8 @@ fn quantize_linear(
     _ctx: &ParsingContext,
     node: &NodeProto,
 ) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
-    let op = QuantizeLinear::new(Some(2).filter(|_| node.input.len() == 3));
+    let axis = node.get_attr_opt("axis")?.unwrap_or(1);
+    let op = QuantizeLinear::new(Some(2).filter(|_| node.input.len() == 3), axis);
     Ok((expand(op), vec![]))
 }
 
@@ -22,7 +23,8 @@ fn dequantize_linear(
     _ctx: &ParsingContext,
     node: &NodeProto,
 ) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
-    let op = DequantizeLinear::new(Some(2).filter(|_| node.input.len() == 3));
+    let axis = node.get_attr_opt("axis")?.unwrap_or(1);
+    let op = DequantizeLinear::new(Some(2).filter(|_| node.input.len() == 3), axis);
     Ok((expand(op), vec![]))
 }
 
@@ -37,6 +39,7 @@ fn dynamic_quantize_linear(
 #[derive(Debug, Clone, new, Default, Hash)]
 pub struct QuantizeLinear {
     optional_zero_point_input: Option<usize>,
+    axis: i64,
 }
 
 impl_dyn_hash!(QuantizeLinear);
@@ -75,13 +78,8 @@ impl Expansion for QuantizeLinear {
         inputs: &[OutletId],
     ) -> TractResult<TVec<OutletId>> {
         use tract_hir::ops::quant::*;
-        let scale = target
-            .outlet_fact(inputs[1])?
-            .konst
-            .as_ref()
-            .context("y_scale must be a const")?
-            .as_slice::<f32>()?[0]
-            .recip();
+        let scale =
+            target.outlet_fact(inputs[1])?.konst.clone().context("y_scale must be a const")?;
         let zero_point = if self.optional_zero_point_input.is_some() {
             target
                 .outlet_fact(inputs[2])?
@@ -92,6 +90,17 @@ impl Expansion for QuantizeLinear {
         } else {
             rctensor0(0u8)
         };
+        if scale.len() > 1 {
+            return wire_per_axis_quantize(
+                prefix,
+                target,
+                inputs[0],
+                self.axis,
+                &scale,
+                &zero_point,
+            );
+        }
+        let scale = scale.as_slice::<f32>()?[0].recip();
         let op: Box<dyn TypedOp> = if zero_point.datum_type() == u8::datum_type() {
             Box::new(quantize_linear_u8(scale, zero_point.as_slice::<u8>()?[0]))
         } else {
@@ -104,6 +113,7 @@ impl Expansion for QuantizeLinear {
 #[derive(Debug, Clone, new, Default, Hash)]
 pub struct DequantizeLinear {
     optional_zero_point_input: Option<usize>,
+    axis: i64,
 }
 
 impl_dyn_hash!(DequantizeLinear);
@@ -140,12 +150,8 @@ impl Expansion for DequantizeLinear {
         target: &mut TypedModel,
         inputs: &[OutletId],
     ) -> TractResult<TVec<OutletId>> {
-        let scale = target
-            .outlet_fact(inputs[1])?
-            .konst
-            .as_ref()
-            .context("y_scale must be a const")?
-            .as_slice::<f32>()?[0];
+        let scale =
+            target.outlet_fact(inputs[1])?.konst.clone().context("y_scale must be a const")?;
         let zero_point = if self.optional_zero_point_input.is_some() {
             target
                 .outlet_fact(inputs[2])?
@@ -156,6 +162,17 @@ impl Expansion for DequantizeLinear {
         } else {
             rctensor0(0u8)
         };
+        if scale.len() > 1 {
+            return wire_per_axis_dequantize(
+                prefix,
+                target,
+                inputs[0],
+                self.axis,
+                &scale,
+                &zero_point,
+            );
+        }
+        let scale = scale.as_slice::<f32>()?[0];
         let op: Box<dyn TypedOp> = if zero_point.datum_type() == u8::datum_type() {
             Box::new(DequantizeLinearF32::new(scale, zero_point.as_slice::<u8>()?[0] as i32))
         } else if zero_point.datum_type() == i8::datum_type() {
@@ -167,6 +184,79 @@ impl Expansion for DequantizeLinear {
     }
 }
 
+/// Lays a per-axis quantization parameter along `axis` of a tensor of rank
+/// `rank`, as a f32 tensor of the same rank.
+fn per_axis_param(param: &Tensor, rank: usize, axis: i64) -> TractResult<Arc<Tensor>> {
+    let param = param.cast_to::<f32>()?.into_owned();
+    if param.len() == 1 {
+        return Ok(param.into_shape(&[])?.broadcast_into_rank(rank)?.into_arc_tensor());
+    }
+    let axis = if axis < 0 { axis + rank as i64 } else { axis } as usize;
+    ensure!(axis < rank, "Invalid quantization axis {} for a tensor of rank {}", axis, rank);
+    let mut shape = tvec!(1; rank);
+    shape[axis] = param.len();
+    Ok(param.into_shape(&shape)?.into_arc_tensor())
+}
+
+fn wire_per_axis_quantize(
+    prefix: &str,
+    target: &mut TypedModel,
+    input: OutletId,
+    axis: i64,
+    scale: &Tensor,
+    zero_point: &Tensor,
+) -> TractResult<TVec<OutletId>> {
+    use tract_hir::ops::{cast::cast, math};
+    let rank = target.outlet_fact(input)?.rank();
+    let dt = zero_point.datum_type();
+    let scale = per_axis_param(scale, rank, axis)?.to_array_view::<f32>()?.mapv(f32::recip);
+    let zero_point = per_axis_param(zero_point, rank, axis)?;
+    let inf = dt.min_value().cast_to::<f32>()?.into_owned().broadcast_into_rank(rank)?;
+    let sup = dt.max_value().cast_to::<f32>()?.into_owned().broadcast_into_rank(rank)?;
+    let mut wire = tvec!(input);
+    wire = target.wire_node(
+        format!("{}.scale", prefix),
+        math::mul::unary(scale.into_arc_tensor()),
+        &wire,
+    )?;
+    wire = target.wire_node(format!("{}.round", prefix), math::round(), &wire)?;
+    wire =
+        target.wire_node(format!("{}.zero_point", prefix), math::add::unary(zero_point), &wire)?;
+    wire = target.wire_node(
+        format!("{}.max", prefix),
+        math::max::unary(inf.into_arc_tensor()),
+        &wire,
+    )?;
+    wire = target.wire_node(
+        format!("{}.min", prefix),
+        math::min::unary(sup.into_arc_tensor()),
+        &wire,
+    )?;
+    target.wire_node(prefix, cast(dt), &wire)
+}
+
+fn wire_per_axis_dequantize(
+    prefix: &str,
+    target: &mut TypedModel,
+    input: OutletId,
+    axis: i64,
+    scale: &Tensor,
+    zero_point: &Tensor,
+) -> TractResult<TVec<OutletId>> {
+    let rank = target.outlet_fact(input)?.rank();
+    let axis = if axis < 0 { axis + rank as i64 } else { axis } as usize;
+    let scales = scale.cast_to::<f32>()?.into_owned().into_shape(&[scale.len()])?;
+    let zero_points = zero_point.cast_to::<i32>()?.into_owned();
+    let zero_points = if zero_points.len() == 1 {
+        zero_points.into_shape(&[])?.broadcast_scalar_to_shape(&[scales.len()])?
+    } else {
+        zero_points.into_shape(&[zero_point.len()])?
+    };
+    let op =
+        DequantizeLinearPerAxis::new(axis, zero_points.into_arc_tensor(), scales.into_arc_tensor());
+    target.wire_node(prefix, op, &[input])
+}
+
 #[derive(Debug, Clone, new, Default, Hash)]
 pub struct DynamicQuantizeLinear {}
 
@@ -339,6 +429,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_per_axis_quantize_dequantize() {
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", f32::fact(&[2, 3])).unwrap();
+        let scale = model.add_const("scale", rctensor1(&[0.5f32, 2.])).unwrap();
+        let zero_point = model.add_const("zero_point", rctensor1(&[0i8, 10])).unwrap();
+        let q =
+            QuantizeLinear::new(Some(2), 0).wire("q", &mut model, &[x, scale, zero_point]).unwrap();
+        let dq = DequantizeLinear::new(Some(2), -2)
+            .wire("dq", &mut model, &[q[0], scale, zero_point])
+            .unwrap();
+        model.set_output_outlets(&[q[0], dq[0]]).unwrap();
+        let x = tensor2(&[[1f32, -2., 100.], [3., -300., 4.]]);
+        let outputs = model.into_runnable().unwrap().run(tvec!(x)).unwrap();
+        assert_eq!(*outputs[0], tensor2(&[[2i8, -4, 127], [12, -128, 12]]));
+        assert_eq!(*outputs[1], tensor2(&[[1f32, -2., 63.5], [4., -276., 4.]]));
+    }
+
     #[test]
     fn test_dynamic_quantize_linear_u8() {
         let data: [(&[f32], &[u8]); 3] = [
@@ -12,19 +12,19 @@ pub fn resize(
             "align_corners" => CoordTransformer::AlignCorners,
             "half_pixel" => CoordTransformer::HalfPixel,
             "asymmetric" => CoordTransformer::Asymmetric,
-            s => todo!("coordinate_transformation_mode: {}", s),
+            s => bail!("Unsupported coordinate_transformation_mode: {}", s),
         };
     let interpolator = match node.get_attr_opt("mode")?.unwrap_or("nearest") {
         "nearest" => Interpolator::Nearest,
         "linear" => Interpolator::Linear,
-        s => todo!("mode: {}", s),
+        s => bail!("Unsupported mode: {}", s),
     };
     let nearest = match node.get_attr_opt("nearest_mode")?.unwrap_or("round_prefer_floor") {
         "floor" => Nearest::Floor,
         "ceil" => Nearest::Ceil,
         "round_prefer_floor" => Nearest::RoundPreferFloor,
         "round_prefer_ceil" => Nearest::RoundPreferCeil,
-        s => todo!("nearest_mode: {}", s),
+        s => bail!("Unsupported nearest_mode: {}", s),
     };
     let mut options = crate::model::optional_inputs(node).skip(2);
     Ok((
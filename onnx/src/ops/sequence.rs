@@ -0,0 +1,407 @@
+use crate::model::{optional_inputs, OnnxOpRegister, ParsingContext};
+use crate::pb::*;
+use tract_hir::internal::*;
+use tract_hir::tract_core::ops::sequence::*;
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert("SequenceConstruct", |_, _| {
+        Ok((inference_wrap(SequenceConstruct, 1, sequence_rules), vec![]))
+    });
+    reg.insert("SequenceEmpty", |_, _| {
+        Ok((inference_wrap(SequenceConstruct, 1, sequence_rules), vec![]))
+    });
+    reg.insert("SequenceInsert", |_, _| {
+        Ok((inference_wrap(SequenceInsert, 1, sequence_rules), vec![]))
+    });
+    reg.insert("SequenceErase", |_, _| {
+        Ok((inference_wrap(SequenceErase, 1, sequence_rules), vec![]))
+    });
+    reg.insert("SequenceAt", |_, _| Ok((inference_wrap(SequenceAt, 1, sequence_at_rules), vec![])));
+    reg.insert("SequenceLength", |_, _| {
+        Ok((inference_wrap(SequenceLength, 1, sequence_length_rules), vec![]))
+    });
+    reg.insert("SplitToSequence", split_to_sequence);
+    reg.insert("ConcatFromSequence", concat_from_sequence);
+    reg.insert("Optional", |_, _| {
+        Ok((inference_wrap(OptionalConstruct, 1, optional_rules), vec![]))
+    });
+    reg.insert("OptionalHasElement", |_, node| {
+        let input = optional_inputs(node).next().unwrap();
+        Ok((expand(HasElement { input }), vec![]))
+    });
+    reg.insert("OptionalGetElement", |_, _| Ok((expand(GetElement), vec![])));
+}
+
+fn sequence_rules<'r, 'p, 's>(
+    _op: &'s dyn Op,
+    s: &mut Solver<'r>,
+    _inputs: &'p [TensorProxy],
+    outputs: &'p [TensorProxy],
+) -> InferenceResult {
+    check_output_arity(outputs, 1)?;
+    s.equals(&outputs[0].datum_type, DatumType::Sequence)?;
+    s.equals(&outputs[0].rank, 0)?;
+    Ok(())
+}
+
+fn sequence_at_rules<'r, 'p, 's>(
+    _op: &'s dyn Op,
+    s: &mut Solver<'r>,
+    inputs: &'p [TensorProxy],
+    outputs: &'p [TensorProxy],
+) -> InferenceResult {
+    check_input_arity(inputs, 2)?;
+    check_output_arity(outputs, 1)?;
+    s.equals(&inputs[0].datum_type, DatumType::Sequence)?;
+    Ok(())
+}
+
+fn sequence_length_rules<'r, 'p, 's>(
+    _op: &'s dyn Op,
+    s: &mut Solver<'r>,
+    inputs: &'p [TensorProxy],
+    outputs: &'p [TensorProxy],
+) -> InferenceResult {
+    check_input_arity(inputs, 1)?;
+    check_output_arity(outputs, 1)?;
+    s.equals(&outputs[0].datum_type, DatumType::I64)?;
+    s.equals(&outputs[0].rank, 0)?;
+    Ok(())
+}
+
+fn optional_rules<'r, 'p, 's>(
+    _op: &'s dyn Op,
+    s: &mut Solver<'r>,
+    _inputs: &'p [TensorProxy],
+    outputs: &'p [TensorProxy],
+) -> InferenceResult {
+    check_output_arity(outputs, 1)?;
+    s.equals(&outputs[0].datum_type, DatumType::Optional)?;
+    s.equals(&outputs[0].rank, 0)?;
+    Ok(())
+}
+
+fn resolve_axis(axis: i64, rank: usize) -> TractResult<usize> {
+    let resolved = if axis < 0 { axis + rank as i64 } else { axis };
+    ensure!(0 <= resolved && resolved < rank as i64, "Invalid axis {} for rank {}", axis, rank);
+    Ok(resolved as usize)
+}
+
+fn split_to_sequence(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let axis = node.get_attr_opt("axis")?.unwrap_or(0);
+    let keep_dims = node.get_attr_opt::<i64>("keepdims")?.unwrap_or(1) != 0;
+    Ok((expand(SplitToSequenceExpansion { axis, keep_dims }), vec![]))
+}
+
+#[derive(Debug, Clone, Hash)]
+struct SplitToSequenceExpansion {
+    axis: i64,
+    keep_dims: bool,
+}
+
+impl_dyn_hash!(SplitToSequenceExpansion);
+
+impl Expansion for SplitToSequenceExpansion {
+    fn name(&self) -> Cow<str> {
+        "SplitToSequence".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        _inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_output_arity(outputs, 1)?;
+        s.equals(&outputs[0].datum_type, DatumType::Sequence)?;
+        s.equals(&outputs[0].rank, 0)?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let axis = resolve_axis(self.axis, model.outlet_fact(inputs[0])?.rank())?;
+        model.wire_node(prefix, SplitToSequence { axis, keep_dims: self.keep_dims }, inputs)
+    }
+}
+
+fn concat_from_sequence(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let axis = node.get_attr("axis")?;
+    let new_axis = node.get_attr_opt::<i64>("new_axis")?.unwrap_or(0) != 0;
+    Ok((expand(ConcatFromSequenceExpansion { axis, new_axis }), vec![]))
+}
+
+#[derive(Debug, Clone, Hash)]
+struct ConcatFromSequenceExpansion {
+    axis: i64,
+    new_axis: bool,
+}
+
+impl_dyn_hash!(ConcatFromSequenceExpansion);
+
+impl Expansion for ConcatFromSequenceExpansion {
+    fn name(&self) -> Cow<str> {
+        "ConcatFromSequence".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 1)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&inputs[0].datum_type, DatumType::Sequence)?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let fact = model.outlet_fact(inputs[0])?;
+        let rank = match fact.sequence.as_deref() {
+            Some(SequenceFact::Known(facts)) if !facts.is_empty() => facts[0].rank(),
+            Some(SequenceFact::Dynamic(fact)) => fact.rank(),
+            _ => bail!("Can not concatenate {:?}", fact),
+        };
+        let axis = resolve_axis(self.axis, rank + self.new_axis as usize)?;
+        model.wire_node(prefix, ConcatFromSequence { axis, new_axis: self.new_axis }, inputs)
+    }
+}
+
+/// OptionalHasElement also accepts a plain tensor or sequence (always holding an element) or
+/// no input at all (never holding one).
+#[derive(Debug, Clone, Hash)]
+struct HasElement {
+    input: Option<usize>,
+}
+
+impl_dyn_hash!(HasElement);
+
+impl Expansion for HasElement {
+    fn name(&self) -> Cow<str> {
+        "OptionalHasElement".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, self.input.is_some() as usize)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&outputs[0].datum_type, DatumType::Bool)?;
+        s.equals(&outputs[0].rank, 0)?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        if let Some(input) = inputs.first() {
+            if model.outlet_fact(*input)?.datum_type == DatumType::Optional {
+                return model.wire_node(prefix, OptionalHasElement, inputs);
+            }
+        }
+        Ok(tvec!(model.add_const(prefix, tensor0(!inputs.is_empty()))?))
+    }
+}
+
+/// OptionalGetElement also accepts a plain tensor or sequence, returned as is.
+#[derive(Debug, Clone, Hash)]
+struct GetElement;
+
+impl_dyn_hash!(GetElement);
+
+impl Expansion for GetElement {
+    fn name(&self) -> Cow<str> {
+        "OptionalGetElement".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        _s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 1)?;
+        check_output_arity(outputs, 1)?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        if model.outlet_fact(inputs[0])?.datum_type == DatumType::Optional {
+            model.wire_node(prefix, OptionalGetElement, inputs)
+        } else {
+            Ok(inputs.into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryInto;
+
+    fn model(
+        nodes: Vec<NodeProto>,
+        initializers: &[(&str, Tensor)],
+        input: &Tensor,
+    ) -> TractResult<InferenceModel> {
+        let initializer = initializers
+            .iter()
+            .map(|(name, t)| {
+                let mut proto: TensorProto = t.try_into()?;
+                proto.name = name.to_string();
+                Ok(proto)
+            })
+            .collect::<TractResult<_>>()?;
+        let output = nodes.last().unwrap().output[0].clone();
+        let graph = GraphProto {
+            node: nodes,
+            input: vec![crate::ser::value_info("x", &input.datum_type().fact(input.shape()))?],
+            output: vec![ValueInfoProto { name: output, ..ValueInfoProto::default() }],
+            initializer,
+            ..GraphProto::default()
+        };
+        let proto = ModelProto {
+            ir_version: 8,
+            opset_import: vec![OperatorSetIdProto { domain: String::new(), version: 15 }],
+            graph: Some(graph),
+            ..ModelProto::default()
+        };
+        crate::onnx().model_for_proto_model(&proto)
+    }
+
+    /// Run both the typed model, evaluating sequences, and the optimized one, where they
+    /// must have been lowered to plain tensors.
+    fn run(
+        nodes: Vec<NodeProto>,
+        initializers: &[(&str, Tensor)],
+        input: Tensor,
+    ) -> TractResult<Arc<Tensor>> {
+        let model = model(nodes, initializers, &input)?;
+        let typed = model.into_typed()?;
+        let evaluated = typed.clone().into_runnable()?.run(tvec!(input.clone()))?.remove(0);
+        let optimized = typed.into_optimized()?;
+        for node in optimized.nodes() {
+            let dt = node.outputs[0].fact.datum_type;
+            ensure!(dt != DatumType::Sequence && dt != DatumType::Optional, "{}", node);
+        }
+        let lowered = optimized.into_runnable()?.run(tvec!(input))?.remove(0);
+        assert_eq!(evaluated, lowered);
+        Ok(lowered)
+    }
+
+    fn node(
+        op_type: &str,
+        inputs: &[&str],
+        output: &str,
+        attribute: Vec<AttributeProto>,
+    ) -> NodeProto {
+        NodeProto {
+            op_type: op_type.to_string(),
+            input: inputs.iter().map(|s| s.to_string()).collect(),
+            output: vec![output.to_string()],
+            attribute,
+            ..NodeProto::default()
+        }
+    }
+
+    fn int_attr(name: &str, i: i64) -> AttributeProto {
+        AttributeProto {
+            name: name.to_string(),
+            r#type: attribute_proto::AttributeType::Int as i32,
+            i,
+            ..AttributeProto::default()
+        }
+    }
+
+    #[test]
+    fn split_insert_at_concat() -> TractResult<()> {
+        let nodes = vec![
+            node("SplitToSequence", &["x", "split"], "seq", vec![int_attr("axis", -1)]),
+            node("SequenceAt", &["seq", "first"], "head", vec![]),
+            node("SequenceInsert", &["seq", "head"], "longer", vec![]),
+            node("SequenceLength", &["longer"], "len", vec![]),
+            node("SequenceErase", &["longer", "first"], "tail", vec![]),
+            node("ConcatFromSequence", &["tail"], "y", vec![int_attr("axis", 1)]),
+        ];
+        let initializers = [("split", tensor1(&[1i64, 2])), ("first", tensor0(0i64))];
+        let y = run(nodes, &initializers, tensor2(&[[1f32, 2., 3.], [4., 5., 6.]]))?;
+        assert_eq!(y, rctensor2(&[[2f32, 3., 1.], [5., 6., 4.]]));
+        Ok(())
+    }
+
+    #[test]
+    fn split_and_stack() -> TractResult<()> {
+        let nodes = vec![
+            node("SplitToSequence", &["x"], "seq", vec![int_attr("keepdims", 0)]),
+            node(
+                "ConcatFromSequence",
+                &["seq"],
+                "y",
+                vec![int_attr("axis", -1), int_attr("new_axis", 1)],
+            ),
+        ];
+        let y = run(nodes, &[], tensor2(&[[1f32, 2., 3.], [4., 5., 6.]]))?;
+        assert_eq!(y, rctensor2(&[[1f32, 4.], [2., 5.], [3., 6.]]));
+        Ok(())
+    }
+
+    #[test]
+    fn optional_roundtrip() -> TractResult<()> {
+        let nodes = vec![
+            node("Optional", &["x"], "opt", vec![]),
+            node("OptionalHasElement", &["opt"], "has", vec![]),
+            node("OptionalGetElement", &["opt"], "value", vec![]),
+            node("Where", &["has", "value", "zero"], "y", vec![]),
+        ];
+        let y = run(nodes, &[("zero", tensor0(0f32))], tensor1(&[1f32, 2.]))?;
+        assert_eq!(y, rctensor1(&[1f32, 2.]));
+        Ok(())
+    }
+
+    #[test]
+    fn optional_has_element_without_input() -> TractResult<()> {
+        let nodes = vec![
+            node("OptionalHasElement", &[""], "empty", vec![]),
+            node("OptionalHasElement", &["x"], "tensor", vec![]),
+            node("Xor", &["empty", "tensor"], "y", vec![]),
+        ];
+        let y = run(nodes, &[], tensor1(&[1f32]))?;
+        assert_eq!(y, rctensor0(true));
+        Ok(())
+    }
+}
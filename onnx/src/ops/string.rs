@@ -0,0 +1,241 @@
+use crate::model::{OnnxOpRegister, ParsingContext};
+use crate::pb::*;
+use tract_hir::internal::*;
+use tract_hir::tract_core::ops::string::*;
+use tract_onnx_opl::string::*;
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert("RegexFullMatch", regex_full_match);
+    reg.insert("StringNormalizer", string_normalizer);
+    reg.insert("StringSplit", string_split);
+    reg.insert("Tokenizer", tokenizer);
+}
+
+fn regex_full_match(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let op = RegexFullMatch::new(node.get_attr::<String>("pattern")?)?;
+    Ok((inference_wrap(op, 1, regex_full_match_rules), vec![]))
+}
+
+fn regex_full_match_rules<'r, 'p, 's>(
+    _op: &'s dyn Op,
+    s: &mut Solver<'r>,
+    inputs: &'p [TensorProxy],
+    outputs: &'p [TensorProxy],
+) -> InferenceResult {
+    check_input_arity(inputs, 1)?;
+    check_output_arity(outputs, 1)?;
+    s.equals(&inputs[0].datum_type, DatumType::String)?;
+    s.equals(&outputs[0].datum_type, bool::datum_type())?;
+    s.equals(&inputs[0].shape, &outputs[0].shape)?;
+    Ok(())
+}
+
+fn string_normalizer(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let case_change = match node.get_attr_opt::<&str>("case_change_action")?.unwrap_or("NONE") {
+        "LOWER" => Some(CaseChange::Lower),
+        "UPPER" => Some(CaseChange::Upper),
+        "NONE" => None,
+        other => bail!("Unsupported case_change_action {:?}", other),
+    };
+    let case_sensitive = node.get_attr_opt("is_case_sensitive")?.unwrap_or(false);
+    let stopwords = node.get_attr_opt_vec("stopwords")?.unwrap_or_default();
+    let op = StringNormalizer::new(case_change, case_sensitive, stopwords);
+    Ok((inference_wrap(op, 1, string_normalizer_rules), vec![]))
+}
+
+fn string_normalizer_rules<'r, 'p, 's>(
+    _op: &'s dyn Op,
+    s: &mut Solver<'r>,
+    inputs: &'p [TensorProxy],
+    outputs: &'p [TensorProxy],
+) -> InferenceResult {
+    check_input_arity(inputs, 1)?;
+    check_output_arity(outputs, 1)?;
+    s.equals(&inputs[0].datum_type, DatumType::String)?;
+    s.equals(&outputs[0].datum_type, DatumType::String)?;
+    s.equals(&inputs[0].rank, &outputs[0].rank)?;
+    Ok(())
+}
+
+fn string_split(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let separator = match node.get_attr_opt::<String>("delimiter")? {
+        Some(delimiter) if !delimiter.is_empty() => Separator::Exact(delimiter),
+        _ => Separator::Whitespace,
+    };
+    let max_split = node.get_attr_opt::<i64>("maxsplit")?;
+    node.expect_attr("maxsplit", max_split.map(|m| m > 0).unwrap_or(true), "positive")?;
+    let op = StringSplit::new(separator, max_split.map(|m| m as usize));
+    Ok((inference_wrap(op, 2, string_split_rules), vec![]))
+}
+
+fn string_split_rules<'r, 'p, 's>(
+    _op: &'s dyn Op,
+    s: &mut Solver<'r>,
+    inputs: &'p [TensorProxy],
+    outputs: &'p [TensorProxy],
+) -> InferenceResult {
+    check_input_arity(inputs, 1)?;
+    s.equals(&inputs[0].datum_type, DatumType::String)?;
+    s.equals(&outputs[0].datum_type, DatumType::String)?;
+    s.equals(&outputs[0].rank, inputs[0].rank.bex() + 1)?;
+    if let Some(counts) = outputs.get(1) {
+        s.equals(&counts.datum_type, i64::datum_type())?;
+        s.equals(&counts.shape, &inputs[0].shape)?;
+    }
+    Ok(())
+}
+
+fn tokenizer(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let separators: Option<Vec<String>> = node.get_attr_opt_vec("separators")?;
+    let tokenexp: Option<String> = node.get_attr_opt("tokenexp")?;
+    let pattern = match (separators, tokenexp) {
+        (Some(seps), None) if seps.len() == 1 && seps[0].is_empty() => TokenPattern::Chars,
+        (Some(seps), None) => TokenPattern::Separators(seps),
+        (None, Some(exp)) => TokenPattern::Tokens(exp),
+        _ => bail!("Tokenizer requires exactly one of separators and tokenexp"),
+    };
+    let mark = node.get_attr("mark")?;
+    let pad_value = node.get_attr("pad_value")?;
+    let min_chars = node.get_attr("mincharnum")?;
+    let op = Tokenizer::new(pattern, mark, pad_value, min_chars)?;
+    Ok((inference_wrap(op, 1, tokenizer_rules), vec![]))
+}
+
+fn tokenizer_rules<'r, 'p, 's>(
+    _op: &'s dyn Op,
+    s: &mut Solver<'r>,
+    inputs: &'p [TensorProxy],
+    outputs: &'p [TensorProxy],
+) -> InferenceResult {
+    check_input_arity(inputs, 1)?;
+    check_output_arity(outputs, 1)?;
+    s.equals(&inputs[0].datum_type, DatumType::String)?;
+    s.equals(&outputs[0].datum_type, DatumType::String)?;
+    s.equals(&outputs[0].rank, inputs[0].rank.bex() + 1)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(s: &[&str]) -> Tensor {
+        tensor1(&s.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+    }
+
+    fn run(nodes: Vec<NodeProto>, input: Tensor) -> TractResult<TVec<Arc<Tensor>>> {
+        let outputs = nodes.last().unwrap().output.clone();
+        let graph = GraphProto {
+            node: nodes,
+            input: vec![crate::ser::value_info("x", &input.datum_type().fact(input.shape()))?],
+            output: outputs
+                .into_iter()
+                .map(|name| ValueInfoProto { name, ..ValueInfoProto::default() })
+                .collect(),
+            ..GraphProto::default()
+        };
+        let proto = ModelProto {
+            ir_version: 8,
+            opset_import: vec![OperatorSetIdProto { domain: String::new(), version: 20 }],
+            graph: Some(graph),
+            ..ModelProto::default()
+        };
+        let model = crate::onnx().model_for_proto_model(&proto)?;
+        model.into_optimized()?.into_runnable()?.run(tvec!(input))
+    }
+
+    fn node(
+        op_type: &str,
+        input: &str,
+        output: &[&str],
+        attribute: Vec<AttributeProto>,
+    ) -> NodeProto {
+        NodeProto {
+            op_type: op_type.to_string(),
+            input: vec![input.to_string()],
+            output: output.iter().map(|s| s.to_string()).collect(),
+            attribute,
+            ..NodeProto::default()
+        }
+    }
+
+    fn attr(name: &str, r#type: attribute_proto::AttributeType) -> AttributeProto {
+        AttributeProto {
+            name: name.to_string(),
+            r#type: r#type as i32,
+            ..AttributeProto::default()
+        }
+    }
+
+    fn int_attr(name: &str, i: i64) -> AttributeProto {
+        AttributeProto { i, ..attr(name, attribute_proto::AttributeType::Int) }
+    }
+
+    fn string_attr(name: &str, s: &str) -> AttributeProto {
+        AttributeProto {
+            s: s.as_bytes().to_vec(),
+            ..attr(name, attribute_proto::AttributeType::String)
+        }
+    }
+
+    fn strings_attr(name: &str, s: &[&str]) -> AttributeProto {
+        let strings = s.iter().map(|s| s.as_bytes().to_vec()).collect();
+        AttributeProto { strings, ..attr(name, attribute_proto::AttributeType::Strings) }
+    }
+
+    #[test]
+    fn normalize_and_tokenize() -> TractResult<()> {
+        let nodes = vec![
+            node(
+                "StringNormalizer",
+                "x",
+                &["normalized"],
+                vec![string_attr("case_change_action", "LOWER"), strings_attr("stopwords", &["a"])],
+            ),
+            node(
+                "Tokenizer",
+                "normalized",
+                &["y"],
+                vec![
+                    strings_attr("separators", &[" "]),
+                    int_attr("mark", 0),
+                    string_attr("pad_value", "#"),
+                    int_attr("mincharnum", 1),
+                ],
+            ),
+        ];
+        let y = run(nodes, strings(&["Hello World", "A", "tract"]))?;
+        assert_eq!(*y[0], strings(&["hello", "world", "tract", "#"]).into_shape(&[2, 2])?);
+        Ok(())
+    }
+
+    #[test]
+    fn split_and_match() -> TractResult<()> {
+        let split = node(
+            "StringSplit",
+            "x",
+            &["tokens", "counts"],
+            vec![string_attr("delimiter", ","), int_attr("maxsplit", 1)],
+        );
+        let y = run(vec![split.clone()], strings(&["a,b,c", "d"]))?;
+        assert_eq!(*y[0], strings(&["a", "b,c", "d", ""]).into_shape(&[2, 2])?);
+        assert_eq!(*y[1], tensor1(&[2i64, 1]));
+        let matcher =
+            node("RegexFullMatch", "tokens", &["y"], vec![string_attr("pattern", "[a-z]")]);
+        let y = run(vec![split, matcher], strings(&["a,b,c", "d"]))?;
+        assert_eq!(*y[0], tensor2(&[[true, false], [true, false]]));
+        Ok(())
+    }
+}
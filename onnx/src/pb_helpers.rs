@@ -99,8 +99,11 @@ pub trait AttrScalarType<'a>: 'a + Sized {
 impl<'a> AttrScalarType<'a> for DatumType {
     fn get_attr_opt_scalar(node: &'a NodeProto, name: &str) -> TractResult<Option<Self>> {
         i32::get_attr_opt_scalar(node, name)?
-            .map(tensor_proto::DataType::from_i32)
-            .map(|d| d.unwrap().try_into())
+            .map(|d| {
+                tensor_proto::DataType::from_i32(d)
+                    .with_context(|| format!("Invalid data type {}", d))?
+                    .try_into()
+            })
             .transpose()
     }
 }
@@ -108,7 +111,7 @@ impl<'a> AttrScalarType<'a> for DatumType {
 impl<'a> AttrScalarType<'a> for Tensor {
     fn get_attr_opt_scalar(node: &'a NodeProto, name: &str) -> TractResult<Option<Self>> {
         node.get_attr_opt_with_type(name, AttributeType::Tensor)?
-            .map(|attr| attr.t.as_ref().unwrap().try_into())
+            .map(|attr| attr.t.as_ref().context("Tensor attribute without value")?.try_into())
             .transpose()
     }
 }
@@ -161,7 +164,8 @@ impl<'a> AttrScalarType<'a> for usize {
 
 impl<'a> AttrScalarType<'a> for &'a GraphProto {
     fn get_attr_opt_scalar(node: &'a NodeProto, name: &str) -> TractResult<Option<Self>> {
-        node.get_attr_opt_with_type(name, AttributeType::Graph)?.and_ok(|a| a.g.as_ref().unwrap())
+        node.get_attr_opt_with_type(name, AttributeType::Graph)?
+            .and_try(|a| a.g.as_ref().context("Graph attribute without value"))
     }
 }
 
@@ -335,7 +339,7 @@ impl NodeProto {
             Some(attr) => attr,
             _ => return Ok(None),
         };
-        self.expect_attr(name, AttributeType::from_i32(attr.r#type).unwrap() == ty, || {
+        self.expect_attr(name, AttributeType::from_i32(attr.r#type) == Some(ty), || {
             format!("{}, got {}", ty, attr.r#type)
         })?;
         Ok(Some(attr))
@@ -0,0 +1,519 @@
+use std::any::TypeId;
+use std::convert::TryInto;
+
+use tract_hir::internal::*;
+use tract_hir::tract_core::ops;
+use tract_hir::tract_core::ops::cnn::{KernelFormat, PaddingSpec, PoolSpec};
+
+use super::{float, int, ints, string, IntoOnnx, OnnxDumpRegister};
+use crate::pb::tensor_proto::DataType;
+use crate::pb::AttributeProto;
+
+type Dumped = TractResult<Option<TVec<String>>>;
+
+pub fn register_all_dumpers(reg: &mut OnnxDumpRegister) {
+    macro_rules! dumper {
+        ($op:ty, $path: path) => {
+            reg.register_dumper(TypeId::of::<$op>(), |onnx, node| {
+                $path(onnx, node, node.op().downcast_ref::<$op>().unwrap())
+            })
+        };
+    }
+    macro_rules! element_wise {
+        ($op:ty, $path: path) => {
+            reg.register_element_wise(TypeId::of::<$op>(), |onnx, node| {
+                let op = node.op_as::<ops::element_wise::ElementWiseOp>().unwrap();
+                $path(onnx, node, op.0.downcast_ref::<$op>().unwrap())
+            })
+        };
+    }
+
+    dumper!(ops::konst::Const, konst);
+    dumper!(ops::cast::Cast, cast);
+    dumper!(ops::change_axes::AxisOp, axis_op);
+    dumper!(ops::array::TypedConcat, concat);
+    dumper!(ops::array::Slice, slice);
+    dumper!(ops::Downsample, downsample);
+    dumper!(ops::array::Gather, gather);
+    dumper!(ops::array::Pad, pad);
+    dumper!(ops::array::Tile, tile);
+    dumper!(ops::array::MultiBroadcastTo, broadcast);
+    dumper!(ops::logic::Iff, iff);
+    dumper!(ops::matmul::MatMul, matmul);
+    dumper!(ops::matmul::MatMulUnary, matmul_unary);
+    dumper!(ops::nn::Reduce, reduce);
+    dumper!(ops::nn::Softmax, softmax);
+    dumper!(ops::cnn::ConvUnary, conv);
+    dumper!(ops::cnn::DeconvUnary, deconv);
+    dumper!(ops::cnn::MaxPool, max_pool);
+    dumper!(ops::cnn::SumPool, sum_pool);
+
+    reg.register_binary("Add", &ops::math::Add);
+    reg.register_binary("Sub", &ops::math::Sub);
+    reg.register_binary("Mul", &ops::math::Mul);
+    reg.register_binary("Div", &ops::math::Div);
+    reg.register_binary("Pow", &ops::math::Pow);
+    reg.register_binary_flipped("Pow", &ops::math::FlippedPow);
+    reg.register_binary("Min", &ops::math::Min);
+    reg.register_binary("Max", &ops::math::Max);
+    reg.register_binary("And", &ops::logic::And);
+    reg.register_binary("Or", &ops::logic::Or);
+    reg.register_binary("Xor", &ops::logic::Xor);
+    reg.register_binary("Equal", &ops::logic::Equals);
+    reg.register_binary("Less", &ops::logic::Less);
+    reg.register_binary("LessOrEqual", &ops::logic::LessEqual);
+    reg.register_binary("Greater", &ops::logic::Greater);
+    reg.register_binary("GreaterOrEqual", &ops::logic::GreaterEqual);
+
+    reg.register_unit_element_wise("Abs", &ops::math::Abs {});
+    reg.register_unit_element_wise("Exp", &ops::math::Exp {});
+    reg.register_unit_element_wise("Log", &ops::math::Ln {});
+    reg.register_unit_element_wise("Sqrt", &ops::math::Sqrt {});
+    reg.register_unit_element_wise("Reciprocal", &ops::math::Recip {});
+    reg.register_unit_element_wise("Neg", &ops::math::Neg {});
+    reg.register_unit_element_wise("Sign", &ops::math::Sign {});
+    reg.register_unit_element_wise("Ceil", &ops::math::Ceil {});
+    reg.register_unit_element_wise("Floor", &ops::math::Floor {});
+    reg.register_unit_element_wise("Round", &ops::math::RoundHalfToEven {});
+    reg.register_unit_element_wise("Sin", &ops::math::Sin {});
+    reg.register_unit_element_wise("Cos", &ops::math::Cos {});
+    reg.register_unit_element_wise("Tan", &ops::math::Tan {});
+    reg.register_unit_element_wise("Asin", &ops::math::Asin {});
+    reg.register_unit_element_wise("Acos", &ops::math::Acos {});
+    reg.register_unit_element_wise("Atan", &ops::math::Atan {});
+    reg.register_unit_element_wise("Sinh", &ops::math::Sinh {});
+    reg.register_unit_element_wise("Cosh", &ops::math::Cosh {});
+    reg.register_unit_element_wise("Tanh", &ops::math::Tanh {});
+    reg.register_unit_element_wise("Asinh", &ops::math::Asinh {});
+    reg.register_unit_element_wise("Acosh", &ops::math::Acosh {});
+    reg.register_unit_element_wise("Atanh", &ops::math::Atanh {});
+    reg.register_unit_element_wise("Sigmoid", &ops::nn::Sigmoid {});
+    reg.register_unit_element_wise("Not", &ops::logic::Not {});
+    reg.register_unit_element_wise("Erf", &tract_onnx_opl::erf::Erf {});
+    reg.register_unit_element_wise("IsNaN", &tract_onnx_opl::is_nan::IsNan {});
+
+    element_wise!(ops::nn::LeakyRelu, leaky_relu);
+    element_wise!(ops::math::Rsqrt, rsqrt);
+    element_wise!(ops::math::Square, square);
+}
+
+/// Wire a chain of single-input ONNX nodes, the last one computing the node outputs.
+/// Each step is an operator name, extra inputs following the chained value, and attributes.
+fn chain(
+    onnx: &mut IntoOnnx,
+    node: &TypedNode,
+    mut wire: String,
+    steps: Vec<(&str, Vec<String>, Vec<AttributeProto>)>,
+) -> TractResult<TVec<String>> {
+    let ((op_type, extra, attributes), init) = match steps.split_last() {
+        Some(split) => split,
+        None => bail!("Empty chain of ONNX nodes for {}", node),
+    };
+    for (op_type, extra, attributes) in init {
+        let mut inputs = vec![wire];
+        inputs.extend(extra.iter().cloned());
+        let name = format!("{}.{}", node.name, op_type.to_lowercase());
+        wire = onnx.wire(&name, op_type, inputs, attributes.clone());
+    }
+    let mut inputs = vec![wire];
+    inputs.extend(extra.iter().cloned());
+    Ok(onnx.wire_node(node, op_type, inputs, attributes.clone()))
+}
+
+fn to_i64s(dims: &[TDim]) -> TractResult<Vec<i64>> {
+    dims.iter().map(|d| d.to_i64()).collect()
+}
+
+fn swap_last_two(rank: usize) -> Vec<i64> {
+    let mut perm: Vec<i64> = (0..rank as i64).collect();
+    perm.swap(rank - 2, rank - 1);
+    perm
+}
+
+fn konst(onnx: &mut IntoOnnx, node: &TypedNode, op: &ops::konst::Const) -> Dumped {
+    let name = onnx.model.outlet_label(OutletId::new(node.id, 0)).unwrap_or(&node.name).to_string();
+    Ok(Some(tvec!(onnx.konst(&name, &op.0)?)))
+}
+
+fn cast(onnx: &mut IntoOnnx, node: &TypedNode, op: &ops::cast::Cast) -> Dumped {
+    let to: DataType = op.to.try_into()?;
+    let input = onnx.input(node, 0);
+    Ok(Some(onnx.wire_node(node, "Cast", vec![input], vec![int("to", to as i64)])))
+}
+
+fn axis_op(onnx: &mut IntoOnnx, node: &TypedNode, op: &ops::change_axes::AxisOp) -> Dumped {
+    use ops::change_axes::AxisOp;
+    let input = onnx.input(node, 0);
+    let rank = onnx.model.outlet_fact(node.inputs[0])?.rank();
+    let (op_type, extra, attributes) = match op {
+        AxisOp::Add(axis) => {
+            let axes = onnx.konst_i64s(&format!("{}.axes", node.name), &[*axis as i64])?;
+            ("Unsqueeze", vec![axes], vec![])
+        }
+        AxisOp::Rm(axis) => {
+            let axes = onnx.konst_i64s(&format!("{}.axes", node.name), &[*axis as i64])?;
+            ("Squeeze", vec![axes], vec![])
+        }
+        AxisOp::Move(from, to) => {
+            let mut perm: Vec<i64> = (0..rank as i64).collect();
+            let axis = perm.remove(*from);
+            perm.insert(*to, axis);
+            ("Transpose", vec![], vec![ints("perm", perm)])
+        }
+        AxisOp::Reshape(at, _, _) => {
+            // 0 copies the input dimension, -1 is inferred: enough for one symbolic dim
+            // after the reshaped axes
+            let mut shape = vec![];
+            for (ix, d) in node.outputs[0].fact.shape.iter().enumerate() {
+                shape.push(match d.to_i64() {
+                    Ok(d) => d,
+                    Err(_) if ix < *at => 0,
+                    Err(_) if !shape.contains(&-1) => -1,
+                    Err(_) => return Ok(None),
+                });
+            }
+            let shape = onnx.konst_i64s(&format!("{}.shape", node.name), &shape)?;
+            ("Reshape", vec![shape], vec![])
+        }
+    };
+    let mut inputs = vec![input];
+    inputs.extend(extra);
+    Ok(Some(onnx.wire_node(node, op_type, inputs, attributes)))
+}
+
+fn concat(onnx: &mut IntoOnnx, node: &TypedNode, op: &ops::array::TypedConcat) -> Dumped {
+    let mut vars = node.inputs.iter();
+    let mut inputs = vec![];
+    for (ix, slice) in op.slices.iter().enumerate() {
+        inputs.push(match slice {
+            ops::array::ConcatSlice::Const(t) => onnx.konst(&format!("{}.{}", node.name, ix), t)?,
+            ops::array::ConcatSlice::Var => onnx.mapping[vars.next().unwrap()].clone(),
+        });
+    }
+    Ok(Some(onnx.wire_node(node, "Concat", inputs, vec![int("axis", op.axis as i64)])))
+}
+
+fn slice(onnx: &mut IntoOnnx, node: &TypedNode, op: &ops::array::Slice) -> Dumped {
+    let dim = &onnx.model.outlet_fact(node.inputs[0])?.shape[op.axis];
+    let start = if let Ok(start) = op.start.to_i64() { start } else { return Ok(None) };
+    let end = if &op.end == dim {
+        i64::MAX
+    } else if let Ok(end) = op.end.to_i64() {
+        end
+    } else {
+        return Ok(None);
+    };
+    slice_node(onnx, node, op.axis, start, end, 1)
+}
+
+fn downsample(onnx: &mut IntoOnnx, node: &TypedNode, op: &ops::Downsample) -> Dumped {
+    let modulo = op.modulo as i64;
+    if op.stride > 0 {
+        slice_node(onnx, node, op.axis, modulo, i64::MAX, op.stride as i64)
+    } else {
+        // walk backward from the last item, stopping at modulo
+        let end = if modulo == 0 { i64::MIN } else { modulo - 1 };
+        slice_node(onnx, node, op.axis, -1, end, op.stride as i64)
+    }
+}
+
+fn slice_node(
+    onnx: &mut IntoOnnx,
+    node: &TypedNode,
+    axis: usize,
+    start: i64,
+    end: i64,
+    step: i64,
+) -> Dumped {
+    let input = onnx.input(node, 0);
+    let starts = onnx.konst_i64s(&format!("{}.starts", node.name), &[start])?;
+    let ends = onnx.konst_i64s(&format!("{}.ends", node.name), &[end])?;
+    let axes = onnx.konst_i64s(&format!("{}.axes", node.name), &[axis as i64])?;
+    let mut inputs = vec![input, starts, ends, axes];
+    if step != 1 {
+        inputs.push(onnx.konst_i64s(&format!("{}.steps", node.name), &[step])?);
+    }
+    Ok(Some(onnx.wire_node(node, "Slice", inputs, vec![])))
+}
+
+fn gather(onnx: &mut IntoOnnx, node: &TypedNode, op: &ops::array::Gather) -> Dumped {
+    let inputs = vec![onnx.input(node, 0), onnx.input(node, 1)];
+    Ok(Some(onnx.wire_node(node, "Gather", inputs, vec![int("axis", op.axis as i64)])))
+}
+
+fn pad(onnx: &mut IntoOnnx, node: &TypedNode, op: &ops::array::Pad) -> Dumped {
+    use ops::array::PadMode;
+    let pads: Vec<i64> =
+        op.pads.iter().map(|p| p.0 as i64).chain(op.pads.iter().map(|p| p.1 as i64)).collect();
+    let mut inputs = vec![onnx.input(node, 0)];
+    inputs.push(onnx.konst_i64s(&format!("{}.pads", node.name), &pads)?);
+    let mode = match &op.mode {
+        PadMode::Constant(value) => {
+            let dt = node.outputs[0].fact.datum_type;
+            let value = value.cast_to_dt(dt)?.into_owned().into_shape(&[])?;
+            inputs.push(onnx.konst(&format!("{}.value", node.name), &value)?);
+            "constant"
+        }
+        PadMode::Reflect => "reflect",
+        PadMode::Edge => "edge",
+    };
+    Ok(Some(onnx.wire_node(node, "Pad", inputs, vec![string("mode", mode)])))
+}
+
+fn tile(onnx: &mut IntoOnnx, node: &TypedNode, op: &ops::array::Tile) -> Dumped {
+    let repeats = if let Ok(r) = to_i64s(&op.multipliers) { r } else { return Ok(None) };
+    let input = onnx.input(node, 0);
+    let repeats = onnx.konst_i64s(&format!("{}.repeats", node.name), &repeats)?;
+    Ok(Some(onnx.wire_node(node, "Tile", vec![input, repeats], vec![])))
+}
+
+fn broadcast(onnx: &mut IntoOnnx, node: &TypedNode, op: &ops::array::MultiBroadcastTo) -> Dumped {
+    let shape = if let Ok(s) = to_i64s(&op.shape) { s } else { return Ok(None) };
+    let input = onnx.input(node, 0);
+    let shape = onnx.konst_i64s(&format!("{}.shape", node.name), &shape)?;
+    Ok(Some(onnx.wire_node(node, "Expand", vec![input, shape], vec![])))
+}
+
+fn iff(onnx: &mut IntoOnnx, node: &TypedNode, _op: &ops::logic::Iff) -> Dumped {
+    let inputs = (0..3).map(|ix| onnx.input(node, ix)).collect();
+    Ok(Some(onnx.wire_node(node, "Where", inputs, vec![])))
+}
+
+fn matmul(onnx: &mut IntoOnnx, node: &TypedNode, op: &ops::matmul::MatMul) -> Dumped {
+    let (mut a, mut b) = (onnx.input(node, 0), onnx.input(node, 1));
+    if op.a_trans {
+        let rank = onnx.model.outlet_fact(node.inputs[0])?.rank();
+        let perm = ints("perm", swap_last_two(rank));
+        a = onnx.wire(&format!("{}.a_trans", node.name), "Transpose", vec![a], vec![perm]);
+    }
+    if op.b_trans {
+        let rank = onnx.model.outlet_fact(node.inputs[1])?.rank();
+        let perm = ints("perm", swap_last_two(rank));
+        b = onnx.wire(&format!("{}.b_trans", node.name), "Transpose", vec![b], vec![perm]);
+    }
+    matmul_node(onnx, node, a, b, op.c_trans)
+}
+
+fn matmul_unary(onnx: &mut IntoOnnx, node: &TypedNode, op: &ops::matmul::MatMulUnary) -> Dumped {
+    let mut a = op.a.clone().into_tensor();
+    if op.a_trans {
+        let rank = a.rank();
+        a = a.move_axis(rank - 1, rank - 2)?;
+    }
+    let a = onnx.konst(&format!("{}.a", node.name), &a)?;
+    let mut b = onnx.input(node, 0);
+    if op.b_trans {
+        let rank = onnx.model.outlet_fact(node.inputs[0])?.rank();
+        let perm = ints("perm", swap_last_two(rank));
+        b = onnx.wire(&format!("{}.b_trans", node.name), "Transpose", vec![b], vec![perm]);
+    }
+    matmul_node(onnx, node, a, b, op.c_trans)
+}
+
+fn matmul_node(
+    onnx: &mut IntoOnnx,
+    node: &TypedNode,
+    a: String,
+    b: String,
+    c_trans: bool,
+) -> Dumped {
+    if c_trans {
+        let perm = ints("perm", swap_last_two(node.outputs[0].fact.rank()));
+        Ok(Some(chain(
+            onnx,
+            node,
+            a,
+            vec![("MatMul", vec![b], vec![]), ("Transpose", vec![], vec![perm])],
+        )?))
+    } else {
+        Ok(Some(onnx.wire_node(node, "MatMul", vec![a, b], vec![])))
+    }
+}
+
+fn reduce(onnx: &mut IntoOnnx, node: &TypedNode, op: &ops::nn::Reduce) -> Dumped {
+    use ops::nn::Reducer;
+    let input = onnx.input(node, 0);
+    let axes: Vec<i64> = op.axes.iter().map(|&a| a as i64).collect();
+    let keep = int("keepdims", 1);
+    let dumped = match op.reducer {
+        Reducer::Sum => {
+            let axes = onnx.konst_i64s(&format!("{}.axes", node.name), &axes)?;
+            onnx.wire_node(node, "ReduceSum", vec![input, axes], vec![keep])
+        }
+        Reducer::Max => {
+            onnx.wire_node(node, "ReduceMax", vec![input], vec![ints("axes", axes), keep])
+        }
+        Reducer::Min => {
+            onnx.wire_node(node, "ReduceMin", vec![input], vec![ints("axes", axes), keep])
+        }
+        Reducer::Prod => {
+            onnx.wire_node(node, "ReduceProd", vec![input], vec![ints("axes", axes), keep])
+        }
+        Reducer::ArgMax(last) | Reducer::ArgMin(last) if axes.len() == 1 => {
+            let op_type = if let Reducer::ArgMax(_) = op.reducer { "ArgMax" } else { "ArgMin" };
+            let attributes =
+                vec![int("axis", axes[0]), keep, int("select_last_index", last as i64)];
+            onnx.wire_node(node, op_type, vec![input], attributes)
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(dumped))
+}
+
+fn softmax(onnx: &mut IntoOnnx, node: &TypedNode, op: &ops::nn::Softmax) -> Dumped {
+    if op.axes.len() != 1 {
+        return Ok(None);
+    }
+    let input = onnx.input(node, 0);
+    Ok(Some(onnx.wire_node(node, "Softmax", vec![input], vec![int("axis", op.axes[0] as i64)])))
+}
+
+fn leaky_relu(onnx: &mut IntoOnnx, node: &TypedNode, op: &ops::nn::LeakyRelu) -> Dumped {
+    let input = onnx.input(node, 0);
+    Ok(Some(onnx.wire_node(node, "LeakyRelu", vec![input], vec![float("alpha", op.alpha)])))
+}
+
+fn rsqrt(onnx: &mut IntoOnnx, node: &TypedNode, _op: &ops::math::Rsqrt) -> Dumped {
+    let input = onnx.input(node, 0);
+    Ok(Some(chain(
+        onnx,
+        node,
+        input,
+        vec![("Sqrt", vec![], vec![]), ("Reciprocal", vec![], vec![])],
+    )?))
+}
+
+fn square(onnx: &mut IntoOnnx, node: &TypedNode, _op: &ops::math::Square) -> Dumped {
+    let input = onnx.input(node, 0);
+    Ok(Some(onnx.wire_node(node, "Mul", vec![input.clone(), input], vec![])))
+}
+
+/// Attributes common to Conv, ConvTranspose and pooling operators.
+fn pool_attributes(pool_spec: &PoolSpec, pool: bool) -> Vec<AttributeProto> {
+    let as_i64 = |v: &[usize]| v.iter().map(|&x| x as i64).collect::<Vec<_>>();
+    let mut attributes = vec![
+        ints("kernel_shape", as_i64(&pool_spec.kernel_shape)),
+        ints("strides", as_i64(&pool_spec.strides())),
+    ];
+    if pool_spec.dilations().iter().any(|&d| d != 1) {
+        attributes.push(ints("dilations", as_i64(&pool_spec.dilations())));
+    }
+    match &pool_spec.padding {
+        PaddingSpec::Explicit(before, after, ceil_mode) => {
+            attributes.push(ints("pads", as_i64(before).into_iter().chain(as_i64(after))));
+            if pool && *ceil_mode {
+                attributes.push(int("ceil_mode", 1));
+            }
+        }
+        PaddingSpec::Valid => attributes.push(string("auto_pad", "VALID")),
+        PaddingSpec::SameUpper => attributes.push(string("auto_pad", "SAME_UPPER")),
+        PaddingSpec::SameLower => attributes.push(string("auto_pad", "SAME_LOWER")),
+    }
+    attributes
+}
+
+/// Wire a convolution or pooling operator, converting from and to the data format of the
+/// pool spec, as ONNX works with NCHW only.
+fn cnn(
+    onnx: &mut IntoOnnx,
+    node: &TypedNode,
+    pool_spec: &PoolSpec,
+    op_type: &str,
+    extra: Vec<String>,
+    attributes: Vec<AttributeProto>,
+) -> TractResult<TVec<String>> {
+    let fmt = pool_spec.data_format;
+    let geo_rank = pool_spec.rank() as i64;
+    let mut wire = onnx.input(node, 0);
+    if !fmt.has_n() {
+        let axes = onnx.konst_i64s(&format!("{}.n_axis", node.name), &[0])?;
+        wire = onnx.wire(&format!("{}.add_n", node.name), "Unsqueeze", vec![wire, axes], vec![]);
+    }
+    if fmt.c_is_last() {
+        let perm = std::iter::once(0).chain(std::iter::once(geo_rank + 1)).chain(1..=geo_rank);
+        let name = format!("{}.to_nchw", node.name);
+        wire = onnx.wire(&name, "Transpose", vec![wire], vec![ints("perm", perm)]);
+    }
+    let mut steps = vec![(op_type, extra, attributes)];
+    if fmt.c_is_last() {
+        let perm = std::iter::once(0).chain(2..geo_rank + 2).chain(std::iter::once(1));
+        steps.push(("Transpose", vec![], vec![ints("perm", perm)]));
+    }
+    if !fmt.has_n() {
+        let axes = onnx.konst_i64s(&format!("{}.n_axis", node.name), &[0])?;
+        steps.push(("Squeeze", vec![axes], vec![]));
+    }
+    chain(onnx, node, wire, steps)
+}
+
+fn bias(
+    onnx: &mut IntoOnnx,
+    node: &TypedNode,
+    bias: &Tensor,
+    channels: usize,
+) -> TractResult<String> {
+    let bias = if bias.len() == 1 {
+        bias.clone().into_shape(&[])?.broadcast_scalar_to_shape(&[channels])?
+    } else {
+        bias.clone().into_shape(&[channels])?
+    };
+    onnx.konst(&format!("{}.bias", node.name), &bias)
+}
+
+fn conv(onnx: &mut IntoOnnx, node: &TypedNode, op: &ops::cnn::ConvUnary) -> Dumped {
+    if op.q_params.is_some() {
+        return Ok(None);
+    }
+    // tract HWIO: H W I/g O, tract OIHW and ONNX: O I/g H W
+    let mut kernel = op.kernel.clone().into_tensor();
+    if op.kernel_fmt == KernelFormat::HWIO {
+        let geo_rank = kernel.rank() - 2;
+        kernel = kernel.move_axis(geo_rank, 0)?.move_axis(geo_rank + 1, 0)?;
+    }
+    let channels = kernel.shape()[0];
+    let mut extra = vec![onnx.konst(&format!("{}.kernel", node.name), &kernel)?];
+    if let Some(b) = &op.bias {
+        extra.push(bias(onnx, node, b, channels)?);
+    }
+    let mut attributes = pool_attributes(&op.pool_spec, false);
+    attributes.push(int("group", op.group as i64));
+    Ok(Some(cnn(onnx, node, &op.pool_spec, "Conv", extra, attributes)?))
+}
+
+fn deconv(onnx: &mut IntoOnnx, node: &TypedNode, op: &ops::cnn::DeconvUnary) -> Dumped {
+    // tract HWIO: H W I O/g, tract OIHW: O/g I H W, ONNX: I O/g H W
+    let mut kernel = op.kernel.clone().into_tensor();
+    if op.kernel_format == KernelFormat::HWIO {
+        let geo_rank = kernel.rank() - 2;
+        kernel = kernel.move_axis(geo_rank, 0)?.move_axis(geo_rank + 1, 0)?;
+    }
+    kernel = kernel.move_axis(1, 0)?;
+    let channels = kernel.shape()[1] * op.group;
+    let mut extra = vec![onnx.konst(&format!("{}.kernel", node.name), &kernel)?];
+    if let Some(b) = &op.bias {
+        extra.push(bias(onnx, node, b, channels)?);
+    }
+    let mut attributes = pool_attributes(&op.pool_spec, false);
+    attributes.push(int("group", op.group as i64));
+    if op.adjustments.iter().any(|&a| a != 0) {
+        attributes.push(ints("output_padding", op.adjustments.iter().map(|&a| a as i64)));
+    }
+    Ok(Some(cnn(onnx, node, &op.pool_spec, "ConvTranspose", extra, attributes)?))
+}
+
+fn max_pool(onnx: &mut IntoOnnx, node: &TypedNode, op: &ops::cnn::MaxPool) -> Dumped {
+    if op.with_index_outputs.is_some() {
+        return Ok(None);
+    }
+    let attributes = pool_attributes(&op.pool_spec, true);
+    Ok(Some(cnn(onnx, node, &op.pool_spec, "MaxPool", vec![], attributes)?))
+}
+
+fn sum_pool(onnx: &mut IntoOnnx, node: &TypedNode, op: &ops::cnn::SumPool) -> Dumped {
+    if !op.normalize || op.pool_spec.dilations().iter().any(|&d| d != 1) {
+        return Ok(None);
+    }
+    let mut attributes = pool_attributes(&op.pool_spec, true);
+    attributes.push(int("count_include_pad", op.count_include_pad as i64));
+    Ok(Some(cnn(onnx, node, &op.pool_spec, "AveragePool", vec![], attributes)?))
+}
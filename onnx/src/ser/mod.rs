@@ -0,0 +1,455 @@
+//! Serialization of decluttered TypedModel to ONNX.
+//!
+//! Each tract operator is translated by a dumper, looked up by operator TypeId in
+//! the OnnxDumpRegister of the framework. Element-wise and binary operators are looked up
+//! by their mini-op. Operators without an ONNX equivalent make the export fail.
+use std::any::TypeId;
+use std::collections::HashSet;
+use std::convert::TryInto;
+
+use tract_hir::internal::*;
+use tract_hir::tract_core::ops;
+use tract_hir::tract_core::ops::binary::BinMiniOp;
+
+use crate::model::Onnx;
+use crate::pb::tensor_proto::DataType;
+use crate::pb::*;
+
+pub mod dumpers;
+
+/// ONNX operator set version of exported models.
+pub const OPSET_VERSION: i64 = 13;
+/// ONNX IR version matching OPSET_VERSION.
+pub const IR_VERSION: i64 = 7;
+
+/// Translate a node, returning the ONNX value names of its outputs, or None if the
+/// operator configuration can not be expressed in ONNX.
+pub type OpDumper = fn(&mut IntoOnnx, node: &TypedNode) -> TractResult<Option<TVec<String>>>;
+
+#[derive(Clone, Default)]
+pub struct OnnxDumpRegister {
+    pub dumpers: HashMap<TypeId, OpDumper>,
+    pub element_wise: HashMap<TypeId, OpDumper>,
+    pub unit_element_wise: HashMap<TypeId, String>,
+    pub binary: HashMap<TypeId, (String, bool)>,
+}
+
+impl OnnxDumpRegister {
+    pub fn register_dumper(&mut self, id: TypeId, func: OpDumper) {
+        self.dumpers.insert(id, func);
+    }
+
+    /// Register a dumper for an element-wise mini-op with parameters.
+    pub fn register_element_wise(&mut self, id: TypeId, func: OpDumper) {
+        self.element_wise.insert(id, func);
+    }
+
+    /// Register a parameter-less element-wise mini-op mapping to a single ONNX operator.
+    pub fn register_unit_element_wise(&mut self, op_type: &str, ew: &dyn ElementWiseMiniOp) {
+        self.unit_element_wise.insert(ew.type_id(), op_type.to_string());
+    }
+
+    pub fn register_binary(&mut self, op_type: &str, op: &dyn BinMiniOp) {
+        self.binary.insert(op.type_id(), (op_type.to_string(), false));
+    }
+
+    /// Register a binary mini-op computing the ONNX operator with swapped operands.
+    pub fn register_binary_flipped(&mut self, op_type: &str, op: &dyn BinMiniOp) {
+        self.binary.insert(op.type_id(), (op_type.to_string(), true));
+    }
+
+    pub fn serialize(
+        &self,
+        onnx: &mut IntoOnnx,
+        node: &TypedNode,
+    ) -> TractResult<Option<TVec<String>>> {
+        if node.op_is::<ops::identity::Identity>() {
+            return Ok(Some(tvec!(onnx.input(node, 0))));
+        } else if let Some(op) = node.op_as::<ops::element_wise::ElementWiseOp>() {
+            let id = op.0.as_ref().type_id();
+            if let Some(op_type) = self.unit_element_wise.get(&id) {
+                let input = onnx.input(node, 0);
+                return Ok(Some(onnx.wire_node(node, op_type, vec![input], vec![])));
+            } else if let Some(dumper) = self.element_wise.get(&id) {
+                return dumper(onnx, node);
+            }
+        } else if let Some(op) = node.op_as::<ops::binary::TypedBinOp>() {
+            if let Some((op_type, flipped)) = self.binary.get(&op.0.as_ref().type_id()) {
+                let (a, b) = (onnx.input(node, 0), onnx.input(node, 1));
+                let inputs = if *flipped { vec![b, a] } else { vec![a, b] };
+                return Ok(Some(onnx.wire_node(node, op_type, inputs, vec![])));
+            }
+        } else if let Some(op) = node.op_as::<ops::binary::UnaryOp>() {
+            if let Some((op_type, flipped)) = self.binary.get(&op.mini_op.as_ref().type_id()) {
+                let a = onnx.konst(&format!("{}.a", node.name), &op.a)?;
+                let b = onnx.input(node, 0);
+                let inputs = if *flipped { vec![b, a] } else { vec![a, b] };
+                return Ok(Some(onnx.wire_node(node, op_type, inputs, vec![])));
+            }
+        } else if let Some(dumper) = self.dumpers.get(&node.op().type_id()) {
+            return dumper(onnx, node);
+        }
+        Ok(None)
+    }
+}
+
+pub fn to_proto_model(framework: &Onnx, model: &TypedModel) -> TractResult<ModelProto> {
+    let mut into_onnx = IntoOnnx::new(framework, model);
+    into_onnx.translate()?;
+    Ok(into_onnx.into_proto_model())
+}
+
+pub struct IntoOnnx<'a> {
+    pub framework: &'a Onnx,
+    pub model: &'a TypedModel,
+    pub mapping: HashMap<OutletId, String>,
+    pub graph: GraphProto,
+    names: HashSet<String>,
+    node_names: HashSet<String>,
+}
+
+impl<'a> IntoOnnx<'a> {
+    pub fn new(framework: &'a Onnx, model: &'a TypedModel) -> IntoOnnx<'a> {
+        IntoOnnx {
+            framework,
+            model,
+            mapping: Default::default(),
+            graph: GraphProto { name: "tract".to_string(), ..GraphProto::default() },
+            names: Default::default(),
+            node_names: Default::default(),
+        }
+    }
+
+    fn translate(&mut self) -> TractResult<()> {
+        let model = self.model;
+        for input in model.input_outlets()? {
+            let name = self.unique_name(&model.node(input.node).name);
+            self.graph.input.push(value_info(&name, model.outlet_fact(*input)?)?);
+            self.mapping.insert(*input, name);
+        }
+        for id in model.eval_order()? {
+            let node = model.node(id);
+            if model.input_outlets()?.iter().any(|i| i.node == id) {
+                continue;
+            }
+            if node.op_is::<ops::source::TypedSource>() {
+                bail!("Source {} is not a model input", node);
+            }
+            let framework = self.framework;
+            let outputs = framework
+                .dump_register
+                .serialize(self, node)
+                .with_context(|| format!("Serializing node {} to ONNX", node))?;
+            let outputs = match outputs {
+                Some(outputs) => outputs,
+                None => bail!(
+                    "No ONNX translation for node {}. Operator {} is tract-specific, or not \
+                     supported by the ONNX exporter in this configuration.",
+                    node,
+                    node.op().name()
+                ),
+            };
+            for (slot, name) in outputs.into_iter().enumerate() {
+                self.mapping.insert(OutletId::new(id, slot), name);
+            }
+        }
+        let mut exported = HashSet::new();
+        for (ix, output) in model.output_outlets()?.iter().enumerate() {
+            let mut name = self.mapping[output].clone();
+            let produced = self.graph.node.iter().any(|n| n.output.contains(&name));
+            if !produced || exported.contains(&name) {
+                let label = model
+                    .outlet_label(*output)
+                    .map(|l| l.to_string())
+                    .unwrap_or_else(|| format!("output_{}", ix));
+                name = self.wire(&label, "Identity", vec![name], vec![]);
+            }
+            self.graph.output.push(value_info(&name, model.outlet_fact(*output)?)?);
+            exported.insert(name);
+        }
+        Ok(())
+    }
+
+    /// ONNX value name of a node input.
+    pub fn input(&self, node: &TypedNode, ix: usize) -> String {
+        self.mapping[&node.inputs[ix]].clone()
+    }
+
+    /// Make a value name unique in the graph.
+    pub fn unique_name(&mut self, name: &str) -> String {
+        let unique = uniquify(&self.names, name);
+        self.names.insert(unique.clone());
+        unique
+    }
+
+    fn unique_node_name(&mut self, name: &str) -> String {
+        let unique = uniquify(&self.node_names, name);
+        self.node_names.insert(unique.clone());
+        unique
+    }
+
+    /// Store a tensor as a graph initializer, returning its value name.
+    pub fn konst(&mut self, name: &str, tensor: &Tensor) -> TractResult<String> {
+        let name = self.unique_name(name);
+        let mut proto: TensorProto =
+            tensor.try_into().with_context(|| format!("Serializing tensor {}", name))?;
+        proto.name = name.clone();
+        self.graph.initializer.push(proto);
+        Ok(name)
+    }
+
+    /// Store a list of integers as an i64 rank 1 initializer, as used for axes, shapes
+    /// or pads inputs.
+    pub fn konst_i64s(&mut self, name: &str, values: &[i64]) -> TractResult<String> {
+        self.konst(name, &tensor1(values))
+    }
+
+    /// Add an ONNX node with a single output, returning the output value name.
+    pub fn wire(
+        &mut self,
+        name: &str,
+        op_type: &str,
+        inputs: Vec<String>,
+        attribute: Vec<AttributeProto>,
+    ) -> String {
+        let name = self.unique_name(name);
+        let node_name = self.unique_node_name(&name);
+        self.graph.node.push(NodeProto {
+            name: node_name,
+            op_type: op_type.to_string(),
+            input: inputs,
+            output: vec![name.clone()],
+            attribute,
+            ..NodeProto::default()
+        });
+        name
+    }
+
+    /// Add the ONNX node computing the outputs of a tract node, returning the output
+    /// value names. Values are named after outlet labels when they exist.
+    pub fn wire_node(
+        &mut self,
+        node: &TypedNode,
+        op_type: &str,
+        inputs: Vec<String>,
+        attribute: Vec<AttributeProto>,
+    ) -> TVec<String> {
+        let outputs: TVec<String> = (0..node.outputs.len())
+            .map(|slot| {
+                let outlet = OutletId::new(node.id, slot);
+                let name = if let Some(label) = self.model.outlet_label(outlet) {
+                    label.to_string()
+                } else if slot == 0 {
+                    node.name.clone()
+                } else {
+                    format!("{}.{}", node.name, slot)
+                };
+                self.unique_name(&name)
+            })
+            .collect();
+        let name = self.unique_node_name(&node.name);
+        self.graph.node.push(NodeProto {
+            name,
+            op_type: op_type.to_string(),
+            input: inputs,
+            output: outputs.to_vec(),
+            attribute,
+            ..NodeProto::default()
+        });
+        outputs
+    }
+
+    pub fn into_proto_model(self) -> ModelProto {
+        ModelProto {
+            ir_version: IR_VERSION,
+            opset_import: vec![OperatorSetIdProto {
+                domain: String::new(),
+                version: OPSET_VERSION,
+            }],
+            producer_name: "tract".to_string(),
+            producer_version: env!("CARGO_PKG_VERSION").to_string(),
+            graph: Some(self.graph),
+            ..ModelProto::default()
+        }
+    }
+}
+
+fn uniquify(names: &HashSet<String>, name: &str) -> String {
+    let mut unique = name.to_string();
+    let mut ix = 0;
+    while names.contains(&unique) {
+        ix += 1;
+        unique = format!("{}.{}", name, ix);
+    }
+    unique
+}
+
+pub(crate) fn value_info(name: &str, fact: &TypedFact) -> TractResult<ValueInfoProto> {
+    use tensor_shape_proto::dimension::Value;
+    let elem_type: DataType = fact.datum_type.try_into()?;
+    let dim = fact
+        .shape
+        .iter()
+        .map(|d| tensor_shape_proto::Dimension {
+            value: match d {
+                TDim::Val(v) => Some(Value::DimValue(v)),
+                TDim::Sym(s) => Some(Value::DimParam(s.as_char().to_string())),
+                _ => None,
+            },
+            ..Default::default()
+        })
+        .collect();
+    let tensor =
+        type_proto::Tensor { elem_type: elem_type as i32, shape: Some(TensorShapeProto { dim }) };
+    Ok(ValueInfoProto {
+        name: name.to_string(),
+        r#type: Some(TypeProto {
+            value: Some(type_proto::Value::TensorType(tensor)),
+            ..TypeProto::default()
+        }),
+        ..ValueInfoProto::default()
+    })
+}
+
+pub fn int(name: &str, i: i64) -> AttributeProto {
+    use attribute_proto::AttributeType;
+    AttributeProto {
+        name: name.to_string(),
+        r#type: AttributeType::Int as i32,
+        i,
+        ..AttributeProto::default()
+    }
+}
+
+pub fn ints(name: &str, ints: impl IntoIterator<Item = i64>) -> AttributeProto {
+    use attribute_proto::AttributeType;
+    AttributeProto {
+        name: name.to_string(),
+        r#type: AttributeType::Ints as i32,
+        ints: ints.into_iter().collect(),
+        ..AttributeProto::default()
+    }
+}
+
+pub fn float(name: &str, f: f32) -> AttributeProto {
+    use attribute_proto::AttributeType;
+    AttributeProto {
+        name: name.to_string(),
+        r#type: AttributeType::Float as i32,
+        f,
+        ..AttributeProto::default()
+    }
+}
+
+pub fn string(name: &str, s: &str) -> AttributeProto {
+    use attribute_proto::AttributeType;
+    AttributeProto {
+        name: name.to_string(),
+        r#type: AttributeType::String as i32,
+        s: s.as_bytes().to_vec(),
+        ..AttributeProto::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tract_hir::tract_core::ops::cnn::{
+        ConvUnary, KernelFormat, MaxPool, PaddingSpec, PoolSpec,
+    };
+    use tract_hir::tract_core::ops::nn::{DataFormat, Softmax};
+    use tract_hir::tract_core::ops::{math, matmul, nn};
+
+    fn round_trip(model: &TypedModel, input: Tensor) -> TractResult<()> {
+        let onnx = crate::onnx();
+        let buffer = onnx.write(model, vec![])?;
+        let reloaded = onnx.model_for_read(&mut &*buffer)?.into_optimized()?;
+        let expected = model.clone().into_runnable()?.run(tvec!(input.clone()))?;
+        let found = reloaded.into_runnable()?.run(tvec!(input))?;
+        assert_eq!(expected.len(), found.len());
+        for (e, f) in expected.iter().zip(found.iter()) {
+            f.close_enough(e, Approximation::Close)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn dense() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", f32::fact([2, 3]))?;
+        let w = rctensor2(&[[1f32, -2.], [3., 4.], [-5., 6.]]);
+        let mm = matmul::MatMulUnary::new(w, true, true, true);
+        let y = model.wire_node("mm", mm, &[x])?;
+        let y = model.wire_node("bias", math::add::unary(rctensor2(&[[0.5f32, -0.5]])), &y)?;
+        let y = model.wire_node("relu", math::max::unary(rctensor2(&[[0f32]])), &y)?;
+        let y = model.wire_node("softmax", Softmax::new(tvec!(1), f32::datum_type()), &y)?;
+        model.set_output_outlets(&y)?;
+        round_trip(&model, tensor2(&[[1f32, 2., 3.], [-1., 0., 1.]]))
+    }
+
+    #[test]
+    fn conv_nhwc() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", f32::fact([1, 5, 5, 2]))?;
+        let kernel: Tensor = tract_ndarray::Array4::from_shape_fn((3, 2, 3, 3), |(o, i, h, w)| {
+            (o + 2 * i + h) as f32 - w as f32
+        })
+        .into();
+        let pool_spec = PoolSpec::new(
+            DataFormat::NHWC,
+            tvec!(3, 3),
+            PaddingSpec::Explicit(tvec!(1, 0), tvec!(1, 0), false),
+            None,
+            Some(tvec!(1, 2)),
+            Some(3),
+        );
+        let conv = ConvUnary {
+            pool_spec: pool_spec.clone(),
+            kernel_fmt: KernelFormat::OIHW,
+            kernel: kernel.into_arc_tensor(),
+            group: 1,
+            bias: Some(rctensor1(&[1f32, 2., 3.])),
+            q_params: None,
+            weight_quant: None,
+        };
+        let y = model.wire_node("conv", conv, &[x])?;
+        let pool_spec =
+            PoolSpec::new(DataFormat::NHWC, tvec!(2, 1), PaddingSpec::Valid, None, None, None);
+        let y = model.wire_node("pool", MaxPool::new(pool_spec, None), &y)?;
+        let y = model.wire_node("reduce", nn::Reduce::new(tvec!(2), nn::Reducer::Sum), &y)?;
+        let y = model.wire_node("rm", AxisOp::Rm(2), &y)?;
+        model.set_output_outlets(&y)?;
+        let input = tract_ndarray::Array4::from_shape_fn((1, 5, 5, 2), |(_, h, w, c)| {
+            (h * 3 + w * 2) as f32 - c as f32
+        });
+        round_trip(&model, input.into())
+    }
+
+    #[test]
+    fn symbols_and_labels() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let n = Symbol::new('N');
+        let x = model.add_source("x", f32::fact(&[n.to_dim(), 2.to_dim()]))?;
+        let y = model.wire_node("neg", math::neg(), &[x])?;
+        model.set_outlet_label(y[0], "minus_x".to_string())?;
+        model.set_output_outlets(&y)?;
+        let proto = crate::onnx().model_to_proto_model(&model)?;
+        let graph = proto.graph.as_ref().unwrap();
+        assert_eq!(graph.input[0].name, "x");
+        assert_eq!(graph.output[0].name, "minus_x");
+        let reloaded = crate::onnx().model_for_proto_model(&proto)?.into_typed()?;
+        assert_eq!(reloaded.input_fact(0)?.shape[0].to_string(), "N");
+        Ok(())
+    }
+
+    #[test]
+    fn tract_only_op_fails() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", f32::fact([2]))?;
+        let y = model.wire_node("round", math::round(), &[x])?;
+        model.set_output_outlets(&y)?;
+        let err = crate::onnx().model_to_proto_model(&model).unwrap_err();
+        assert!(format!("{:?}", err).contains("No ONNX translation for node"));
+        Ok(())
+    }
+}
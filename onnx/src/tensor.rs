@@ -31,12 +31,36 @@ impl TryFrom<DataType> for DatumType {
     }
 }
 
+impl TryFrom<DatumType> for DataType {
+    type Error = TractError;
+    fn try_from(dt: DatumType) -> TractResult<DataType> {
+        match dt {
+            DatumType::Bool => Ok(DataType::Bool),
+            DatumType::U8 => Ok(DataType::Uint8),
+            DatumType::U16 => Ok(DataType::Uint16),
+            DatumType::U32 => Ok(DataType::Uint32),
+            DatumType::U64 => Ok(DataType::Uint64),
+            DatumType::I8 => Ok(DataType::Int8),
+            DatumType::I16 => Ok(DataType::Int16),
+            DatumType::I32 => Ok(DataType::Int32),
+            DatumType::I64 => Ok(DataType::Int64),
+            DatumType::F16 => Ok(DataType::Float16),
+            DatumType::F32 => Ok(DataType::Float),
+            DatumType::F64 => Ok(DataType::Double),
+            DatumType::String => Ok(DataType::String),
+            _ => bail!("DatumType {:?} has no ONNX equivalent", dt),
+        }
+    }
+}
+
 pub fn translate_inference_fact<'a, 'b>(
     t: &'a type_proto::Tensor,
     symbol_map: &'b mut HashMap<&'a str, Symbol>,
 ) -> TractResult<InferenceFact> {
     let mut fact = InferenceFact::default();
-    fact = fact.with_datum_type(DataType::from_i32(t.elem_type).unwrap().try_into()?);
+    let dt = DataType::from_i32(t.elem_type)
+        .with_context(|| format!("Invalid elem_type {}", t.elem_type))?;
+    fact = fact.with_datum_type(dt.try_into()?);
     if let Some(shape) = &t.shape {
         let shape: TVec<DimFact> = shape
             .dim
@@ -99,14 +123,20 @@ fn create_tensor(shape: Vec<usize>, dt: DatumType, data: &[u8]) -> TractResult<T
                 .into_array::<u8>()?
                 .mapv(|x| x != 0)
                 .into()),
-            _ => unimplemented!("FIXME, raw tensor loading"),
+            _ => bail!("Can not load raw {:?} tensor", dt),
         }
     }
 }
 
 fn common_tryfrom(t: &TensorProto, path: Option<&str>) -> TractResult<Tensor> {
-    let dt = DataType::from_i32(t.data_type).unwrap().try_into()?;
-    let shape: Vec<usize> = t.dims.iter().map(|&i| i as usize).collect();
+    let dt = DataType::from_i32(t.data_type)
+        .with_context(|| format!("Invalid data_type {}", t.data_type))?
+        .try_into()?;
+    let shape: Vec<usize> = t
+        .dims
+        .iter()
+        .map(|&i| usize::try_from(i).with_context(|| format!("Invalid tensor dims {:?}", t.dims)))
+        .collect::<TractResult<_>>()?;
     // detect if the tensor is rather in an external file than inside the onnx file directly
     let is_external = t.data_location.is_some() && t.data_location == Some(1);
     if t.raw_data.len() > 0 {
@@ -134,8 +164,11 @@ fn common_tryfrom(t: &TensorProto, path: Option<&str>) -> TractResult<Tensor> {
                 Array::from_shape_vec(&*shape, t.int32_data.iter().map(|&x| x as u16).collect())?
                     .into()
             }
-            DatumType::U32 => Array::from_shape_vec(&*shape, t.int32_data.to_vec())?.into(),
-            DatumType::U64 => Array::from_shape_vec(&*shape, t.int64_data.to_vec())?.into(),
+            DatumType::U32 => {
+                Array::from_shape_vec(&*shape, t.uint64_data.iter().map(|&x| x as u32).collect())?
+                    .into()
+            }
+            DatumType::U64 => Array::from_shape_vec(&*shape, t.uint64_data.to_vec())?.into(),
             DatumType::I8 => {
                 Array::from_shape_vec(&*shape, t.int32_data.iter().map(|&x| x as i8).collect())?
                     .into()
@@ -146,6 +179,11 @@ fn common_tryfrom(t: &TensorProto, path: Option<&str>) -> TractResult<Tensor> {
             }
             DatumType::I32 => Array::from_shape_vec(&*shape, t.int32_data.to_vec())?.into(),
             DatumType::I64 => Array::from_shape_vec(&*shape, t.int64_data.to_vec())?.into(),
+            DatumType::F16 => Array::from_shape_vec(
+                &*shape,
+                t.int32_data.iter().map(|&x| f16::from_bits(x as u16)).collect(),
+            )?
+            .into(),
             DatumType::F32 => Array::from_shape_vec(&*shape, t.float_data.to_vec())?.into(),
             DatumType::F64 => Array::from_shape_vec(&*shape, t.double_data.to_vec())?.into(),
             DatumType::String => {
@@ -158,7 +196,7 @@ fn common_tryfrom(t: &TensorProto, path: Option<&str>) -> TractResult<Tensor> {
                     .context("Invalid UTF8 buffer")?;
                 Array::from_shape_vec(&*shape, strings)?.into()
             }
-            _ => unimplemented!("FIXME, struct tensor loading"),
+            _ => bail!("Can not load {:?} tensor", dt),
         };
         Ok(it)
     }
@@ -185,6 +223,33 @@ impl TryFrom<TensorProto> for Tensor {
     }
 }
 
+impl TryFrom<&Tensor> for TensorProto {
+    type Error = TractError;
+    fn try_from(t: &Tensor) -> TractResult<TensorProto> {
+        let data_type: DataType = t.datum_type().try_into()?;
+        let mut proto = TensorProto {
+            dims: t.shape().iter().map(|&d| d as i64).collect(),
+            data_type: data_type as i32,
+            ..TensorProto::default()
+        };
+        match t.datum_type() {
+            DatumType::String => {
+                proto.string_data =
+                    t.as_slice::<String>()?.iter().map(|s| s.as_bytes().to_vec()).collect()
+            }
+            DatumType::Bool => {
+                proto.raw_data = t.as_slice::<bool>()?.iter().map(|&b| b as u8).collect()
+            }
+            _ => {
+                ensure!(cfg!(target_endian = "little"), "ONNX raw data is little endian");
+                // only plain old data types get here
+                proto.raw_data = unsafe { t.as_bytes() }.to_vec()
+            }
+        }
+        Ok(proto)
+    }
+}
+
 pub fn proto_from_reader<R: ::std::io::Read>(mut r: R) -> TractResult<TensorProto> {
     let mut v = vec![];
     r.read_to_end(&mut v)?;
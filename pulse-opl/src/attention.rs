@@ -0,0 +1,127 @@
+use tract_core::ndarray::*;
+use tract_core::ops::nn::BandedAttention;
+use tract_nnef::internal::*;
+
+/// Banded attention over the streaming axis.
+///
+/// Keys and values of the last `left_context + right_context` frames are kept
+/// in the op state so each query of the current pulse sees its full window.
+/// Queries are expected to be delayed by `right_context` frames relative to
+/// keys and values, so the lookahead is already available when a query comes
+/// in. Keys outside of the valid input range are masked out, and output frames
+/// of invalid queries are zeroed.
+#[derive(Debug, Clone, Hash)]
+pub struct PulsedBandedAttention {
+    pub attention: BandedAttention,
+    pub begin_input: usize,
+    pub end_input: TDim,
+}
+
+impl_dyn_hash!(PulsedBandedAttention);
+
+impl Op for PulsedBandedAttention {
+    fn name(&self) -> Cow<str> {
+        "PulsedBandedAttention".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        let mut info = self.attention.info()?;
+        info.push(format!("valid input: {}..{}", self.begin_input, self.end_input));
+        Ok(info)
+    }
+
+    op_pulse!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for PulsedBandedAttention {
+    fn is_stateless(&self) -> bool {
+        false
+    }
+
+    fn state(
+        &self,
+        _session: &mut SessionState,
+        _node_id: usize,
+    ) -> TractResult<Option<Box<dyn OpState>>> {
+        Ok(Some(Box::new(PulsedBandedAttentionState::default())))
+    }
+}
+
+impl TypedOp for PulsedBandedAttention {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        self.attention.output_facts(inputs)
+    }
+
+    fn invariants(&self, inputs: &[&TypedFact], outputs: &[&TypedFact]) -> TractResult<Invariants> {
+        self.attention.invariants(inputs, outputs)
+    }
+
+    as_op!();
+}
+
+#[derive(Debug, Clone, Default)]
+struct PulsedBandedAttentionState {
+    current_pos: usize,
+    cache: Option<(Array3<f32>, Array3<f32>)>,
+}
+
+impl OpState for PulsedBandedAttentionState {
+    fn eval(
+        &mut self,
+        session: &mut SessionState,
+        op: &dyn Op,
+        inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let op = op
+            .downcast_ref::<PulsedBandedAttention>()
+            .ok_or_else(|| format_err!("Wrong Op type"))?;
+        let begin_input = op.begin_input as isize;
+        let end_input =
+            op.end_input.eval(&session.resolved_symbols).to_isize().unwrap_or(isize::MAX);
+        let (q, k, v) = (&inputs[0], &inputs[1], &inputs[2]);
+        let rank = q.rank();
+        let pulse = q.shape()[rank - 2];
+        let n = q.shape()[..rank - 2].iter().product::<usize>();
+        let q = q.to_array_view::<f32>()?.into_shape((n, pulse, q.shape()[rank - 1]))?;
+        let k = k.to_array_view::<f32>()?.into_shape((n, pulse, k.shape()[rank - 1]))?;
+        let v = v.to_array_view::<f32>()?.into_shape((n, pulse, v.shape()[rank - 1]))?;
+
+        let context = op.attention.left_context + op.attention.right_context;
+        let (k_cache, v_cache) = self.cache.get_or_insert_with(|| {
+            (Array3::zeros((n, context, k.shape()[2])), Array3::zeros((n, context, v.shape()[2])))
+        });
+        let keys = concatenate(Axis(1), &[k_cache.view(), k])?;
+        let values = concatenate(Axis(1), &[v_cache.view(), v])?;
+
+        // keys[.., i, ..] is the frame at position keys_pos + i in the stream
+        let keys_pos = self.current_pos as isize - context as isize;
+        let mut output = Array3::<f32>::zeros((n, pulse, values.shape()[2]));
+        for i in 0..pulse {
+            // the query comes with a delay of right_context frames
+            let query_pos = (self.current_pos + i) as isize - op.attention.right_context as isize;
+            if query_pos < begin_input || query_pos >= end_input {
+                continue;
+            }
+            let lo = (query_pos - op.attention.left_context as isize).max(begin_input);
+            let hi = (query_pos + op.attention.right_context as isize + 1).min(end_input);
+            let (lo, hi) = ((lo - keys_pos) as usize, (hi - keys_pos) as usize);
+            for b in 0..n {
+                op.attention.attend(
+                    q.slice(s![b, i, ..]),
+                    keys.slice(s![b, lo..hi, ..]),
+                    values.slice(s![b, lo..hi, ..]),
+                    output.slice_mut(s![b, i, ..]),
+                );
+            }
+        }
+
+        k_cache.assign(&keys.slice(s![.., pulse.., ..]));
+        v_cache.assign(&values.slice(s![.., pulse.., ..]));
+        self.current_pos += pulse;
+
+        let mut output_shape: TVec<usize> = inputs[0].shape().into();
+        output_shape[rank - 1] = output.shape()[2];
+        Ok(tvec!(output.into_tensor().into_shape(&output_shape)?.into_arc_tensor()))
+    }
+}
@@ -42,6 +42,14 @@ impl TypedOp for PulsedSameAxisConcat {
     fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
         Ok(tvec!(inputs[0].clone()))
     }
+
+    fn invariants(
+        &self,
+        inputs: &[&TypedFact],
+        _outputs: &[&TypedFact],
+    ) -> TractResult<Invariants> {
+        Ok((0..inputs[0].rank()).filter(|&ax| ax != self.axis).map(AxisInfo::simple).collect())
+    }
 }
 
 #[derive(Clone, Debug, Default)]
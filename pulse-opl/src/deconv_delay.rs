@@ -10,7 +10,6 @@ pub struct DeconvDelay {
     pub overlap: usize,
     pub delay: usize,
     pub stride: usize,
-    pub pulse: usize,
     pub deconv_input_dim: TDim,
     pub deconv_output_dim: TDim,
 }
@@ -52,6 +51,14 @@ impl TypedOp for DeconvDelay {
         Ok(tvec!(fact))
     }
 
+    fn invariants(
+        &self,
+        inputs: &[&TypedFact],
+        _outputs: &[&TypedFact],
+    ) -> TractResult<Invariants> {
+        Ok((0..inputs[0].rank()).filter(|&ax| ax != self.axis).map(AxisInfo::simple).collect())
+    }
+
     as_op!();
 }
 
@@ -8,6 +8,7 @@ pub fn register(registry: &mut Registry) {
             TypeName::Integer.named("axis"),
             TypeName::Integer.named("delay"),
             TypeName::Integer.named("overlap"),
+            TypeName::Integer.named("max_pulse").default(0),
         ],
         de_delay,
     );
@@ -21,45 +22,70 @@ fn de_delay(
     let axis = invocation.named_arg_as::<i64>(builder, "axis")? as usize;
     let delay = invocation.named_arg_as::<i64>(builder, "delay")? as usize;
     let overlap = invocation.named_arg_as::<i64>(builder, "overlap")? as usize;
+    let max_pulse = invocation.named_arg_as::<i64>(builder, "max_pulse")? as usize;
     let input_fact = builder.model.outlet_fact(wire)?;
-    let op = Delay::new_typed(input_fact, axis, delay, overlap);
+    let op = Delay::new_typed(input_fact, axis, delay, overlap)
+        .with_max_pulse(Some(max_pulse).filter(|m| *m > 0));
     builder.wire(op, &[wire])
 }
 
 #[derive(Debug, Clone)]
 pub struct DelayState {
+    /// Last `delay + overlap` frames along the axis, followed by room for the
+    /// next pulse.
     pub buffer: Option<Tensor>,
 }
 
 impl DelayState {
-    unsafe fn apply_delay_unchecked(&mut self, op: &Delay, input: &Tensor, output: &mut Tensor) {
+    // (re)allocate the buffer for the shape of `input`, keeping the buffered
+    // frames of the lanes that are still there
+    fn resize(&mut self, op: &Delay, input: &Tensor) -> TractResult<()> {
         let buffered = op.delay + op.overlap;
         let input_pulse = input.shape()[op.axis];
-        let output_pulse = input_pulse + op.overlap;
-        let buffer = self.buffer.as_mut().unwrap();
-        if op.delay < input_pulse {
-            let from_input = input_pulse - op.delay;
-            let from_buffer = output_pulse - from_input;
-            output.assign_slice_unchecked(..from_buffer, buffer, ..from_buffer, op.axis);
-            output.assign_slice_unchecked(from_buffer.., input, ..from_input, op.axis);
-        } else {
-            output.assign_slice_unchecked(.., buffer, ..output_pulse, op.axis);
-        };
-        // maintain buffer
-        if buffered < input_pulse {
-            buffer.assign_slice_unchecked(.., input, (input_pulse - buffered).., op.axis);
-        } else {
-            let stride = buffer.shape().iter().skip(op.axis + 1).product::<usize>()
-                * input.datum_type().size_of()
-                * input_pulse;
+        let mut shape: TVec<usize> = input.shape().into();
+        shape[op.axis] = buffered + op.max_pulse.unwrap_or(0).max(input_pulse);
+        if let Some(previous) = &self.buffer {
+            shape[op.axis] = shape[op.axis].max(previous.shape()[op.axis]);
+        }
+        let mut buffer = Tensor::zero_dt(input.datum_type(), &shape)?;
+        if let Some(previous) = self.buffer.take() {
+            let mut previous = previous.slice(op.axis, 0, buffered)?;
+            for axis in (0..shape.len()).filter(|ax| *ax != op.axis) {
+                let (had, has) = (previous.shape()[axis], shape[axis]);
+                if had > has {
+                    previous = previous.slice(axis, 0, has)?;
+                } else if had < has {
+                    let mut zeroes_shape: TVec<usize> = previous.shape().into();
+                    zeroes_shape[axis] = has - had;
+                    let zeroes = Tensor::zero_dt(input.datum_type(), &zeroes_shape)?;
+                    previous = Tensor::stack_tensors(axis, &[previous, zeroes])?;
+                }
+            }
+            buffer.assign_slice(..buffered, &previous, .., op.axis)?;
+        }
+        self.buffer = Some(buffer);
+        Ok(())
+    }
+
+    // move `len` frames starting at `from` to the beginning of the axis
+    unsafe fn shift_unchecked(buffer: &mut Tensor, axis: usize, from: usize, len: usize) {
+        if from == 0 || len == 0 || buffer.len() == 0 {
+            return;
+        }
+        let dt = buffer.datum_type();
+        if dt.is_copy() {
+            let frame = buffer.shape()[axis + 1..].iter().product::<usize>() * dt.size_of();
+            let outer_stride = buffer.shape()[axis] * frame;
             std::slice::from_raw_parts_mut(
                 buffer.as_ptr_mut_unchecked::<u8>(),
-                buffer.len() * input.datum_type().size_of(),
+                buffer.len() * dt.size_of(),
             )
-            .rotate_left(stride);
-            buffer.assign_slice_unchecked((buffered - input_pulse).., input, .., op.axis);
+            .chunks_exact_mut(outer_stride)
+            .for_each(|slice| slice.copy_within(from * frame..(from + len) * frame, 0));
+        } else {
+            let kept = buffer.slice(axis, from, from + len).unwrap();
+            buffer.assign_slice_unchecked(..len, &kept, .., axis);
         }
-
     }
 }
 
@@ -74,20 +100,29 @@ impl OpState for DelayState {
         let op = op.downcast_ref::<Delay>().ok_or_else(|| format_err!("Wrong Op type"))?;
         let buffered = op.delay + op.overlap;
         let input_pulse = input.shape()[op.axis];
-        let output_pulse = input_pulse + op.overlap;
-        let mut output_shape: TVec<usize> = input.shape().into();
-        output_shape[op.axis] = output_pulse;
-        // build output
+        if let Some(max) = op.max_pulse {
+            if input_pulse > max {
+                bail!("Pulse of {} frames exceeds the bound of {} frames", input_pulse, max)
+            }
+        }
+        let fits = |buffer: &Tensor| {
+            buffer.shape().iter().zip(input.shape()).enumerate().all(|(axis, (b, i))| {
+                if axis == op.axis {
+                    *b >= buffered + input_pulse
+                } else {
+                    b == i
+                }
+            })
+        };
+        if !self.buffer.as_ref().map(fits).unwrap_or(false) {
+            self.resize(op, &input)?;
+        }
+        let buffer = self.buffer.as_mut().unwrap();
         unsafe {
-            if self.buffer.is_none() {
-                let mut shape = input.shape().to_owned();
-                shape[op.axis] = buffered;
-                self.buffer = Some(Tensor::zero_dt(input.datum_type(), &shape)?)
-            };
-            let mut output = Tensor::uninitialized_dt(input.datum_type(), &*output_shape)?;
-            self.apply_delay_unchecked(op, &input, &mut output);            
-            let output = output.into_arc_tensor();
-            Ok(tvec!(output))
+            buffer.assign_slice_unchecked(buffered..buffered + input_pulse, &input, .., op.axis);
+            let output = buffer.slice(op.axis, 0, input_pulse + op.overlap)?;
+            Self::shift_unchecked(buffer, op.axis, input_pulse, buffered);
+            Ok(tvec!(output.into_arc_tensor()))
         }
     }
 }
@@ -99,6 +134,10 @@ pub struct Delay {
     pub axis: usize,
     pub delay: usize,
     pub overlap: usize,
+    /// Longest input pulse along the axis. The buffer is allocated for it once
+    /// and for all, and longer pulses are rejected. Without a bound, the
+    /// buffer grows to the longest pulse seen.
+    pub max_pulse: Option<usize>,
 }
 
 impl_dyn_hash!(Delay);
@@ -112,7 +151,20 @@ impl Delay {
     ) -> Delay {
         let mut buffer_shape: TVec<TDim> = input_fact.shape.to_tvec();
         buffer_shape[axis] = (delay + overlap).to_dim();
-        Delay { datum_type: input_fact.datum_type, buffer_shape, axis, delay, overlap }
+        Delay {
+            datum_type: input_fact.datum_type,
+            buffer_shape,
+            axis,
+            delay,
+            overlap,
+            max_pulse: None,
+        }
+    }
+
+    pub fn with_max_pulse(self, max_pulse: Option<usize>) -> Delay {
+        let mut buffer_shape = self.buffer_shape.clone();
+        buffer_shape[self.axis] = (self.delay + self.overlap + max_pulse.unwrap_or(0)).to_dim();
+        Delay { buffer_shape, max_pulse, ..self }
     }
 }
 
@@ -123,7 +175,10 @@ impl Op for Delay {
 
     fn info(&self) -> TractResult<Vec<String>> {
         Ok(vec![
-            format!("axis: {} delay: {} overlap: {}", self.axis, self.delay, self.overlap),
+            format!(
+                "axis: {} delay: {} overlap: {} max pulse: {:?}",
+                self.axis, self.delay, self.overlap, self.max_pulse
+            ),
             format!("buffer: {:?} {:?}", self.buffer_shape, self.datum_type),
         ])
     }
@@ -156,6 +211,14 @@ impl TypedOp for Delay {
         Ok(tvec!(fact))
     }
 
+    fn invariants(
+        &self,
+        inputs: &[&TypedFact],
+        _outputs: &[&TypedFact],
+    ) -> TractResult<Invariants> {
+        Ok((0..inputs[0].rank()).filter(|&ax| ax != self.axis).map(AxisInfo::simple).collect())
+    }
+
     fn cost(&self, _inputs: &[&TypedFact]) -> TractResult<TVec<(Cost, TDim)>> {
         Ok(tvec!((Cost::Buffer(self.datum_type), self.buffer_shape.iter().product())))
     }
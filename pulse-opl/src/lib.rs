@@ -3,10 +3,12 @@ use tract_nnef::internal::*;
 #[macro_use]
 mod macros;
 
+mod attention;
 mod concat;
 mod deconv_delay;
 mod delay;
 mod pad;
+mod reduce;
 mod slice;
 
 pub use tract_nnef;
@@ -18,9 +20,11 @@ pub mod prelude {
 }
 
 pub mod ops {
+    pub use super::attention::PulsedBandedAttention;
     pub use super::deconv_delay::DeconvDelay;
     pub use super::delay::{ Delay, DelayState };
     pub use super::pad::PulsePad;
+    pub use super::reduce::PulsedCumulativeReduce;
     pub use super::slice::PulsedAxisSlice;
 }
 
@@ -0,0 +1,131 @@
+use std::ops::{Add, Mul};
+use tract_core::ndarray::*;
+use tract_core::ops::nn::Reducer;
+use tract_nnef::internal::*;
+
+/// Causal reduction along the streaming axis.
+///
+/// Each output frame is the reduction of all valid input frames up to and
+/// including itself, so the last valid frame holds the reduction of the whole
+/// stream. Frames outside of the valid input range are forwarded unchanged.
+#[derive(Debug, Clone, Hash)]
+pub struct PulsedCumulativeReduce {
+    pub axis: usize,
+    pub reducer: Reducer,
+    pub begin_input: usize,
+    pub end_input: TDim,
+}
+
+impl_dyn_hash!(PulsedCumulativeReduce);
+
+impl Op for PulsedCumulativeReduce {
+    fn name(&self) -> Cow<str> {
+        format!("PulsedCumulativeReduce<{:?}>", self.reducer).into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "axis: {} valid input: {}..{}",
+            self.axis, self.begin_input, self.end_input
+        )])
+    }
+
+    op_pulse!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for PulsedCumulativeReduce {
+    fn is_stateless(&self) -> bool {
+        false
+    }
+
+    fn state(
+        &self,
+        _session: &mut SessionState,
+        _node_id: usize,
+    ) -> TractResult<Option<Box<dyn OpState>>> {
+        Ok(Some(Box::new(PulsedCumulativeReduceState::default())))
+    }
+}
+
+impl TypedOp for PulsedCumulativeReduce {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        ensure!(
+            matches!(self.reducer, Reducer::Sum | Reducer::Prod | Reducer::Max | Reducer::Min),
+            "{:?} has no cumulative form",
+            self.reducer
+        );
+        Ok(tvec!(inputs[0].clone()))
+    }
+
+    fn invariants(
+        &self,
+        inputs: &[&TypedFact],
+        _outputs: &[&TypedFact],
+    ) -> TractResult<Invariants> {
+        Ok((0..inputs[0].rank()).filter(|&ax| ax != self.axis).map(AxisInfo::simple).collect())
+    }
+
+    as_op!();
+}
+
+#[derive(Debug, Clone, Default)]
+struct PulsedCumulativeReduceState {
+    current_pos: usize,
+    acc: Option<Tensor>,
+}
+
+impl OpState for PulsedCumulativeReduceState {
+    fn eval(
+        &mut self,
+        session: &mut SessionState,
+        op: &dyn Op,
+        mut inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let op = op
+            .downcast_ref::<PulsedCumulativeReduce>()
+            .ok_or_else(|| format_err!("Wrong Op type"))?;
+        let mut input = args_1!(inputs).into_tensor();
+        let end_input =
+            op.end_input.eval(&session.resolved_symbols).to_usize().unwrap_or(std::usize::MAX);
+        dispatch_numbers!(Self::eval_t(input.datum_type())(self, op, &mut input, end_input))?;
+        Ok(tvec!(input.into_arc_tensor()))
+    }
+}
+
+impl PulsedCumulativeReduceState {
+    fn eval_t<T>(
+        &mut self,
+        op: &PulsedCumulativeReduce,
+        input: &mut Tensor,
+        end_input: usize,
+    ) -> TractResult<()>
+    where
+        T: Datum + Copy + PartialOrd + Add<Output = T> + Mul<Output = T>,
+    {
+        let combine: fn(T, T) -> T = match op.reducer {
+            Reducer::Sum => |a, b| a + b,
+            Reducer::Prod => |a, b| a * b,
+            Reducer::Max => |a, b| if b > a { b } else { a },
+            Reducer::Min => |a, b| if b < a { b } else { a },
+            _ => bail!("{:?} has no cumulative form", op.reducer),
+        };
+        let mut input = input.to_array_view_mut::<T>()?;
+        for mut frame in input.axis_iter_mut(Axis(op.axis)) {
+            let pos = self.current_pos;
+            self.current_pos += 1;
+            if pos < op.begin_input || pos >= end_input {
+                continue;
+            }
+            if let Some(acc) = self.acc.as_mut() {
+                Zip::from(acc.to_array_view_mut::<T>()?).and(&mut frame).for_each(|a, f| {
+                    *a = combine(*a, *f);
+                    *f = *a;
+                });
+            } else {
+                self.acc = Some(frame.to_owned().into_tensor());
+            }
+        }
+        Ok(())
+    }
+}
@@ -27,6 +27,14 @@ impl TypedOp for PulsedAxisSlice {
         Ok(tvec!(inputs[0].clone()))
     }
 
+    fn invariants(
+        &self,
+        inputs: &[&TypedFact],
+        _outputs: &[&TypedFact],
+    ) -> TractResult<Invariants> {
+        Ok((0..inputs[0].rank()).filter(|&ax| ax != self.axis).map(AxisInfo::simple).collect())
+    }
+
     as_op!();
 }
 
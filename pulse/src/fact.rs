@@ -2,6 +2,7 @@ use crate::internal::*;
 
 lazy_static::lazy_static! {
     static ref S: Symbol = Symbol::new('S');
+    static ref P: Symbol = Symbol::new('P');
 }
 
 pub fn stream_symbol() -> Symbol {
@@ -12,6 +13,16 @@ pub fn stream_dim() -> TDim {
     (*S).into()
 }
 
+/// Symbol used for variable pulse sizes. Its value is resolved from the
+/// input tensors at each run, so each pulse can have a different length.
+pub fn pulse_symbol() -> Symbol {
+    *P
+}
+
+pub fn pulse_dim() -> TDim {
+    (*P).into()
+}
+
 pub trait StreamFact {
     fn stream_info(&self) -> Option<(usize, &TDim)>;
 }
@@ -31,6 +42,83 @@ impl StreamFact for ShapeFact {
     }
 }
 
+/// How to pulsify a model.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PulseSpec {
+    /// Pulse along the streaming axis: a fixed size, or an expression of
+    /// `pulse_symbol()` (like `4*P`) to feed variable length pulses.
+    pub pulse: TDim,
+    /// Upper bound of `pulse_symbol()`. Delay buffers are sized for it, and
+    /// bigger pulses are rejected at runtime.
+    pub max_pulse: Option<usize>,
+    /// Other streaming symbols and their pulse. Each of them streams one
+    /// axis made of independent lanes (see `LaneAxis`).
+    pub lanes: TVec<(Symbol, TDim)>,
+    /// Turn reductions over the streaming axis into running reductions: the
+    /// streaming axis keeps its length, and each frame holds the reduction of
+    /// the stream up to it. Without it, they can not be pulsified.
+    pub cumulative_reductions: bool,
+}
+
+impl PulseSpec {
+    pub fn new(pulse: impl ToDim) -> PulseSpec {
+        PulseSpec {
+            pulse: pulse.to_dim(),
+            max_pulse: None,
+            lanes: tvec!(),
+            cumulative_reductions: false,
+        }
+    }
+
+    pub fn with_max_pulse(self, max_pulse: usize) -> PulseSpec {
+        PulseSpec { max_pulse: Some(max_pulse), ..self }
+    }
+
+    pub fn with_cumulative_reductions(self) -> PulseSpec {
+        PulseSpec { cumulative_reductions: true, ..self }
+    }
+
+    pub fn with_lanes(mut self, symbol: Symbol, pulse: impl ToDim) -> PulseSpec {
+        self.lanes.push((symbol, pulse.to_dim()));
+        self
+    }
+
+    pub(crate) fn check(&self) -> TractResult<()> {
+        if self.pulse.symbols().iter().any(|s| *s != pulse_symbol()) {
+            bail!(
+                "Pulse must be an integer or an expression of {}, got {}",
+                pulse_symbol().as_char(),
+                self.pulse
+            );
+        }
+        if let Ok(pulse) = self.pulse.to_usize() {
+            if self.max_pulse.is_some() {
+                bail!("Pulse bound declared for a fixed pulse ({})", pulse)
+            }
+        }
+        for (symbol, pulse) in &self.lanes {
+            if *symbol == stream_symbol() || *symbol == pulse_symbol() {
+                bail!("Lanes can not stream along {}", symbol.as_char())
+            }
+            if pulse.symbols().contains(&stream_symbol()) {
+                bail!("Invalid pulse {} for lanes along {}", pulse, symbol.as_char())
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A streaming axis made of independent lanes, like a batch of concurrent
+/// streams. Each pulse carries the next frames of every lane: ops can not mix
+/// values across lanes, and state (like delay buffers) is kept per lane. If
+/// the pulse along the lane axis is variable, lanes can join or leave at the
+/// end of the axis between pulses.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LaneAxis {
+    pub axis: usize,
+    pub dim: TDim,
+}
+
 #[derive(Clone, PartialEq, Eq, Hash)]
 pub struct PulsedFact {
     pub datum_type: DatumType,
@@ -38,24 +126,75 @@ pub struct PulsedFact {
     pub axis: usize,
     pub dim: TDim,
     pub delay: usize,
+    /// upper bound of `pulse_symbol()`, for variable pulses
+    pub max_pulse: Option<usize>,
+    pub lanes: TVec<LaneAxis>,
 }
 
 impl_dyn_hash!(PulsedFact);
 
 impl PulsedFact {
-    pub fn from_tensor_fact_pulse(tf: &TypedFact, pulse: usize) -> TractResult<PulsedFact> {
+    pub fn from_tensor_fact_pulse(tf: &TypedFact, pulse: &TDim) -> TractResult<PulsedFact> {
+        PulsedFact::from_tensor_fact_spec(tf, &PulseSpec::new(pulse))
+    }
+
+    pub fn from_tensor_fact_spec(tf: &TypedFact, spec: &PulseSpec) -> TractResult<PulsedFact> {
         let datum_type = tf.datum_type;
         let (axis, len) = tf
             .shape
             .stream_info()
             .ok_or_else(|| format_err!("Can not pulse a tensor with no streaming dim"))?;
         let mut shape: TVec<TDim> = tf.shape.iter().collect();
-        shape[axis] = pulse.into();
-        Ok(PulsedFact { datum_type, shape: shape.into(), axis, dim: len.clone(), delay: 0 })
+        shape[axis] = spec.pulse.clone();
+        let mut lanes = tvec!();
+        for (symbol, pulse) in &spec.lanes {
+            for (ix, dim) in tf.shape.iter().enumerate() {
+                if dim.symbols().contains(symbol) {
+                    if ix == axis {
+                        bail!("Axis {} can not both stream and be made of lanes", ix)
+                    }
+                    lanes.push(LaneAxis { axis: ix, dim: dim.clone() });
+                    shape[ix] = pulse.clone();
+                }
+            }
+        }
+        Ok(PulsedFact {
+            datum_type,
+            shape: shape.into(),
+            axis,
+            dim: len.clone(),
+            delay: 0,
+            max_pulse: spec.max_pulse,
+            lanes,
+        })
     }
 
-    pub fn pulse(&self) -> usize {
-        self.shape[self.axis].to_usize().expect("Pulse should be an integer. This is a tract bug.")
+    /// Build a fact streaming along `axis`, with no delay, lanes nor pulse bound.
+    pub fn new(datum_type: DatumType, shape: ShapeFact, axis: usize, dim: TDim) -> PulsedFact {
+        PulsedFact { datum_type, shape, axis, dim, delay: 0, max_pulse: None, lanes: tvec!() }
+    }
+
+    /// Longest pulse this fact can take along its streaming axis, if bounded.
+    pub fn max_pulse_len(&self) -> Option<usize> {
+        if let Ok(pulse) = self.pulse().to_usize() {
+            Some(pulse)
+        } else {
+            let max = self.max_pulse?;
+            let values = SymbolValues::default().with(pulse_symbol(), max as i64);
+            self.pulse().eval(&values).to_usize().ok()
+        }
+    }
+
+    /// Pulse length along the streaming axis. May be symbolic (see `pulse_symbol`).
+    pub fn pulse(&self) -> &TDim {
+        &self.shape[self.axis]
+    }
+
+    /// Pulse length, for callers that require a fixed pulse.
+    pub fn fixed_pulse(&self) -> TractResult<usize> {
+        self.pulse().to_usize().with_context(|| {
+            format!("Expected a fixed pulse, got a variable one ({})", self.pulse())
+        })
     }
 
     pub fn to_pulse_fact(&self) -> TypedFact {
@@ -66,14 +205,20 @@ impl PulsedFact {
         self.shape
             .iter()
             .enumerate()
-            .map(|(ix, d)| if ix == self.axis { self.dim.clone() } else { d })
+            .map(|(ix, d)| {
+                if ix == self.axis {
+                    self.dim.clone()
+                } else if let Some(lane) = self.lanes.iter().find(|l| l.axis == ix) {
+                    lane.dim.clone()
+                } else {
+                    d
+                }
+            })
             .collect()
     }
 
     pub fn to_streaming_fact(&self) -> TypedFact {
-        let mut info = self.to_pulse_fact();
-        info.shape.set(self.axis, self.dim.clone());
-        info
+        self.datum_type.fact(self.streaming_shape())
     }
 }
 
@@ -82,13 +227,20 @@ impl fmt::Debug for PulsedFact {
         use tract_itertools::Itertools;
         write!(
             fmt,
-            "{},{:?} [pulse axis:{} ∂:{} full dim:{}]",
+            "{},{:?} [pulse axis:{} ∂:{} full dim:{}",
             self.shape.iter().join(","),
             self.datum_type,
             self.axis,
             self.delay,
             self.dim
-        )
+        )?;
+        if let Some(max) = self.max_pulse {
+            write!(fmt, " {}<={}", pulse_symbol().as_char(), max)?;
+        }
+        for lane in &self.lanes {
+            write!(fmt, " lanes axis:{} full dim:{}", lane.axis, lane.dim)?;
+        }
+        write!(fmt, "]")
     }
 }
 
@@ -13,7 +13,9 @@ pub mod internal {
 
     pub use downcast_rs::Downcast;
 
-    pub use crate::fact::{stream_dim, stream_symbol, PulsedFact};
+    pub use crate::fact::{
+        pulse_dim, pulse_symbol, stream_dim, stream_symbol, LaneAxis, PulseSpec, PulsedFact,
+    };
     pub use crate::model::{PulsedModel, PulsedModelExt};
     pub use crate::ops::{OpPulsifier, PulsedOp};
     pub use tract_pulse_opl::op_pulse;
@@ -92,4 +94,121 @@ mod tests {
         assert_eq!(*pulse.input_fact(0).unwrap().to_typed_fact().unwrap(), f32::fact([4, 2, 3]));
         assert_eq!(*pulse.output_fact(0).unwrap().to_typed_fact().unwrap(), f32::fact([4, 2, 3]));
     }
+
+    #[test]
+    fn test_variable_pulse() {
+        let mut model = TypedModel::default();
+        let _a = model
+            .add_source("a", f32::fact([stream_dim(), 2.to_dim()].as_ref()))
+            .unwrap();
+        model.auto_outputs().unwrap();
+
+        let pulse = PulsedModel::new(&model, pulse_dim() * 2).unwrap();
+        let fact = pulse.input_fact(0).unwrap();
+        assert_eq!(fact.pulse(), &(pulse_dim() * 2));
+        assert!(fact.fixed_pulse().is_err());
+    }
+
+    #[test]
+    fn test_pulse_with_foreign_symbol() {
+        let mut model = TypedModel::default();
+        let _a = model
+            .add_source("a", f32::fact([stream_dim(), 2.to_dim()].as_ref()))
+            .unwrap();
+        model.auto_outputs().unwrap();
+        assert!(PulsedModel::new(&model, stream_dim()).is_err());
+    }
+
+    fn padded_lanes_model() -> TypedModel {
+        use tract_core::ops::array::{Pad, PadMode};
+        let mut model = TypedModel::default();
+        let a = model
+            .add_source("a", f32::fact([Symbol::from('B').into(), stream_dim()].as_ref()))
+            .unwrap();
+        let pad = Pad { pads: vec![(0, 0), (2, 0)], mode: PadMode::Constant(rctensor0(0f32)) };
+        model.wire_node("pad", pad, &[a]).unwrap();
+        model.auto_outputs().unwrap();
+        model
+    }
+
+    #[test]
+    fn test_lanes_with_bounded_pulse() {
+        let model = padded_lanes_model();
+        let b = Symbol::from('B');
+        let spec = PulseSpec::new(pulse_dim()).with_max_pulse(4).with_lanes(b, 3);
+        let pulsed = PulsedModel::new_with_spec(&model, &spec).unwrap();
+        let fact = pulsed.output_fact(0).unwrap().clone();
+        assert_eq!(fact.lanes, tvec!(LaneAxis { axis: 0, dim: b.into() }));
+        assert_eq!(fact.max_pulse, Some(4));
+        let delay =
+            pulsed.nodes().iter().find_map(|n| n.op_as::<tract_pulse_opl::ops::Delay>()).unwrap();
+        assert_eq!(delay.max_pulse, Some(4));
+
+        let plan = SimplePlan::new(pulsed).unwrap();
+        let mut state = SimpleState::new(&plan).unwrap();
+        let mut got: Vec<TVec<f32>> = vec![tvec!(); 3];
+        let mut written = 0;
+        for len in [3, 1, 4, 2] {
+            let input = tract_ndarray::Array2::from_shape_fn((3, len), |(lane, t)| {
+                (lane * 100 + written + t + 1) as f32
+            });
+            written += len;
+            let output = state.run(tvec!(input.into_tensor())).unwrap();
+            let output = output[0].to_array_view::<f32>().unwrap();
+            for (lane, got) in got.iter_mut().enumerate() {
+                got.extend(output.index_axis(tract_ndarray::Axis(0), lane).iter().copied());
+            }
+        }
+        for (lane, got) in got.iter().enumerate() {
+            let expect =
+                [0f32, 0.].iter().copied().chain((0..written).map(|t| (lane * 100 + t + 1) as f32));
+            assert!(got[fact.delay..].iter().copied().eq(expect.take(written - fact.delay)));
+        }
+        let input = Tensor::zero::<f32>(&[3, 5]).unwrap();
+        assert!(state.run(tvec!(input)).is_err());
+    }
+
+    #[test]
+    fn test_lanes_follow_axis_moves() {
+        let mut model = TypedModel::default();
+        let b = Symbol::from('B');
+        let a = model.add_source("a", f32::fact([b.into(), stream_dim()].as_ref())).unwrap();
+        let moved = model.wire_node("moved", AxisOp::Move(0, 1), &[a]).unwrap();
+        let double = tract_core::ops::math::mul::unary(rctensor2(&[[2f32]]));
+        model.wire_node("double", double, &moved).unwrap();
+        model.auto_outputs().unwrap();
+
+        let spec = PulseSpec::new(2).with_lanes(b, Symbol::from('L'));
+        let pulsed = PulsedModel::new_with_spec(&model, &spec).unwrap();
+        let fact = pulsed.output_fact(0).unwrap().clone();
+        assert_eq!(fact.axis, 0);
+        assert_eq!(fact.lanes, tvec!(LaneAxis { axis: 1, dim: b.into() }));
+
+        // stateless, so lanes can join and leave
+        let plan = SimplePlan::new(pulsed).unwrap();
+        let mut state = SimpleState::new(&plan).unwrap();
+        let output = state.run(tvec!(tensor2(&[[1f32, 2.], [3., 4.], [5., 6.]]))).unwrap();
+        assert_eq!(*output[0], tensor2(&[[2f32, 6., 10.], [4., 8., 12.]]));
+        let output = state.run(tvec!(tensor2(&[[7f32, 8.]]))).unwrap();
+        assert_eq!(*output[0], tensor2(&[[14f32], [16.]]));
+    }
+
+    #[test]
+    fn test_invalid_specs() {
+        let model = padded_lanes_model();
+        let b = Symbol::from('B');
+        // bound on a fixed pulse
+        assert!(PulsedModel::new_with_spec(&model, &PulseSpec::new(4).with_max_pulse(4)).is_err());
+        // the padding state can not follow lanes joining or leaving
+        let spec = PulseSpec::new(4).with_lanes(b, Symbol::from('L'));
+        assert!(PulsedModel::new_with_spec(&model, &spec).is_err());
+        // lanes can not be mixed, unlike the values along a regular axis
+        let mut model = padded_lanes_model();
+        let pad = model.output_outlets().unwrap()[0];
+        let sum = tract_core::ops::nn::Reduce::new(tvec!(0), tract_core::ops::nn::Reducer::Sum);
+        let sum = model.wire_node("sum", sum, &[pad]).unwrap();
+        model.set_output_outlets(&sum).unwrap();
+        assert!(PulsedModel::new_with_spec(&model, &PulseSpec::new(4).with_lanes(b, 3)).is_err());
+        assert!(PulsedModel::new(&model, 4).is_ok());
+    }
 }
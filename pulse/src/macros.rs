@@ -29,7 +29,7 @@ macro_rules! register_all {
                            node: &TypedNode,
                            target: &mut PulsedModel,
                            mapping: &HashMap<OutletId, OutletId>,
-                           pulse: usize|
+                           pulse: &PulseSpec|
                      -> TractResult<Option<TVec<OutletId>>> {
                         let op = node.op_as::<$op>().unwrap();
                         ($func)(op, source, node, target, mapping, pulse)
@@ -1,32 +1,43 @@
 use crate::{internal::*, ops::sync_inputs};
 use tract_core::model::translator::Translate;
+use tract_pulse_opl::ops::Delay;
 
 pub type PulsedModel = Graph<PulsedFact, Box<dyn PulsedOp>>;
 pub type PulsedNode = Node<PulsedFact, Box<dyn PulsedOp>>;
 
 #[allow(clippy::new_ret_no_self)]
 pub trait PulsedModelExt {
-    fn new(source: &TypedModel, pulse: usize) -> TractResult<PulsedModel>;
+    /// Pulsify `source`. `pulse` can be a fixed size, or an expression of
+    /// `pulse_symbol()` (like `4*P`) to feed variable length pulses.
+    fn new(source: &TypedModel, pulse: impl ToDim) -> TractResult<PulsedModel>;
+
+    /// Pulsify `source` with a bounded pulse or extra streaming axes.
+    fn new_with_spec(source: &TypedModel, spec: &PulseSpec) -> TractResult<PulsedModel>;
 
     fn new_with_mapping(
         source: &TypedModel,
-        pulse: usize,
+        spec: &PulseSpec,
     ) -> TractResult<(PulsedModel, HashMap<OutletId, OutletId>)>;
 
     fn into_typed(self) -> TractResult<TypedModel>;
 }
 
 impl PulsedModelExt for PulsedModel {
-    fn new(source: &TypedModel, pulse: usize) -> TractResult<PulsedModel> {
-        Ok(PulsedModel::new_with_mapping(source, pulse)?.0)
+    fn new(source: &TypedModel, pulse: impl ToDim) -> TractResult<PulsedModel> {
+        PulsedModel::new_with_spec(source, &PulseSpec::new(pulse))
+    }
+
+    fn new_with_spec(source: &TypedModel, spec: &PulseSpec) -> TractResult<PulsedModel> {
+        Ok(PulsedModel::new_with_mapping(source, spec)?.0)
     }
 
     fn new_with_mapping(
         source: &TypedModel,
-        pulse: usize,
+        spec: &PulseSpec,
     ) -> TractResult<(PulsedModel, HashMap<OutletId, OutletId>)> {
+        spec.check()?;
         let pulsifiers = crate::ops::OpPulsifier::inventory();
-        Pulsifier(pulse, pulsifiers).translate_model_with_mappings(source)
+        Pulsifier(spec.clone(), pulsifiers).translate_model_with_mappings(source)
     }
 
     fn into_typed(self) -> TractResult<TypedModel> {
@@ -41,7 +52,7 @@ impl PulsedModelExt for PulsedModel {
         typed.properties.insert("pulse.delay".to_string(), delays.into_arc_tensor());
         let input_axes = tensor1(
             &self
-                .output_outlets()?
+                .input_outlets()?
                 .iter()
                 .map(|oo| Ok(self.outlet_fact(*oo)?.axis as _))
                 .collect::<TractResult<TVec<i64>>>()?,
@@ -82,7 +93,9 @@ impl SpecialOps<PulsedFact, Box<dyn PulsedOp>> for PulsedModel {
         let output_facts = {
             let input_facts =
                 inputs.iter().map(|o| self.outlet_fact(*o)).collect::<TractResult<TVec<_>>>()?;
-            op.pulsed_output_facts(&*input_facts)?
+            let mut output_facts = op.pulsed_output_facts(&*input_facts)?;
+            propagate_stream_info(&*op, &input_facts, &mut output_facts)?;
+            output_facts
         };
         let id = self.add_node(name, op, output_facts)?;
         inputs
@@ -93,11 +106,62 @@ impl SpecialOps<PulsedFact, Box<dyn PulsedOp>> for PulsedModel {
     }
 }
 
-struct Pulsifier(usize, HashMap<TypeId, crate::ops::OpPulsifier>);
+// Carry the pulse bound and the lane axes from the inputs to the outputs of
+// an op. Lane axes are tracked through the invariants of the op: an op that
+// can not keep lanes apart can not be pulsified.
+fn propagate_stream_info(
+    op: &dyn PulsedOp,
+    inputs: &[&PulsedFact],
+    outputs: &mut [PulsedFact],
+) -> TractResult<()> {
+    if let Some(max_pulse) = inputs.iter().find_map(|f| f.max_pulse) {
+        outputs.iter_mut().for_each(|f| f.max_pulse = Some(max_pulse));
+    }
+    if inputs.iter().all(|f| f.lanes.is_empty()) {
+        return Ok(());
+    }
+    let variable_lanes =
+        inputs.iter().any(|f| f.lanes.iter().any(|l| f.shape[l.axis].to_usize().is_err()));
+    if variable_lanes && op.as_op().downcast_ref::<Delay>().is_none() && !op.is_stateless() {
+        bail!("{} keeps a state and does not support a variable number of lanes", op.name());
+    }
+    let typed_op = op.to_typed();
+    let input_facts = inputs.iter().map(|f| f.to_pulse_fact()).collect::<TVec<_>>();
+    let output_facts = outputs.iter().map(|f| f.to_pulse_fact()).collect::<TVec<_>>();
+    let invariants = typed_op.invariants(
+        &input_facts.iter().collect::<TVec<_>>(),
+        &output_facts.iter().collect::<TVec<_>>(),
+    )?;
+    outputs.iter_mut().for_each(|f| f.lanes.clear());
+    for (ix, input) in inputs.iter().enumerate() {
+        for lane in &input.lanes {
+            let axes = invariants
+                .track_input_axis(ix, lane.axis)
+                .filter(|info| info.outputs.iter().all(|axis| axis.is_some()))
+                .with_context(|| {
+                    format!(
+                        "Can not track lane axis {} of input {} through {}",
+                        lane.axis,
+                        ix,
+                        op.name()
+                    )
+                })?;
+            for (output, axis) in outputs.iter_mut().zip(axes.outputs.iter()) {
+                let axis = axis.unwrap();
+                if !output.lanes.iter().any(|l| l.axis == axis) {
+                    output.lanes.push(LaneAxis { axis, dim: lane.dim.clone() });
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+struct Pulsifier(PulseSpec, HashMap<TypeId, crate::ops::OpPulsifier>);
 
 impl std::fmt::Debug for Pulsifier {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Pulsifier({})", self.0)
+        write!(f, "Pulsifier({:?})", self.0)
     }
 }
 
@@ -117,7 +181,7 @@ impl
         mapping: &HashMap<OutletId, OutletId>,
     ) -> TractResult<TVec<OutletId>> {
         if let Some(pulsifier) = self.1.get(&node.op.type_id()) {
-            if let Some(pulsified) = (pulsifier.func)(source, node, target, mapping, self.0)? {
+            if let Some(pulsified) = (pulsifier.func)(source, node, target, mapping, &self.0)? {
                 return Ok(pulsified);
             }
         }
@@ -195,6 +259,8 @@ impl PulsedOp for PulseWrappingOp {
                     delay: inputs[0].delay,
                     axis: axis_info.outputs[ix].context("Disappearing streaming axis")?,
                     dim: inputs[0].dim.clone(),
+                    max_pulse: inputs[0].max_pulse,
+                    lanes: tvec!(),
                 })
             })
             .collect()
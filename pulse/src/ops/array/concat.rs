@@ -1,8 +1,8 @@
 use crate::internal::*;
+use crate::ops::delay::pulsed_delay;
 use std::ops::Range;
 use tract_core::ndarray::*;
 use tract_core::ops::array::TypedConcat;
-use tract_pulse_opl::ops::Delay;
 
 register_all!(TypedConcat: pulsify);
 
@@ -12,7 +12,7 @@ fn pulsify(
     node: &TypedNode,
     target: &mut PulsedModel,
     mapping: &HashMap<OutletId, OutletId>,
-    _pulse: usize,
+    _pulse: &PulseSpec,
 ) -> TractResult<Option<TVec<OutletId>>> {
     let input = mapping[&node.inputs[0]];
     let fact = target.outlet_fact(input)?;
@@ -53,7 +53,7 @@ fn pulsify_along_concat_axis(
     if fact.delay < before {
         input = target.wire_node(
             format!("{}.Delay", node.name),
-            Delay::new_typed(&(&fact).into(), fact.axis, before - fact.delay, 0),
+            pulsed_delay(&fact, before - fact.delay, 0),
             &[input],
         )?[0];
     }
@@ -0,0 +1,36 @@
+use crate::internal::*;
+use crate::ops::sync_inputs;
+use tract_core::ops::array::GatherElements;
+
+register_all!(GatherElements: pulsify);
+
+fn pulsify(
+    op: &GatherElements,
+    _source: &TypedModel,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    _pulse: &PulseSpec,
+) -> TractResult<Option<TVec<OutletId>>> {
+    let inputs = sync_inputs(node, target, mapping)?;
+    let data = target.outlet_fact(inputs[0])?;
+    let indices = target.outlet_fact(inputs[1])?;
+    if data.axis == op.axis {
+        bail!("Can not pulsify GatherElements along the streaming axis");
+    }
+    if data.axis != indices.axis || data.pulse() != indices.pulse() {
+        bail!("GatherElements data and indices must stream along the same axis, with the same pulse");
+    }
+    Ok(Some(target.wire_node(&*node.name, op.clone(), &inputs)?))
+}
+
+impl PulsedOp for GatherElements {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[1].clone();
+        fact.datum_type = inputs[0].datum_type;
+        Ok(tvec!(fact))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
@@ -1,7 +1,8 @@
 use crate::internal::*;
 
 mod concat;
+mod gather_elements;
 mod pad;
 mod slice;
 
-register_all_mod!(concat, pad, slice);
+register_all_mod!(concat, gather_elements, pad, slice);
@@ -0,0 +1,64 @@
+use crate::internal::*;
+use crate::ops::sync_inputs;
+use tract_core::ops::binary::{TypedBinOp, UnaryOp};
+
+register_all!(TypedBinOp: pulsify_bin, UnaryOp: pulsify_unary);
+
+fn pulsify_bin(
+    op: &TypedBinOp,
+    _source: &TypedModel,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    _pulse: &PulseSpec,
+) -> TractResult<Option<TVec<OutletId>>> {
+    let inputs = sync_inputs(node, target, mapping)?;
+    let a = target.outlet_fact(inputs[0])?;
+    let b = target.outlet_fact(inputs[1])?;
+    if a.axis != b.axis || a.pulse() != b.pulse() {
+        bail!("{} operands must stream along the same axis, with the same pulse", op.name());
+    }
+    Ok(Some(target.wire_node(&*node.name, op.clone(), &inputs)?))
+}
+
+fn pulsify_unary(
+    op: &UnaryOp,
+    _source: &TypedModel,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    _pulse: &PulseSpec,
+) -> TractResult<Option<TVec<OutletId>>> {
+    let input = mapping[&node.inputs[0]];
+    let fact = target.outlet_fact(input)?;
+    if op.a.shape().get(fact.axis).map(|d| *d != 1).unwrap_or(false) {
+        bail!("Can not pulsify {}: constant operand varies along the streaming axis", op.name());
+    }
+    Ok(Some(target.wire_node(&*node.name, op.clone(), &[input])?))
+}
+
+impl PulsedOp for TypedBinOp {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let typed = self.output_facts(&[&inputs[0].to_pulse_fact(), &inputs[1].to_pulse_fact()])?;
+        let mut fact = inputs[0].clone();
+        fact.datum_type = typed[0].datum_type;
+        fact.shape = typed[0].shape.clone();
+        Ok(tvec!(fact))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
+
+impl PulsedOp for UnaryOp {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let typed = self.output_facts(&[&inputs[0].to_pulse_fact()])?;
+        let mut fact = inputs[0].clone();
+        fact.datum_type = typed[0].datum_type;
+        fact.shape = typed[0].shape.clone();
+        Ok(tvec!(fact))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
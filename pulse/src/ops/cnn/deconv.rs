@@ -12,18 +12,26 @@ fn pulsify(
     node: &TypedNode,
     target: &mut PulsedModel,
     mapping: &HashMap<OutletId, OutletId>,
-    _pulse: usize,
+    _pulse: &PulseSpec,
 ) -> TractResult<Option<TVec<OutletId>>> {
     let fact = target.outlet_fact(mapping[&node.inputs[0]])?.clone();
     let c_axis = op.pool_spec.data_format.shape(&fact.shape)?.c_axis();
     if c_axis == fact.axis {
         bail!("Pulsification on C axis is not supported");
     }
-    let pulse = fact.pulse();
     let geo_axis = fact.axis - op.pool_spec.data_format.h_axis();
     let stride = op.pool_spec.stride(geo_axis);
     let mut pulse_op = op.clone();
     pulse_op.adjustments[geo_axis] = stride - 1;
+    if matches!(op.pool_spec.padding, PaddingSpec::SameUpper | PaddingSpec::SameLower) {
+        // SAME output can extend past the full deconvolution when the kernel is shorter than
+        // the stride: make the valid deconvolution long enough for the slices below
+        for ax in (0..op.pool_spec.rank()).filter(|ax| *ax != geo_axis) {
+            let kernel_field = (op.pool_spec.kernel_shape[ax] - 1) * op.pool_spec.dilation(ax) + 1;
+            pulse_op.adjustments[ax] =
+                pulse_op.adjustments[ax].max(op.pool_spec.stride(ax).saturating_sub(kernel_field));
+        }
+    }
     pulse_op.pool_spec.padding = PaddingSpec::Valid;
     let deconv =
         target.wire_node(format!("{}.deconv", node.name), pulse_op, &[mapping[&node.inputs[0]]])?
@@ -55,7 +63,6 @@ fn pulsify(
             delay: paddings[geo_axis].pad_before.to_usize()? + fact.delay,
             deconv_input_dim,
             stride,
-            pulse,
             deconv_output_dim: output_shape[fact.axis].clone(),
         },
         &[deconv],
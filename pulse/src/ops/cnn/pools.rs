@@ -1,4 +1,5 @@
 use crate::internal::*;
+use crate::ops::delay::pulsed_delay;
 use tract_core::num_traits::Zero;
 use tract_core::ops::cnn::{MaxPool, PaddingSpec, PoolSpec, SumPool};
 
@@ -10,7 +11,7 @@ fn pulsify_max_pool(
     node: &TypedNode,
     target: &mut PulsedModel,
     mapping: &HashMap<OutletId, OutletId>,
-    _pulse: usize,
+    _pulse: &PulseSpec,
 ) -> TractResult<Option<TVec<OutletId>>> {
     fn min_value<D: Datum + tract_core::num_traits::Bounded>() -> Tensor {
         tensor0(D::min_value())
@@ -32,7 +33,7 @@ fn pulsify_sum_pool(
     node: &TypedNode,
     target: &mut PulsedModel,
     mapping: &HashMap<OutletId, OutletId>,
-    _pulse: usize,
+    _pulse: &PulseSpec,
 ) -> TractResult<Option<TVec<OutletId>>> {
     if let Some((wire, pool_spec)) =
         pulsify_pooled_input(&op.pool_spec, source, node, target, mapping, None)?
@@ -77,7 +78,7 @@ pub fn pulsed_output_facts(
         &*spec.kernel_shape,
         &spec.dilations(),
         &spec.strides(),
-    );
+    )?;
     let spatial_dims = computed.into_iter().map(|d| d.convoluted).collect::<TVec<TDim>>();
     let oshape = spec.data_format.from_n_c_hw(
         ishape.n().cloned().unwrap_or_else(|| 1.to_dim()),
@@ -117,8 +118,7 @@ pub fn pulsify_pooled_input(
 
     let geo_axis = fact.axis - input_shape.h_axis();
     let stride = spec.strides.as_ref().and_then(|v| v.get(geo_axis).cloned()).unwrap_or(1);
-    let pulse = fact.pulse();
-    if pulse % stride != 0 {
+    if !(fact.pulse().clone() % stride).is_zero() {
         bail!("Pulsificaton requires pulse to be a stride multiple")
     }
 
@@ -144,14 +144,13 @@ pub fn pulsify_pooled_input(
         if extra_delay > 0 {
             wire = target.wire_node(
                 format!("{}.delay-for-pad", node.name),
-                tract_pulse_opl::ops::Delay::new_typed(&(&fact).into(), fact.axis, extra_delay, 0),
+                pulsed_delay(&fact, extra_delay, 0),
                 &[wire],
             )?[0];
             fact = target.outlet_fact(wire)?.clone();
         }
         let op = tract_pulse_opl::ops::PulsePad {
             axis: fact.axis,
-            pulse,
             before,
             after: computed_padding.pad_after,
             begin_input: fact.delay,
@@ -165,14 +164,14 @@ pub fn pulsify_pooled_input(
     let dilation = spec.dilations.as_ref().map(|d| d[geo_axis]).unwrap_or(1);
     let kernel_len = (spec.kernel_shape[geo_axis] - 1) * dilation;
     let overlap = (kernel_len + 1).saturating_sub(stride);
-    let misalignment = fact.delay % pulse;
+    let misalignment = fact.delay % stride;
 
     if overlap > 0 || misalignment > 0 {
         let align_to = (overlap + fact.delay).divceil(stride) * stride;
         let delay = align_to - overlap - fact.delay;
         wire = target.wire_node(
             format!("{}.delay", node.name),
-            tract_pulse_opl::ops::Delay::new_typed(&(&fact).into(), fact.axis, delay, overlap),
+            pulsed_delay(&fact, delay, overlap),
             &[wire],
         )?[0];
     }
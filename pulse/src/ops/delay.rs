@@ -8,19 +8,34 @@ pub fn register(registry: &mut Registry) {
 fn ser_delay(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
     let op = node.op().downcast_ref::<Delay>().unwrap();
     let wire = ast.mapping[&node.inputs[0]].clone();
-    Ok(Some(invocation(
-        "tract_pulse_delay",
-        &[wire],
-        &[
-            ("axis", numeric(op.axis)),
-            ("delay", numeric(op.delay)),
-            ("overlap", numeric(op.overlap)),
-        ],
-    )))
+    let mut named_args = tvec!(
+        ("axis", numeric(op.axis)),
+        ("delay", numeric(op.delay)),
+        ("overlap", numeric(op.overlap)),
+    );
+    if let Some(max_pulse) = op.max_pulse {
+        named_args.push(("max_pulse", numeric(max_pulse)));
+    }
+    Ok(Some(invocation("tract_pulse_delay", &[wire], &named_args)))
+}
+
+/// Delay along the streaming axis of `fact`, its buffer sized for the longest pulse.
+pub fn pulsed_delay(fact: &PulsedFact, delay: usize, overlap: usize) -> Delay {
+    Delay::new_typed(&fact.into(), fact.axis, delay, overlap).with_max_pulse(fact.max_pulse_len())
 }
 
 impl PulsedOp for Delay {
     fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        if self.axis == inputs[0].axis && inputs[0].pulse().to_usize().is_err() {
+            if let Some(max) = inputs[0].max_pulse_len() {
+                ensure!(
+                    self.max_pulse.map(|m| m >= max).unwrap_or(false),
+                    "Delay is sized for pulses up to {:?}, input pulse goes up to {}",
+                    self.max_pulse,
+                    max
+                );
+            }
+        }
         let mut fact = inputs[0].clone();
         fact.shape.set(self.axis, fact.shape[self.axis].clone() + self.overlap);
         fact.delay += self.delay + self.overlap;
@@ -37,13 +52,7 @@ mod test {
 
     fn test_pulse_delay_over(pulse: usize, delay: usize, overlap: usize) {
         let mut model = PulsedModel::default();
-        let fact1 = PulsedFact {
-            datum_type: u8::datum_type(),
-            shape: (&[pulse]).into(),
-            axis: 0,
-            dim: stream_dim(),
-            delay: 0,
-        };
+        let fact1 = PulsedFact::new(u8::datum_type(), (&[pulse]).into(), 0, stream_dim());
         let source = model.add_source("source", fact1.clone()).unwrap();
         model
             .wire_node("delay", Delay::new_typed(&(&fact1).into(), fact1.axis, delay, overlap), &[source])
@@ -84,17 +93,88 @@ mod test {
         test_pulse_delay_over(4, 0, 6);
     }
 
+    #[test]
+    fn variable_pulse() {
+        let mut model = PulsedModel::default();
+        let fact = PulsedFact::new(u8::datum_type(), [pulse_dim()].into(), 0, stream_dim());
+        let source = model.add_source("source", fact.clone()).unwrap();
+        model
+            .wire_node("delay", Delay::new_typed(&(&fact).into(), fact.axis, 3, 0), &[source])
+            .unwrap();
+        model.auto_outputs().unwrap();
+
+        let plan = SimplePlan::new(model).unwrap();
+        let mut state = tract_core::plan::SimpleState::new(plan).unwrap();
+
+        let mut got: Vec<u8> = vec![];
+        let mut written = 0;
+        for len in [1, 5, 2, 7, 3] {
+            let input: Vec<u8> = (written..written + len).map(|i| i as u8 + 1).collect();
+            written += len;
+            let output = state.run(tvec!(tensor1(&input))).unwrap();
+            assert_eq!(output[0].len(), len);
+            got.extend(output[0].as_slice::<u8>().unwrap());
+        }
+        let expect: Vec<u8> = (0..written).map(|i| (i as u8 + 1).saturating_sub(3)).collect();
+        assert_eq!(got, expect);
+    }
+
+    #[test]
+    fn bounded_pulse() {
+        let mut model = PulsedModel::default();
+        let mut fact = PulsedFact::new(u8::datum_type(), [pulse_dim()].into(), 0, stream_dim());
+        fact.max_pulse = Some(4);
+        let source = model.add_source("source", fact.clone()).unwrap();
+        let unbounded = Delay::new_typed(&(&fact).into(), fact.axis, 2, 0);
+        assert!(model.wire_node("unbounded", unbounded, &[source]).is_err());
+        let delay = model.wire_node("delay", pulsed_delay(&fact, 2, 0), &[source]).unwrap();
+        model.auto_outputs().unwrap();
+        assert_eq!(model.node(delay[0].node).op_as::<Delay>().unwrap().max_pulse, Some(4));
+
+        let plan = SimplePlan::new(model).unwrap();
+        let mut state = tract_core::plan::SimpleState::new(plan).unwrap();
+        let output = state.run(tvec!(tensor1(&[1u8, 2, 3, 4]))).unwrap();
+        assert_eq!(*output[0], tensor1(&[0u8, 0, 1, 2]));
+        let output = state.run(tvec!(tensor1(&[5u8]))).unwrap();
+        assert_eq!(*output[0], tensor1(&[3u8]));
+        assert!(state.run(tvec!(tensor1(&[6u8, 7, 8, 9, 10]))).is_err());
+    }
+
+    #[test]
+    fn variable_lanes() {
+        let mut model = PulsedModel::default();
+        let mut fact = PulsedFact::new(
+            u8::datum_type(),
+            [Symbol::from('L').into(), 2.to_dim()].into(),
+            1,
+            stream_dim(),
+        );
+        fact.lanes.push(LaneAxis { axis: 0, dim: Symbol::from('B').into() });
+        let source = model.add_source("source", fact.clone()).unwrap();
+        model
+            .wire_node("delay", Delay::new_typed(&(&fact).into(), fact.axis, 1, 0), &[source])
+            .unwrap();
+        model.auto_outputs().unwrap();
+
+        let plan = SimplePlan::new(model).unwrap();
+        let mut state = tract_core::plan::SimpleState::new(plan).unwrap();
+        // lanes keep their own history as they join and leave
+        for (input, expect) in [
+            (tensor2(&[[1u8, 2], [11, 12]]), tensor2(&[[0u8, 1], [0, 11]])),
+            (tensor2(&[[3u8, 4], [13, 14], [23, 24]]), tensor2(&[[2u8, 3], [12, 13], [0, 23]])),
+            (tensor2(&[[5u8, 6]]), tensor2(&[[4u8, 5]])),
+            (tensor2(&[[7u8, 8], [37, 38]]), tensor2(&[[6u8, 7], [0, 37]])),
+        ] {
+            let output = state.run(tvec!(input)).unwrap();
+            assert_eq!(*output[0], expect);
+        }
+    }
+
     #[test]
     fn test_two_delays() {
         let pulse = 4usize;
         let mut model = PulsedModel::default();
-        let fact_0 = PulsedFact {
-            datum_type: u8::datum_type(),
-            shape: (&[pulse]).into(),
-            axis: 0,
-            dim: stream_dim(),
-            delay: 0,
-        };
+        let fact_0 = PulsedFact::new(u8::datum_type(), (&[pulse]).into(), 0, stream_dim());
         let source = model.add_source("source", fact_0.clone()).unwrap();
         let delay_1 = model
             .wire_node("delay-1", Delay::new_typed(&(&fact_0).into(), fact_0.axis, 2, 0), &[source])
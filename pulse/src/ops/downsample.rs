@@ -1,4 +1,5 @@
 use crate::internal::*;
+use tract_core::num_traits::Zero;
 use tract_core::ops::Downsample;
 
 register_all!(Downsample: pulsify);
@@ -9,16 +10,16 @@ fn pulsify(
     node: &TypedNode,
     target: &mut PulsedModel,
     mapping: &HashMap<OutletId, OutletId>,
-    _pulse: usize,
+    _pulse: &PulseSpec,
 ) -> TractResult<Option<TVec<OutletId>>> {
     let input = mapping[&node.inputs[0]];
-    let pulse = target.outlet_fact(input)?.pulse();
+    let pulse = target.outlet_fact(input)?.pulse().clone();
     let stride = if op.stride > 0 {
         op.stride as usize
     } else {
         bail!("Negative strides are not causal, can not pulsify.")
     };
-    if pulse % stride != 0 {
+    if !(pulse.clone() % stride).is_zero() {
         bail!("Pulsificaton requires pulse to be a stride multiple")
     }
     Ok(Some(target.wire_node(&*node.name, op.clone(), &[input])?))
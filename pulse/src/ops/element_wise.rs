@@ -0,0 +1,28 @@
+use crate::internal::*;
+use tract_core::ops::element_wise::ElementWiseOp;
+
+register_all!(ElementWiseOp: pulsify);
+
+fn pulsify(
+    op: &ElementWiseOp,
+    _source: &TypedModel,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    _pulse: &PulseSpec,
+) -> TractResult<Option<TVec<OutletId>>> {
+    Ok(Some(target.wire_node(&*node.name, op.clone(), &[mapping[&node.inputs[0]]])?))
+}
+
+impl PulsedOp for ElementWiseOp {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        if let Some(dt) = self.0.output_type(fact.datum_type) {
+            fact.datum_type = dt;
+        }
+        Ok(tvec!(fact))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
@@ -0,0 +1,54 @@
+use crate::internal::*;
+use crate::ops::delay::pulsed_delay;
+use tract_core::num_traits::Zero;
+use tract_core::ops::fft::Stft;
+
+register_all!(Stft: pulsify);
+
+fn pulsify(
+    op: &Stft,
+    _source: &TypedModel,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    _pulse: &PulseSpec,
+) -> TractResult<Option<TVec<OutletId>>> {
+    let mut wire = mapping[&node.inputs[0]];
+    let fact = target.outlet_fact(wire)?.clone();
+    if fact.axis != op.axis {
+        return Ok(Some(target.wire_node(&*node.name, op.clone(), &[wire])?));
+    }
+    if !(fact.pulse().clone() % op.stride).is_zero() {
+        bail!("Pulsificaton requires pulse to be a stride multiple")
+    }
+    let overlap = op.frame.saturating_sub(op.stride);
+    let misalignment = fact.delay % op.stride;
+    if overlap > 0 || misalignment > 0 {
+        let align_to = (overlap + fact.delay).divceil(op.stride) * op.stride;
+        let delay = align_to - overlap - fact.delay;
+        wire = target.wire_node(
+            format!("{}.delay", node.name),
+            pulsed_delay(&fact, delay, overlap),
+            &[wire],
+        )?[0];
+    }
+    Ok(Some(target.wire_node(&*node.name, op.clone(), &[wire])?))
+}
+
+impl PulsedOp for Stft {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        let typed = self.output_facts(&[&fact.to_pulse_fact()])?.remove(0);
+        fact.shape = typed.shape;
+        if fact.axis == self.axis {
+            fact.delay /= self.stride;
+            fact.dim = self.frames(&fact.dim);
+        } else if fact.axis > self.axis {
+            fact.axis += 1;
+        }
+        Ok(tvec!(fact))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
@@ -1,12 +1,16 @@
 use crate::internal::*;
-use tract_pulse_opl::ops::Delay;
+use delay::pulsed_delay;
 
 pub mod array;
+pub mod binary;
 pub mod cnn;
 pub mod delay;
 pub mod downsample;
 pub mod dummy;
+pub mod element_wise;
+pub mod fft;
 pub mod matmul;
+pub mod nn;
 pub mod qmatmul;
 pub mod scan;
 pub mod slice;
@@ -29,10 +33,9 @@ pub(crate) fn sync_inputs(
         let fact = target.outlet_fact(input)?.clone();
         if fact.delay < delay {
             let add_delay = delay - fact.delay;
-            let delay_axis = fact.axis;
             input = target.wire_node(
                 format!("{}.Delay", &*node.name),
-                Delay::new_typed(&fact.into(), delay_axis, add_delay, 0),
+                pulsed_delay(&fact, add_delay, 0),
                 &[input],
             )?[0];
         }
@@ -41,14 +44,14 @@ pub(crate) fn sync_inputs(
     Ok(inputs)
 }
 
-register_all_mod!(array, cnn, downsample, matmul, qmatmul, scan, source);
+register_all_mod!(array, binary, cnn, downsample, element_wise, fft, matmul, nn, qmatmul, scan, source);
 
 type PulsifierFn = fn(
     &TypedModel,
     &TypedNode,
     &mut PulsedModel,
     &HashMap<OutletId, OutletId>,
-    usize,
+    &PulseSpec,
 ) -> TractResult<Option<TVec<OutletId>>>;
 
 pub struct OpPulsifier {
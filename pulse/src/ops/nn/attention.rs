@@ -0,0 +1,192 @@
+use crate::internal::*;
+use crate::ops::delay::pulsed_delay;
+use crate::ops::sync_inputs;
+use tract_core::ops::nn::BandedAttention;
+use tract_pulse_opl::ops::PulsedBandedAttention;
+
+register_all!(BandedAttention: pulsify);
+
+fn pulsify(
+    op: &BandedAttention,
+    _source: &TypedModel,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    _pulse: &PulseSpec,
+) -> TractResult<Option<TVec<OutletId>>> {
+    let mut inputs = sync_inputs(node, target, mapping)?;
+    let facts =
+        inputs.iter().map(|i| target.outlet_fact(*i).cloned()).collect::<TractResult<TVec<_>>>()?;
+    if facts.iter().any(|f| f.axis != facts[0].axis || f.pulse() != facts[0].pulse()) {
+        bail!("BandedAttention inputs must stream along the same axis, with the same pulse");
+    }
+    let fact = &facts[0];
+    let rank = fact.shape.rank();
+    if fact.axis < rank - 2 {
+        return Ok(Some(target.wire_node(&*node.name, op.clone(), &inputs)?));
+    }
+    if fact.axis != rank - 2 {
+        bail!("Can not pulsify BandedAttention along the feature axis");
+    }
+    if op.right_context > 0 {
+        inputs[0] = target.wire_node(
+            format!("{}.query-lookahead", node.name),
+            pulsed_delay(&fact, op.right_context, 0),
+            &[inputs[0]],
+        )?[0];
+    }
+    let op = PulsedBandedAttention {
+        attention: op.clone(),
+        begin_input: fact.delay,
+        end_input: fact.delay.to_dim() + &fact.dim,
+    };
+    Ok(Some(target.wire_node(&*node.name, op, &inputs)?))
+}
+
+impl PulsedOp for BandedAttention {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let rank = inputs[0].shape.rank();
+        let mut fact = inputs[0].clone();
+        fact.shape.set(rank - 1, inputs[2].shape[rank - 1].clone());
+        Ok(tvec!(fact))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
+
+impl PulsedOp for PulsedBandedAttention {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        self.attention.pulsed_output_facts(inputs)
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn model(left_context: usize, right_context: usize) -> TypedModel {
+        let mut model = TypedModel::default();
+        let fact = f32::fact([2.to_dim(), stream_dim(), 3.to_dim()].as_ref());
+        let q = model.add_source("q", fact.clone()).unwrap();
+        let k = model.add_source("k", fact.clone()).unwrap();
+        let v = model.add_source("v", fact).unwrap();
+        let att = model
+            .wire_node("att", BandedAttention::new(left_context, right_context, 0.5), &[q, k, v])
+            .unwrap();
+        model.set_output_outlets(&att).unwrap();
+        model
+    }
+
+    // softmax(q·kᵀ × 0.5 + mask)·v, with a band mask computed from the stream length
+    fn decomposed(left_context: usize, right_context: usize) -> TypedModel {
+        use tract_core::ops::array::Range;
+        use tract_core::ops::cast::cast;
+        use tract_core::ops::logic::{and, greater_equal, less_equal, Iff};
+        use tract_core::ops::math::{add, mul, sub};
+        use tract_core::ops::matmul::MatMul;
+        let mut model = TypedModel::default();
+        let fact = f32::fact([2.to_dim(), stream_dim(), 3.to_dim()].as_ref());
+        let q = model.add_source("q", fact.clone()).unwrap();
+        let k = model.add_source("k", fact.clone()).unwrap();
+        let v = model.add_source("v", fact).unwrap();
+        let range = Range::new(tensor0(0.to_dim()), tensor0(stream_dim()), tensor0(1.to_dim()));
+        let range = model.wire_node("range", range, &[]).unwrap();
+        let range = model.wire_node("range.i64", cast(i64::datum_type()), &range).unwrap();
+        let queries = model.wire_node("queries", AxisOp::Add(1), &range).unwrap();
+        let keys = model.wire_node("keys", AxisOp::Add(0), &range).unwrap();
+        let rel = model.wire_node("rel", sub::bin_typed(), &[keys[0], queries[0]]).unwrap();
+        let left = model.add_const("left", rctensor2(&[[-(left_context as i64)]])).unwrap();
+        let right = model.add_const("right", rctensor2(&[[right_context as i64]])).unwrap();
+        let after = model.wire_node("after", greater_equal::bin_typed(), &[rel[0], left]).unwrap();
+        let before = model.wire_node("before", less_equal::bin_typed(), &[rel[0], right]).unwrap();
+        let band = model.wire_node("band", and::bin_typed(), &[after[0], before[0]]).unwrap();
+        let zero = model.add_const("zero", rctensor2(&[[0f32]])).unwrap();
+        let masked = model.add_const("minus-inf", rctensor2(&[[-1e9f32]])).unwrap();
+        let mask = model.wire_node("mask", Iff, &[band[0], zero, masked]).unwrap();
+        let mask = model.wire_node("mask.batch", AxisOp::Add(0), &mask).unwrap();
+        let qk = MatMul { a_trans: false, b_trans: true, c_trans: false };
+        let scores = model.wire_node("scores", qk, &[q, k]).unwrap();
+        let scale = model.add_const("scale", rctensor3(&[[[0.5f32]]])).unwrap();
+        let scaled = model.wire_node("scaled", mul::bin_typed(), &[scores[0], scale]).unwrap();
+        let scores = model.wire_node("masked", add::bin_typed(), &[scaled[0], mask[0]]).unwrap();
+        let softmax = tract_core::ops::nn::Softmax::new(tvec!(2), f32::datum_type());
+        let weights = model.wire_node("weights", softmax, &scores).unwrap();
+        let wv = MatMul { a_trans: false, b_trans: false, c_trans: false };
+        let output = model.wire_node("output", wv, &[weights[0], v]).unwrap();
+        model.set_output_outlets(&output).unwrap();
+        model
+    }
+
+    fn check(left_context: usize, right_context: usize, pulse: usize, len: usize) {
+        check_model(&model(left_context, right_context), right_context, pulse, len)
+    }
+
+    fn check_model(model: &TypedModel, right_context: usize, pulse: usize, len: usize) {
+        let inputs: TVec<Tensor> = (0..3)
+            .map(|i| {
+                tract_ndarray::Array3::from_shape_fn((2, len, 3), |(a, b, c)| {
+                    ((a * 7 + b * 3 + c * 5 + i * 11) % 13) as f32 / 13.
+                })
+                .into_tensor()
+            })
+            .collect();
+        let reference = SimplePlan::new(model).unwrap().run(inputs.clone()).unwrap().remove(0);
+
+        let pulsed = PulsedModel::new(model, pulse).unwrap();
+        let output_fact = pulsed.output_fact(0).unwrap().clone();
+        assert_eq!(output_fact.delay, right_context);
+        let plan = SimplePlan::new(pulsed.into_typed().unwrap()).unwrap();
+        let mut state = SimpleState::new(&plan).unwrap();
+        let mut got: Vec<Tensor> = vec![];
+        let mut fed = 0;
+        while got.iter().map(|t| t.shape()[1]).sum::<usize>() < len + right_context {
+            let chunk: TVec<Tensor> = inputs
+                .iter()
+                .map(|t| {
+                    let mut chunk = Tensor::zero::<f32>(&[2, pulse, 3]).unwrap();
+                    for i in 0..pulse.min(len.saturating_sub(fed)) {
+                        chunk.assign_slice(i..i + 1, t, fed + i..fed + i + 1, 1).unwrap();
+                    }
+                    chunk
+                })
+                .collect();
+            if fed + pulse > len {
+                state.session_state.resolved_symbols[stream_symbol()] = Some(len as _);
+            }
+            fed += pulse;
+            got.push(state.run(chunk).unwrap().remove(0).into_tensor());
+        }
+        let got = Tensor::stack_tensors(1, &got).unwrap();
+        let got = got.slice(1, right_context, right_context + len).unwrap();
+        got.close_enough(&reference, true).unwrap();
+    }
+
+    #[test]
+    fn causal() {
+        check(3, 0, 2, 9);
+    }
+
+    #[test]
+    fn with_lookahead() {
+        check(2, 2, 3, 10);
+    }
+
+    #[test]
+    fn lookahead_longer_than_pulse() {
+        check(4, 5, 2, 7);
+    }
+
+    #[test]
+    fn decomposed_with_streaming_mask() {
+        let model = decomposed(3, 1);
+        let decluttered = model.clone().into_decluttered().unwrap();
+        let output = decluttered.node(decluttered.output_outlets().unwrap()[0].node);
+        let op = output.op_as::<BandedAttention>().unwrap();
+        assert_eq!((op.left_context, op.right_context, op.scale), (3, 1, 0.5));
+        check_model(&decluttered, 1, 2, 9);
+    }
+}
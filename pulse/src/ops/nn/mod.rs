@@ -0,0 +1,7 @@
+use crate::internal::*;
+
+mod attention;
+mod reduce;
+mod softmax;
+
+register_all_mod!(attention, reduce, softmax);
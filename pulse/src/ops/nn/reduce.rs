@@ -0,0 +1,180 @@
+use crate::internal::*;
+use tract_core::ops::nn::{Reduce, Reducer};
+use tract_pulse_opl::ops::PulsedCumulativeReduce;
+
+register_all!(Reduce: pulsify);
+
+fn pulsify(
+    op: &Reduce,
+    _source: &TypedModel,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    pulse: &PulseSpec,
+) -> TractResult<Option<TVec<OutletId>>> {
+    let mut input = mapping[&node.inputs[0]];
+    let fact = target.outlet_fact(input)?.clone();
+    if !op.axes.contains(&fact.axis) {
+        return Ok(Some(target.wire_node(&*node.name, op.clone(), &[input])?));
+    }
+    if !pulse.cumulative_reductions {
+        bail!(
+            "Cannot reduce over the streaming axis ({}), unless cumulative reductions are enabled",
+            node
+        );
+    }
+    if !matches!(op.reducer, Reducer::Sum | Reducer::Prod | Reducer::Max | Reducer::Min) {
+        bail!("Can not pulsify {:?} along the streaming axis", op.reducer);
+    }
+    if fact.datum_type.is_quantized() {
+        bail!("Can not pulsify quantized reductions along the streaming axis");
+    }
+    let other_axes: TVec<usize> = op.axes.iter().copied().filter(|ax| *ax != fact.axis).collect();
+    if !other_axes.is_empty() {
+        input = target.wire_node(
+            format!("{}.other-axes", node.name),
+            Reduce::new(other_axes, op.reducer),
+            &[input],
+        )?[0];
+    }
+    let op = PulsedCumulativeReduce {
+        axis: fact.axis,
+        reducer: op.reducer,
+        begin_input: fact.delay,
+        end_input: fact.delay.to_dim() + &fact.dim,
+    };
+    Ok(Some(target.wire_node(&*node.name, op, &[input])?))
+}
+
+impl PulsedOp for Reduce {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let typed = self.output_facts(&[&inputs[0].to_pulse_fact()])?.remove(0);
+        let mut fact = inputs[0].clone();
+        fact.datum_type = typed.datum_type;
+        fact.shape = typed.shape;
+        Ok(tvec!(fact))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
+
+impl PulsedOp for PulsedCumulativeReduce {
+    // one running reduction per input frame: the streaming axis keeps its length
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let typed = self.output_facts(&[&inputs[0].to_pulse_fact()])?.remove(0);
+        let mut fact = inputs[0].clone();
+        fact.datum_type = typed.datum_type;
+        fact.shape = typed.shape;
+        Ok(tvec!(fact))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cumulative_sum_on_streaming_axis() {
+        let mut model = TypedModel::default();
+        let a = model.add_source("a", f32::fact([stream_dim(), 2.to_dim()].as_ref())).unwrap();
+        let sum = model.wire_node("sum", Reduce::new(tvec!(0, 1), Reducer::Sum), &[a]).unwrap();
+        model.set_output_outlets(&sum).unwrap();
+
+        let spec = PulseSpec::new(2).with_cumulative_reductions();
+        let pulsed = PulsedModel::new_with_spec(&model, &spec).unwrap();
+        let output_fact = pulsed.output_fact(0).unwrap().clone();
+        assert_eq!(output_fact.delay, 0);
+        assert_eq!(output_fact.dim, stream_dim());
+        let plan = SimplePlan::new(pulsed).unwrap();
+        let mut state = tract_core::plan::SimpleState::new(plan).unwrap();
+
+        let mut got = vec![];
+        for chunk in [tensor2(&[[1f32, 2.], [3., 4.]]), tensor2(&[[5f32, 6.], [7., 8.]])] {
+            let output = state.run(tvec!(chunk)).unwrap();
+            got.extend(output[0].as_slice::<f32>().unwrap().iter().copied());
+        }
+        assert_eq!(got, vec![3., 10., 21., 36.]);
+    }
+
+    // the last valid frame of the pulsed output must match the regular output
+    fn check_against_typed(model: TypedModel, input: Tensor, pulse: usize) {
+        let len = input.shape()[0];
+        let s = stream_symbol();
+        let regular = model
+            .concretize_dims(&SymbolValues::default().with(s, len as i64))
+            .unwrap()
+            .into_runnable()
+            .unwrap();
+        let expected = regular.run(tvec!(input.clone())).unwrap().remove(0);
+
+        let spec = PulseSpec::new(pulse).with_cumulative_reductions();
+        let pulsed = PulsedModel::new_with_spec(&model, &spec).unwrap();
+        let fact = pulsed.output_fact(0).unwrap().clone();
+        assert_eq!(fact.datum_type, expected.datum_type());
+        let values = SymbolValues::default().with(s, len as i64);
+        let last = fact.delay + fact.dim.eval(&values).to_usize().unwrap() - 1;
+        let plan = SimplePlan::new(pulsed).unwrap();
+        let mut state = tract_core::plan::SimpleState::new(plan).unwrap();
+        state.session_state.resolved_symbols[s] = Some(len as i64);
+        let mut outputs = vec![];
+        for ix in 0..(last / pulse + 1) {
+            let mut chunk = Tensor::zero::<f32>(&[pulse, input.shape()[1]]).unwrap();
+            let valid = len.saturating_sub(ix * pulse).min(pulse);
+            if valid > 0 {
+                chunk.assign_slice(..valid, &input, ix * pulse..ix * pulse + valid, 0).unwrap();
+            }
+            outputs.push(state.run(tvec!(chunk)).unwrap().remove(0));
+        }
+        let got = Tensor::stack_tensors(0, &outputs).unwrap().slice(0, last, last + 1).unwrap();
+        assert_eq!(got, *expected);
+    }
+
+    fn reduce_model(reduce: Reduce) -> TypedModel {
+        use tract_core::ops::array::{Pad, PadMode};
+        let mut model = TypedModel::default();
+        let a = model.add_source("a", f32::fact([stream_dim(), 3.to_dim()].as_ref())).unwrap();
+        let pad = Pad::new(vec![(2, 0), (0, 0)], PadMode::Constant(rctensor0(0f32)));
+        let pad = model.wire_node("pad", pad, &[a]).unwrap();
+        let reduce = model.wire_node("reduce", reduce, &pad).unwrap();
+        let scale = tract_core::ops::math::mul::unary(rctensor2(&[[2f32]]));
+        let scaled = model.wire_node("scaled", scale, &reduce).unwrap();
+        model.set_output_outlets(&scaled).unwrap();
+        model
+    }
+
+    #[test]
+    fn reduce_on_streaming_axis_is_rejected_by_default() {
+        let model = reduce_model(Reduce::new(tvec!(0), Reducer::Sum));
+        let err = PulsedModel::new(&model, 2).unwrap_err();
+        assert!(format!("{:?}", err).contains("Cannot reduce over the streaming axis"));
+    }
+
+    #[test]
+    fn cumulative_max_against_typed() {
+        let input = tensor2(&[[-1f32, -2., 3.], [-4., 5., -6.], [-7., -8., -9.]]);
+        let model = reduce_model(Reduce::new(tvec!(0), Reducer::Max));
+        check_against_typed(model, input, 2);
+    }
+
+    #[test]
+    fn cumulative_sum_other_axes_against_typed() {
+        let input = tensor2(&[[1f32, 2., 3.], [4., 5., 6.], [7., 8., 9.], [10., 11., 12.]]);
+        let model = reduce_model(Reduce::new(tvec!(0, 1), Reducer::Sum));
+        check_against_typed(model, input, 3);
+    }
+
+    #[test]
+    fn argmax_on_streaming_axis_is_rejected() {
+        let mut model = TypedModel::default();
+        let a = model.add_source("a", f32::fact([stream_dim()].as_ref())).unwrap();
+        let max =
+            model.wire_node("argmax", Reduce::new(tvec!(0), Reducer::ArgMax(false)), &[a]).unwrap();
+        model.set_output_outlets(&max).unwrap();
+        let spec = PulseSpec::new(2).with_cumulative_reductions();
+        assert!(PulsedModel::new_with_spec(&model, &spec).is_err());
+    }
+}
@@ -0,0 +1,31 @@
+use crate::internal::*;
+use tract_core::ops::nn::Softmax;
+
+register_all!(Softmax: pulsify);
+
+fn pulsify(
+    op: &Softmax,
+    _source: &TypedModel,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    _pulse: &PulseSpec,
+) -> TractResult<Option<TVec<OutletId>>> {
+    let input = mapping[&node.inputs[0]];
+    let fact = target.outlet_fact(input)?;
+    if op.axes.contains(&fact.axis) {
+        bail!("Can not pulsify Softmax along the streaming axis");
+    }
+    Ok(Some(target.wire_node(&*node.name, op.clone(), &[input])?))
+}
+
+impl PulsedOp for Softmax {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        fact.datum_type = self.output_dt;
+        Ok(tvec!(fact))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
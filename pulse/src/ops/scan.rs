@@ -9,7 +9,7 @@ fn pulsify(
     node: &TypedNode,
     target: &mut PulsedModel,
     mapping: &HashMap<OutletId, OutletId>,
-    _pulse: usize,
+    _pulse: &PulseSpec,
 ) -> TractResult<Option<TVec<OutletId>>> {
     for input_id in 0..node.inputs.len() {
         let input = mapping[&node.inputs[input_id]];
@@ -65,7 +65,7 @@ impl PulsedOp for Scan {
                 .map(
                     |(axis, d)| {
                         if axis == output_mapping.axis {
-                            inputs[0].pulse().to_dim()
+                            inputs[0].pulse().clone()
                         } else {
                             d
                         }
@@ -78,6 +78,8 @@ impl PulsedOp for Scan {
                 axis: output_mapping.axis,
                 dim: inputs[0].dim.clone(),
                 delay: inputs[0].delay,
+                max_pulse: inputs[0].max_pulse,
+                lanes: tvec!(),
             };
             facts.push(fact);
         }
@@ -0,0 +1,271 @@
+//! Python bindings for tract.
+//!
+//! Exposes ONNX and NNEF loaders, model optimization and execution. Tensors
+//! are passed in and out as numpy arrays.
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use numpy::{PyReadonlyArrayDyn, ToPyArray};
+use pyo3::exceptions::{PyRuntimeError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+
+use tract_nnef::internal::*;
+use tract_onnx::prelude::{InferenceFact, InferenceModelExt};
+use tract_onnx::WithOnnx;
+
+fn err(e: TractError) -> PyErr {
+    PyRuntimeError::new_err(format!("{:?}", e))
+}
+
+/// ONNX parser, loading models as InferenceModel.
+#[pyclass(name = "Onnx")]
+struct Onnx(tract_onnx::Onnx);
+
+#[pymethods]
+impl Onnx {
+    fn model_for_path(&self, path: PathBuf) -> PyResult<InferenceModel> {
+        Ok(InferenceModel(self.0.model_for_path(path).map_err(err)?))
+    }
+}
+
+/// Create an ONNX parser.
+#[pyfunction]
+fn onnx() -> Onnx {
+    Onnx(tract_onnx::onnx())
+}
+
+/// NNEF parser and serializer, with tract core and ONNX extensions.
+#[pyclass(name = "Nnef")]
+struct Nnef(tract_nnef::framework::Nnef);
+
+#[pymethods]
+impl Nnef {
+    /// Load a model from a directory, a .tar or a .tgz archive.
+    fn model_for_path(&self, path: PathBuf) -> PyResult<Model> {
+        Ok(Model(self.0.model_for_path(path).map_err(err)?))
+    }
+
+    /// Write a model as an NNEF directory. The directory must not exist.
+    fn write_model_to_dir(&self, model: &Model, path: PathBuf) -> PyResult<()> {
+        self.0.write_to_dir(&model.0, path).map_err(err)
+    }
+
+    /// Write a model as an NNEF tar archive.
+    fn write_model_to_tar(&self, model: &Model, path: PathBuf) -> PyResult<()> {
+        let file = std::fs::File::create(path)?;
+        self.0.write_to_tar(&model.0, file).map_err(err)?;
+        Ok(())
+    }
+
+    /// Write a model as an NNEF gzipped tar archive.
+    fn write_model_to_tar_gz(&self, model: &Model, path: PathBuf) -> PyResult<()> {
+        let file = std::fs::File::create(path)?;
+        let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+        self.0.write_to_tar(&model.0, encoder).map_err(err)?.finish()?;
+        Ok(())
+    }
+}
+
+/// Create an NNEF parser and serializer.
+#[pyfunction]
+fn nnef() -> Nnef {
+    Nnef(tract_nnef::nnef().with_tract_core().with_onnx())
+}
+
+/// A model as loaded from ONNX, before type and shape analysis.
+#[pyclass(name = "InferenceModel")]
+struct InferenceModel(tract_onnx::prelude::InferenceModel);
+
+#[pymethods]
+impl InferenceModel {
+    fn input_count(&self) -> usize {
+        self.0.inputs.len()
+    }
+
+    fn output_count(&self) -> usize {
+        self.0.outputs.len()
+    }
+
+    /// Set an input fact, like the command line `-i` option: "1,3,224,224,f32" or "N,4,f32".
+    fn set_input_fact(&mut self, input: usize, fact: &str) -> PyResult<()> {
+        let fact =
+            InferenceFact::from_str(fact).map_err(|e| PyValueError::new_err(format!("{:?}", e)))?;
+        self.0.set_input_fact(input, fact).map_err(err)
+    }
+
+    /// Run type and shape analysis, returning a new Model.
+    fn into_typed(&self) -> PyResult<Model> {
+        Ok(Model(self.0.clone().into_typed().map_err(err)?))
+    }
+
+    /// Run type and shape analysis, decluttering and codegen, returning a new Model.
+    fn into_optimized(&self) -> PyResult<Model> {
+        Ok(Model(self.0.clone().into_optimized().map_err(err)?))
+    }
+}
+
+/// A typed model.
+#[pyclass(name = "Model")]
+struct Model(TypedModel);
+
+#[pymethods]
+impl Model {
+    fn input_count(&self) -> usize {
+        self.0.inputs.len()
+    }
+
+    fn output_count(&self) -> usize {
+        self.0.outputs.len()
+    }
+
+    fn input_name(&self, input: usize) -> PyResult<String> {
+        let outlet = self.0.inputs.get(input).context("Invalid input index").map_err(err)?;
+        Ok(self.0.node(outlet.node).name.clone())
+    }
+
+    fn output_name(&self, output: usize) -> PyResult<String> {
+        let outlet = self.0.outputs.get(output).context("Invalid output index").map_err(err)?;
+        Ok(self.0.node(outlet.node).name.clone())
+    }
+
+    fn input_fact(&self, input: usize) -> PyResult<String> {
+        Ok(format!("{:?}", self.0.input_fact(input).map_err(err)?))
+    }
+
+    fn output_fact(&self, output: usize) -> PyResult<String> {
+        Ok(format!("{:?}", self.0.output_fact(output).map_err(err)?))
+    }
+
+    /// Replace symbols by values, from a dict like {"N": 1}.
+    fn concretize_symbols(&mut self, values: HashMap<String, i64>) -> PyResult<()> {
+        let mut table = SymbolValues::default();
+        for (name, value) in values {
+            let mut chars = name.chars();
+            let symbol = match (chars.next(), chars.next()) {
+                (Some(c), None) => Symbol::from(c),
+                _ => {
+                    return Err(PyValueError::new_err(format!(
+                        "Symbols are single letters, got {:?}",
+                        name
+                    )))
+                }
+            };
+            table.set(symbol, value);
+        }
+        self.0 = self.0.concretize_dims(&table).map_err(err)?;
+        Ok(())
+    }
+
+    fn declutter(&mut self) -> PyResult<()> {
+        self.0.declutter().map_err(err)
+    }
+
+    fn optimize(&mut self) -> PyResult<()> {
+        self.0 = std::mem::take(&mut self.0).into_optimized().map_err(err)?;
+        Ok(())
+    }
+
+    fn into_runnable(&self) -> PyResult<Runnable> {
+        Ok(Runnable(self.0.clone().into_runnable().map_err(err)?))
+    }
+}
+
+/// A model ready to run.
+#[pyclass(name = "Runnable")]
+struct Runnable(TypedRunnableModel<TypedModel>);
+
+#[pymethods]
+impl Runnable {
+    fn input_count(&self) -> usize {
+        self.0.model().inputs.len()
+    }
+
+    fn output_count(&self) -> usize {
+        self.0.model().outputs.len()
+    }
+
+    /// Run the model on a list of numpy arrays, returning a list of numpy arrays.
+    fn run(&self, py: Python, inputs: Vec<&PyAny>) -> PyResult<Vec<PyObject>> {
+        let inputs =
+            inputs.into_iter().map(|i| tensor_from_numpy(py, i)).collect::<PyResult<TVec<_>>>()?;
+        let outputs = py.allow_threads(|| self.0.run(inputs)).map_err(err)?;
+        outputs.iter().map(|t| tensor_to_numpy(py, t)).collect()
+    }
+}
+
+fn tensor_from_numpy(py: Python, array: &PyAny) -> PyResult<Tensor> {
+    let array = py.import("numpy")?.call_method1("ascontiguousarray", (array,))?;
+    let dtype = array.getattr("dtype")?.str()?.to_str()?.to_string();
+    macro_rules! from_numpy {
+        ($t: ty) => {{
+            let array: PyReadonlyArrayDyn<$t> = array.extract()?;
+            array.as_array().to_owned().into_tensor()
+        }};
+    }
+    Ok(match &*dtype {
+        "bool" => from_numpy!(bool),
+        "uint8" => from_numpy!(u8),
+        "uint16" => from_numpy!(u16),
+        "uint32" => from_numpy!(u32),
+        "uint64" => from_numpy!(u64),
+        "int8" => from_numpy!(i8),
+        "int16" => from_numpy!(i16),
+        "int32" => from_numpy!(i32),
+        "int64" => from_numpy!(i64),
+        "float32" => from_numpy!(f32),
+        "float64" => from_numpy!(f64),
+        "float16" => {
+            // no f16 in rust-numpy, go through the bits
+            let array: PyReadonlyArrayDyn<u16> =
+                array.call_method1("view", ("uint16",))?.extract()?;
+            let mut tensor = array.as_array().to_owned().into_tensor();
+            unsafe { tensor.set_datum_type(f16::datum_type()) };
+            tensor
+        }
+        _ => return Err(PyTypeError::new_err(format!("Unsupported numpy dtype {}", dtype))),
+    })
+}
+
+fn tensor_to_numpy(py: Python, tensor: &Tensor) -> PyResult<PyObject> {
+    macro_rules! to_numpy {
+        ($t: ty) => {
+            tensor.to_array_view::<$t>().map_err(err)?.to_pyarray(py).to_object(py)
+        };
+    }
+    Ok(match tensor.datum_type().unquantized() {
+        DatumType::Bool => to_numpy!(bool),
+        DatumType::U8 => to_numpy!(u8),
+        DatumType::U16 => to_numpy!(u16),
+        DatumType::U32 => to_numpy!(u32),
+        DatumType::U64 => to_numpy!(u64),
+        DatumType::I8 => to_numpy!(i8),
+        DatumType::I16 => to_numpy!(i16),
+        DatumType::I32 => to_numpy!(i32),
+        DatumType::I64 => to_numpy!(i64),
+        DatumType::F32 => to_numpy!(f32),
+        DatumType::F64 => to_numpy!(f64),
+        DatumType::F16 => {
+            let mut bits = tensor.clone();
+            unsafe { bits.set_datum_type(u16::datum_type()) };
+            let array = bits.to_array_view::<u16>().map_err(err)?.to_pyarray(py);
+            array.call_method1("view", ("float16",))?.to_object(py)
+        }
+        dt => {
+            return Err(PyTypeError::new_err(format!("Can not convert {:?} to a numpy array", dt)))
+        }
+    })
+}
+
+#[pymodule]
+fn tract(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    m.add_function(wrap_pyfunction!(onnx, m)?)?;
+    m.add_function(wrap_pyfunction!(nnef, m)?)?;
+    m.add_class::<Onnx>()?;
+    m.add_class::<Nnef>()?;
+    m.add_class::<InferenceModel>()?;
+    m.add_class::<Model>()?;
+    m.add_class::<Runnable>()?;
+    Ok(())
+}
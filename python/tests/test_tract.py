@@ -0,0 +1,101 @@
+import os
+
+import numpy
+import pytest
+
+import tract
+
+HERE = os.path.dirname(os.path.abspath(__file__))
+
+
+def mul_model():
+    return tract.nnef().model_for_path(os.path.join(HERE, "mul"))
+
+
+def test_version():
+    assert tract.__version__
+
+
+def test_nnef_run():
+    model = mul_model()
+    assert model.input_count() == 1
+    assert model.output_count() == 1
+    assert model.input_name(0) == "input"
+    model.concretize_symbols({"N": 3})
+    model.optimize()
+    input = numpy.arange(12, dtype=numpy.float32).reshape(3, 4)
+    [output] = model.into_runnable().run([input])
+    assert output.dtype == numpy.float32
+    assert numpy.array_equal(output, input * 2)
+
+
+def test_nnef_error():
+    with pytest.raises(RuntimeError):
+        tract.nnef().model_for_path(os.path.join(HERE, "no-such-model"))
+
+
+def test_unsupported_dtype():
+    model = mul_model()
+    model.concretize_symbols({"N": 1})
+    with pytest.raises(TypeError):
+        model.into_runnable().run([numpy.array([["a", "b", "c", "d"]])])
+
+
+@pytest.mark.parametrize("suffix", ["", ".tar", ".tgz"])
+def test_nnef_export(tmp_path, suffix):
+    model = mul_model()
+    model.concretize_symbols({"N": 2})
+    path = str(tmp_path / ("mul" + suffix))
+    nnef = tract.nnef()
+    if suffix == "":
+        nnef.write_model_to_dir(model, path)
+    elif suffix == ".tar":
+        nnef.write_model_to_tar(model, path)
+    else:
+        nnef.write_model_to_tar_gz(model, path)
+    reloaded = nnef.model_for_path(path)
+    input = numpy.random.rand(2, 4).astype(numpy.float32)
+    [expected] = model.into_runnable().run([input])
+    [found] = reloaded.into_runnable().run([input])
+    assert numpy.array_equal(expected, found)
+
+
+def add_onnx_model(path, dtype):
+    onnx = pytest.importorskip("onnx")
+    from onnx import helper
+
+    if hasattr(helper, "np_dtype_to_tensor_dtype"):
+        elem = helper.np_dtype_to_tensor_dtype(numpy.dtype(dtype))
+    else:
+        elem = onnx.mapping.NP_TYPE_TO_TENSOR_TYPE[numpy.dtype(dtype)]
+    graph = helper.make_graph(
+        [helper.make_node("Add", ["a", "b"], ["c"])],
+        "add",
+        [
+            helper.make_tensor_value_info("a", elem, ["N", 4]),
+            helper.make_tensor_value_info("b", elem, ["N", 4]),
+        ],
+        [helper.make_tensor_value_info("c", elem, ["N", 4])],
+    )
+    model = helper.make_model(graph, opset_imports=[helper.make_opsetid("", 13)])
+    onnx.save(model, path)
+
+
+@pytest.mark.parametrize("dtype", [numpy.float32, numpy.float64, numpy.int32, numpy.int64])
+def test_onnx_vs_onnxruntime(tmp_path, dtype):
+    ort = pytest.importorskip("onnxruntime")
+    path = str(tmp_path / "add.onnx")
+    add_onnx_model(path, dtype)
+    a = (numpy.random.rand(3, 4) * 10).astype(dtype)
+    b = (numpy.random.rand(3, 4) * 10).astype(dtype)
+
+    model = tract.onnx().model_for_path(path)
+    name = numpy.dtype(dtype).name
+    tract_dt = {"float32": "f32", "float64": "f64", "int32": "i32", "int64": "i64"}[name]
+    model.set_input_fact(0, "3,4," + tract_dt)
+    model.set_input_fact(1, "3,4," + tract_dt)
+    [found] = model.into_optimized().into_runnable().run([a, b])
+
+    [expected] = ort.InferenceSession(path).run(None, {"a": a, "b": b})
+    assert found.dtype == expected.dtype
+    assert numpy.array_equal(found, expected)
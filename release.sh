@@ -37,7 +37,7 @@ function retry {
 
 CRATE=$1
 VERSION=$2
-CRATES="data linalg core nnef pulse-opl pulse hir tensorflow onnx-opl onnx kaldi cli"
+CRATES="data linalg core nnef pulse-opl pulse hir tensorflow onnx-opl onnx kaldi cli ffi"
 
 if [ `uname` = "Darwin" ]
 then
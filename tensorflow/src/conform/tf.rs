@@ -1,5 +1,6 @@
 #![allow(dead_code)]
 
+use std::convert::{TryFrom, TryInto};
 use std::{fs, path};
 
 use tensorflow as tf;
@@ -59,9 +60,10 @@ impl TensorHolder {
     }
 }
 
-impl From<Tensor> for TensorHolder {
-    fn from(m: Tensor) -> TensorHolder {
-        match m.datum_type() {
+impl TryFrom<Tensor> for TensorHolder {
+    type Error = TractError;
+    fn try_from(m: Tensor) -> TractResult<TensorHolder> {
+        let holder = match m.datum_type() {
             DatumType::Bool => TensorHolder::Bool(Self::to_tensor(m.into_array().unwrap())),
             DatumType::F16 => unimplemented!(),
             DatumType::F32 => TensorHolder::F32(Self::to_tensor(m.into_array().unwrap())),
@@ -93,7 +95,11 @@ impl From<Tensor> for TensorHolder {
             }
             DatumType::String => TensorHolder::String(Self::to_tensor(m.into_array().unwrap())),
             DatumType::Blob => TensorHolder::String(Self::to_tensor(m.into_array().unwrap())),
-        }
+            dt @ (DatumType::Sequence | DatumType::Optional) => {
+                bail!("{:?} values can not be fed to tensorflow", dt)
+            }
+        };
+        Ok(holder)
     }
 }
 
@@ -109,8 +115,10 @@ impl Tensorflow {
         inputs: Vec<(&str, Tensor)>,
         output_name: &str,
     ) -> TractResult<Vec<Tensor>> {
-        let tensors: Vec<(&str, TensorHolder)> =
-            inputs.into_iter().map(|(name, mat)| (name, mat.into())).collect();
+        let tensors: Vec<(&str, TensorHolder)> = inputs
+            .into_iter()
+            .map(|(name, mat)| Ok((name, mat.try_into()?)))
+            .collect::<TractResult<_>>()?;
 
         let mut step = SessionRunArgs::new();
         for t in &tensors {
@@ -158,7 +166,7 @@ impl Tensorflow {
         let mut excluded = HashSet::new();
 
         for (name, mat) in inputs {
-            input_pairs.push((name, mat.into()));
+            input_pairs.push((name, mat.try_into()?));
             excluded.insert(name.to_string());
         }
 
@@ -107,7 +107,8 @@ impl Tensorflow {
     /// container. Use open_avec_model for more control.
     pub fn read_saved_model(&self, r: &mut dyn std::io::Read) -> TractResult<GraphDef> {
         let mut saved = self.open_saved_model(r)?;
-        Ok(saved.meta_graphs.remove(0).graph_def.unwrap())
+        ensure!(!saved.meta_graphs.is_empty(), "Saved model contains no meta graph");
+        saved.meta_graphs.remove(0).graph_def.context("Meta graph without graph_def")
     }
 
     pub fn parse_graph(&self, graph: &GraphDef) -> TractResult<TfModelAndExtensions> {
@@ -210,7 +211,7 @@ impl Tensorflow {
         for id in 0..model.nodes().len() {
             use crate::ops::vars::*;
             if model.node(id).op_is::<Assign>() {
-                let prec = model.node(id).inputs[0];
+                let prec = *model.node(id).inputs.first().context("Assign without input")?;
                 let var_id = model.node(prec.node).op_as::<VariableV2>().map(|v| v.id.clone());
                 if let (Some(var_id), Some(assign)) =
                     (var_id, model.node_mut(id).op_as_mut::<Assign>())
@@ -221,6 +222,48 @@ impl Tensorflow {
                 }
             }
         }
+        // lookup tables: the keys and values are inputs of the initializer
+        // (LookupTableImportV2 or InitializeTableV2), which shares the table
+        // handle with the LookupTableFindV2 nodes. Tables must be initialized
+        // with constants, which are moved to the find ops.
+        use crate::ops::string::LookupTableFind;
+        let table_of = |model: &InferenceModel, mut outlet: OutletId| {
+            while model.node(outlet.node).op_is::<tract_hir::ops::identity::Identity>() {
+                outlet = model.node(outlet.node).inputs[0];
+            }
+            outlet.node
+        };
+        let mut tables = HashMap::new();
+        for pbnode in &graph.node {
+            if pbnode.op == "LookupTableImportV2" || pbnode.op == "InitializeTableV2" {
+                let node = model.node_by_name(&pbnode.name)?;
+                ensure!(node.inputs.len() == 3, "Table initializer {} expects 3 inputs", node.name);
+                let konst = |outlet: OutletId| {
+                    model
+                        .node(outlet.node)
+                        .op_as::<tract_hir::ops::konst::Const>()
+                        .map(|k| k.0.clone())
+                        .with_context(|| {
+                            format!("Table initializer {} expects constants", node.name)
+                        })
+                };
+                let table = (konst(node.inputs[1])?, konst(node.inputs[2])?);
+                tables.insert(table_of(&model, node.inputs[0]), table);
+            }
+        }
+        for pbnode in &graph.node {
+            if pbnode.op == "LookupTableFindV2" {
+                let id = model.node_id_by_name(&pbnode.name)?;
+                let table =
+                    *model.node(id).inputs.first().context("LookupTableFindV2 without input")?;
+                let table = tables.get(&table_of(&model, table)).cloned();
+                if let Some(op) = model.node_mut(id).op_as_mut::<Box<dyn Expansion>>() {
+                    if let Some(find) = op.as_any_mut().downcast_mut::<LookupTableFind>() {
+                        find.table = table;
+                    }
+                }
+            }
+        }
         model.set_input_outlets(&*inputs)?;
         model.auto_outputs()?;
         let extensions = TfModelExtensions { control_inputs, initializing_nodes: vec![] };
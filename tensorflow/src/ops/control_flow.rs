@@ -91,7 +91,7 @@ impl EvalOp for NextIteration {
         _state: &mut SessionState,
         _id: usize,
     ) -> TractResult<Option<Box<dyn OpState>>> {
-        unimplemented!();
+        bail!("NextIteration can only be evaluated after loop translation")
     }
 }
 
@@ -20,6 +20,7 @@ pub mod nn;
 pub mod quant;
 pub mod random;
 pub mod rec;
+pub mod string;
 pub mod vars;
 
 pub fn register_all_ops(reg: &mut TfOpRegister) {
@@ -31,6 +32,7 @@ pub fn register_all_ops(reg: &mut TfOpRegister) {
     quant::register_all_ops(reg);
     random::register_all_ops(reg);
     rec::register_all_ops(reg);
+    string::register_all_ops(reg);
     vars::register_all_ops(reg);
     reg.insert("Cast", cast);
     reg.insert("Const", konst);
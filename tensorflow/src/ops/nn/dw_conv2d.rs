@@ -55,7 +55,7 @@ impl Expansion for DepthwiseConv2d {
                     &ker[0..2],
                     &self.dilations[img.hw_axes()],
                     &self.strides[img.hw_axes()],
-                );
+                )?;
                 let in_channels = ker[2].to_usize()?;
                 let multiplier = ker[3].to_usize()?;
                 s.equals(&outputs[0].shape[img.h_axis()], &output_shape[0].convoluted)?;
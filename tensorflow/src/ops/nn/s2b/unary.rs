@@ -88,6 +88,7 @@ impl TypedOp for SpaceToBatchUnary {
                         group: conv_op.group,
                         bias: None,
                         q_params: None,
+                        weight_quant: None,
                     };
                     let mut patch = TypedModelPatch::default();
                     let tap = patch.tap_model(model, node.inputs[0])?;
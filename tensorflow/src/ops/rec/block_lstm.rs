@@ -8,9 +8,6 @@ pub fn block_lstm(_ctx: &ParsingContext, node: &NodeDef) -> TractResult<Box<dyn
     let cell_clip = node.get_attr_opt_float("cell_clip")?.unwrap_or(3.0);
     let t = node.get_attr_datum_type("T")?;
     let use_peephole = node.get_attr_opt_bool("use_peephole")?.unwrap_or(false);
-    if use_peephole {
-        unimplemented!("Block LSTM peeplholes");
-    }
     Ok(expand(BlockLSTM::new(forget_bias, cell_clip, t, use_peephole)))
 }
 
@@ -90,6 +87,23 @@ impl Expansion for BlockLSTM {
         let cell_size = w.shape()[1] / 4;
         let mut b = b.into_tensor();
         b.insert_axis(0)?;
+        // peepholes: wci, wcf, wco, as [1, cell_size] to broadcast over batch
+        let peepholes = if self.use_peephole {
+            let mut peepholes = tvec!();
+            for (ix, name) in &[(5, "wci"), (6, "wcf"), (7, "wco")] {
+                let mut p = model
+                    .outlet_fact(inputs[*ix])?
+                    .konst
+                    .clone()
+                    .with_context(|| format!("Peephole {} must be constant", name))?
+                    .into_tensor();
+                p.insert_axis(0)?;
+                peepholes.push(p.into_arc_tensor());
+            }
+            Some(peepholes)
+        } else {
+            None
+        };
 
         macro_rules! wire {
             ($name: ident = $op: expr, $($param: expr),*) => {
@@ -135,22 +149,41 @@ impl Expansion for BlockLSTM {
         wire!(i_ci_f_o = math::add::unary(b.into_arc_tensor()), i_ci_f_o_1);
 
         wire!(i_1 = array::Slice::new(1, 0, cell_size), i_ci_f_o);
+        let mut i_1 = i_1;
+        if let Some(peepholes) = &peepholes {
+            wire!(cs_prev_wci = math::mul::unary(peepholes[0].clone()), cs_prev);
+            wire!(i_peep = math::add::bin_typed(), i_1, cs_prev_wci);
+            i_1 = i_peep;
+        }
         wire!(i = nn::sigmoid(), i_1);
 
         wire!(f_1 = array::Slice::new(1, 2 * cell_size, 3 * cell_size), i_ci_f_o);
         wire!(f_2 = math::add::unary(rctensor2(&[[self.forget_bias]])), f_1);
+        let mut f_2 = f_2;
+        if let Some(peepholes) = &peepholes {
+            wire!(cs_prev_wcf = math::mul::unary(peepholes[1].clone()), cs_prev);
+            wire!(f_peep = math::add::bin_typed(), f_2, cs_prev_wcf);
+            f_2 = f_peep;
+        }
         wire!(f = nn::sigmoid(), f_2);
 
         wire!(ci_1 = array::Slice::new(1, cell_size, 2 * cell_size), i_ci_f_o);
         wire!(ci = math::tanh(), ci_1);
 
-        wire!(o_1 = array::Slice::new(1, 3 * cell_size, 4 * cell_size), i_ci_f_o);
-        wire!(o = nn::sigmoid(), o_1);
-
         wire!(ci_i = math::mul::bin_typed(), ci, i);
         wire!(cs_1 = math::mul::bin_typed(), cs_prev, f);
         wire!(cs = math::add::bin_typed(), cs_1, ci_i);
 
+        // output gate peephole looks at the new cell state
+        wire!(o_1 = array::Slice::new(1, 3 * cell_size, 4 * cell_size), i_ci_f_o);
+        let mut o_1 = o_1;
+        if let Some(peepholes) = &peepholes {
+            wire!(cs_wco = math::mul::unary(peepholes[2].clone()), cs);
+            wire!(o_peep = math::add::bin_typed(), o_1, cs_wco);
+            o_1 = o_peep;
+        }
+        wire!(o = nn::sigmoid(), o_1);
+
         wire!(co = math::tanh(), cs);
         wire!(h = math::mul::bin_typed(), co, o);
 
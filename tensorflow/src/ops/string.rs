@@ -0,0 +1,350 @@
+use tract_hir::internal::*;
+use tract_hir::tract_core::ops::cast::cast;
+use tract_hir::tract_core::ops::string::{LookupTable, Separator, StringToHashBucket};
+
+use crate::model::{ParsingContext, TfOpRegister};
+use crate::tfpb::tensorflow::NodeDef;
+
+pub fn register_all_ops(reg: &mut TfOpRegister) {
+    reg.insert("HashTableV2", hash_table);
+    reg.insert("InitializeTableV2", |_, _| Ok(Box::new(super::Noop)));
+    reg.insert("LookupTableFindV2", |_, _| Ok(expand(LookupTableFind::default())));
+    reg.insert("LookupTableImportV2", |_, _| Ok(Box::new(super::Noop)));
+    reg.insert("StringSplit", string_split);
+    reg.insert("StringSplitV2", string_split_v2);
+    reg.insert("StringToHashBucketFast", string_to_hash_bucket_fast);
+}
+
+/// TensorFlow strings are loaded as Blob, tract string ops work on String.
+fn stringify(t: &Arc<Tensor>) -> TractResult<Arc<Tensor>> {
+    if t.datum_type() == DatumType::Blob {
+        Ok(t.cast_to::<String>()?.into_owned().into_arc_tensor())
+    } else {
+        Ok(t.clone())
+    }
+}
+
+fn wire_stringify(name: String, model: &mut TypedModel, wire: OutletId) -> TractResult<OutletId> {
+    if model.outlet_fact(wire)?.datum_type == DatumType::Blob {
+        Ok(model.wire_node(name, cast(DatumType::String), &[wire])?[0])
+    } else {
+        Ok(wire)
+    }
+}
+
+fn string_dt(dt: DatumType) -> DatumType {
+    if dt == DatumType::Blob {
+        DatumType::String
+    } else {
+        dt
+    }
+}
+
+fn hash_table(_ctx: &ParsingContext, node: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    // the handle is never used at runtime: the initializer keys and values are moved to the
+    // consuming LookupTableFindV2 by the graph parser
+    let handle = rctensor0(node.name.to_string());
+    Ok(Box::new(tract_hir::ops::konst::Const(handle)))
+}
+
+#[derive(Debug, Clone, Default, Hash)]
+pub struct LookupTableFind {
+    pub table: Option<(Arc<Tensor>, Arc<Tensor>)>,
+}
+
+impl_dyn_hash!(LookupTableFind);
+
+impl Expansion for LookupTableFind {
+    fn name(&self) -> Cow<str> {
+        "LookupTableFind".into()
+    }
+
+    op_tf!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 3)?;
+        check_output_arity(outputs, 1)?;
+        s.equals(&inputs[1].shape, &outputs[0].shape)?;
+        if let Some((_, values)) = &self.table {
+            s.equals(&outputs[0].datum_type, string_dt(values.datum_type()))?;
+        }
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let (keys, values) = self
+            .table
+            .as_ref()
+            .with_context(|| format!("Uninitialized lookup table in {}", prefix))?;
+        let default = model
+            .outlet_fact(inputs[2])?
+            .konst
+            .clone()
+            .context("LookupTableFindV2 expects a constant default value")?;
+        let op = LookupTable::new(stringify(keys)?, stringify(values)?, stringify(&default)?)?;
+        let wire = wire_stringify(format!("{}.cast", prefix), model, inputs[1])?;
+        model.wire_node(prefix, op, &[wire])
+    }
+}
+
+fn string_to_hash_bucket_fast(
+    _ctx: &ParsingContext,
+    node: &NodeDef,
+) -> TractResult<Box<dyn InferenceOp>> {
+    let buckets = node.get_attr_int::<u64>("num_buckets")?;
+    Ok(inference_wrap(StringToHashBucket { buckets }, 1, string_to_hash_bucket_rules))
+}
+
+fn string_to_hash_bucket_rules<'r, 'p, 's>(
+    _op: &'s dyn Op,
+    s: &mut Solver<'r>,
+    inputs: &'p [TensorProxy],
+    outputs: &'p [TensorProxy],
+) -> InferenceResult {
+    check_input_arity(inputs, 1)?;
+    check_output_arity(outputs, 1)?;
+    s.equals(&outputs[0].datum_type, i64::datum_type())?;
+    s.equals(&inputs[0].shape, &outputs[0].shape)?;
+    Ok(())
+}
+
+fn string_split(_ctx: &ParsingContext, node: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let skip_empty = node.get_attr_opt_bool("skip_empty")?.unwrap_or(true);
+    Ok(expand(StringSplit { v2: false, skip_empty, max_split: None }))
+}
+
+fn string_split_v2(_ctx: &ParsingContext, node: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let max_split = node.get_attr_opt_int::<i64>("maxsplit")?.filter(|m| *m >= 0);
+    Ok(expand(StringSplit {
+        v2: true,
+        skip_empty: false,
+        max_split: max_split.map(|m| m as usize),
+    }))
+}
+
+/// StringSplit and StringSplitV2: the separator comes as a (constant) input.
+#[derive(Debug, Clone, Hash)]
+pub struct StringSplit {
+    pub v2: bool,
+    pub skip_empty: bool,
+    pub max_split: Option<usize>,
+}
+
+impl_dyn_hash!(StringSplit);
+
+impl Expansion for StringSplit {
+    fn name(&self) -> Cow<str> {
+        if self.v2 { "StringSplitV2" } else { "StringSplit" }.into()
+    }
+
+    op_tf!();
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(3)
+    }
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(inputs, 2)?;
+        check_output_arity(outputs, 3)?;
+        s.equals(&inputs[0].rank, 1)?;
+        s.equals(&outputs[0].datum_type, i64::datum_type())?;
+        s.equals(&outputs[0].rank, 2)?;
+        s.equals(&outputs[0].shape[1], 2.to_dim())?;
+        s.equals(&outputs[1].datum_type, DatumType::String)?;
+        s.equals(&outputs[1].rank, 1)?;
+        s.equals(&outputs[0].shape[0], &outputs[1].shape[0])?;
+        s.equals(&outputs[2].datum_type, i64::datum_type())?;
+        s.equals(&outputs[2].shape, shapefactoid![2])?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let sep = model
+            .outlet_fact(inputs[1])?
+            .konst
+            .clone()
+            .with_context(|| format!("{} expects a constant separator", self.name()))?;
+        let sep = stringify(&sep)?.to_scalar::<String>()?.clone();
+        let separator = if !self.v2 {
+            Separator::AnyOf(sep)
+        } else if sep.is_empty() {
+            Separator::Whitespace
+        } else {
+            Separator::Exact(sep)
+        };
+        let op = SparseStringSplit {
+            separator,
+            max_split: self.max_split,
+            skip_empty: self.skip_empty,
+            tokens: Symbol::fresh('T'),
+        };
+        let wire = wire_stringify(format!("{}.cast", prefix), model, inputs[0])?;
+        model.wire_node(prefix, op, &[wire])
+    }
+}
+
+/// Split a vector of strings, producing the tokens as a sparse matrix:
+/// indices, values and dense shape.
+#[derive(Debug, Clone, Hash)]
+pub struct SparseStringSplit {
+    pub separator: Separator,
+    pub max_split: Option<usize>,
+    pub skip_empty: bool,
+    pub tokens: Symbol,
+}
+
+impl_dyn_hash!(SparseStringSplit);
+
+impl Op for SparseStringSplit {
+    fn name(&self) -> Cow<str> {
+        "SparseStringSplit".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "separator:{:?} max_split:{:?} skip_empty:{}",
+            self.separator, self.max_split, self.skip_empty
+        )])
+    }
+
+    op_tf!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for SparseStringSplit {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let mut indices = vec![];
+        let mut values = vec![];
+        let mut width = 0;
+        for (row, s) in input.as_slice::<String>()?.iter().enumerate() {
+            let tokens = self.separator.split(s, self.max_split);
+            let tokens = tokens.into_iter().filter(|t| !self.skip_empty || !t.is_empty());
+            let mut col = 0;
+            for token in tokens {
+                indices.push(row as i64);
+                indices.push(col as i64);
+                values.push(token.to_string());
+                col += 1;
+            }
+            width = width.max(col);
+        }
+        let indices = tensor1(&indices).into_shape(&[values.len(), 2])?;
+        let shape = tensor1(&[input.len() as i64, width as i64]);
+        Ok(tvec!(
+            indices.into_arc_tensor(),
+            tensor1(&values).into_arc_tensor(),
+            shape.into_arc_tensor()
+        ))
+    }
+}
+
+impl TypedOp for SparseStringSplit {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        ensure!(inputs[0].datum_type == DatumType::String && inputs[0].rank() == 1);
+        Ok(tvec!(
+            i64::fact(&[self.tokens.to_dim(), 2.to_dim()]),
+            String::fact(&[self.tokens.to_dim()]),
+            i64::fact([2])
+        ))
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tfpb::tensorflow::DataType::*;
+    use crate::tfpb::tensorflow::TensorProto;
+    use crate::tfpb::*;
+    use std::convert::TryFrom;
+    use tract_hir::prelude::*;
+
+    fn blobs(s: &[&str]) -> Tensor {
+        tensor1(&s.iter().map(|s| Blob(s.as_bytes().to_vec())).collect::<Vec<_>>())
+    }
+
+    fn konst(name: &str, t: Tensor) -> TractResult<crate::tfpb::tensorflow::NodeDef> {
+        let dt = crate::tfpb::tensorflow::DataType::try_from(t.datum_type())?;
+        Ok(node()
+            .name(name)
+            .op("Const")
+            .attr("dtype", dt)
+            .attr("value", TensorProto::try_from(&t)?))
+    }
+
+    #[test]
+    fn split_hash_and_lookup() -> TractResult<()> {
+        let graph = graph()
+            .node(node().name("x").op("Placeholder").attr("dtype", DtString))
+            .node(konst("sep", tensor0(Blob(b" ".to_vec())))?)
+            .node(node().name("split").op("StringSplitV2").input("x").input("sep"))
+            .node(
+                node()
+                    .name("table")
+                    .op("HashTableV2")
+                    .attr("key_dtype", DtString)
+                    .attr("value_dtype", DtInt64),
+            )
+            .node(konst("keys", blobs(&["the", "cat"]))?)
+            .node(konst("values", tensor1(&[1i64, 2]))?)
+            .node(
+                node()
+                    .name("init")
+                    .op("LookupTableImportV2")
+                    .input("table")
+                    .input("keys")
+                    .input("values"),
+            )
+            .node(konst("default", tensor0(0i64))?)
+            .node(
+                node()
+                    .name("ids")
+                    .op("LookupTableFindV2")
+                    .input("table")
+                    .input("split:1")
+                    .input("default"),
+            )
+            .node(
+                node()
+                    .name("hash")
+                    .op("StringToHashBucketFast")
+                    .input("split:1")
+                    .attr("num_buckets", 10i64),
+            );
+        let model = crate::tensorflow()
+            .model_for_proto_model(&graph)?
+            .with_input_fact(0, InferenceFact::dt_shape(DatumType::Blob, tvec!(2)))?
+            .with_output_names(["split", "ids", "hash"])?
+            .into_optimized()?;
+        let outputs = model.into_runnable()?.run(tvec!(blobs(&["the cat", "a"])))?;
+        assert_eq!(*outputs[0], tensor2(&[[0i64, 0], [0, 1], [1, 0]]));
+        assert_eq!(*outputs[1], tensor1(&[1i64, 2, 0]));
+        assert_eq!(outputs[2].as_slice::<i64>()?[2], 9);
+        Ok(())
+    }
+}
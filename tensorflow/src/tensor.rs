@@ -66,20 +66,27 @@ impl TryFrom<DatumType> for DataType {
             DatumType::QI8(_) => Ok(DataType::DtQint8),
             DatumType::QU8(_) => Ok(DataType::DtQint8),
             DatumType::QI32(_) => Ok(DataType::DtQint32),
-            DatumType::ComplexI16 
-                | DatumType::ComplexI32 
-                | DatumType::ComplexI64  
-                | DatumType::ComplexF16
-                | DatumType::ComplexF32
-                | DatumType::ComplexF64
-            => bail!("Dimension is not translatable in protobuf"),
+            DatumType::ComplexI16
+            | DatumType::ComplexI32
+            | DatumType::ComplexI64
+            | DatumType::ComplexF16
+            | DatumType::ComplexF32
+            | DatumType::ComplexF64 => bail!("Dimension is not translatable in protobuf"),
             DatumType::TDim => bail!("Dimension is not translatable in protobuf"),
+            DatumType::Sequence | DatumType::Optional => {
+                bail!("{:?} is not translatable in protobuf", dt)
+            }
         }
     }
 }
 
 fn tensor_from_repeated_field<T: Datum>(shape: &[usize], data: Vec<T>) -> TractResult<Tensor> {
     let t = if data.len() == 1 {
+        ensure!(
+            shape.iter().try_fold(1usize, |acc, d| acc.checked_mul(*d)).is_some(),
+            "Invalid tensor shape {:?}",
+            shape
+        );
         tract_ndarray::ArrayD::from_elem(shape, data[0].clone()).into()
     } else {
         tract_ndarray::ArrayD::from_shape_vec(shape, data.to_vec())?.into()
@@ -90,11 +97,18 @@ fn tensor_from_repeated_field<T: Datum>(shape: &[usize], data: Vec<T>) -> TractR
 impl<'a> TryFrom<&'a TensorProto> for Tensor {
     type Error = TractError;
     fn try_from(t: &TensorProto) -> TractResult<Tensor> {
-        let dims: TVec<usize> =
-            t.tensor_shape.as_ref().unwrap().dim.iter().map(|x| x.size as _).collect();
+        let dims: TVec<usize> = t
+            .tensor_shape
+            .as_ref()
+            .context("Tensor without shape")?
+            .dim
+            .iter()
+            .map(|x| usize::try_from(x.size).with_context(|| format!("Invalid dim {}", x.size)))
+            .collect::<TractResult<_>>()?;
         let rank = dims.len();
         let content = &t.tensor_content;
-        let dtype = DataType::from_i32(t.dtype).unwrap();
+        let dtype =
+            DataType::from_i32(t.dtype).with_context(|| format!("Invalid dtype {}", t.dtype))?;
         let mat: Tensor = if content.len() != 0 {
             unsafe {
                 match dtype {
@@ -102,7 +116,7 @@ impl<'a> TryFrom<&'a TensorProto> for Tensor {
                     DataType::DtDouble => Self::from_raw::<f64>(&dims, content)?,
                     DataType::DtInt32 => Self::from_raw::<i32>(&dims, content)?,
                     DataType::DtInt64 => Self::from_raw::<i64>(&dims, content)?,
-                    _ => unimplemented!("missing type (for get_tensor_content) {:?}", dtype),
+                    _ => bail!("Unsupported type (for tensor_content) {:?}", dtype),
                 }
             }
         } else {
@@ -116,10 +130,10 @@ impl<'a> TryFrom<&'a TensorProto> for Tensor {
                         t.string_val.iter().map(|s| Blob(s.to_owned())).collect::<Vec<Blob>>();
                     tensor_from_repeated_field(&*dims, strings)?
                 }
-                _ => unimplemented!("missing type (for _val()) {:?}", t.dtype),
+                _ => bail!("Unsupported type (for _val()) {:?}", dtype),
             }
         };
-        assert_eq!(rank, mat.shape().len());
+        ensure!(rank == mat.rank(), "Tensor rank mismatch");
         Ok(mat)
     }
 }
@@ -170,7 +184,14 @@ impl<'a> TryFrom<&'a Tensor> for TensorProto {
             DatumType::I64 => {
                 tensor.int64_val = from.to_array_view::<i64>()?.iter().cloned().collect();
             }
-            _ => unimplemented!("missing type {:?}", from.datum_type()),
+            DatumType::Blob => {
+                tensor.string_val = from.as_slice::<Blob>()?.iter().map(|b| b.0.clone()).collect();
+            }
+            DatumType::String => {
+                tensor.string_val =
+                    from.as_slice::<String>()?.iter().map(|s| s.as_bytes().to_vec()).collect();
+            }
+            _ => bail!("Unsupported type {:?}", from.datum_type()),
         }
         Ok(tensor)
     }
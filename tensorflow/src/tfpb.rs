@@ -80,7 +80,7 @@ impl NodeDef {
 
     pub fn get_attr_opt_raw_str(&self, name: &str) -> TractResult<Option<&[u8]>> {
         if let Some(a) = self.attr.get(name) {
-            if let Value::S(bytes) = a.value.as_ref().unwrap() {
+            if let Some(Value::S(bytes)) = a.value.as_ref() {
                 return Ok(Some(bytes));
             }
         };
@@ -116,7 +116,7 @@ impl NodeDef {
 
     pub fn get_attr_opt_bool(&self, name: &str) -> TractResult<Option<bool>> {
         if let Some(a) = self.attr.get(name) {
-            if let Value::B(v) = a.value.as_ref().unwrap() {
+            if let Some(Value::B(v)) = a.value.as_ref() {
                 return Ok(Some(*v));
             }
         };
@@ -131,8 +131,14 @@ impl NodeDef {
 
     pub fn get_attr_opt_datum_type(&self, name: &str) -> TractResult<Option<DatumType>> {
         if let Some(a) = self.attr.get(name) {
-            if let Value::Type(v) = a.value.as_ref().unwrap() {
-                return Ok(Some(DataType::from_i32(*v).unwrap().try_into()?));
+            if let Some(Value::Type(v)) = a.value.as_ref() {
+                let dt = DataType::from_i32(*v).with_context(|| {
+                    format!(
+                        "Node {} ({}) invalid datum_type {} for '{}'",
+                        self.name, self.op, v, name
+                    )
+                })?;
+                return Ok(Some(dt.try_into()?));
             }
         };
         Ok(None)
@@ -146,7 +152,7 @@ impl NodeDef {
 
     pub fn get_attr_opt_shape(&self, name: &str) -> TractResult<Option<TVec<isize>>> {
         if let Some(a) = self.attr.get(name) {
-            if let Value::Shape(ref shape) = a.value.as_ref().unwrap() {
+            if let Some(Value::Shape(ref shape)) = a.value.as_ref() {
                 return Ok(Some(shape.try_into()?));
             }
         };
@@ -161,7 +167,7 @@ impl NodeDef {
 
     pub fn get_attr_opt_tensor(&self, name: &str) -> TractResult<Option<Tensor>> {
         if let Some(a) = self.attr.get(name) {
-            if let Value::Tensor(ref t) = a.value.as_ref().unwrap() {
+            if let Some(Value::Tensor(ref t)) = a.value.as_ref() {
                 return Ok(Some(t.try_into()?));
             }
         };
@@ -179,8 +185,13 @@ impl NodeDef {
         name: &str,
     ) -> TractResult<Option<T>> {
         if let Some(a) = self.attr.get(name) {
-            if let Value::I(i) = a.value.as_ref().unwrap() {
-                return Ok(Some(T::from_i64(*i).unwrap()));
+            if let Some(Value::I(i)) = a.value.as_ref() {
+                return Ok(Some(T::from_i64(*i).with_context(|| {
+                    format!(
+                        "Node {} ({}) int attribute '{}' out of range",
+                        self.name, self.op, name
+                    )
+                })?));
             }
         };
         Ok(None)
@@ -197,8 +208,13 @@ impl NodeDef {
         name: &str,
     ) -> TractResult<Option<T>> {
         if let Some(a) = self.attr.get(name) {
-            if let Value::F(i) = a.value.as_ref().unwrap() {
-                return Ok(Some(T::from_f32(*i).unwrap()));
+            if let Some(Value::F(i)) = a.value.as_ref() {
+                return Ok(Some(T::from_f32(*i).with_context(|| {
+                    format!(
+                        "Node {} ({}) float attribute '{}' out of range",
+                        self.name, self.op, name
+                    )
+                })?));
             }
         };
         Ok(None)
@@ -218,8 +234,20 @@ impl NodeDef {
         name: &str,
     ) -> TractResult<Option<Vec<T>>> {
         if let Some(a) = self.attr.get(name) {
-            if let Value::List(list) = a.value.as_ref().unwrap() {
-                return Ok(Some(list.i.iter().map(|&i| T::from_i64(i).unwrap()).collect()));
+            if let Some(Value::List(list)) = a.value.as_ref() {
+                return Ok(Some(
+                    list.i
+                        .iter()
+                        .map(|&i| {
+                            T::from_i64(i).with_context(|| {
+                                format!(
+                                    "Node {} ({}) list<int> attribute '{}' out of range",
+                                    self.name, self.op, name
+                                )
+                            })
+                        })
+                        .collect::<TractResult<_>>()?,
+                ));
             }
         };
         Ok(None)